@@ -0,0 +1,14 @@
+-- Store the full serialized event context alongside each event
+ALTER TABLE events
+    ADD COLUMN aggregate_type VARCHAR(255) NOT NULL DEFAULT '',
+    ADD COLUMN event_version VARCHAR(32) NOT NULL DEFAULT '',
+    ADD COLUMN metadata BYTEA NOT NULL DEFAULT ''::BYTEA;
+
+-- Tenant-wide reads are ordered by time; the composite index replaces the single-column one
+DROP INDEX IF EXISTS idx_events_tenant_id;
+CREATE INDEX idx_events_tenant_timestamp ON events(tenant_id, timestamp, id);
+CREATE INDEX idx_events_tenant_aggregate_type ON events(tenant_id, aggregate_type, timestamp);
+
+-- Covered by the UNIQUE (aggregate_id, sequence) constraint
+DROP INDEX IF EXISTS idx_events_aggregate_id;
+DROP INDEX IF EXISTS idx_events_sequence;
@@ -0,0 +1,19 @@
+-- Message log and consumer cursors for the Postgres event bus (LISTEN/NOTIFY).
+-- Only used when the services run with EVENT_BUS=postgres.
+
+CREATE TABLE bus_messages (
+    id BIGSERIAL PRIMARY KEY,
+    topic VARCHAR(255) NOT NULL,
+    event_type VARCHAR(255) NOT NULL,
+    payload BYTEA NOT NULL,
+    published_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Last message id handled by each named subscription
+CREATE TABLE bus_cursors (
+    subscription VARCHAR(255) PRIMARY KEY,
+    last_id BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_bus_messages_published_at ON bus_messages(published_at);
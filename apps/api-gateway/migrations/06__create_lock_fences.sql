@@ -0,0 +1,6 @@
+-- Fencing tokens for Postgres advisory locks, incremented on every acquisition
+CREATE TABLE lock_fences (
+    name VARCHAR(255) PRIMARY KEY,
+    token BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
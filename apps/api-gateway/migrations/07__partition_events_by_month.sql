@@ -0,0 +1,84 @@
+-- Range-partition the events table by month so old months can be archived and dropped.
+-- Partitions are named events_YYYY_MM; the projection worker keeps creating upcoming ones.
+
+ALTER TABLE events RENAME TO events_unpartitioned;
+-- Dropping the old table would drop its id sequence with it
+ALTER SEQUENCE events_id_seq OWNED BY NONE;
+ALTER SEQUENCE events_id_seq AS BIGINT;
+
+CREATE TABLE events (
+    id BIGINT NOT NULL DEFAULT nextval('events_id_seq'),
+    aggregate_id VARCHAR(36) NOT NULL,
+    sequence BIGINT NOT NULL,
+    event_type VARCHAR(255) NOT NULL,
+    payload BYTEA NOT NULL,
+    tenant_id VARCHAR(36),
+    timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    aggregate_type VARCHAR(255) NOT NULL DEFAULT '',
+    event_version VARCHAR(32) NOT NULL DEFAULT '',
+    metadata BYTEA NOT NULL DEFAULT ''::BYTEA
+) PARTITION BY RANGE (timestamp);
+
+-- Catches rows outside every monthly partition
+CREATE TABLE events_default PARTITION OF events DEFAULT;
+
+-- Monthly partitions from the oldest stored event up to three months ahead (UTC months)
+DO $$
+DECLARE
+    month TIMESTAMP := date_trunc('month', COALESCE(
+        (SELECT MIN(timestamp) FROM events_unpartitioned), NOW()) AT TIME ZONE 'UTC');
+    last_month TIMESTAMP := date_trunc('month', NOW() AT TIME ZONE 'UTC') + INTERVAL '3 months';
+BEGIN
+    WHILE month <= last_month LOOP
+        EXECUTE format(
+            'CREATE TABLE %I PARTITION OF events FOR VALUES FROM (%L) TO (%L)',
+            'events_' || to_char(month, 'YYYY_MM'),
+            month AT TIME ZONE 'UTC',
+            (month + INTERVAL '1 month') AT TIME ZONE 'UTC'
+        );
+        month := month + INTERVAL '1 month';
+    END LOOP;
+END $$;
+
+INSERT INTO events (id, aggregate_id, sequence, event_type, payload, tenant_id, timestamp,
+                    aggregate_type, event_version, metadata)
+SELECT id, aggregate_id, sequence, event_type, payload, tenant_id, timestamp,
+       aggregate_type, event_version, metadata
+FROM events_unpartitioned;
+
+-- A unique constraint on a partitioned table must include the partition key, so
+-- (aggregate_id, sequence) can no longer be enforced there. Each stream's head version
+-- is kept here instead and is what concurrent writers conflict on.
+CREATE TABLE events_streams (
+    aggregate_id VARCHAR(36) PRIMARY KEY,
+    version BIGINT NOT NULL
+);
+
+INSERT INTO events_streams (aggregate_id, version)
+SELECT aggregate_id, MAX(sequence) FROM events_unpartitioned GROUP BY aggregate_id;
+
+DROP TABLE events_unpartitioned;
+ALTER SEQUENCE events_id_seq OWNED BY events.id;
+
+ALTER TABLE events ADD PRIMARY KEY (id, timestamp);
+CREATE INDEX idx_events_aggregate_sequence ON events(aggregate_id, sequence);
+CREATE INDEX idx_events_event_type ON events(event_type);
+CREATE INDEX idx_events_timestamp ON events(timestamp);
+CREATE INDEX idx_events_tenant_timestamp ON events(tenant_id, timestamp, id);
+CREATE INDEX idx_events_tenant_aggregate_type ON events(tenant_id, aggregate_type, timestamp);
+
+-- Partitions moved to compressed files, and the streams that have events in them
+CREATE TABLE events_archives (
+    partition_name VARCHAR(63) PRIMARY KEY,
+    path TEXT NOT NULL,
+    range_start TIMESTAMPTZ NOT NULL,
+    range_end TIMESTAMPTZ NOT NULL,
+    event_count BIGINT NOT NULL,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE events_archive_streams (
+    aggregate_id VARCHAR(36) NOT NULL,
+    partition_name VARCHAR(63) NOT NULL REFERENCES events_archives(partition_name),
+    PRIMARY KEY (aggregate_id, partition_name)
+);
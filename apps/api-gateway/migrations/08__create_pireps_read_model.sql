@@ -0,0 +1,30 @@
+-- Read model for PIREPs, maintained by the projection worker
+
+CREATE TABLE pireps (
+    pirep_id VARCHAR(36) PRIMARY KEY,
+    tenant_id VARCHAR(36) NOT NULL,
+    user_id VARCHAR(36) NOT NULL, -- Submitting pilot
+    aircraft_id VARCHAR(255) NOT NULL,
+    departure_icao VARCHAR(255) NOT NULL,
+    arrival_icao VARCHAR(255) NOT NULL,
+    flight_number VARCHAR(255) NOT NULL DEFAULT '',
+    flight_time_hours DOUBLE PRECISION NOT NULL,
+    remarks TEXT NOT NULL DEFAULT '',
+    status VARCHAR(30) NOT NULL, -- PENDING, CHANGES_REQUESTED, APPROVED, REJECTED or CANCELLED
+    status_note TEXT NULL, -- Comment or reason given with the current status
+    reviewer_id VARCHAR(36) NULL, -- Staff member of the last review
+    submitted_at TIMESTAMPTZ NOT NULL,
+    reviewed_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Listing is always tenant scoped and newest first
+CREATE INDEX idx_pireps_tenant_submitted_at ON pireps(tenant_id, submitted_at DESC);
+CREATE INDEX idx_pireps_user_submitted_at ON pireps(user_id, submitted_at DESC);
+CREATE INDEX idx_pireps_status ON pireps(tenant_id, status);
+
+CREATE TRIGGER set_timestamp_pireps
+BEFORE UPDATE ON pireps
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_timestamp();
@@ -0,0 +1,8 @@
+-- Results of the tenant's PIREP rules, recorded by the projection worker
+
+ALTER TABLE pireps
+    ADD COLUMN landing_rate_fpm DOUBLE PRECISION NULL, -- Touchdown vertical speed as reported, positive down
+    ADD COLUMN rule_results JSONB NULL, -- [{rule, passed, message}] of the last evaluation
+    ADD COLUMN flagged BOOLEAN NOT NULL DEFAULT FALSE; -- Failed a rule and needs manual review
+
+CREATE INDEX idx_pireps_flagged ON pireps(tenant_id, flagged) WHERE flagged;
@@ -0,0 +1,4 @@
+-- Imported flight track a PIREP was filled from, kept in the blob store for review
+
+ALTER TABLE pireps
+    ADD COLUMN track_key VARCHAR(1024) NULL; -- Blob store key; served by GET /api/pireps/{id}/track
@@ -0,0 +1,4 @@
+-- Dispatch flight plan (imported SimBrief OFP) attached to a PIREP, to compare planned vs flown
+
+ALTER TABLE pireps
+    ADD COLUMN flight_plan JSONB NULL; -- FlightPlan as JSON; NULL until the pilot attaches one
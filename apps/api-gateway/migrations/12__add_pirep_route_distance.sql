@@ -0,0 +1,4 @@
+-- Great-circle distance between the PIREP's airports, from the embedded airport database
+
+ALTER TABLE pireps
+    ADD COLUMN route_distance_nm DOUBLE PRECISION NULL; -- NULL for PIREPs filed before airports were checked
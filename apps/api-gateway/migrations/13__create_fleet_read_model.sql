@@ -0,0 +1,25 @@
+-- Read model for tenants' fleets, maintained by the projection worker
+
+CREATE TABLE aircraft (
+    aircraft_id VARCHAR(36) PRIMARY KEY,
+    tenant_id VARCHAR(36) NOT NULL,
+    registration VARCHAR(10) NOT NULL, -- e.g. LN-ALB
+    icao_type VARCHAR(4) NOT NULL, -- ICAO type designator, e.g. B738
+    name VARCHAR(255) NOT NULL DEFAULT '',
+    home_base_icao VARCHAR(255) NOT NULL,
+    status VARCHAR(20) NOT NULL, -- ACTIVE, MAINTENANCE, STORED or RETIRED
+    status_note TEXT NULL, -- Reason given with the current status
+    added_at TIMESTAMPTZ NOT NULL,
+    retired_at TIMESTAMPTZ NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Listing is always tenant scoped and by registration
+CREATE INDEX idx_aircraft_tenant_registration ON aircraft(tenant_id, registration);
+CREATE INDEX idx_aircraft_tenant_status ON aircraft(tenant_id, status);
+
+CREATE TRIGGER set_timestamp_aircraft
+BEFORE UPDATE ON aircraft
+FOR EACH ROW
+EXECUTE FUNCTION trigger_set_timestamp();
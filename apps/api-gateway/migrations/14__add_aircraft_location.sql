@@ -0,0 +1,28 @@
+-- Where each aircraft is, maintained by the projection worker. Aircraft start at their
+-- home base and move with approved PIREPs and relocations by tenant admins.
+
+ALTER TABLE aircraft
+    ADD COLUMN location_icao VARCHAR(255) NULL,
+    ADD COLUMN location_since TIMESTAMPTZ NULL, -- Submission of the PIREP or time of the relocation that put it there
+    ADD COLUMN location_pirep_id VARCHAR(36) NULL; -- NULL unless a PIREP put it there
+
+UPDATE aircraft SET location_icao = home_base_icao, location_since = added_at;
+
+ALTER TABLE aircraft
+    ALTER COLUMN location_icao SET NOT NULL,
+    ALTER COLUMN location_since SET NOT NULL;
+
+CREATE INDEX idx_aircraft_tenant_location ON aircraft(tenant_id, location_icao);
+
+-- Relocations by tenant admins, e.g. ferry flights flown outside the VA
+CREATE TABLE aircraft_relocations (
+    aircraft_id VARCHAR(36) NOT NULL,
+    tenant_id VARCHAR(36) NOT NULL,
+    from_icao VARCHAR(255) NOT NULL, -- Empty if the location was unknown
+    to_icao VARCHAR(255) NOT NULL,
+    reason TEXT NOT NULL,
+    relocated_by VARCHAR(36) NOT NULL, -- User ID of the admin
+    relocated_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (aircraft_id, relocated_at)
+);
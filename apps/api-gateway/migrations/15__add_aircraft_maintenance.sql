@@ -0,0 +1,21 @@
+-- Airframe totals from approved PIREPs, and the maintenance history of each aircraft:
+-- groundings for inspections that fell due and the maintenance that released them.
+
+ALTER TABLE aircraft
+    ADD COLUMN airframe_hours DOUBLE PRECISION NOT NULL DEFAULT 0,
+    ADD COLUMN cycles INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE aircraft_maintenance (
+    aircraft_id VARCHAR(36) NOT NULL,
+    tenant_id VARCHAR(36) NOT NULL,
+    kind VARCHAR(16) NOT NULL, -- GROUNDED or COMPLETED
+    inspections JSONB NOT NULL, -- Names of the inspections that fell due or were done
+    notes TEXT NOT NULL DEFAULT '',
+    completed_by VARCHAR(36) NULL, -- User ID of the admin; NULL for groundings
+    airframe_hours DOUBLE PRECISION NOT NULL,
+    cycles INTEGER NOT NULL,
+    released BOOLEAN NOT NULL DEFAULT FALSE, -- The maintenance made the aircraft active again
+    occurred_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (aircraft_id, kind, occurred_at)
+);
@@ -0,0 +1,20 @@
+-- The Postgres event bus reads the events table itself instead of a separate message log,
+-- so an event is delivered if and only if its save committed.
+-- Each event records the transaction that wrote it (epoch-extended txid). Subscribers only
+-- read events of transactions older than every running one, in (transaction_id, id) order,
+-- so an event that commits late is never skipped by a cursor that has already moved on.
+ALTER TABLE events ADD COLUMN transaction_id BIGINT NOT NULL DEFAULT txid_current();
+
+CREATE INDEX idx_events_transaction ON events(transaction_id, id);
+
+DROP TABLE bus_messages;
+
+-- Position of each named subscription in the events table; existing cursors pointed at
+-- bus_messages and restart after the latest event.
+DROP TABLE bus_cursors;
+CREATE TABLE bus_cursors (
+    subscription VARCHAR(255) PRIMARY KEY,
+    last_transaction_id BIGINT NOT NULL,
+    last_id BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
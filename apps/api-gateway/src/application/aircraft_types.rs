@@ -0,0 +1,58 @@
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+};
+use core_lib::domain::aircraft_type::{AircraftCatalog, AircraftType};
+use core_lib::domain::tenant::Tenant;
+use core_lib::framework::AggregateRepository;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct AircraftTypeSearchParams {
+    /// Designator prefix, or part of the name or manufacturer; all types if absent
+    pub q: Option<String>,
+}
+
+// The embedded catalog plus the types of the caller's tenant, if any
+async fn catalog_for(
+    state: &AppState,
+    ctx: &AuthenticatedUser,
+) -> Result<AircraftCatalog, StatusCode> {
+    let Some(tenant_id) = &ctx.tenant_id else {
+        return Ok(AircraftCatalog::embedded().clone());
+    };
+    let tenants = AggregateRepository::<Tenant>::new(
+        state.tenant_repo.clone(),
+        state.services.clock.clone(),
+    );
+    let tenant = tenants.load(tenant_id).await.map_err(map_core_error)?.aggregate;
+    Ok(tenant.aircraft_catalog())
+}
+
+// GET /api/aircraft-types?q= - ordered by designator
+pub async fn handle_search_aircraft_types(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Query(params): Query<AircraftTypeSearchParams>,
+) -> Result<Json<Vec<AircraftType>>, StatusCode> {
+    let catalog = catalog_for(&state, &ctx).await?;
+    let types = catalog.search(params.q.as_deref().unwrap_or_default());
+    Ok(Json(types.into_iter().cloned().collect()))
+}
+
+// GET /api/aircraft-types/{icao_type}
+pub async fn handle_get_aircraft_type(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(icao_type): Path<String>,
+) -> Result<Json<AircraftType>, StatusCode> {
+    let catalog = catalog_for(&state, &ctx).await?;
+    catalog
+        .get(&icao_type)
+        .cloned()
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
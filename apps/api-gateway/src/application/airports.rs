@@ -0,0 +1,39 @@
+use axum::{
+    Json,
+    extract::{Path, Query},
+    http::StatusCode,
+};
+use core_lib::domain::airport::{Airport, AirportDatabase};
+use serde::Deserialize;
+
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const MAX_SEARCH_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct AirportSearchParams {
+    /// ICAO or IATA code, or part of the name or city
+    pub q: String,
+    pub limit: Option<usize>,
+}
+
+// GET /api/airports?q= - best matches first, exact codes before names
+pub async fn handle_search_airports(
+    Query(params): Query<AirportSearchParams>,
+) -> Json<Vec<Airport>> {
+    let limit = params
+        .limit
+        .filter(|limit| *limit > 0)
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .min(MAX_SEARCH_LIMIT);
+    let airports = AirportDatabase::global().search(&params.q, limit);
+    Json(airports.into_iter().cloned().collect())
+}
+
+// GET /api/airports/{icao}
+pub async fn handle_get_airport(Path(icao): Path<String>) -> Result<Json<Airport>, StatusCode> {
+    AirportDatabase::global()
+        .get(&icao)
+        .cloned()
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
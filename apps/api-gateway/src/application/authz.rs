@@ -23,6 +23,14 @@ pub enum Requirement {
         target_user_id: String,
         target_tenant_id: Option<String>,
     },
+    // Any member of the tenant (or a PlatformAdmin)
+    TenantMember {
+        target_tenant_id: String,
+    },
+    // Administrators of the tenant (or a PlatformAdmin)
+    TenantAdminOf {
+        target_tenant_id: String,
+    },
 }
 
 pub fn authorize(
@@ -57,5 +65,25 @@ pub fn authorize(
             }
             Err(StatusCode::FORBIDDEN)
         }
+        Requirement::TenantMember { target_tenant_id } => {
+            if ctx_role == AuthRole::PlatformAdmin {
+                return Ok(());
+            }
+            if ctx_tenant_id.as_deref() == Some(target_tenant_id.as_str()) {
+                return Ok(());
+            }
+            Err(StatusCode::FORBIDDEN)
+        }
+        Requirement::TenantAdminOf { target_tenant_id } => {
+            if ctx_role == AuthRole::PlatformAdmin {
+                return Ok(());
+            }
+            if ctx_role == AuthRole::TenantAdmin
+                && ctx_tenant_id.as_deref() == Some(target_tenant_id.as_str())
+            {
+                return Ok(());
+            }
+            Err(StatusCode::FORBIDDEN)
+        }
     }
 }
@@ -0,0 +1,151 @@
+use crate::application::authz::{authorize, parse_role, Requirement};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::aircraft::{Aircraft, AircraftCommand, normalize_registration},
+    domain::tenant::Tenant,
+    framework::{AggregateCqrs, AggregateRepository, aggregate_cqrs, tenant_metadata},
+};
+use proto::aircraft::AddAircraft;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::warn;
+
+pub struct AddAircraftHandler {
+    cqrs: AggregateCqrs<Aircraft>,
+    tenants: AggregateRepository<Tenant>,
+}
+
+impl AddAircraftHandler {
+    pub fn new(
+        aircraft_repository: Arc<dyn Repository>,
+        tenant_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+    ) -> Self {
+        Self {
+            tenants: AggregateRepository::new(tenant_repository, services.clock.clone()),
+            cqrs: aggregate_cqrs(aircraft_repository, event_publisher, services),
+        }
+    }
+}
+
+impl CommandHandler<AddAircraft> for AddAircraftHandler {
+    async fn handle(&self, command: AddAircraft) -> Result<(), CoreError> {
+        // The type has to be in the tenant's catalog: embedded or defined by the tenant
+        let tenant = self.tenants.load_existing(&command.tenant_id).await?.aggregate;
+        if tenant.aircraft_catalog().get(&command.icao_type).is_none() {
+            return Err(CoreError::Validation(format!(
+                "Unknown aircraft type: {}",
+                command.icao_type
+            )));
+        }
+
+        // The aggregate validates the registration and home base
+        let aircraft_id = command.aircraft_id.clone();
+        let tenant_id = command.tenant_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &aircraft_id,
+                AircraftCommand::Add(command),
+                tenant_metadata(Some(&tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AddAircraftDto {
+    registration: String,
+    icao_type: String,
+    home_base_icao: String,
+    #[serde(default)]
+    name: String,
+}
+
+// --- Axum Route Handler ---
+
+// POST /api/tenants/{tenant_id}/fleet - adds an aircraft to the tenant's fleet
+pub async fn handle_add_aircraft_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(tenant_id): Path<String>,
+    Json(payload): Json<AddAircraftDto>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantAdminOf {
+            target_tenant_id: tenant_id.clone(),
+        },
+    )?;
+
+    // Registrations are unique among the aircraft a tenant flies. Checked against the
+    // read model, so two aircraft added at the same moment can slip through.
+    let registration = normalize_registration(&payload.registration)
+        .map_err(|e| map_core_error(e.into()))?;
+    if let Some(pool) = &state.pg_pool
+        && registration_in_use(pool, &tenant_id, &registration).await?
+    {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let aircraft_id = state.services.ids.next_id();
+    let command = AddAircraft {
+        aircraft_id: aircraft_id.clone(),
+        tenant_id,
+        registration,
+        icao_type: payload.icao_type,
+        home_base_icao: payload.home_base_icao,
+        name: payload.name,
+    };
+
+    let handler = AddAircraftHandler::new(
+        state.aircraft_repo.clone(),
+        state.tenant_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::json!({"aircraft_id": aircraft_id})),
+    ))
+}
+
+// Whether an aircraft of the tenant that is not retired has the registration
+async fn registration_in_use(
+    pool: &PgPool,
+    tenant_id: &str,
+    registration: &str,
+) -> Result<bool, StatusCode> {
+    sqlx::query_scalar::<_, bool>(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM aircraft
+            WHERE tenant_id = $1 AND registration = $2 AND status <> 'RETIRED'
+        )
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(registration)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| {
+        warn!("DB error checking registration {}: {}", registration, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
@@ -0,0 +1,115 @@
+use crate::application::commands::submit_pirep::{check_not_grounded, submission_facts};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{Json, extract::{Extension, Path, State}, http::StatusCode};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::aircraft::Aircraft,
+    domain::pirep::{Pirep, PirepCommand},
+    domain::tenant::Tenant,
+    framework::{AggregateCqrs, AggregateRepository, aggregate_cqrs, tenant_metadata},
+};
+use proto::pirep::AmendPirep;
+use serde::Deserialize;
+use std::sync::Arc;
+
+pub struct AmendPirepHandler {
+    cqrs: AggregateCqrs<Pirep>,
+    tenants: AggregateRepository<Tenant>,
+    aircraft: AggregateRepository<Aircraft>,
+    tenant_id: String,
+}
+
+impl AmendPirepHandler {
+    pub fn new(
+        pirep_repository: Arc<dyn Repository>,
+        tenant_repository: Arc<dyn Repository>,
+        aircraft_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+        tenant_id: String,
+    ) -> Self {
+        Self {
+            tenants: AggregateRepository::new(tenant_repository, services.clock.clone()),
+            aircraft: AggregateRepository::new(aircraft_repository, services.clock.clone()),
+            cqrs: aggregate_cqrs(pirep_repository, event_publisher, services),
+            tenant_id,
+        }
+    }
+}
+
+impl CommandHandler<AmendPirep> for AmendPirepHandler {
+    async fn handle(&self, mut command: AmendPirep) -> Result<(), CoreError> {
+        // The amended flight is checked against the tenant's current rules
+        let tenant = self.tenants.load(&self.tenant_id).await?.aggregate;
+        command.rules = tenant.pirep_rules().cloned();
+        let aircraft = self.aircraft.load(&command.aircraft_id).await?.aggregate;
+        check_not_grounded(&aircraft, &self.tenant_id, &tenant)?;
+
+        // The aggregate checks that the caller flew the PIREP and validates the new details
+        let pirep_id = command.pirep_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &pirep_id,
+                PirepCommand::Amend(command),
+                tenant_metadata(Some(&self.tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+// Replaces every flight detail, like a new submission
+#[derive(Deserialize, Debug)]
+pub struct AmendPirepDto {
+    aircraft_id: String,
+    departure_icao: String,
+    arrival_icao: String,
+    #[serde(default)]
+    flight_number: String,
+    flight_time_hours: f64,
+    #[serde(default)]
+    remarks: String,
+    #[serde(default)]
+    landing_rate_fpm: f64,
+}
+
+// --- Axum Route Handler ---
+
+// POST /api/pireps/{pirep_id}/amend - the pilot corrects a PIREP that is pending or
+// was sent back, which puts it up for review again
+pub async fn handle_amend_pirep_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(pirep_id): Path<String>,
+    Json(payload): Json<AmendPirepDto>,
+) -> Result<StatusCode, StatusCode> {
+    let tenant_id = ctx.tenant_id.clone().ok_or(StatusCode::FORBIDDEN)?;
+
+    let facts = submission_facts(&state, &ctx.user_id, &tenant_id, &payload.aircraft_id).await;
+    let handler = AmendPirepHandler::new(
+        state.pirep_repo.clone(),
+        state.tenant_repo.clone(),
+        state.aircraft_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+        tenant_id,
+    );
+    let command = AmendPirep {
+        pirep_id,
+        user_id: ctx.user_id,
+        aircraft_id: payload.aircraft_id,
+        departure_icao: payload.departure_icao.to_uppercase(),
+        arrival_icao: payload.arrival_icao.to_uppercase(),
+        flight_number: payload.flight_number,
+        flight_time_hours: payload.flight_time_hours,
+        remarks: payload.remarks,
+        landing_rate_fpm: payload.landing_rate_fpm,
+        rules: None,
+        facts: Some(facts),
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
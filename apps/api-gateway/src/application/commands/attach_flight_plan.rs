@@ -0,0 +1,98 @@
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{
+    Json,
+    body::Bytes,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::pirep::{Pirep, PirepCommand},
+    domain::simbrief::parse_ofp,
+    domain::tenant::Tenant,
+    framework::{AggregateCqrs, AggregateRepository, aggregate_cqrs, tenant_metadata},
+};
+use proto::pirep::AttachFlightPlan;
+use std::sync::Arc;
+
+pub struct AttachFlightPlanHandler {
+    cqrs: AggregateCqrs<Pirep>,
+    tenants: AggregateRepository<Tenant>,
+    tenant_id: String,
+}
+
+impl AttachFlightPlanHandler {
+    pub fn new(
+        pirep_repository: Arc<dyn Repository>,
+        tenant_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+        tenant_id: String,
+    ) -> Self {
+        Self {
+            tenants: AggregateRepository::new(tenant_repository, services.clock.clone()),
+            cqrs: aggregate_cqrs(pirep_repository, event_publisher, services),
+            tenant_id,
+        }
+    }
+}
+
+impl CommandHandler<AttachFlightPlan> for AttachFlightPlanHandler {
+    async fn handle(&self, mut command: AttachFlightPlan) -> Result<(), CoreError> {
+        // The plan is checked against the tenant's current rules
+        let tenant = self.tenants.load(&self.tenant_id).await?.aggregate;
+        command.rules = tenant.pirep_rules().cloned();
+
+        // The aggregate checks that the caller flew the PIREP and that it is still open
+        let pirep_id = command.pirep_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &pirep_id,
+                PirepCommand::AttachFlightPlan(command),
+                tenant_metadata(Some(&self.tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+// --- Axum Route Handler ---
+
+// POST /api/pireps/{pirep_id}/flight-plan - the body is a SimBrief OFP in XML, as
+// downloaded by the pilot. The parsed plan is kept with the PIREP for review, and checked
+// by the tenant's flight plan rule if the PIREP was validated on submission.
+pub async fn handle_attach_flight_plan_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(pirep_id): Path<String>,
+    body: Bytes,
+) -> Result<Response, StatusCode> {
+    let tenant_id = ctx.tenant_id.clone().ok_or(StatusCode::FORBIDDEN)?;
+    let plan = match parse_ofp(&body) {
+        Ok(plan) => plan,
+        Err(e) => {
+            let body = Json(serde_json::json!({"error": e.to_string()}));
+            return Ok((StatusCode::BAD_REQUEST, body).into_response());
+        }
+    };
+
+    let handler = AttachFlightPlanHandler::new(
+        state.pirep_repo.clone(),
+        state.tenant_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+        tenant_id,
+    );
+    let command = AttachFlightPlan {
+        pirep_id,
+        user_id: ctx.user_id.clone(),
+        plan: Some(plan.clone()),
+        rules: None,
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok(Json(serde_json::json!({"flight_plan": plan})).into_response())
+}
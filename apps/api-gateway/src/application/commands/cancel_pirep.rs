@@ -0,0 +1,79 @@
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{Json, extract::{Extension, Path, State}, http::StatusCode};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::pirep::{Pirep, PirepCommand},
+    framework::{AggregateCqrs, aggregate_cqrs, tenant_metadata},
+};
+use proto::pirep::CancelPirep;
+use serde::Deserialize;
+use std::sync::Arc;
+
+pub struct CancelPirepHandler {
+    cqrs: AggregateCqrs<Pirep>,
+    tenant_id: String,
+}
+
+impl CancelPirepHandler {
+    pub fn new(
+        pirep_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+        tenant_id: String,
+    ) -> Self {
+        Self {
+            cqrs: aggregate_cqrs(pirep_repository, event_publisher, services),
+            tenant_id,
+        }
+    }
+}
+
+impl CommandHandler<CancelPirep> for CancelPirepHandler {
+    async fn handle(&self, command: CancelPirep) -> Result<(), CoreError> {
+        // The aggregate checks that the caller flew the PIREP and that it is still open
+        let pirep_id = command.pirep_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &pirep_id,
+                PirepCommand::Cancel(command),
+                tenant_metadata(Some(&self.tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CancelPirepDto {
+    #[serde(default)]
+    reason: String,
+}
+
+// --- Axum Route Handler ---
+
+// POST /api/pireps/{pirep_id}/cancel - the pilot withdraws a PIREP that is not reviewed yet
+pub async fn handle_cancel_pirep_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(pirep_id): Path<String>,
+    Json(payload): Json<CancelPirepDto>,
+) -> Result<StatusCode, StatusCode> {
+    let tenant_id = ctx.tenant_id.clone().ok_or(StatusCode::FORBIDDEN)?;
+
+    let handler = CancelPirepHandler::new(
+        state.pirep_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+        tenant_id,
+    );
+    let command = CancelPirep {
+        pirep_id,
+        user_id: ctx.user_id,
+        reason: payload.reason,
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
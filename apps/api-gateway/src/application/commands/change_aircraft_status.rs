@@ -0,0 +1,91 @@
+use crate::application::authz::{authorize, parse_role, Requirement};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{Json, extract::{Extension, Path, State}, http::StatusCode};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::aircraft::{Aircraft, AircraftCommand, AircraftStatus},
+    framework::{AggregateCqrs, aggregate_cqrs, tenant_metadata},
+};
+use proto::aircraft::ChangeAircraftStatus;
+use serde::Deserialize;
+use std::sync::Arc;
+
+pub struct ChangeAircraftStatusHandler {
+    cqrs: AggregateCqrs<Aircraft>,
+}
+
+impl ChangeAircraftStatusHandler {
+    pub fn new(
+        aircraft_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+    ) -> Self {
+        Self {
+            cqrs: aggregate_cqrs(aircraft_repository, event_publisher, services),
+        }
+    }
+}
+
+impl CommandHandler<ChangeAircraftStatus> for ChangeAircraftStatusHandler {
+    async fn handle(&self, command: ChangeAircraftStatus) -> Result<(), CoreError> {
+        let aircraft_id = command.aircraft_id.clone();
+        let tenant_id = command.tenant_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &aircraft_id,
+                AircraftCommand::ChangeStatus(command),
+                tenant_metadata(Some(&tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ChangeAircraftStatusDto {
+    // "active", "maintenance" or "stored"
+    status: String,
+    #[serde(default)]
+    reason: String,
+}
+
+// --- Axum Route Handler ---
+
+// PUT /api/tenants/{tenant_id}/fleet/{aircraft_id}/status
+pub async fn handle_change_aircraft_status_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path((tenant_id, aircraft_id)): Path<(String, String)>,
+    Json(payload): Json<ChangeAircraftStatusDto>,
+) -> Result<StatusCode, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantAdminOf {
+            target_tenant_id: tenant_id.clone(),
+        },
+    )?;
+
+    // The aggregate decides which statuses can be set
+    let name = format!("AIRCRAFT_STATUS_{}", payload.status.trim().to_uppercase());
+    let status = AircraftStatus::from_str_name(&name).ok_or(StatusCode::BAD_REQUEST)?;
+
+    let handler = ChangeAircraftStatusHandler::new(
+        state.aircraft_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
+    let command = ChangeAircraftStatus {
+        aircraft_id,
+        tenant_id,
+        status: status as i32,
+        reason: payload.reason,
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
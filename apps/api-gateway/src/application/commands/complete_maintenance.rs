@@ -0,0 +1,99 @@
+use crate::application::authz::{authorize, parse_role, Requirement};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{Json, extract::{Extension, Path, State}, http::StatusCode};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::aircraft::{Aircraft, AircraftCommand},
+    domain::tenant::Tenant,
+    framework::{AggregateCqrs, AggregateRepository, aggregate_cqrs, tenant_metadata},
+};
+use proto::aircraft::CompleteMaintenance;
+use serde::Deserialize;
+use std::sync::Arc;
+
+pub struct CompleteMaintenanceHandler {
+    cqrs: AggregateCqrs<Aircraft>,
+    tenants: AggregateRepository<Tenant>,
+}
+
+impl CompleteMaintenanceHandler {
+    pub fn new(
+        aircraft_repository: Arc<dyn Repository>,
+        tenant_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+    ) -> Self {
+        Self {
+            tenants: AggregateRepository::new(tenant_repository, services.clock.clone()),
+            cqrs: aggregate_cqrs(aircraft_repository, event_publisher, services),
+        }
+    }
+}
+
+impl CommandHandler<CompleteMaintenance> for CompleteMaintenanceHandler {
+    async fn handle(&self, mut command: CompleteMaintenance) -> Result<(), CoreError> {
+        // Inspections are checked against the tenant's current program
+        let tenant = self.tenants.load_existing(&command.tenant_id).await?.aggregate;
+        command.program = tenant.maintenance_program().cloned();
+
+        // The aggregate releases the aircraft once nothing is due any more
+        let aircraft_id = command.aircraft_id.clone();
+        let tenant_id = command.tenant_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &aircraft_id,
+                AircraftCommand::CompleteMaintenance(command),
+                tenant_metadata(Some(&tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CompleteMaintenanceDto {
+    inspections: Vec<String>,
+    #[serde(default)]
+    notes: String,
+}
+
+// --- Axum Route Handler ---
+
+// POST /api/tenants/{tenant_id}/fleet/{aircraft_id}/maintenance - records inspections
+// done on the aircraft, releasing it if it was grounded for them
+pub async fn handle_complete_maintenance_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path((tenant_id, aircraft_id)): Path<(String, String)>,
+    Json(payload): Json<CompleteMaintenanceDto>,
+) -> Result<StatusCode, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantAdminOf {
+            target_tenant_id: tenant_id.clone(),
+        },
+    )?;
+
+    let handler = CompleteMaintenanceHandler::new(
+        state.aircraft_repo.clone(),
+        state.tenant_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
+    let command = CompleteMaintenance {
+        aircraft_id,
+        tenant_id,
+        inspections: payload.inspections,
+        notes: payload.notes,
+        completed_by: ctx.user_id,
+        program: None,
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,79 @@
+use crate::application::authz::{authorize, parse_role, Requirement};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{Json, extract::{Extension, Path, State}, http::StatusCode};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::maintenance::MaintenanceProgram,
+    domain::tenant::{Tenant, TenantCommand},
+    framework::{AggregateCqrs, aggregate_cqrs, tenant_metadata},
+};
+use proto::tenant::ConfigureMaintenanceProgram;
+use std::sync::Arc;
+
+pub struct ConfigureMaintenanceProgramHandler {
+    cqrs: AggregateCqrs<Tenant>,
+}
+
+impl ConfigureMaintenanceProgramHandler {
+    pub fn new(
+        tenant_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+    ) -> Self {
+        Self {
+            cqrs: aggregate_cqrs(tenant_repository, event_publisher, services),
+        }
+    }
+}
+
+impl CommandHandler<ConfigureMaintenanceProgram> for ConfigureMaintenanceProgramHandler {
+    async fn handle(&self, command: ConfigureMaintenanceProgram) -> Result<(), CoreError> {
+        // The aggregate validates the intervals. Aircraft already past an interval are
+        // turned away from PIREPs until the inspection is recorded.
+        let tenant_id = command.tenant_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &tenant_id,
+                TenantCommand::ConfigureMaintenanceProgram(command),
+                tenant_metadata(Some(&tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+// --- Axum Route Handler ---
+
+// PUT /api/tenants/{tenant_id}/maintenance-program - replaces the tenant's inspection
+// intervals
+pub async fn handle_configure_maintenance_program_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(tenant_id): Path<String>,
+    Json(program): Json<MaintenanceProgram>,
+) -> Result<StatusCode, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantAdminOf {
+            target_tenant_id: tenant_id.clone(),
+        },
+    )?;
+
+    let handler = ConfigureMaintenanceProgramHandler::new(
+        state.tenant_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
+    let command = ConfigureMaintenanceProgram {
+        tenant_id,
+        program: Some(program),
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,77 @@
+use crate::application::authz::{authorize, parse_role, Requirement};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{Json, extract::{Extension, Path, State}, http::StatusCode};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::pirep_rules::PirepRules,
+    domain::tenant::{Tenant, TenantCommand},
+    framework::{AggregateCqrs, aggregate_cqrs, tenant_metadata},
+};
+use proto::tenant::ConfigurePirepRules;
+use std::sync::Arc;
+
+pub struct ConfigurePirepRulesHandler {
+    cqrs: AggregateCqrs<Tenant>,
+}
+
+impl ConfigurePirepRulesHandler {
+    pub fn new(
+        tenant_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+    ) -> Self {
+        Self {
+            cqrs: aggregate_cqrs(tenant_repository, event_publisher, services),
+        }
+    }
+}
+
+impl CommandHandler<ConfigurePirepRules> for ConfigurePirepRulesHandler {
+    async fn handle(&self, command: ConfigurePirepRules) -> Result<(), CoreError> {
+        // The aggregate validates the rules; they apply to submissions from now on
+        let tenant_id = command.tenant_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &tenant_id,
+                TenantCommand::ConfigurePirepRules(command),
+                tenant_metadata(Some(&tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+// --- Axum Route Handler ---
+
+// PUT /api/tenants/{tenant_id}/pirep-rules - replaces the tenant's rules
+pub async fn handle_configure_pirep_rules_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(tenant_id): Path<String>,
+    Json(rules): Json<PirepRules>,
+) -> Result<StatusCode, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantAdminOf {
+            target_tenant_id: tenant_id.clone(),
+        },
+    )?;
+
+    let handler = ConfigurePirepRulesHandler::new(
+        state.tenant_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
+    let command = ConfigurePirepRules {
+        tenant_id,
+        rules: Some(rules),
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
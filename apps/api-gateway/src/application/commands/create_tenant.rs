@@ -2,84 +2,44 @@ use crate::AppState;
 use crate::application::middleware::AuthenticatedUser;
 use crate::application::authz::{parse_role, AuthRole};
 use axum::{Json, extract::{State, Extension}, http::StatusCode, response::IntoResponse};
+use core_lib::services::DomainServices;
 use core_lib::{
     CommandHandler, CoreError, EventPublisher, Repository,
-    domain::tenant::{Tenant, TenantCommand, TenantError, TenantEvent},
+    domain::tenant::{Tenant, TenantCommand},
+    framework::{AggregateCqrs, aggregate_cqrs, tenant_metadata},
 };
-use cqrs_es::{Aggregate, DomainEvent};
-use prost::Message;
 use proto::tenant::CreateTenant;
 use serde::Deserialize;
 use std::sync::Arc;
-use uuid::Uuid;
 
 pub struct CreateTenantHandler {
-    tenant_repository: Arc<dyn Repository>,
-    event_publisher: Arc<dyn EventPublisher>,
+    cqrs: AggregateCqrs<Tenant>,
 }
 
 impl CreateTenantHandler {
-    #[allow(dead_code)]
     pub fn new(
         tenant_repository: Arc<dyn Repository>,
         event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
     ) -> Self {
         Self {
-            tenant_repository,
-            event_publisher,
+            cqrs: aggregate_cqrs(tenant_repository, event_publisher, services),
         }
     }
 }
 
 impl CommandHandler<CreateTenant> for CreateTenantHandler {
     async fn handle(&self, command: CreateTenant) -> Result<(), CoreError> {
-        // 1. Load Aggregate (or check if exists - Create should fail if exists)
-        // Similar to RegisterUser, the aggregate's handle method checks for existence.
-        let aggregate_command = TenantCommand::Create(command.clone());
-
-        // 2. Execute command on a *default* aggregate instance for creation commands
-        let default_tenant = Tenant::default();
-        let events = default_tenant
-            .handle(aggregate_command, &())
-            .await
-            .map_err(|e| {
-                // Map TenantError to CoreError for the return type
-                match e {
-                    TenantError::Core(ce) => ce,
-                    TenantError::AlreadyExists(id) => {
-                        CoreError::Validation(format!("Tenant already exists: {}", id))
-                    }
-                    TenantError::InvalidInput(msg) => CoreError::Validation(msg),
-                }
-            })?;
-
-        // --- Serialize Events for Saving ---
-        let events_to_save: Vec<(String, Vec<u8>)> = events
-            .iter()
-            .map(|event| {
-                let payload = match event {
-                    TenantEvent::Created(e) => e.encode_to_vec(),
-                };
-                (event.event_type(), payload)
-            })
-            .collect();
-        // --- End Serialization ---
-
-        // 3. Save events using the repository
-        // For creation, expected_version is 0
-        self.tenant_repository
-            .save(&command.tenant_id, 0, &events_to_save) // Pass serialized data
+        // The aggregate rejects creation if the tenant already exists.
+        // A new tenant owns itself, which the aggregate cannot know before its first event.
+        let tenant_id = command.tenant_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &tenant_id,
+                TenantCommand::Create(command),
+                tenant_metadata(Some(&tenant_id)),
+            )
             .await?;
-
-        // 4. Publish events (using serialized data)
-        for (event_type, payload) in &events_to_save {
-            // Iterate over serialized data
-            let topic = format!("tenant.{}", command.tenant_id);
-            self.event_publisher
-                .publish(&topic, event_type, payload) // Publish raw bytes
-                .await?;
-        }
-
         Ok(())
     }
 }
@@ -108,7 +68,7 @@ pub async fn handle_create_tenant_request(
         return (StatusCode::FORBIDDEN, Json(serde_json::json!({"error": "forbidden"}))).into_response();
     }
     // 1. Generate Tenant ID
-    let tenant_id = Uuid::new_v4().to_string();
+    let tenant_id = state.services.ids.next_id();
 
     // 2. Create the Protobuf Command
     let command = CreateTenant {
@@ -117,7 +77,11 @@ pub async fn handle_create_tenant_request(
     };
 
     // 3. Instantiate the handler
-    let handler = CreateTenantHandler::new(state.tenant_repo.clone(), state.event_bus.clone());
+    let handler = CreateTenantHandler::new(
+        state.tenant_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
 
     // 4. Execute the command
     match handler.handle(command).await {
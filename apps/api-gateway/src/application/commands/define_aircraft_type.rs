@@ -0,0 +1,82 @@
+use crate::application::authz::{authorize, parse_role, Requirement};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{Json, extract::{Extension, Path, State}, http::StatusCode};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::aircraft_type::AircraftType,
+    domain::tenant::{Tenant, TenantCommand},
+    framework::{AggregateCqrs, aggregate_cqrs, tenant_metadata},
+};
+use proto::tenant::DefineAircraftType;
+use std::sync::Arc;
+
+pub struct DefineAircraftTypeHandler {
+    cqrs: AggregateCqrs<Tenant>,
+}
+
+impl DefineAircraftTypeHandler {
+    pub fn new(
+        tenant_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+    ) -> Self {
+        Self {
+            cqrs: aggregate_cqrs(tenant_repository, event_publisher, services),
+        }
+    }
+}
+
+impl CommandHandler<DefineAircraftType> for DefineAircraftTypeHandler {
+    async fn handle(&self, command: DefineAircraftType) -> Result<(), CoreError> {
+        // The aggregate validates the type; it replaces an earlier one with the same designator
+        let tenant_id = command.tenant_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &tenant_id,
+                TenantCommand::DefineAircraftType(command),
+                tenant_metadata(Some(&tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+// --- Axum Route Handler ---
+
+// PUT /api/tenants/{tenant_id}/aircraft-types/{icao_type} - adds a type to the tenant's
+// catalog, or overrides the embedded one with that designator
+pub async fn handle_define_aircraft_type_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path((tenant_id, icao_type)): Path<(String, String)>,
+    Json(aircraft_type): Json<AircraftType>,
+) -> Result<StatusCode, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantAdminOf {
+            target_tenant_id: tenant_id.clone(),
+        },
+    )?;
+
+    let handler = DefineAircraftTypeHandler::new(
+        state.tenant_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
+    // The designator in the path wins over one in the body
+    let command = DefineAircraftType {
+        tenant_id,
+        aircraft_type: Some(AircraftType {
+            icao_type,
+            ..aircraft_type
+        }),
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
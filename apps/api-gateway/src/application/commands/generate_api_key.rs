@@ -2,20 +2,19 @@ use argon2::{
     Argon2,
     password_hash::{PasswordHasher, SaltString, rand_core::OsRng}, // Removed unused PasswordHash, PasswordVerifier
 };
+use core_lib::services::DomainServices;
 use core_lib::{
     CoreError,
     EventPublisher,
     Repository, // Removed unused CommandHandler
-    domain::user::{User, UserCommand, UserEvent},
+    domain::user::{User, UserCommand},
+    framework::{AggregateCqrs, AggregateRepository, aggregate_cqrs},
 };
-use cqrs_es::{Aggregate, DomainEvent};
-use prost::Message;
-use proto::user::{ApiKeyGenerated, GenerateApiKey, PasswordChanged, UserLoggedIn, UserRegistered};
+use proto::user::GenerateApiKey;
 use rand::distr::{Alphanumeric, SampleString}; // Corrected module name again
 use rand::rng; // Separate import
 use std::sync::Arc;
 use tracing;
-use uuid::Uuid;
 
 // Input for the GenerateApiKeyHandler
 #[derive(Debug, Clone)]
@@ -30,9 +29,10 @@ use core_lib::Cache; // Added Cache import
 use serde_json; // Added serde_json import
 
 pub struct GenerateApiKeyHandler {
-    user_repository: Arc<dyn Repository>,
-    event_publisher: Arc<dyn EventPublisher>,
+    cqrs: AggregateCqrs<User>,
+    users: AggregateRepository<User>,
     cache: Arc<dyn Cache>, // Added cache field
+    services: DomainServices,
 }
 
 impl GenerateApiKeyHandler {
@@ -41,18 +41,20 @@ impl GenerateApiKeyHandler {
         user_repository: Arc<dyn Repository>,
         event_publisher: Arc<dyn EventPublisher>,
         cache: Arc<dyn Cache>, // Added cache parameter
+        services: DomainServices,
     ) -> Self {
         Self {
-            user_repository,
-            event_publisher,
+            users: AggregateRepository::new(user_repository.clone(), services.clock.clone()),
+            cqrs: aggregate_cqrs(user_repository, event_publisher, services.clone()),
             cache, // Store cache
+            services,
         }
     }
 
     // Generates a secure key, its hash, and a key ID.
     // Returns (key_id, plain_key, key_hash_string) or CoreError
-    fn generate_secure_key() -> Result<(String, String, String), CoreError> {
-        let key_id = format!("key_{}", Uuid::new_v4());
+    fn generate_secure_key(&self) -> Result<(String, String, String), CoreError> {
+        let key_id = format!("key_{}", self.services.ids.next_id());
 
         // Generate a cryptographically secure random string for the API key
         // Example: 32 characters alphanumeric
@@ -75,85 +77,10 @@ impl GenerateApiKeyHandler {
 impl GenerateApiKeyHandler {
     // Returns (key_id, plain_key) on success
     pub async fn handle(&self, input: GenerateApiKeyInput) -> Result<(String, String), CoreError> {
-        // 1. Load Aggregate
-        let stored_events = self.user_repository.load(&input.user_id).await?;
-        if stored_events.is_empty() {
-            return Err(CoreError::NotFound(format!(
-                "User not found: {}",
-                input.user_id
-            )));
-        }
-        // --- Correct Aggregate Loading ---
-        let mut user = User::default();
-        for stored_event in stored_events {
-            // Deserialize and apply based on type string
-            match stored_event.event_type.as_str() {
-                "UserRegistered" => match UserRegistered::decode(stored_event.payload.as_slice()) {
-                    Ok(e) => user.apply(UserEvent::Registered(e)),
-                    Err(err) => {
-                        return Err(CoreError::Deserialization(format!(
-                            "Failed to decode UserRegistered: {}",
-                            err
-                        )));
-                    }
-                },
-                "PasswordChanged" => {
-                    match PasswordChanged::decode(stored_event.payload.as_slice()) {
-                        Ok(e) => user.apply(UserEvent::PasswordChanged(e)),
-                        Err(err) => {
-                            return Err(CoreError::Deserialization(format!(
-                                "Failed to decode PasswordChanged: {}",
-                                err
-                            )));
-                        }
-                    }
-                }
-                "ApiKeyGenerated" => {
-                    match ApiKeyGenerated::decode(stored_event.payload.as_slice()) {
-                        Ok(e) => user.apply(UserEvent::ApiKeyGenerated(e)),
-                        Err(err) => {
-                            return Err(CoreError::Deserialization(format!(
-                                "Failed to decode ApiKeyGenerated: {}",
-                                err
-                            )));
-                        }
-                    }
-                }
-                "UserLoggedIn" => match UserLoggedIn::decode(stored_event.payload.as_slice()) {
-                    Ok(e) => user.apply(UserEvent::LoggedIn(e)),
-                    Err(err) => {
-                        return Err(CoreError::Deserialization(format!(
-                            "Failed to decode UserLoggedIn: {}",
-                            err
-                        )));
-                    }
-                },
-                "ApiKeyRevoked" => {
-                    // Need ApiKeyRevoked proto type for decoding
-                    match proto::user::ApiKeyRevoked::decode(stored_event.payload.as_slice()) {
-                        Ok(e) => user.apply(UserEvent::ApiKeyRevoked(e)),
-                        Err(err) => {
-                            return Err(CoreError::Deserialization(format!(
-                                "Failed to decode ApiKeyRevoked: {}",
-                                err
-                            )));
-                        }
-                    }
-                }
-                _ => {
-                    tracing::warn!(
-                        "Skipping unknown event type during user load: {}",
-                        stored_event.event_type
-                    );
-                }
-            }
-        }
-        // --- End Aggregate Loading ---
-
-        // 2. Generate Key ID, Plain Key, and Hash
-        let (key_id, plain_key, key_hash) = Self::generate_secure_key()?;
+        // 1. Generate Key ID, Plain Key, and Hash
+        let (key_id, plain_key, key_hash) = self.generate_secure_key()?;
 
-        // 3. Create the aggregate command enum variant, now including key_id and hash
+        // 2. Create the aggregate command enum variant, now including key_id and hash
         let key_hash_for_command = key_hash.clone(); // Clone hash explicitly for command
         let aggregate_command = UserCommand::GenerateApiKey(GenerateApiKey {
             user_id: input.user_id.clone(),     // Use input.user_id
@@ -162,43 +89,13 @@ impl GenerateApiKeyHandler {
             api_key_hash: key_hash_for_command, // Pass the clone
         });
 
-        // 4. Execute command on the loaded aggregate instance
-        let resulting_events: Vec<UserEvent> = user.handle(aggregate_command, &()).await?;
-
-        // --- Serialize Events for Saving ---
-        let events_to_save: Vec<(String, Vec<u8>)> = resulting_events
-            .iter()
-            .map(|event| {
-                let payload = match event {
-                    UserEvent::Registered(e) => e.encode_to_vec(),
-                    UserEvent::PasswordChanged(e) => e.encode_to_vec(),
-                    UserEvent::ApiKeyGenerated(e) => e.encode_to_vec(),
-                    UserEvent::ApiKeyRevoked(e) => e.encode_to_vec(), // Added
-                    UserEvent::LoggedIn(e) => e.encode_to_vec(),
-                };
-                (event.event_type(), payload)
-            })
-            .collect();
-        // --- End Serialization ---
-
-        // 5. Save events using the repository, providing the expected version
-        self.user_repository
-            .save(&input.user_id, user.version(), &events_to_save) // Pass serialized data
-            .await?;
+        // 3. Execute on the aggregate (load, handle, save, publish)
+        self.cqrs.execute(&input.user_id, aggregate_command).await?;
 
-        // 6. Publish events (using the serialized data)
-        for (event_type, payload) in &events_to_save {
-            let topic = match event_type.as_str() {
-                "ApiKeyGenerated" => format!("user.{}", input.user_id),
-                _ => "user_events".to_string(),
-            };
-            // Iterate over serialized data
-            self.event_publisher
-                .publish(&topic, event_type, payload) // Publish raw bytes
-                .await?;
-        }
+        // 4. Reload the user for the role and tenant cached alongside the key
+        let user = self.users.load_existing(&input.user_id).await?.aggregate;
 
-        // 7. Store AuthenticatedUser in cache, keyed by the PLAIN TEXT key
+        // 5. Store AuthenticatedUser in cache, keyed by the PLAIN TEXT key
         let role_str = match user.role() {
             proto::user::Role::PlatformAdmin => "PlatformAdmin",
             proto::user::Role::TenantAdmin => "TenantAdmin",
@@ -1,116 +1,37 @@
 use crate::AppState;
 use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use core_lib::services::DomainServices;
 use core_lib::{
-    CommandHandler, CoreError, EventPublisher, Repository,
-    domain::user::{User, UserCommand, UserEvent},
+    CoreError, EventPublisher, Repository,
+    domain::user::{User, UserCommand},
+    framework::{AggregateCqrs, aggregate_cqrs},
 };
-use cqrs_es::{Aggregate, DomainEvent};
-use prost::Message;
-use proto::user::{LoginUser, UserLoggedIn};
+use proto::user::LoginUser;
 use argon2::password_hash::PasswordVerifier;
 use serde::Deserialize;
 use sqlx::Row;
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
+/// Records a successful login on the user aggregate (`UserLoggedIn`).
+/// Credentials are verified before this handler is called.
 pub struct LoginHandler {
-    user_repository: Arc<dyn Repository>,
-    event_publisher: Arc<dyn EventPublisher>,
+    cqrs: AggregateCqrs<User>,
 }
 
 impl LoginHandler {
     pub fn new(
         user_repository: Arc<dyn Repository>,
         event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
     ) -> Self {
         Self {
-            user_repository,
-            event_publisher,
+            cqrs: aggregate_cqrs(user_repository, event_publisher, services),
         }
     }
-}
-
-impl CommandHandler<LoginUser> for LoginHandler {
-    async fn handle(&self, command: LoginUser) -> Result<(), CoreError> {
-        // For now, create a demo user_id from username
-        // In production, you'd look up the user by username first
-        let user_id = format!("user-{}", command.username);
-
-        // Load the user aggregate
-        let stored_events = self.user_repository.load(&user_id).await?;
-        let mut user = User::default();
-        for stored_event in stored_events {
-            match stored_event.event_type.as_str() {
-                "UserRegistered" => {
-                    if let Ok(e) = proto::user::UserRegistered::decode(stored_event.payload.as_slice()) {
-                        user.apply(UserEvent::Registered(e))
-                    }
-                }
-                "PasswordChanged" => {
-                    if let Ok(e) = proto::user::PasswordChanged::decode(stored_event.payload.as_slice()) {
-                        user.apply(UserEvent::PasswordChanged(e))
-                    }
-                }
-                "ApiKeyGenerated" => {
-                    if let Ok(e) = proto::user::ApiKeyGenerated::decode(stored_event.payload.as_slice()) {
-                        user.apply(UserEvent::ApiKeyGenerated(e))
-                    }
-                }
-                "UserLoggedIn" => {
-                    if let Ok(e) = UserLoggedIn::decode(stored_event.payload.as_slice()) {
-                        user.apply(UserEvent::LoggedIn(e))
-                    }
-                }
-                "ApiKeyRevoked" => {
-                    if let Ok(e) = proto::user::ApiKeyRevoked::decode(stored_event.payload.as_slice()) {
-                        user.apply(UserEvent::ApiKeyRevoked(e))
-                    }
-                }
-                _ => {}
-            }
-        }
-
-        // Execute login command
-        let aggregate_command = UserCommand::Login(LoginUser {
-            username: command.username.clone(),
-            password_attempt: command.password_attempt,
-        });
-
-        let events: Vec<UserEvent> = user
-            .handle(aggregate_command, &())
-            .await
-            .map_err(|e| match e {
-                core_lib::domain::user::UserError::Core(ce) => ce,
-                core_lib::domain::user::UserError::InvalidPassword => {
-                    CoreError::Unauthorized("Invalid credentials".into())
-                }
-                _ => CoreError::Validation("Login failed".into()),
-            })?;
-
-        // Serialize and save events
-        let events_to_save: Vec<(String, Vec<u8>)> = events
-            .iter()
-            .map(|event| {
-                let payload = match event {
-                    UserEvent::LoggedIn(e) => e.encode_to_vec(),
-                    _ => Vec::new(),
-                };
-                (event.event_type(), payload)
-            })
-            .collect();
-
-        self.user_repository
-            .save(&user_id, user.version(), &events_to_save)
-            .await?;
-
-        // Publish events
-        for (event_type, payload) in &events_to_save {
-            let topic = format!("user.{}", user_id);
-            self.event_publisher
-                .publish(&topic, event_type, payload)
-                .await?;
-        }
 
+    pub async fn handle(&self, user_id: &str, command: LoginUser) -> Result<(), CoreError> {
+        self.cqrs.execute(user_id, UserCommand::Login(command)).await?;
         Ok(())
     }
 }
@@ -183,8 +104,22 @@ pub async fn handle_login_request(
         return Err(StatusCode::UNAUTHORIZED);
     }
 
+    // Record the login on the aggregate; failure to do so does not block the login
+    let login_handler = LoginHandler::new(
+        state.user_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
+    let login_command = LoginUser {
+        username: payload.username.clone(),
+        password_attempt: String::new(), // Already verified above; never forwarded
+    };
+    if let Err(e) = login_handler.handle(&user_id, login_command).await {
+        warn!("Failed to record login for user {}: {}", user_id, e);
+    }
+
     // Password is valid, generate API key
-    let api_key = format!("api-key-{}", uuid::Uuid::new_v4());
+    let api_key = format!("api-key-{}", state.services.ids.next_id());
 
     // Store API key in cache with user context
     let user_context = serde_json::json!({
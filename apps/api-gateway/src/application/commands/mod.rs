@@ -1,13 +1,43 @@
+pub mod add_aircraft;
+pub mod amend_pirep;
+pub mod attach_flight_plan;
+pub mod cancel_pirep;
+pub mod change_aircraft_status;
 pub mod change_password;
+pub mod complete_maintenance;
+pub mod configure_maintenance_program;
+pub mod configure_pirep_rules;
 pub mod create_tenant;
+pub mod define_aircraft_type;
 pub mod generate_api_key;
 pub mod login;
 pub mod register_user;
+pub mod relocate_aircraft;
+pub mod remove_aircraft_type;
+pub mod retire_aircraft;
+pub mod review_pirep;
 pub mod revoke_api_key; // Added
+pub mod submit_pirep;
+pub mod transfer_aircraft_base;
 
+pub use add_aircraft::AddAircraftHandler;
+pub use amend_pirep::AmendPirepHandler;
+pub use attach_flight_plan::AttachFlightPlanHandler;
+pub use cancel_pirep::CancelPirepHandler;
+pub use change_aircraft_status::ChangeAircraftStatusHandler;
 pub use change_password::ChangePasswordHandler;
+pub use complete_maintenance::CompleteMaintenanceHandler;
+pub use configure_maintenance_program::ConfigureMaintenanceProgramHandler;
+pub use configure_pirep_rules::ConfigurePirepRulesHandler;
 pub use create_tenant::CreateTenantHandler;
+pub use define_aircraft_type::DefineAircraftTypeHandler;
 pub use generate_api_key::{GenerateApiKeyHandler, GenerateApiKeyInput}; // Added Input
 pub use login::handle_login_request;
 pub use register_user::RegisterUserHandler;
+pub use relocate_aircraft::RelocateAircraftHandler;
+pub use remove_aircraft_type::RemoveAircraftTypeHandler;
+pub use retire_aircraft::RetireAircraftHandler;
+pub use review_pirep::ReviewPirepHandler;
 pub use revoke_api_key::RevokeApiKeyHandler; // Added
+pub use submit_pirep::SubmitPirepHandler;
+pub use transfer_aircraft_base::TransferAircraftBaseHandler;
@@ -1,11 +1,11 @@
 use crate::AppState;
 use axum::{Json, extract::State, http::{StatusCode, HeaderMap, header}, response::IntoResponse};
+use core_lib::services::DomainServices;
 use core_lib::{
     CommandHandler, CoreError, EventPublisher, Repository,
-    domain::user::{User, UserCommand, UserError, UserEvent},
+    domain::user::{User, UserCommand},
+    framework::{AggregateCqrs, aggregate_cqrs, tenant_metadata},
 };
-use cqrs_es::{Aggregate, DomainEvent};
-use prost::Message;
 use proto::user::{RegisterUser, Role as ProtoRole};
 use crate::application::authz::{parse_role, AuthRole};
 use crate::application::middleware::AuthenticatedUser;
@@ -13,91 +13,32 @@ use argon2::{Argon2, PasswordHasher};
 use argon2::password_hash::{rand_core::OsRng, SaltString};
 use serde::Deserialize;
 use std::sync::Arc;
-use uuid::Uuid;
 
 pub struct RegisterUserHandler {
-    user_repository: Arc<dyn Repository>,
-    event_publisher: Arc<dyn EventPublisher>,
+    cqrs: AggregateCqrs<User>,
 }
 
 impl RegisterUserHandler {
-    #[allow(dead_code)]
     pub fn new(
         user_repository: Arc<dyn Repository>,
         event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
     ) -> Self {
         Self {
-            user_repository,
-            event_publisher,
+            cqrs: aggregate_cqrs(user_repository, event_publisher, services),
         }
     }
 }
 
 impl CommandHandler<RegisterUser> for RegisterUserHandler {
     async fn handle(&self, command: RegisterUser) -> Result<(), CoreError> {
-        // 1. Load Aggregate (Not needed for RegisterUser)
-
-        // 2. Create the aggregate command enum variant
-        // Password is already hashed by the HTTP handler
-        let aggregate_command = UserCommand::Register(RegisterUser {
-            user_id: command.user_id.clone(),
-            username: command.username,
-            email: command.email,
-            password_hash: command.password_hash,
-            initial_role: command.initial_role,
-            tenant_id: command.tenant_id,
-        });
-
-        // 3. Execute command on a *default* aggregate instance
-        let default_user = User::default();
-        let events: Vec<UserEvent> =
-            default_user
-                .handle(aggregate_command, &())
-                .await
-                .map_err(|e| match e {
-                    UserError::Core(ce) => ce,
-                    UserError::AlreadyExists(id) => {
-                        CoreError::Validation(format!("User already exists: {}", id))
-                    }
-                    UserError::InvalidInput(msg) | UserError::InvalidRole(msg) => {
-                        CoreError::Validation(msg)
-                    }
-                    UserError::TenantIdRequired => {
-                        CoreError::Validation("Tenant ID required".into())
-                    }
-                    _ => CoreError::Internal("Unexpected error during registration".into()),
-                })?;
-
-        // --- Serialize Events for Saving ---
-        let events_to_save: Vec<(String, Vec<u8>)> = events
-            .iter()
-            .map(|event| {
-                let payload = match event {
-                    UserEvent::Registered(e) => e.encode_to_vec(),
-                    // Other variants not expected from RegisterUser command
-                    _ => Vec::new(), // Or handle error
-                };
-                (event.event_type(), payload)
-            })
-            .collect();
-        // --- End Serialization ---
-
-        // 4. Save events using the repository
-        self.user_repository
-            .save(&command.user_id, 0, &events_to_save) // Pass serialized data
+        // Password is already hashed by the HTTP handler.
+        // The aggregate rejects registration if the user already exists.
+        let user_id = command.user_id.clone();
+        let metadata = tenant_metadata(command.tenant_id.as_deref());
+        self.cqrs
+            .execute_with_metadata(&user_id, UserCommand::Register(command), metadata)
             .await?;
-
-        // 5. Publish events (using the serialized data)
-        for (event_type, payload) in &events_to_save {
-            let topic = match event_type.as_str() {
-                "UserRegistered" => format!("user.{}", command.user_id),
-                _ => "user_events".to_string(),
-            };
-            self.event_publisher
-                .publish(&topic, event_type, payload)
-                .await?;
-        }
-
         Ok(())
     }
 }
@@ -191,7 +132,7 @@ pub async fn handle_register_user_request(
             return (StatusCode::UNAUTHORIZED, Json(serde_json::json!({"error": "authentication required"}))).into_response();
         }
     }
-    let user_id = Uuid::new_v4().to_string();
+    let user_id = state.services.ids.next_id();
 
     // Hash password with argon2
     let salt = SaltString::generate(&mut OsRng);
@@ -210,7 +151,11 @@ pub async fn handle_register_user_request(
         tenant_id: payload.tenant_id,
     };
 
-    let handler = RegisterUserHandler::new(state.user_repo.clone(), state.event_bus.clone());
+    let handler = RegisterUserHandler::new(
+        state.user_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
 
     match handler.handle(command).await {
         Ok(_) => (
@@ -0,0 +1,122 @@
+use crate::application::authz::{authorize, parse_role, Requirement};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{Json, extract::{Extension, Path, State}, http::StatusCode};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::aircraft::{Aircraft, AircraftCommand},
+    framework::{AggregateCqrs, aggregate_cqrs, tenant_metadata},
+};
+use proto::aircraft::RelocateAircraft;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::warn;
+
+pub struct RelocateAircraftHandler {
+    cqrs: AggregateCqrs<Aircraft>,
+}
+
+impl RelocateAircraftHandler {
+    pub fn new(
+        aircraft_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+    ) -> Self {
+        Self {
+            cqrs: aggregate_cqrs(aircraft_repository, event_publisher, services),
+        }
+    }
+}
+
+impl CommandHandler<RelocateAircraft> for RelocateAircraftHandler {
+    async fn handle(&self, command: RelocateAircraft) -> Result<(), CoreError> {
+        // The aggregate checks the tenant, the destination and that a reason is given
+        let aircraft_id = command.aircraft_id.clone();
+        let tenant_id = command.tenant_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &aircraft_id,
+                AircraftCommand::Relocate(command),
+                tenant_metadata(Some(&tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RelocateAircraftDto {
+    to_icao: String,
+    reason: String,
+}
+
+// --- Axum Route Handler ---
+
+// POST /api/tenants/{tenant_id}/fleet/{aircraft_id}/relocate - moves the aircraft
+// without a PIREP, e.g. after a ferry flight. Each relocation is kept with the admin
+// and reason in the aircraft's relocation history.
+pub async fn handle_relocate_aircraft_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path((tenant_id, aircraft_id)): Path<(String, String)>,
+    Json(payload): Json<RelocateAircraftDto>,
+) -> Result<StatusCode, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantAdminOf {
+            target_tenant_id: tenant_id.clone(),
+        },
+    )?;
+
+    // Recorded with the relocation so the history shows where the aircraft came from
+    let from_icao = match &state.pg_pool {
+        Some(pool) => aircraft_location(pool, &tenant_id, &aircraft_id)
+            .await
+            .map_err(|e| {
+                warn!("DB error looking up location of aircraft {}: {}", aircraft_id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .unwrap_or_default(),
+        None => String::new(),
+    };
+
+    let handler = RelocateAircraftHandler::new(
+        state.aircraft_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
+    let command = RelocateAircraft {
+        aircraft_id,
+        tenant_id,
+        to_icao: payload.to_icao,
+        reason: payload.reason,
+        relocated_by: ctx.user_id,
+        from_icao,
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// Where an aircraft of the tenant is according to the fleet read model
+pub(crate) async fn aircraft_location(
+    pool: &PgPool,
+    tenant_id: &str,
+    aircraft_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT location_icao FROM aircraft
+        WHERE tenant_id = $1 AND aircraft_id = $2
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(aircraft_id)
+    .fetch_optional(pool)
+    .await
+}
@@ -0,0 +1,74 @@
+use crate::application::authz::{authorize, parse_role, Requirement};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{extract::{Extension, Path, State}, http::StatusCode};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::tenant::{Tenant, TenantCommand},
+    framework::{AggregateCqrs, aggregate_cqrs, tenant_metadata},
+};
+use proto::tenant::RemoveAircraftType;
+use std::sync::Arc;
+
+pub struct RemoveAircraftTypeHandler {
+    cqrs: AggregateCqrs<Tenant>,
+}
+
+impl RemoveAircraftTypeHandler {
+    pub fn new(
+        tenant_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+    ) -> Self {
+        Self {
+            cqrs: aggregate_cqrs(tenant_repository, event_publisher, services),
+        }
+    }
+}
+
+impl CommandHandler<RemoveAircraftType> for RemoveAircraftTypeHandler {
+    async fn handle(&self, command: RemoveAircraftType) -> Result<(), CoreError> {
+        let tenant_id = command.tenant_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &tenant_id,
+                TenantCommand::RemoveAircraftType(command),
+                tenant_metadata(Some(&tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+// --- Axum Route Handler ---
+
+// DELETE /api/tenants/{tenant_id}/aircraft-types/{icao_type} - removes a type the tenant
+// defined; an embedded type it overrode becomes visible again
+pub async fn handle_remove_aircraft_type_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path((tenant_id, icao_type)): Path<(String, String)>,
+) -> Result<StatusCode, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantAdminOf {
+            target_tenant_id: tenant_id.clone(),
+        },
+    )?;
+
+    let handler = RemoveAircraftTypeHandler::new(
+        state.tenant_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
+    handler
+        .handle(RemoveAircraftType { tenant_id, icao_type })
+        .await
+        .map_err(map_core_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
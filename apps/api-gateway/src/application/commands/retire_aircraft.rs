@@ -0,0 +1,86 @@
+use crate::application::authz::{authorize, parse_role, Requirement};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{Json, extract::{Extension, Path, State}, http::StatusCode};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::aircraft::{Aircraft, AircraftCommand},
+    framework::{AggregateCqrs, aggregate_cqrs, tenant_metadata},
+};
+use proto::aircraft::RetireAircraft;
+use serde::Deserialize;
+use std::sync::Arc;
+
+pub struct RetireAircraftHandler {
+    cqrs: AggregateCqrs<Aircraft>,
+}
+
+impl RetireAircraftHandler {
+    pub fn new(
+        aircraft_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+    ) -> Self {
+        Self {
+            cqrs: aggregate_cqrs(aircraft_repository, event_publisher, services),
+        }
+    }
+}
+
+impl CommandHandler<RetireAircraft> for RetireAircraftHandler {
+    async fn handle(&self, command: RetireAircraft) -> Result<(), CoreError> {
+        // The aggregate checks that the aircraft belongs to the tenant
+        let aircraft_id = command.aircraft_id.clone();
+        let tenant_id = command.tenant_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &aircraft_id,
+                AircraftCommand::Retire(command),
+                tenant_metadata(Some(&tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RetireAircraftDto {
+    #[serde(default)]
+    reason: String,
+}
+
+// --- Axum Route Handler ---
+
+// POST /api/tenants/{tenant_id}/fleet/{aircraft_id}/retire - the aircraft stays on
+// record but can no longer be changed
+pub async fn handle_retire_aircraft_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path((tenant_id, aircraft_id)): Path<(String, String)>,
+    Json(payload): Json<RetireAircraftDto>,
+) -> Result<StatusCode, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantAdminOf {
+            target_tenant_id: tenant_id.clone(),
+        },
+    )?;
+
+    let handler = RetireAircraftHandler::new(
+        state.aircraft_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
+    let command = RetireAircraft {
+        aircraft_id,
+        tenant_id,
+        reason: payload.reason,
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,157 @@
+use crate::application::authz::{authorize, parse_role, Requirement};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{Json, extract::{Extension, Path, State}, http::StatusCode};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::pirep::{Pirep, PirepCommand},
+    framework::{AggregateCqrs, AggregateRepository, aggregate_cqrs, tenant_metadata},
+};
+use proto::pirep::{ApprovePirep, RejectPirep, RequestPirepChanges};
+use serde::Deserialize;
+use std::sync::Arc;
+
+// Approves, rejects or sends back PIREPs of a tenant
+pub struct ReviewPirepHandler {
+    cqrs: AggregateCqrs<Pirep>,
+    tenant_id: String,
+}
+
+impl ReviewPirepHandler {
+    pub fn new(
+        pirep_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+        tenant_id: String,
+    ) -> Self {
+        Self {
+            cqrs: aggregate_cqrs(pirep_repository, event_publisher, services),
+            tenant_id,
+        }
+    }
+
+    // The aggregate checks the transition and that reviewers don't review their own flights
+    async fn execute(&self, pirep_id: &str, command: PirepCommand) -> Result<(), CoreError> {
+        self.cqrs
+            .execute_with_metadata(pirep_id, command, tenant_metadata(Some(&self.tenant_id)))
+            .await?;
+        Ok(())
+    }
+}
+
+impl CommandHandler<ApprovePirep> for ReviewPirepHandler {
+    async fn handle(&self, command: ApprovePirep) -> Result<(), CoreError> {
+        let pirep_id = command.pirep_id.clone();
+        self.execute(&pirep_id, PirepCommand::Approve(command)).await
+    }
+}
+
+impl CommandHandler<RejectPirep> for ReviewPirepHandler {
+    async fn handle(&self, command: RejectPirep) -> Result<(), CoreError> {
+        let pirep_id = command.pirep_id.clone();
+        self.execute(&pirep_id, PirepCommand::Reject(command)).await
+    }
+}
+
+impl CommandHandler<RequestPirepChanges> for ReviewPirepHandler {
+    async fn handle(&self, command: RequestPirepChanges) -> Result<(), CoreError> {
+        let pirep_id = command.pirep_id.clone();
+        self.execute(&pirep_id, PirepCommand::RequestChanges(command)).await
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ApprovePirepDto {
+    #[serde(default)]
+    comment: String,
+}
+
+#[derive(Deserialize)]
+pub struct ReviewReasonDto {
+    #[serde(default)]
+    reason: String,
+}
+
+// --- Axum Route Handlers ---
+
+// Reviews are left to the admins of the PIREP's tenant; returns the handler for it
+async fn reviewer(
+    state: &AppState,
+    ctx: &AuthenticatedUser,
+    pirep_id: &str,
+) -> Result<ReviewPirepHandler, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    let pireps =
+        AggregateRepository::<Pirep>::new(state.pirep_repo.clone(), state.services.clock.clone());
+    let pirep = pireps
+        .load_existing(pirep_id)
+        .await
+        .map_err(map_core_error)?
+        .aggregate;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantAdminOf {
+            target_tenant_id: pirep.tenant_id().to_string(),
+        },
+    )?;
+    Ok(ReviewPirepHandler::new(
+        state.pirep_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+        pirep.tenant_id().to_string(),
+    ))
+}
+
+// POST /api/pireps/{pirep_id}/approve - with an optional `comment` to the pilot
+pub async fn handle_approve_pirep_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(pirep_id): Path<String>,
+    Json(payload): Json<ApprovePirepDto>,
+) -> Result<StatusCode, StatusCode> {
+    let handler = reviewer(&state, &ctx, &pirep_id).await?;
+    let command = ApprovePirep {
+        pirep_id,
+        reviewer_id: ctx.user_id,
+        comment: payload.comment,
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// POST /api/pireps/{pirep_id}/reject - a `reason` is required
+pub async fn handle_reject_pirep_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(pirep_id): Path<String>,
+    Json(payload): Json<ReviewReasonDto>,
+) -> Result<StatusCode, StatusCode> {
+    let handler = reviewer(&state, &ctx, &pirep_id).await?;
+    let command = RejectPirep {
+        pirep_id,
+        reviewer_id: ctx.user_id,
+        reason: payload.reason,
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// POST /api/pireps/{pirep_id}/request-changes - the `reason` tells the pilot what to fix
+pub async fn handle_request_pirep_changes_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(pirep_id): Path<String>,
+    Json(payload): Json<ReviewReasonDto>,
+) -> Result<StatusCode, StatusCode> {
+    let handler = reviewer(&state, &ctx, &pirep_id).await?;
+    let command = RequestPirepChanges {
+        pirep_id,
+        reviewer_id: ctx.user_id,
+        reason: payload.reason,
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -1,19 +1,16 @@
 use core_lib::Cache; // Added Cache import
+use core_lib::services::DomainServices;
 use core_lib::{
     CommandHandler, CoreError, EventPublisher, Repository,
-    domain::user::{User, UserCommand, UserEvent},
-};
-use cqrs_es::{Aggregate, DomainEvent};
-use prost::Message;
-use proto::user::{
-    ApiKeyGenerated, ApiKeyRevoked, PasswordChanged, RevokeApiKey, UserLoggedIn, UserRegistered,
+    domain::user::{User, UserCommand},
+    framework::{AggregateCqrs, aggregate_cqrs},
 };
+use proto::user::RevokeApiKey;
 use std::sync::Arc;
 use tracing;
 
 pub struct RevokeApiKeyHandler {
-    user_repository: Arc<dyn Repository>,
-    event_publisher: Arc<dyn EventPublisher>,
+    cqrs: AggregateCqrs<User>,
     cache: Arc<dyn Cache>, // Added cache field
 }
 
@@ -23,10 +20,10 @@ impl RevokeApiKeyHandler {
         user_repository: Arc<dyn Repository>,
         event_publisher: Arc<dyn EventPublisher>,
         cache: Arc<dyn Cache>, // Added cache parameter
+        services: DomainServices,
     ) -> Self {
         Self {
-            user_repository,
-            event_publisher,
+            cqrs: aggregate_cqrs(user_repository, event_publisher, services),
             cache, // Store cache
         }
     }
@@ -35,119 +32,12 @@ impl RevokeApiKeyHandler {
 // Implement CommandHandler for the RevokeApiKey proto message
 impl CommandHandler<RevokeApiKey> for RevokeApiKeyHandler {
     async fn handle(&self, command: RevokeApiKey) -> Result<(), CoreError> {
-        // 1. Load Aggregate
-        let stored_events = self.user_repository.load(&command.user_id).await?;
-        if stored_events.is_empty() {
-            return Err(CoreError::NotFound(format!(
-                "User not found: {}",
-                command.user_id
-            )));
-        }
-        // --- Correct Aggregate Loading ---
-        let mut user = User::default();
-        for stored_event in stored_events {
-            // Deserialize and apply based on type string
-            match stored_event.event_type.as_str() {
-                "UserRegistered" => match UserRegistered::decode(stored_event.payload.as_slice()) {
-                    Ok(e) => user.apply(UserEvent::Registered(e)),
-                    Err(err) => {
-                        return Err(CoreError::Deserialization(format!(
-                            "Failed to decode UserRegistered: {}",
-                            err
-                        )));
-                    }
-                },
-                "PasswordChanged" => {
-                    match PasswordChanged::decode(stored_event.payload.as_slice()) {
-                        Ok(e) => user.apply(UserEvent::PasswordChanged(e)),
-                        Err(err) => {
-                            return Err(CoreError::Deserialization(format!(
-                                "Failed to decode PasswordChanged: {}",
-                                err
-                            )));
-                        }
-                    }
-                }
-                "ApiKeyGenerated" => {
-                    match ApiKeyGenerated::decode(stored_event.payload.as_slice()) {
-                        Ok(e) => user.apply(UserEvent::ApiKeyGenerated(e)),
-                        Err(err) => {
-                            return Err(CoreError::Deserialization(format!(
-                                "Failed to decode ApiKeyGenerated: {}",
-                                err
-                            )));
-                        }
-                    }
-                }
-                "UserLoggedIn" => match UserLoggedIn::decode(stored_event.payload.as_slice()) {
-                    Ok(e) => user.apply(UserEvent::LoggedIn(e)),
-                    Err(err) => {
-                        return Err(CoreError::Deserialization(format!(
-                            "Failed to decode UserLoggedIn: {}",
-                            err
-                        )));
-                    }
-                },
-                "ApiKeyRevoked" => match ApiKeyRevoked::decode(stored_event.payload.as_slice()) {
-                    Ok(e) => user.apply(UserEvent::ApiKeyRevoked(e)),
-                    Err(err) => {
-                        return Err(CoreError::Deserialization(format!(
-                            "Failed to decode ApiKeyRevoked: {}",
-                            err
-                        )));
-                    }
-                },
-                _ => {
-                    tracing::warn!(
-                        "Skipping unknown event type during user load: {}",
-                        stored_event.event_type
-                    );
-                }
-            }
-        }
-        // --- End Aggregate Loading ---
-
-        // 2. Create the aggregate command enum variant
+        // 1. Execute on the aggregate (load, handle, save, publish)
         let aggregate_command = UserCommand::RevokeApiKey(RevokeApiKey {
             user_id: command.user_id.clone(),
             key_id: command.key_id.clone(),
         });
-
-        // 3. Execute command on the loaded aggregate instance
-        // Use '?' directly - relies on From<UserError> for CoreError impl in core-lib
-        let resulting_events: Vec<UserEvent> = user.handle(aggregate_command, &()).await?;
-
-        // --- Serialize Events for Saving ---
-        let events_to_save: Vec<(String, Vec<u8>)> = resulting_events
-            .iter()
-            .map(|event| {
-                let payload = match event {
-                    UserEvent::Registered(e) => e.encode_to_vec(),
-                    UserEvent::PasswordChanged(e) => e.encode_to_vec(),
-                    UserEvent::ApiKeyGenerated(e) => e.encode_to_vec(),
-                    UserEvent::ApiKeyRevoked(e) => e.encode_to_vec(),
-                    UserEvent::LoggedIn(e) => e.encode_to_vec(),
-                };
-                (event.event_type(), payload)
-            })
-            .collect();
-        // --- End Serialization ---
-
-        // 4. Save events using the repository, providing the expected version
-        self.user_repository
-            .save(&command.user_id, user.version(), &events_to_save)
-            .await?;
-
-        // 5. Publish events (using the serialized data)
-        for (event_type, payload) in &events_to_save {
-            let topic = match event_type.as_str() {
-                "ApiKeyRevoked" => format!("user.{}", command.user_id),
-                _ => "user_events".to_string(),
-            };
-            self.event_publisher
-                .publish(&topic, event_type, payload)
-                .await?;
-        }
+        self.cqrs.execute(&command.user_id, aggregate_command).await?;
 
         // --- Cache Invalidation ---
         // After successfully saving/publishing the event, attempt to remove the key from cache.
@@ -0,0 +1,237 @@
+use crate::application::commands::relocate_aircraft::aircraft_location;
+use crate::application::flight_logs::track_key_prefix;
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{
+    Json,
+    extract::{Extension, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::aircraft::{Aircraft, AircraftStatus},
+    domain::pirep::{Pirep, PirepCommand},
+    domain::pirep_rules::SubmissionFacts,
+    domain::simbrief::parse_ofp,
+    domain::tenant::Tenant,
+    framework::{AggregateCqrs, AggregateRepository, aggregate_cqrs, tenant_metadata},
+};
+use proto::blob::BlobRef;
+use proto::pirep::SubmitPirep;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::warn;
+
+pub struct SubmitPirepHandler {
+    cqrs: AggregateCqrs<Pirep>,
+    tenants: AggregateRepository<Tenant>,
+    aircraft: AggregateRepository<Aircraft>,
+}
+
+impl SubmitPirepHandler {
+    pub fn new(
+        pirep_repository: Arc<dyn Repository>,
+        tenant_repository: Arc<dyn Repository>,
+        aircraft_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+    ) -> Self {
+        Self {
+            tenants: AggregateRepository::new(tenant_repository, services.clock.clone()),
+            aircraft: AggregateRepository::new(aircraft_repository, services.clock.clone()),
+            cqrs: aggregate_cqrs(pirep_repository, event_publisher, services),
+        }
+    }
+}
+
+impl CommandHandler<SubmitPirep> for SubmitPirepHandler {
+    async fn handle(&self, mut command: SubmitPirep) -> Result<(), CoreError> {
+        // The submission is checked against the tenant's current rules
+        let tenant = self.tenants.load(&command.tenant_id).await?.aggregate;
+        command.rules = tenant.pirep_rules().cloned();
+
+        let aircraft = self.aircraft.load(&command.aircraft_id).await?.aggregate;
+        check_not_grounded(&aircraft, &command.tenant_id, &tenant)?;
+
+        // The aggregate validates the flight details and rejects a reused ID. Once the
+        // PIREP is approved, the projection worker's flight recorder adds it to the aircraft.
+        let pirep_id = command.pirep_id.clone();
+        let tenant_id = command.tenant_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &pirep_id,
+                PirepCommand::Submit(command),
+                tenant_metadata(Some(&tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+// Aircraft outside the tenant's fleet are left to the aircraft location rule; fleet
+// aircraft cannot be flown, whether submitted or amended to, while grounded for
+// maintenance. That includes aircraft already past an interval of the tenant's current
+// program, which no approved flight has grounded yet.
+pub(crate) fn check_not_grounded(
+    aircraft: &Aircraft,
+    tenant_id: &str,
+    tenant: &Tenant,
+) -> Result<(), CoreError> {
+    if aircraft.tenant_id() != tenant_id {
+        return Ok(());
+    }
+    let due = match tenant.maintenance_program() {
+        Some(program) => aircraft.inspections_due_under(program),
+        None => aircraft.inspections_due().map(String::from).collect(),
+    };
+    if aircraft.status() == AircraftStatus::Grounded || !due.is_empty() {
+        return Err(CoreError::Validation(format!(
+            "Aircraft {} is grounded until maintenance is completed: {}",
+            aircraft.registration(),
+            due.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SubmitPirepDto {
+    aircraft_id: String,
+    departure_icao: String,
+    arrival_icao: String,
+    #[serde(default)]
+    flight_number: String,
+    flight_time_hours: f64,
+    #[serde(default)]
+    remarks: String,
+    #[serde(default)]
+    landing_rate_fpm: f64,
+    // Key returned by POST /api/flight-logs
+    #[serde(default)]
+    track_key: Option<String>,
+    // SimBrief OFP XML of the flight plan, checked by the tenant's flight plan rule
+    #[serde(default)]
+    simbrief_ofp: Option<String>,
+}
+
+// --- Axum Route Handler ---
+
+// POST /api/pireps - the authenticated user files a PIREP in their own tenant
+pub async fn handle_submit_pirep_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Json(payload): Json<SubmitPirepDto>,
+) -> Result<Response, StatusCode> {
+    // PIREPs belong to a tenant; platform admins without one cannot fly
+    let tenant_id = ctx.tenant_id.clone().ok_or(StatusCode::FORBIDDEN)?;
+    let pirep_id = state.services.ids.next_id();
+
+    let plan = match payload.simbrief_ofp.as_deref().map(|ofp| parse_ofp(ofp.as_bytes())) {
+        Some(Ok(plan)) => Some(plan),
+        Some(Err(e)) => {
+            let body = Json(serde_json::json!({"error": e.to_string()}));
+            return Ok((StatusCode::BAD_REQUEST, body).into_response());
+        }
+        None => None,
+    };
+
+    // Only the pilot's own imported tracks can be attached
+    let track = match &payload.track_key {
+        Some(key) if key.starts_with(&track_key_prefix(&tenant_id, &ctx.user_id)) => {
+            let metadata = state.blob_store.head(key).await.map_err(map_core_error)?;
+            Some(BlobRef::from(metadata.ok_or(StatusCode::BAD_REQUEST)?))
+        }
+        Some(_) => return Err(StatusCode::FORBIDDEN),
+        None => None,
+    };
+
+    // The aggregate checks the airports and works out the route distance itself
+    let facts = submission_facts(&state, &ctx.user_id, &tenant_id, &payload.aircraft_id).await;
+
+    let command = SubmitPirep {
+        pirep_id: pirep_id.clone(),
+        tenant_id,
+        user_id: ctx.user_id,
+        aircraft_id: payload.aircraft_id,
+        departure_icao: payload.departure_icao.to_uppercase(),
+        arrival_icao: payload.arrival_icao.to_uppercase(),
+        flight_number: payload.flight_number,
+        flight_time_hours: payload.flight_time_hours,
+        remarks: payload.remarks,
+        landing_rate_fpm: payload.landing_rate_fpm,
+        rules: None,
+        facts: Some(facts),
+        track,
+        plan,
+    };
+
+    let handler = SubmitPirepHandler::new(
+        state.pirep_repo.clone(),
+        state.tenant_repo.clone(),
+        state.aircraft_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::json!({"pirep_id": pirep_id})),
+    )
+        .into_response())
+}
+
+// What the rules check a flight of the pilot's against, for submitting and amending
+pub(crate) async fn submission_facts(
+    state: &AppState,
+    user_id: &str,
+    tenant_id: &str,
+    aircraft_id: &str,
+) -> SubmissionFacts {
+    match &state.pg_pool {
+        Some(pool) => SubmissionFacts {
+            pilot_location_icao: pilot_location(pool, user_id).await,
+            aircraft_location_icao: fleet_location(pool, tenant_id, aircraft_id).await,
+        },
+        None => SubmissionFacts::default(),
+    }
+}
+
+// Where the pilot is: the arrival of their last approved PIREP (empty before the first)
+async fn pilot_location(pool: &PgPool, user_id: &str) -> String {
+    let location = sqlx::query_scalar::<_, String>(
+        r#"
+        SELECT arrival_icao
+        FROM pireps
+        WHERE user_id = $1 AND status = 'APPROVED'
+        ORDER BY submitted_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await;
+    match location {
+        Ok(location) => location.unwrap_or_default(),
+        Err(e) => {
+            // The departure rule then treats the flight as the pilot's first
+            warn!("DB error looking up pilot location: {}", e);
+            String::new()
+        }
+    }
+}
+
+// Where the aircraft is parked (empty if it is not in the tenant's fleet)
+async fn fleet_location(pool: &PgPool, tenant_id: &str, aircraft_id: &str) -> String {
+    match aircraft_location(pool, tenant_id, aircraft_id).await {
+        Ok(location) => location.unwrap_or_default(),
+        Err(e) => {
+            // The aircraft location rule then flags the PIREP for review
+            warn!("DB error looking up aircraft location: {}", e);
+            String::new()
+        }
+    }
+}
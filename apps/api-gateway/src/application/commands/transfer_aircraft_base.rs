@@ -0,0 +1,84 @@
+use crate::application::authz::{authorize, parse_role, Requirement};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{Json, extract::{Extension, Path, State}, http::StatusCode};
+use core_lib::services::DomainServices;
+use core_lib::{
+    CommandHandler, CoreError, EventPublisher, Repository,
+    domain::aircraft::{Aircraft, AircraftCommand},
+    framework::{AggregateCqrs, aggregate_cqrs, tenant_metadata},
+};
+use proto::aircraft::TransferAircraftBase;
+use serde::Deserialize;
+use std::sync::Arc;
+
+pub struct TransferAircraftBaseHandler {
+    cqrs: AggregateCqrs<Aircraft>,
+}
+
+impl TransferAircraftBaseHandler {
+    pub fn new(
+        aircraft_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+    ) -> Self {
+        Self {
+            cqrs: aggregate_cqrs(aircraft_repository, event_publisher, services),
+        }
+    }
+}
+
+impl CommandHandler<TransferAircraftBase> for TransferAircraftBaseHandler {
+    async fn handle(&self, command: TransferAircraftBase) -> Result<(), CoreError> {
+        // The aggregate checks that the new base is a known airport
+        let aircraft_id = command.aircraft_id.clone();
+        let tenant_id = command.tenant_id.clone();
+        self.cqrs
+            .execute_with_metadata(
+                &aircraft_id,
+                AircraftCommand::TransferBase(command),
+                tenant_metadata(Some(&tenant_id)),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct TransferAircraftBaseDto {
+    home_base_icao: String,
+}
+
+// --- Axum Route Handler ---
+
+// PUT /api/tenants/{tenant_id}/fleet/{aircraft_id}/base
+pub async fn handle_transfer_aircraft_base_request(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path((tenant_id, aircraft_id)): Path<(String, String)>,
+    Json(payload): Json<TransferAircraftBaseDto>,
+) -> Result<StatusCode, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantAdminOf {
+            target_tenant_id: tenant_id.clone(),
+        },
+    )?;
+
+    let handler = TransferAircraftBaseHandler::new(
+        state.aircraft_repo.clone(),
+        state.event_bus.clone(),
+        state.services.clone(),
+    );
+    let command = TransferAircraftBase {
+        aircraft_id,
+        tenant_id,
+        home_base_icao: payload.home_base_icao,
+    };
+    handler.handle(command).await.map_err(map_core_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,135 @@
+use crate::application::authz::{authorize, parse_role, Requirement};
+use crate::application::middleware::AuthenticatedUser;
+use crate::{AppState, map_core_error};
+use axum::{
+    body::Bytes,
+    extract::{Extension, Path, Query, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Response},
+    Json,
+};
+use core_lib::domain::airport::AirportDatabase;
+use core_lib::domain::flight_log::{TrackFormat, parse_track, summarize};
+use core_lib::domain::pirep::Pirep;
+use core_lib::framework::{AggregateRepository, TenantScoped};
+use serde::Deserialize;
+use tracing::warn;
+
+// A track starting or ending further than this from any known airport leaves it blank
+const AIRPORT_MATCH_RADIUS_NM: f64 = 3.0;
+
+#[derive(Debug, Deserialize)]
+pub struct ImportFlightLogParams {
+    /// csv, json, gpx or igc; taken from the Content-Type header when absent
+    pub format: Option<String>,
+}
+
+// Tracks are stored per pilot, so a submission can only reference the pilot's own
+pub fn track_key_prefix(tenant_id: &str, user_id: &str) -> String {
+    format!("tenants/{}/tracks/{}/", tenant_id, user_id)
+}
+
+fn bad_request(message: String) -> Response {
+    (StatusCode::BAD_REQUEST, Json(serde_json::json!({"error": message}))).into_response()
+}
+
+// POST /api/flight-logs - stores the caller's flight track and returns the PIREP fields
+// derived from it. The returned `track.key` is passed as `track_key` when submitting.
+pub async fn handle_import_flight_log(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Query(params): Query<ImportFlightLogParams>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Response, StatusCode> {
+    // Tracks back PIREPs, which belong to a tenant
+    let tenant_id = ctx.tenant_id.clone().ok_or(StatusCode::FORBIDDEN)?;
+    let format = match &params.format {
+        Some(name) => TrackFormat::from_name(name),
+        None => headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(TrackFormat::from_content_type),
+    }
+    .ok_or(StatusCode::UNSUPPORTED_MEDIA_TYPE)?;
+
+    let points = match parse_track(format, &body) {
+        Ok(points) => points,
+        Err(e) => return Ok(bad_request(e.to_string())),
+    };
+    let airports = AirportDatabase::global();
+    let nearest = |latitude, longitude| {
+        airports
+            .nearest(latitude, longitude, AIRPORT_MATCH_RADIUS_NM)
+            .map(|airport| airport.icao.clone())
+    };
+    let summary = match summarize(&points, nearest) {
+        Ok(summary) => summary,
+        Err(e) => return Ok(bad_request(e.to_string())),
+    };
+
+    let key = format!(
+        "{}{}.{}",
+        track_key_prefix(&tenant_id, &ctx.user_id),
+        state.services.ids.next_id(),
+        format.extension()
+    );
+    let track = state
+        .blob_store
+        .put(&key, format.content_type(), &body)
+        .await
+        .map_err(map_core_error)?;
+
+    let response = serde_json::json!({
+        "track": {
+            "key": track.key,
+            "content_type": track.content_type,
+            "size": track.size,
+            "sha256": track.sha256
+        },
+        "points": points.len(),
+        "summary": summary
+    });
+    Ok((StatusCode::CREATED, Json(response)).into_response())
+}
+
+// GET /api/pireps/{pirep_id}/track - the original track file, for the pilot and tenant staff
+pub async fn handle_get_pirep_track(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(pirep_id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+
+    let pireps = AggregateRepository::<Pirep>::new(
+        state.pirep_repo.clone(),
+        state.services.clock.clone(),
+    );
+    let pirep = pireps
+        .load_existing(&pirep_id)
+        .await
+        .map_err(map_core_error)?
+        .aggregate;
+
+    // Same visibility as the PIREP itself
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::SelfOrTenantAdmin {
+            target_user_id: pirep.user_id().to_string(),
+            target_tenant_id: pirep.owning_tenant().map(str::to_string),
+        },
+    )?;
+
+    let track = pirep.track().ok_or(StatusCode::NOT_FOUND)?;
+    let blob = state.blob_store.get(&track.key).await.map_err(|e| {
+        warn!("Track {} of PIREP {} unavailable: {}", track.key, pirep_id, e);
+        map_core_error(e)
+    })?;
+
+    Ok((
+        [(header::CONTENT_TYPE, blob.metadata.content_type)],
+        blob.data,
+    ))
+}
@@ -7,11 +7,9 @@ use axum::{
 };
 // Removed unused Cache import
 use serde::{Deserialize, Serialize};
-use prost::Message;
 use crate::AppState as GatewayAppState;
-use core_lib::domain::user::{User, UserEvent};
-use proto::user::{ApiKeyGenerated, ApiKeyRevoked, PasswordChanged, UserLoggedIn, UserRegistered};
-use cqrs_es::Aggregate;
+use core_lib::domain::user::User;
+use core_lib::framework::AggregateRepository;
 // Removed unused Arc import
 use tracing::{info, warn}; // Added info
 
@@ -66,40 +64,13 @@ pub async fn api_key_auth(
                             match serde_json::from_slice::<LegacyAuthenticatedUser>(&cached_data) {
                                 Ok(legacy) => {
                                     // Rebuild user aggregate to get role (and tenant, confirm)
-                                    let load_res = app_state.user_repo.load(&legacy.user_id).await;
-                                    match load_res {
-                                        Ok(stored_events) => {
-                                            let mut user = User::default();
-                                            for stored_event in stored_events {
-                                                match stored_event.event_type.as_str() {
-                                                    "UserRegistered" => {
-                                                        if let Ok(e) = UserRegistered::decode(stored_event.payload.as_slice()) {
-                                                            user.apply(UserEvent::Registered(e))
-                                                        }
-                                                    }
-                                                    "PasswordChanged" => {
-                                                        if let Ok(e) = PasswordChanged::decode(stored_event.payload.as_slice()) {
-                                                            user.apply(UserEvent::PasswordChanged(e))
-                                                        }
-                                                    }
-                                                    "ApiKeyGenerated" => {
-                                                        if let Ok(e) = ApiKeyGenerated::decode(stored_event.payload.as_slice()) {
-                                                            user.apply(UserEvent::ApiKeyGenerated(e))
-                                                        }
-                                                    }
-                                                    "UserLoggedIn" => {
-                                                        if let Ok(e) = UserLoggedIn::decode(stored_event.payload.as_slice()) {
-                                                            user.apply(UserEvent::LoggedIn(e))
-                                                        }
-                                                    }
-                                                    "ApiKeyRevoked" => {
-                                                        if let Ok(e) = ApiKeyRevoked::decode(stored_event.payload.as_slice()) {
-                                                            user.apply(UserEvent::ApiKeyRevoked(e))
-                                                        }
-                                                    }
-                                                    _ => {}
-                                                }
-                                            }
+                                    let users = AggregateRepository::<User>::new(
+                                        app_state.user_repo.clone(),
+                                        app_state.services.clock.clone(),
+                                    );
+                                    match users.load(&legacy.user_id).await {
+                                        Ok(loaded) => {
+                                            let user = loaded.aggregate;
                                             let role_str = match user.role() {
                                                 proto::user::Role::PlatformAdmin => "PlatformAdmin",
                                                 proto::user::Role::TenantAdmin => "TenantAdmin",
@@ -1,7 +1,10 @@
 use core_lib::CoreError;
 
 // Declare sub-modules within the application layer
+pub mod aircraft_types; // Aircraft type catalog
+pub mod airports; // Embedded airport database
 pub mod commands;
+pub mod flight_logs; // Flight track import
 pub mod middleware; // Added
 pub mod authz; // RBAC helpers
 pub mod query; // Added queries module (named query)
@@ -8,9 +8,13 @@ use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use tracing::{debug, warn};
 
-use crate::AppState;
+use crate::{AppState, map_core_error};
 use super::middleware::AuthenticatedUser;
 use super::authz::{parse_role, AuthRole, authorize, Requirement};
+use core_lib::domain::aircraft::AircraftStatus;
+use core_lib::domain::pirep::PirepStatus;
+use core_lib::domain::tenant::Tenant;
+use core_lib::framework::AggregateRepository;
 
 const TTL_LIST_SECONDS: u64 = 45;
 const TTL_SELF_SECONDS: u64 = 60;
@@ -52,6 +56,104 @@ struct ApiKeyRow {
     last_used_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+#[derive(sqlx::FromRow, Serialize)]
+pub struct PirepRow {
+    pirep_id: String,
+    tenant_id: String,
+    user_id: String,
+    aircraft_id: String,
+    departure_icao: String,
+    arrival_icao: String,
+    flight_number: String,
+    flight_time_hours: f64,
+    remarks: String,
+    landing_rate_fpm: Option<f64>,
+    route_distance_nm: Option<f64>,
+    rule_results: Option<serde_json::Value>,
+    flagged: bool,
+    track_key: Option<String>,
+    flight_plan: Option<serde_json::Value>,
+    status: String,
+    status_note: Option<String>,
+    reviewer_id: Option<String>,
+    submitted_at: chrono::DateTime<chrono::Utc>,
+    reviewed_at: Option<chrono::DateTime<chrono::Utc>>,
+    created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+// Filters for GET /api/pireps; `from` is inclusive and `to` exclusive on submitted_at
+#[derive(Debug, Deserialize)]
+pub struct PirepFilter {
+    pub tenant_id: Option<String>,
+    pub user_id: Option<String>,
+    pub status: Option<String>,
+    pub flagged: Option<bool>,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(sqlx::FromRow, Serialize)]
+pub struct AircraftRow {
+    aircraft_id: String,
+    tenant_id: String,
+    registration: String,
+    icao_type: String,
+    name: String,
+    home_base_icao: String,
+    status: String,
+    status_note: Option<String>,
+    // Where the aircraft is: its home base, the arrival of its last approved PIREP or
+    // where an admin relocated it
+    location_icao: String,
+    location_since: chrono::DateTime<chrono::Utc>,
+    location_pirep_id: Option<String>,
+    // Totals from approved PIREPs
+    airframe_hours: f64,
+    cycles: i32,
+    added_at: chrono::DateTime<chrono::Utc>,
+    retired_at: Option<chrono::DateTime<chrono::Utc>>,
+    created_at: chrono::DateTime<chrono::Utc>,
+    updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+// Filters for GET /api/tenants/{tenant_id}/fleet; retired aircraft are only listed
+// when asked for with `status=retired`
+#[derive(Debug, Deserialize)]
+pub struct FleetFilter {
+    pub status: Option<String>,
+    pub icao_type: Option<String>,
+    pub home_base_icao: Option<String>,
+    pub location_icao: Option<String>,
+    pub limit: Option<u32>,
+    pub offset: Option<u32>,
+}
+
+#[derive(sqlx::FromRow, Serialize)]
+pub struct AircraftRelocationRow {
+    aircraft_id: String,
+    from_icao: String,
+    to_icao: String,
+    reason: String,
+    relocated_by: String,
+    relocated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(sqlx::FromRow, Serialize)]
+pub struct AircraftMaintenanceRow {
+    aircraft_id: String,
+    kind: String, // GROUNDED or COMPLETED
+    inspections: serde_json::Value,
+    notes: String,
+    completed_by: Option<String>,
+    airframe_hours: f64,
+    cycles: i32,
+    released: bool,
+    occurred_at: chrono::DateTime<chrono::Utc>,
+}
+
 fn normalize_pagination(p: &Pagination) -> (u32, u32) {
     let mut limit = p.limit.unwrap_or(DEFAULT_LIMIT);
     if limit == 0 {
@@ -341,3 +443,422 @@ pub async fn handle_list_users(
 
     Ok((StatusCode::OK, Json(response)))
 }
+
+// Read model status for a `status` query value ("approved", "CHANGES_REQUESTED", ...)
+fn parse_pirep_status(s: &str) -> Option<String> {
+    let name = format!("PIREP_STATUS_{}", s.trim().to_uppercase());
+    match PirepStatus::from_str_name(&name) {
+        Some(PirepStatus::Unspecified) | None => None,
+        Some(_) => Some(s.trim().to_uppercase()),
+    }
+}
+
+// GET /api/pireps
+// Not cached: PIREPs change status while under review.
+pub async fn handle_list_pireps(
+    State(app_state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Query(f): Query<PirepFilter>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    let pool = ensure_pool(&app_state).await?;
+
+    // Tenant members see their own tenant; only platform admins may list across tenants
+    let tenant_id = match (&f.tenant_id, &ctx.tenant_id) {
+        (Some(tid), _) => Some(tid.clone()),
+        (None, Some(tid)) => Some(tid.clone()),
+        (None, None) => None,
+    };
+    match &tenant_id {
+        Some(tid) => authorize(
+            &ctx.user_id,
+            &ctx.tenant_id,
+            role,
+            Requirement::TenantMember { target_tenant_id: tid.clone() },
+        )?,
+        None => authorize(&ctx.user_id, &ctx.tenant_id, role, Requirement::PlatformAdminOnly)?,
+    }
+
+    // Pilots only see their own PIREPs
+    let user_id = match (role, &f.user_id) {
+        (AuthRole::Pilot, None) => Some(ctx.user_id.clone()),
+        (_, requested) => requested.clone(),
+    };
+    if let Some(uid) = &user_id {
+        authorize(
+            &ctx.user_id,
+            &ctx.tenant_id,
+            role,
+            Requirement::SelfOrTenantAdmin {
+                target_user_id: uid.clone(),
+                target_tenant_id: tenant_id.clone(),
+            },
+        )?;
+    }
+
+    let status = match &f.status {
+        Some(s) => Some(parse_pirep_status(s).ok_or(StatusCode::BAD_REQUEST)?),
+        None => None,
+    };
+
+    let (limit, offset) = normalize_pagination(&Pagination { limit: f.limit, offset: f.offset });
+
+    let rows: Vec<PirepRow> = sqlx::query_as::<_, PirepRow>(
+        r#"
+        SELECT pirep_id, tenant_id, user_id, aircraft_id, departure_icao, arrival_icao,
+               flight_number, flight_time_hours, remarks, landing_rate_fpm, route_distance_nm,
+               rule_results, flagged, track_key, flight_plan, status, status_note, reviewer_id,
+               submitted_at, reviewed_at, created_at, updated_at
+        FROM pireps
+        WHERE ($1::VARCHAR IS NULL OR tenant_id = $1)
+          AND ($2::VARCHAR IS NULL OR user_id = $2)
+          AND ($3::VARCHAR IS NULL OR status = $3)
+          AND ($4::BOOLEAN IS NULL OR flagged = $4)
+          AND ($5::TIMESTAMPTZ IS NULL OR submitted_at >= $5)
+          AND ($6::TIMESTAMPTZ IS NULL OR submitted_at < $6)
+        ORDER BY submitted_at DESC
+        LIMIT $7 OFFSET $8
+        "#,
+    )
+    .bind(&tenant_id)
+    .bind(&user_id)
+    .bind(&status)
+    .bind(f.flagged)
+    .bind(f.from)
+    .bind(f.to)
+    .bind(limit as i64)
+    .bind(offset as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        warn!("DB error listing pireps: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let response = serde_json::json!({
+        "data": rows,
+        "pagination": {
+            "limit": limit,
+            "offset": offset,
+            "returned": rows.len()
+        }
+    });
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+// GET /api/pireps/{pirep_id}
+pub async fn handle_get_pirep(
+    State(app_state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(pirep_id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    let pool = ensure_pool(&app_state).await?;
+
+    let row = sqlx::query_as::<_, PirepRow>(
+        r#"
+        SELECT pirep_id, tenant_id, user_id, aircraft_id, departure_icao, arrival_icao,
+               flight_number, flight_time_hours, remarks, landing_rate_fpm, route_distance_nm,
+               rule_results, flagged, track_key, flight_plan, status, status_note, reviewer_id,
+               submitted_at, reviewed_at, created_at, updated_at
+        FROM pireps
+        WHERE pirep_id = $1
+        "#,
+    )
+    .bind(&pirep_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        warn!("DB error getting pirep: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    // Same visibility as the list: the pilot, or staff of the PIREP's tenant
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::SelfOrTenantAdmin {
+            target_user_id: row.user_id.clone(),
+            target_tenant_id: Some(row.tenant_id.clone()),
+        },
+    )?;
+
+    Ok((StatusCode::OK, Json(row)))
+}
+
+// GET /api/tenants/{tenant_id}/pirep-rules
+// Read from the tenant aggregate, so a change is visible as soon as it is saved.
+pub async fn handle_get_pirep_rules(
+    State(app_state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(tenant_id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    // Pilots may see the rules their PIREPs are checked against
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantMember { target_tenant_id: tenant_id.clone() },
+    )?;
+
+    let tenants = AggregateRepository::<Tenant>::new(
+        app_state.tenant_repo.clone(),
+        app_state.services.clock.clone(),
+    );
+    let tenant = tenants
+        .load_existing(&tenant_id)
+        .await
+        .map_err(map_core_error)?
+        .aggregate;
+
+    Ok((StatusCode::OK, Json(serde_json::json!({ "rules": tenant.pirep_rules() }))))
+}
+
+// GET /api/tenants/{tenant_id}/maintenance-program
+pub async fn handle_get_maintenance_program(
+    State(app_state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(tenant_id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    // Pilots may see when the aircraft they fly are due for inspection
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantMember { target_tenant_id: tenant_id.clone() },
+    )?;
+
+    let tenants = AggregateRepository::<Tenant>::new(
+        app_state.tenant_repo.clone(),
+        app_state.services.clock.clone(),
+    );
+    let tenant = tenants
+        .load_existing(&tenant_id)
+        .await
+        .map_err(map_core_error)?
+        .aggregate;
+
+    Ok((StatusCode::OK, Json(serde_json::json!({ "program": tenant.maintenance_program() }))))
+}
+
+// Read model status for a `status` query value ("maintenance", "RETIRED", ...)
+fn parse_aircraft_status(s: &str) -> Option<String> {
+    let name = format!("AIRCRAFT_STATUS_{}", s.trim().to_uppercase());
+    match AircraftStatus::from_str_name(&name) {
+        Some(AircraftStatus::Unspecified) | None => None,
+        Some(_) => Some(s.trim().to_uppercase()),
+    }
+}
+
+// GET /api/tenants/{tenant_id}/fleet
+// Not cached: staff change aircraft status throughout the day.
+pub async fn handle_list_fleet(
+    State(app_state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path(tenant_id): Path<String>,
+    Query(f): Query<FleetFilter>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    // Pilots see the fleet they fly; changing it is left to tenant admins
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantMember { target_tenant_id: tenant_id.clone() },
+    )?;
+    let pool = ensure_pool(&app_state).await?;
+
+    let status = match &f.status {
+        Some(s) => Some(parse_aircraft_status(s).ok_or(StatusCode::BAD_REQUEST)?),
+        None => None,
+    };
+    let icao_type = f.icao_type.as_ref().map(|t| t.trim().to_uppercase());
+    let home_base_icao = f.home_base_icao.as_ref().map(|b| b.trim().to_uppercase());
+    let location_icao = f.location_icao.as_ref().map(|l| l.trim().to_uppercase());
+
+    let (limit, offset) = normalize_pagination(&Pagination { limit: f.limit, offset: f.offset });
+
+    let rows: Vec<AircraftRow> = sqlx::query_as::<_, AircraftRow>(
+        r#"
+        SELECT aircraft_id, tenant_id, registration, icao_type, name, home_base_icao, status,
+               status_note, location_icao, location_since, location_pirep_id, airframe_hours,
+               cycles, added_at, retired_at, created_at, updated_at
+        FROM aircraft
+        WHERE tenant_id = $1
+          AND (($2::VARCHAR IS NULL AND status <> 'RETIRED') OR status = $2)
+          AND ($3::VARCHAR IS NULL OR icao_type = $3)
+          AND ($4::VARCHAR IS NULL OR home_base_icao = $4)
+          AND ($5::VARCHAR IS NULL OR location_icao = $5)
+        ORDER BY registration
+        LIMIT $6 OFFSET $7
+        "#,
+    )
+    .bind(&tenant_id)
+    .bind(&status)
+    .bind(&icao_type)
+    .bind(&home_base_icao)
+    .bind(&location_icao)
+    .bind(limit as i64)
+    .bind(offset as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        warn!("DB error listing fleet: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let response = serde_json::json!({
+        "data": rows,
+        "pagination": {
+            "limit": limit,
+            "offset": offset,
+            "returned": rows.len()
+        }
+    });
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+// GET /api/tenants/{tenant_id}/fleet/{aircraft_id}
+pub async fn handle_get_aircraft(
+    State(app_state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path((tenant_id, aircraft_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantMember { target_tenant_id: tenant_id.clone() },
+    )?;
+    let pool = ensure_pool(&app_state).await?;
+
+    // Aircraft of other tenants are not found
+    let row = sqlx::query_as::<_, AircraftRow>(
+        r#"
+        SELECT aircraft_id, tenant_id, registration, icao_type, name, home_base_icao, status,
+               status_note, location_icao, location_since, location_pirep_id, airframe_hours,
+               cycles, added_at, retired_at, created_at, updated_at
+        FROM aircraft
+        WHERE aircraft_id = $1 AND tenant_id = $2
+        "#,
+    )
+    .bind(&aircraft_id)
+    .bind(&tenant_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        warn!("DB error getting aircraft: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?
+    .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok((StatusCode::OK, Json(row)))
+}
+
+// GET /api/tenants/{tenant_id}/fleet/{aircraft_id}/relocations
+// Who moved the aircraft outside the PIREP flow, and why; newest first.
+pub async fn handle_list_aircraft_relocations(
+    State(app_state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path((tenant_id, aircraft_id)): Path<(String, String)>,
+    Query(p): Query<Pagination>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantMember { target_tenant_id: tenant_id.clone() },
+    )?;
+    let pool = ensure_pool(&app_state).await?;
+    let (limit, offset) = normalize_pagination(&p);
+
+    let rows: Vec<AircraftRelocationRow> = sqlx::query_as::<_, AircraftRelocationRow>(
+        r#"
+        SELECT aircraft_id, from_icao, to_icao, reason, relocated_by, relocated_at
+        FROM aircraft_relocations
+        WHERE aircraft_id = $1 AND tenant_id = $2
+        ORDER BY relocated_at DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(&aircraft_id)
+    .bind(&tenant_id)
+    .bind(limit as i64)
+    .bind(offset as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        warn!("DB error listing aircraft relocations: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let response = serde_json::json!({
+        "data": rows,
+        "pagination": {
+            "limit": limit,
+            "offset": offset,
+            "returned": rows.len()
+        }
+    });
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+// GET /api/tenants/{tenant_id}/fleet/{aircraft_id}/maintenance
+// Groundings and completed maintenance of the aircraft; newest first.
+pub async fn handle_list_aircraft_maintenance(
+    State(app_state): State<AppState>,
+    Extension(ctx): Extension<AuthenticatedUser>,
+    Path((tenant_id, aircraft_id)): Path<(String, String)>,
+    Query(p): Query<Pagination>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let role = parse_role(&ctx.role).ok_or(StatusCode::FORBIDDEN)?;
+    authorize(
+        &ctx.user_id,
+        &ctx.tenant_id,
+        role,
+        Requirement::TenantMember { target_tenant_id: tenant_id.clone() },
+    )?;
+    let pool = ensure_pool(&app_state).await?;
+    let (limit, offset) = normalize_pagination(&p);
+
+    let rows: Vec<AircraftMaintenanceRow> = sqlx::query_as::<_, AircraftMaintenanceRow>(
+        r#"
+        SELECT aircraft_id, kind, inspections, notes, completed_by, airframe_hours, cycles,
+               released, occurred_at
+        FROM aircraft_maintenance
+        WHERE aircraft_id = $1 AND tenant_id = $2
+        ORDER BY occurred_at DESC, kind DESC
+        LIMIT $3 OFFSET $4
+        "#,
+    )
+    .bind(&aircraft_id)
+    .bind(&tenant_id)
+    .bind(limit as i64)
+    .bind(offset as i64)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        warn!("DB error listing aircraft maintenance: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let response = serde_json::json!({
+        "data": rows,
+        "pagination": {
+            "limit": limit,
+            "offset": offset,
+            "returned": rows.len()
+        }
+    });
+
+    Ok((StatusCode::OK, Json(response)))
+}
@@ -140,6 +140,8 @@ async fn websocket_connection(
         subs.insert(format!("user:{}:apikeys", ctx.user_id));
         if let Some(tid) = &ctx.tenant_id {
             subs.insert(format!("tenant:{}:updates", tid));
+            subs.insert(format!("tenant:{}:pireps", tid));
+            subs.insert(format!("tenant:{}:fleet", tid));
         }
     }
 
@@ -358,7 +360,8 @@ async fn send_error(
 
 // Channel validation logic
 pub(crate) fn validate_channel(channel: &str, ctx: &AuthenticatedUser) -> bool {
-    // Patterns: user:{id}:updates | user:{id}:apikeys | tenant:{tid}:updates
+    // Patterns: user:{id}:updates | user:{id}:apikeys | tenant:{tid}:updates | tenant:{tid}:pireps
+    //           | tenant:{tid}:fleet
     let parts: Vec<&str> = channel.split(':').collect();
     if parts.len() != 3 {
         return false;
@@ -393,6 +396,10 @@ mod tests {
         let c = ctx("u1", Some("t1"));
         assert!(validate_channel("tenant:t1:updates", &c));
         assert!(!validate_channel("tenant:t2:updates", &c));
+        assert!(validate_channel("tenant:t1:pireps", &c));
+        assert!(!validate_channel("tenant:t2:pireps", &c));
+        assert!(validate_channel("tenant:t1:fleet", &c));
+        assert!(!validate_channel("tenant:t2:fleet", &c));
     }
 
     #[allow(dead_code)]
@@ -431,7 +438,7 @@ mod tests {
             // Only own user id allowed
             parts[1] == ctx.user_id
         }
-        ("tenant", "updates") => {
+        ("tenant", "updates") | ("tenant", "pireps") | ("tenant", "fleet") => {
             if let Some(tid) = &ctx.tenant_id {
                 parts[1] == tid
             } else {
@@ -1,18 +1,21 @@
 use axum::{
     Router,
     body::Body,
-    extract::{Extension, Json, Path, State},
+    extract::{DefaultBodyLimit, Extension, Json, Path, State},
     middleware::{self},
     response::{IntoResponse, Response},
-    routing::{delete, get, post},
+    routing::{delete, get, post, put},
 };
 use tower_http::cors::{CorsLayer, Any};
 use core_lib::{
+    BlobStore,
+    DEFAULT_MAX_BLOB_SIZE,
     Cache,
     CommandHandler, // Keep CommandHandler if used by other handlers moved here
     CoreError,
     EventPublisher,
     Repository,
+    services::DomainServices,
     // Import specific adapters if needed for AppState construction in tests,
     // but prefer keeping concrete types out of lib.rs if possible.
 };
@@ -27,22 +30,45 @@ use sqlx::PgPool;
 pub mod application; // Make application module public
 use application::ws::ws_handler;
 use application::{
+    aircraft_types::{handle_get_aircraft_type, handle_search_aircraft_types},
+    airports::{handle_get_airport, handle_search_airports},
     commands::{
+        add_aircraft::handle_add_aircraft_request,
+        amend_pirep::handle_amend_pirep_request,
+        attach_flight_plan::handle_attach_flight_plan_request,
+        cancel_pirep::handle_cancel_pirep_request,
+        change_aircraft_status::handle_change_aircraft_status_request,
         change_password::ChangePasswordHandler,
+        complete_maintenance::handle_complete_maintenance_request,
+        configure_maintenance_program::handle_configure_maintenance_program_request,
+        configure_pirep_rules::handle_configure_pirep_rules_request,
         create_tenant::handle_create_tenant_request, // Keep if needed by create_app
+        define_aircraft_type::handle_define_aircraft_type_request,
         generate_api_key::{GenerateApiKeyHandler, GenerateApiKeyInput},
         handle_login_request,
         register_user::handle_register_user_request, // Keep if needed by create_app
+        relocate_aircraft::handle_relocate_aircraft_request,
+        remove_aircraft_type::handle_remove_aircraft_type_request,
+        retire_aircraft::handle_retire_aircraft_request,
+        review_pirep::{
+            handle_approve_pirep_request, handle_reject_pirep_request,
+            handle_request_pirep_changes_request,
+        },
         revoke_api_key::RevokeApiKeyHandler,
+        submit_pirep::handle_submit_pirep_request,
+        transfer_aircraft_base::handle_transfer_aircraft_base_request,
     },
+    flight_logs::{handle_get_pirep_track, handle_import_flight_log},
     middleware::{AuthenticatedUser, api_key_auth},
     authz::{authorize, parse_role, Requirement},
-    query::{handle_list_tenants, handle_list_users, handle_list_user_api_keys, UserRow},
+    query::{
+        handle_get_aircraft, handle_get_maintenance_program, handle_get_pirep, handle_list_aircraft_maintenance, handle_list_aircraft_relocations, handle_list_fleet, handle_get_pirep_rules, handle_list_pireps, handle_list_tenants,
+        handle_list_users, handle_list_user_api_keys, UserRow,
+    },
 };
-use cqrs_es::Aggregate;
-use prost::Message;
-use core_lib::domain::user::{User, UserEvent};
-use proto::user::{ApiKeyGenerated, PasswordChanged, UserLoggedIn, UserRegistered, ApiKeyRevoked, RevokeApiKey};
+use core_lib::domain::user::User;
+use core_lib::framework::AggregateRepository;
+use proto::user::RevokeApiKey;
 
 // --- Public Structs ---
 
@@ -70,10 +96,14 @@ pub struct GenerateApiKeyResponse {
 pub struct AppState {
     pub user_repo: Arc<dyn Repository>,
     pub tenant_repo: Arc<dyn Repository>,
+    pub pirep_repo: Arc<dyn Repository>,
+    pub aircraft_repo: Arc<dyn Repository>,
     pub event_bus: Arc<dyn EventPublisher>,
     pub cache: Arc<dyn Cache>,
+    pub blob_store: Arc<dyn BlobStore>, // Imported flight tracks and other binary assets
     pub pg_pool: Option<PgPool>, // Added optional PgPool for query endpoints
     pub redis_client: Option<redis::Client>, // Redis client for WS pubsub
+    pub services: DomainServices, // Clock and ID generation handed to aggregates
 }
 
 // --- Public Functions ---
@@ -117,6 +147,74 @@ pub fn create_app(app_state: AppState) -> Router {
                 api_key_auth,
             )),
         )
+        .route(
+            "/tenants/{tenant_id}/pirep-rules",
+            put(handle_configure_pirep_rules_request)
+                .get(handle_get_pirep_rules)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), api_key_auth)),
+        )
+        .route(
+            "/tenants/{tenant_id}/maintenance-program",
+            put(handle_configure_maintenance_program_request)
+                .get(handle_get_maintenance_program)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), api_key_auth)),
+        )
+        .route(
+            "/tenants/{tenant_id}/aircraft-types/{icao_type}",
+            put(handle_define_aircraft_type_request)
+                .delete(handle_remove_aircraft_type_request)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), api_key_auth)),
+        )
+        .route(
+            "/tenants/{tenant_id}/fleet",
+            post(handle_add_aircraft_request)
+                .get(handle_list_fleet)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), api_key_auth)),
+        )
+        .route(
+            "/tenants/{tenant_id}/fleet/{aircraft_id}",
+            get(handle_get_aircraft).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                api_key_auth,
+            )),
+        )
+        .route(
+            "/tenants/{tenant_id}/fleet/{aircraft_id}/retire",
+            post(handle_retire_aircraft_request).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                api_key_auth,
+            )),
+        )
+        .route(
+            "/tenants/{tenant_id}/fleet/{aircraft_id}/status",
+            put(handle_change_aircraft_status_request).route_layer(
+                middleware::from_fn_with_state(app_state.clone(), api_key_auth),
+            ),
+        )
+        .route(
+            "/tenants/{tenant_id}/fleet/{aircraft_id}/base",
+            put(handle_transfer_aircraft_base_request).route_layer(
+                middleware::from_fn_with_state(app_state.clone(), api_key_auth),
+            ),
+        )
+        .route(
+            "/tenants/{tenant_id}/fleet/{aircraft_id}/relocate",
+            post(handle_relocate_aircraft_request).route_layer(
+                middleware::from_fn_with_state(app_state.clone(), api_key_auth),
+            ),
+        )
+        .route(
+            "/tenants/{tenant_id}/fleet/{aircraft_id}/relocations",
+            get(handle_list_aircraft_relocations).route_layer(
+                middleware::from_fn_with_state(app_state.clone(), api_key_auth),
+            ),
+        )
+        .route(
+            "/tenants/{tenant_id}/fleet/{aircraft_id}/maintenance",
+            post(handle_complete_maintenance_request)
+                .get(handle_list_aircraft_maintenance)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), api_key_auth)),
+        )
         .route(
             "/users/{user_id}/apikeys",            // Use {} syntax for path parameters
             post(handle_generate_api_key_request),
@@ -140,6 +238,102 @@ pub fn create_app(app_state: AppState) -> Router {
                 api_key_auth,
             )),
         )
+        .route(
+            "/pireps",
+            post(handle_submit_pirep_request)
+                .get(handle_list_pireps)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), api_key_auth)),
+        )
+        .route(
+            "/pireps/{pirep_id}",
+            get(handle_get_pirep).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                api_key_auth,
+            )),
+        )
+        .route(
+            "/pireps/{pirep_id}/track",
+            get(handle_get_pirep_track).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                api_key_auth,
+            )),
+        )
+        .route(
+            "/pireps/{pirep_id}/approve",
+            post(handle_approve_pirep_request).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                api_key_auth,
+            )),
+        )
+        .route(
+            "/pireps/{pirep_id}/reject",
+            post(handle_reject_pirep_request).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                api_key_auth,
+            )),
+        )
+        .route(
+            "/pireps/{pirep_id}/request-changes",
+            post(handle_request_pirep_changes_request).route_layer(
+                middleware::from_fn_with_state(app_state.clone(), api_key_auth),
+            ),
+        )
+        .route(
+            "/pireps/{pirep_id}/amend",
+            post(handle_amend_pirep_request).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                api_key_auth,
+            )),
+        )
+        .route(
+            "/pireps/{pirep_id}/cancel",
+            post(handle_cancel_pirep_request).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                api_key_auth,
+            )),
+        )
+        .route(
+            "/pireps/{pirep_id}/flight-plan",
+            // OFPs carry the rendered briefing, which can exceed the default body limit
+            post(handle_attach_flight_plan_request)
+                .layer(DefaultBodyLimit::max(DEFAULT_MAX_BLOB_SIZE as usize))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), api_key_auth)),
+        )
+        .route(
+            "/airports",
+            get(handle_search_airports).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                api_key_auth,
+            )),
+        )
+        .route(
+            "/airports/{icao}",
+            get(handle_get_airport).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                api_key_auth,
+            )),
+        )
+        .route(
+            "/aircraft-types",
+            get(handle_search_aircraft_types).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                api_key_auth,
+            )),
+        )
+        .route(
+            "/aircraft-types/{icao_type}",
+            get(handle_get_aircraft_type).route_layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                api_key_auth,
+            )),
+        )
+        .route(
+            "/flight-logs",
+            // Tracks are stored as blobs, so they may be as large as the blob store accepts
+            post(handle_import_flight_log)
+                .layer(DefaultBodyLimit::max(DEFAULT_MAX_BLOB_SIZE as usize))
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), api_key_auth)),
+        )
         .route(
             "/protected",
             get(protected_route).route_layer(middleware::from_fn_with_state(
@@ -205,38 +399,11 @@ pub async fn handle_generate_api_key_request(
     Json(payload): Json<GenerateApiKeyRequest>,
 ) -> Result<impl IntoResponse, StatusCode> {
     // Resolve target user aggregate and tenant
-    let stored_events = app_state.user_repo.load(&user_id).await.map_err(map_core_error)?;
-    let mut target = User::default();
-    for stored_event in stored_events {
-        match stored_event.event_type.as_str() {
-            "UserRegistered" => {
-                if let Ok(e) = UserRegistered::decode(stored_event.payload.as_slice()) {
-                    target.apply(UserEvent::Registered(e))
-                }
-            }
-            "PasswordChanged" => {
-                if let Ok(e) = PasswordChanged::decode(stored_event.payload.as_slice()) {
-                    target.apply(UserEvent::PasswordChanged(e))
-                }
-            }
-            "ApiKeyGenerated" => {
-                if let Ok(e) = ApiKeyGenerated::decode(stored_event.payload.as_slice()) {
-                    target.apply(UserEvent::ApiKeyGenerated(e))
-                }
-            }
-            "UserLoggedIn" => {
-                if let Ok(e) = UserLoggedIn::decode(stored_event.payload.as_slice()) {
-                    target.apply(UserEvent::LoggedIn(e))
-                }
-            }
-            "ApiKeyRevoked" => {
-                if let Ok(e) = ApiKeyRevoked::decode(stored_event.payload.as_slice()) {
-                    target.apply(UserEvent::ApiKeyRevoked(e))
-                }
-            }
-            _ => {}
-        }
-    }
+    let users = AggregateRepository::<User>::new(
+        app_state.user_repo.clone(),
+        app_state.services.clock.clone(),
+    );
+    let target = users.load(&user_id).await.map_err(map_core_error)?.aggregate;
     let target_tenant_id = target.tenant_id().cloned();
 
     // Attempt to derive context from Authorization header (optional)
@@ -276,6 +443,7 @@ pub async fn handle_generate_api_key_request(
         app_state.user_repo.clone(),
         app_state.event_bus.clone(),
         app_state.cache.clone(), // Pass the cache from AppState
+        app_state.services.clone(),
     );
     let input = GenerateApiKeyInput {
         user_id,
@@ -299,38 +467,11 @@ pub async fn handle_revoke_api_key_request(
 ) -> Result<StatusCode, StatusCode> {
     // Resolve target tenant (self uses ctx; other loads from aggregate)
     let target_tenant_id = if user_id != ctx.user_id {
-        let stored_events = app_state.user_repo.load(&user_id).await.map_err(map_core_error)?;
-        let mut target = User::default();
-        for stored_event in stored_events {
-            match stored_event.event_type.as_str() {
-                "UserRegistered" => {
-                    if let Ok(e) = UserRegistered::decode(stored_event.payload.as_slice()) {
-                        target.apply(UserEvent::Registered(e))
-                    }
-                }
-                "PasswordChanged" => {
-                    if let Ok(e) = PasswordChanged::decode(stored_event.payload.as_slice()) {
-                        target.apply(UserEvent::PasswordChanged(e))
-                    }
-                }
-                "ApiKeyGenerated" => {
-                    if let Ok(e) = ApiKeyGenerated::decode(stored_event.payload.as_slice()) {
-                        target.apply(UserEvent::ApiKeyGenerated(e))
-                    }
-                }
-                "UserLoggedIn" => {
-                    if let Ok(e) = UserLoggedIn::decode(stored_event.payload.as_slice()) {
-                        target.apply(UserEvent::LoggedIn(e))
-                    }
-                }
-                "ApiKeyRevoked" => {
-                    if let Ok(e) = ApiKeyRevoked::decode(stored_event.payload.as_slice()) {
-                        target.apply(UserEvent::ApiKeyRevoked(e))
-                    }
-                }
-                _ => {}
-            }
-        }
+        let users = AggregateRepository::<User>::new(
+            app_state.user_repo.clone(),
+            app_state.services.clock.clone(),
+        );
+        let target = users.load(&user_id).await.map_err(map_core_error)?.aggregate;
         target.tenant_id().cloned()
     } else {
         ctx.tenant_id.clone()
@@ -350,6 +491,7 @@ pub async fn handle_revoke_api_key_request(
         app_state.user_repo.clone(),
         app_state.event_bus.clone(),
         app_state.cache.clone(), // Pass the cache from AppState
+        app_state.services.clone(),
     );
     let command = RevokeApiKey { user_id, key_id };
 
@@ -373,6 +515,7 @@ pub async fn handle_change_password_request(
     let handler = ChangePasswordHandler::new(
         app_state.user_repo.clone(),
         app_state.event_bus.clone(),
+        app_state.services.clone(),
     );
 
     let command = proto::user::ChangePassword {
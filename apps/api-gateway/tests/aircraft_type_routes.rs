@@ -0,0 +1,130 @@
+use api_gateway::{AppState, application::commands::CreateTenantHandler, create_app};
+use axum_test::TestServer;
+use core_lib::{
+    Cache, CommandHandler, EventPublisher, Repository,
+    adapters::{
+        fs_blob_store::FsBlobStore, in_memory_cache::InMemoryCache,
+        in_memory_event_bus::InMemoryEventBus,
+        in_memory_repository::InMemoryEventRepository,
+    },
+    services::DomainServices,
+};
+use http::{HeaderValue, StatusCode, header::AUTHORIZATION};
+use proto::tenant::CreateTenant;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+// In-memory app for tenant-a with a pilot (pi_key), its admin (ta_key) and a platform admin (pa_key)
+async fn setup_test_app() -> TestServer {
+    let tenant_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let event_bus: Arc<dyn EventPublisher> = Arc::new(InMemoryEventBus::default());
+    let cache: Arc<dyn Cache> = Arc::new(InMemoryCache::default());
+
+    for (k, v) in [
+        ("pa_key", r#"{"user_id":"user-pa","tenant_id":null,"role":"PlatformAdmin"}"#),
+        ("pi_key", r#"{"user_id":"user-pi1","tenant_id":"tenant-a","role":"Pilot"}"#),
+        ("ta_key", r#"{"user_id":"user-ta1","tenant_id":"tenant-a","role":"TenantAdmin"}"#),
+    ] {
+        cache.set(k, v.as_bytes(), Some(3600)).await.expect("cache set");
+    }
+
+    CreateTenantHandler::new(tenant_repo.clone(), event_bus.clone(), DomainServices::default())
+        .handle(CreateTenant {
+            tenant_id: "tenant-a".to_string(),
+            name: "Tenant A".to_string(),
+        })
+        .await
+        .expect("create tenant");
+
+    let app_state = AppState {
+        user_repo: Arc::new(InMemoryEventRepository::default()),
+        tenant_repo,
+        pirep_repo: Arc::new(InMemoryEventRepository::default()),
+        aircraft_repo: Arc::new(InMemoryEventRepository::default()),
+        event_bus,
+        cache,
+        blob_store: Arc::new(FsBlobStore::new(
+            std::env::temp_dir().join(format!("albatross-blobs-{}", uuid::Uuid::new_v4())),
+        )),
+        pg_pool: None,
+        redis_client: None,
+        services: DomainServices::default(),
+    };
+
+    TestServer::new(create_app(app_state)).expect("Failed to create TestServer")
+}
+
+#[tokio::test]
+async fn tenant_admins_extend_the_aircraft_type_catalog() {
+    let server = setup_test_app().await;
+    let cargomaster = json!({
+        "name": "208B Grand Caravan EX",
+        "manufacturer": "Cessna",
+        "category": "turboprop",
+        "cruise_speed_kts": 195.0,
+        "range_nm": 900.0,
+        "mtow_kg": 3995.0
+    });
+
+    let res = server
+        .get("/api/aircraft-types/b738")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    assert_eq!(res.json::<Value>()["name"], json!("737-800"));
+
+    // Pilots cannot change the catalog
+    let res = server
+        .put("/api/tenants/tenant-a/aircraft-types/C208")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&cargomaster)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+    let res = server
+        .put("/api/tenants/tenant-a/aircraft-types/c208")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&cargomaster)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+    let res = server
+        .put("/api/tenants/tenant-a/aircraft-types/C-208")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&cargomaster)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+    // The tenant's type overrides the embedded one for its members only
+    let res = server
+        .get("/api/aircraft-types?q=caravan")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let types = res.json::<Value>();
+    assert_eq!(types.as_array().unwrap().len(), 1);
+    assert_eq!(types[0]["icao_type"], json!("C208"));
+    assert_eq!(types[0]["name"], json!("208B Grand Caravan EX"));
+    let res = server
+        .get("/api/aircraft-types/C208")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pa_key"))
+        .await;
+    assert_eq!(res.json::<Value>()["name"], json!("208 Caravan"));
+
+    let res = server
+        .delete("/api/tenants/tenant-a/aircraft-types/C208")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+    let res = server
+        .get("/api/aircraft-types/C208")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.json::<Value>()["name"], json!("208 Caravan"));
+
+    // Embedded types cannot be removed
+    let res = server
+        .delete("/api/tenants/tenant-a/aircraft-types/B738")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+}
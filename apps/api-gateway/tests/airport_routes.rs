@@ -0,0 +1,78 @@
+use api_gateway::{AppState, create_app};
+use axum_test::TestServer;
+use core_lib::{
+    Cache, EventPublisher, Repository,
+    adapters::{
+        fs_blob_store::FsBlobStore, in_memory_cache::InMemoryCache,
+        in_memory_event_bus::InMemoryEventBus,
+        in_memory_repository::InMemoryEventRepository,
+    },
+    services::DomainServices,
+};
+use http::{HeaderValue, StatusCode, header::AUTHORIZATION};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+// In-memory app with a pilot (pi_key) of tenant-a
+async fn setup_test_app() -> TestServer {
+    let tenant_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let event_bus: Arc<dyn EventPublisher> = Arc::new(InMemoryEventBus::default());
+    let cache: Arc<dyn Cache> = Arc::new(InMemoryCache::default());
+
+    cache
+        .set(
+            "pi_key",
+            br#"{"user_id":"user-pi1","tenant_id":"tenant-a","role":"Pilot"}"#,
+            Some(3600),
+        )
+        .await
+        .expect("cache set");
+
+    let app_state = AppState {
+        user_repo: Arc::new(InMemoryEventRepository::default()),
+        tenant_repo,
+        pirep_repo: Arc::new(InMemoryEventRepository::default()),
+        aircraft_repo: Arc::new(InMemoryEventRepository::default()),
+        event_bus,
+        cache,
+        blob_store: Arc::new(FsBlobStore::new(
+            std::env::temp_dir().join(format!("albatross-blobs-{}", uuid::Uuid::new_v4())),
+        )),
+        pg_pool: None,
+        redis_client: None,
+        services: DomainServices::default(),
+    };
+
+    TestServer::new(create_app(app_state)).expect("Failed to create TestServer")
+}
+
+#[tokio::test]
+async fn airports_are_looked_up_and_searched() {
+    let server = setup_test_app().await;
+
+    let res = server
+        .get("/api/airports/engm")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let oslo = res.json::<Value>();
+    assert_eq!(oslo["icao"], json!("ENGM"));
+    assert_eq!(oslo["iata"], json!("OSL"));
+    assert_eq!(oslo["elevation_ft"], json!(681));
+
+    let res = server
+        .get("/api/airports/XXXX")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+    let res = server
+        .get("/api/airports?q=bergen")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    assert_eq!(res.json::<Value>()[0]["icao"], json!("ENBR"));
+
+    let res = server.get("/api/airports?q=bergen").await;
+    assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED);
+}
@@ -2,8 +2,10 @@ use axum::Router;
 use axum_test::TestServer;
 use core_lib::{
     Cache, EventPublisher, Repository,
+    services::DomainServices,
     adapters::{
-        in_memory_cache::InMemoryCache, in_memory_event_bus::InMemoryEventBus,
+        fs_blob_store::FsBlobStore, in_memory_cache::InMemoryCache,
+        in_memory_event_bus::InMemoryEventBus,
         in_memory_repository::InMemoryEventRepository,
     },
 };
@@ -20,16 +22,24 @@ use api_gateway::{AppState, GenerateApiKeyResponse, create_app}; // Assuming the
 fn setup_test_app() -> TestServer {
     let user_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
     let tenant_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let pirep_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let aircraft_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
     let event_bus: Arc<dyn EventPublisher> = Arc::new(InMemoryEventBus::default());
     let cache: Arc<dyn Cache> = Arc::new(InMemoryCache::default());
 
     let app_state = AppState {
         user_repo: user_repo.clone(),
         tenant_repo: tenant_repo.clone(),
+        pirep_repo: pirep_repo.clone(),
+        aircraft_repo,
         event_bus: event_bus.clone(),
         cache: cache.clone(),
+        blob_store: Arc::new(FsBlobStore::new(
+            std::env::temp_dir().join(format!("albatross-blobs-{}", Uuid::new_v4())),
+        )),
         pg_pool: None,
         redis_client: None,
+        services: DomainServices::default(),
     };
 
     let app: Router = create_app(app_state);
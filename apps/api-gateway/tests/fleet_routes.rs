@@ -0,0 +1,423 @@
+use api_gateway::{AppState, application::commands::CreateTenantHandler, create_app};
+use axum_test::TestServer;
+use core_lib::{
+    Cache, CommandHandler, EventPublisher, EventSubscriber, Repository, StoredEvent, Subscription,
+    adapters::{
+        fs_blob_store::FsBlobStore, in_memory_cache::InMemoryCache,
+        in_memory_event_bus::InMemoryEventBus,
+        in_memory_repository::InMemoryEventRepository,
+    },
+    flight_recorder::FlightRecorder,
+    services::DomainServices,
+};
+use http::{HeaderValue, StatusCode, header::AUTHORIZATION};
+use proto::tenant::CreateTenant;
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+// In-memory app for tenant-a with a pilot (pi_key), its admin (ta_key) and a platform admin (pa_key).
+// Approved PIREPs are recorded on the fleet as the projection worker would.
+async fn setup_test_app() -> (TestServer, Arc<dyn Repository>) {
+    let user_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let tenant_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let pirep_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let aircraft_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let bus = Arc::new(InMemoryEventBus::default());
+    let event_bus: Arc<dyn EventPublisher> = bus.clone();
+    let cache: Arc<dyn Cache> = Arc::new(InMemoryCache::default());
+
+    for (k, v) in [
+        ("pa_key", r#"{"user_id":"user-pa","tenant_id":null,"role":"PlatformAdmin"}"#),
+        ("pi_key", r#"{"user_id":"user-pi1","tenant_id":"tenant-a","role":"Pilot"}"#),
+        ("ta_key", r#"{"user_id":"user-ta1","tenant_id":"tenant-a","role":"TenantAdmin"}"#),
+    ] {
+        cache.set(k, v.as_bytes(), Some(3600)).await.expect("cache set");
+    }
+
+    CreateTenantHandler::new(tenant_repo.clone(), event_bus.clone(), DomainServices::default())
+        .handle(CreateTenant {
+            tenant_id: "tenant-a".to_string(),
+            name: "Tenant A".to_string(),
+        })
+        .await
+        .expect("create tenant");
+
+    let recorder = FlightRecorder::new(
+        pirep_repo.clone(),
+        tenant_repo.clone(),
+        aircraft_repo.clone(),
+        event_bus.clone(),
+        DomainServices::default(),
+    );
+    bus.subscribe(Subscription::new("flight-recorder", "pirep.*"), Arc::new(recorder))
+        .await
+        .expect("subscribe flight recorder");
+
+    let app_state = AppState {
+        user_repo,
+        tenant_repo,
+        pirep_repo,
+        aircraft_repo: aircraft_repo.clone(),
+        event_bus,
+        cache,
+        blob_store: Arc::new(FsBlobStore::new(
+            std::env::temp_dir().join(format!("albatross-blobs-{}", uuid::Uuid::new_v4())),
+        )),
+        pg_pool: None,
+        redis_client: None,
+        services: DomainServices::default(),
+    };
+
+    let server = TestServer::new(create_app(app_state)).expect("Failed to create TestServer");
+    (server, aircraft_repo)
+}
+
+fn aircraft(registration: &str, icao_type: &str) -> Value {
+    json!({
+        "registration": registration,
+        "icao_type": icao_type,
+        "home_base_icao": "engm",
+        "name": "Roald Amundsen"
+    })
+}
+
+// Adds an aircraft as the tenant admin and returns its ID
+async fn add_aircraft(server: &TestServer, registration: &str, icao_type: &str) -> String {
+    let res = server
+        .post("/api/tenants/tenant-a/fleet")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&aircraft(registration, icao_type))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::CREATED);
+    res.json::<Value>()["aircraft_id"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn tenant_admin_adds_aircraft_of_catalog_types() {
+    let (server, aircraft_repo) = setup_test_app().await;
+
+    let aircraft_id = add_aircraft(&server, "ln-alb", "b738").await;
+    let events = aircraft_repo.load(&aircraft_id).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event.event_type, "AircraftAdded");
+    assert_eq!(events[0].event.aggregate_type, "aircraft");
+    assert_eq!(events[0].tenant_id.as_deref(), Some("tenant-a"));
+
+    // Types the tenant defined itself can be flown too
+    let res = server
+        .put("/api/tenants/tenant-a/aircraft-types/ZZ01")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({
+            "name": "Homebuilt",
+            "category": "piston",
+            "cruise_speed_kts": 120.0,
+            "range_nm": 500.0,
+            "mtow_kg": 750.0
+        }))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+    add_aircraft(&server, "LN-HBT", "ZZ01").await;
+
+    for (body, expected) in [
+        (aircraft("LN-ZZZ", "ZZZZ"), StatusCode::BAD_REQUEST),
+        (aircraft("LN ALB", "B738"), StatusCode::BAD_REQUEST),
+        (json!({"registration": "LN-ABC", "icao_type": "B738", "home_base_icao": "XXXX"}), StatusCode::BAD_REQUEST),
+    ] {
+        let res = server
+            .post("/api/tenants/tenant-a/fleet")
+            .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+            .json(&body)
+            .await;
+        assert_eq!(res.status_code(), expected, "{}", body);
+    }
+
+    // Only tenant admins manage the fleet, and only of existing tenants
+    let res = server
+        .post("/api/tenants/tenant-a/fleet")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&aircraft("LN-PIL", "B738"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+    let res = server
+        .post("/api/tenants/tenant-b/fleet")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&aircraft("LN-OTH", "B738"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+    let res = server
+        .post("/api/tenants/tenant-zz/fleet")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pa_key"))
+        .json(&aircraft("LN-OTH", "B738"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn tenant_admin_changes_status_base_and_retires_aircraft() {
+    let (server, aircraft_repo) = setup_test_app().await;
+    let aircraft_id = add_aircraft(&server, "LN-ALB", "B738").await;
+    let path = |action: &str| format!("/api/tenants/tenant-a/fleet/{}/{}", aircraft_id, action);
+
+    let res = server
+        .put(&path("status"))
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({"status": "maintenance", "reason": "A-check"}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+    for status in ["broken", "retired", "maintenance"] {
+        let res = server
+            .put(&path("status"))
+            .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+            .json(&json!({"status": status}))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::BAD_REQUEST, "{}", status);
+    }
+
+    let res = server
+        .put(&path("base"))
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({"home_base_icao": "ENBR"}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+    let res = server
+        .put(&path("base"))
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&json!({"home_base_icao": "ENGM"}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+    let res = server
+        .post(&path("retire"))
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({"reason": "Sold"}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+    let res = server
+        .post(&path("retire"))
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+    let event_types: Vec<String> = aircraft_repo
+        .load(&aircraft_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|e| e.event.event_type)
+        .collect();
+    assert_eq!(
+        event_types,
+        vec!["AircraftAdded", "AircraftStatusChanged", "AircraftBaseTransferred", "AircraftRetired"]
+    );
+
+    let res = server
+        .post("/api/tenants/tenant-a/fleet/unknown/retire")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+    // The fleet is listed from the read model, which needs the database
+    let res = server
+        .get("/api/tenants/tenant-a/fleet")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn tenant_admin_relocates_aircraft_with_a_reason() {
+    let (server, aircraft_repo) = setup_test_app().await;
+    let aircraft_id = add_aircraft(&server, "LN-ALB", "B738").await;
+    let path = format!("/api/tenants/tenant-a/fleet/{}/relocate", aircraft_id);
+
+    let res = server
+        .post(&path)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({"to_icao": "ENBR", "reason": "Ferry for C-check"}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+
+    // Relocations are audited, so a reason is required; pilots cannot move aircraft
+    let res = server
+        .post(&path)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({"to_icao": "ENGM", "reason": ""}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+    let res = server
+        .post(&path)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&json!({"to_icao": "ENGM", "reason": "Reposition"}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+    let events = aircraft_repo.load(&aircraft_id).await.unwrap();
+    let relocated = events.last().unwrap();
+    assert_eq!(relocated.event.event_type, "AircraftRelocated");
+    assert_eq!(events.len(), 2);
+}
+
+// Waits until the flight recorder has brought the aircraft up to `count` events
+async fn recorded_events(
+    aircraft_repo: &Arc<dyn Repository>,
+    aircraft_id: &str,
+    count: usize,
+) -> Vec<StoredEvent> {
+    for _ in 0..100 {
+        let events = aircraft_repo.load(aircraft_id).await.unwrap();
+        if events.len() >= count {
+            return events;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    panic!("Aircraft {} never reached {} events", aircraft_id, count);
+}
+
+#[tokio::test]
+async fn approved_flights_ground_aircraft_until_maintenance_is_completed() {
+    let (server, aircraft_repo) = setup_test_app().await;
+    let aircraft_id = add_aircraft(&server, "LN-ALB", "B738").await;
+
+    for (path, body) in [
+        ("/api/tenants/tenant-a/pirep-rules", json!({"auto_accept": true})),
+        (
+            "/api/tenants/tenant-a/maintenance-program",
+            json!({"inspections": [{"name": "A-check", "every_hours": 5.0}]}),
+        ),
+    ] {
+        let res = server
+            .put(path)
+            .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+            .json(&body)
+            .await;
+        assert_eq!(res.status_code(), StatusCode::NO_CONTENT, "{}", path);
+    }
+    let res = server
+        .get("/api/tenants/tenant-a/maintenance-program")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    assert_eq!(res.json::<Value>()["program"]["inspections"][0]["name"], "A-check");
+
+    let submit = || {
+        server
+            .post("/api/pireps")
+            .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+            .json(&json!({
+                "aircraft_id": aircraft_id,
+                "departure_icao": "ENGM",
+                "arrival_icao": "ENBR",
+                "flight_time_hours": 3.0
+            }))
+    };
+    assert_eq!(submit().await.status_code(), StatusCode::CREATED);
+    recorded_events(&aircraft_repo, &aircraft_id, 2).await;
+    assert_eq!(submit().await.status_code(), StatusCode::CREATED);
+
+    // The second flight takes the airframe past the A-check interval
+    let events = recorded_events(&aircraft_repo, &aircraft_id, 4).await;
+    let types: Vec<&str> = events.iter().map(|e| e.event.event_type.as_str()).collect();
+    assert_eq!(
+        types,
+        vec!["AircraftAdded", "AircraftFlightRecorded", "AircraftFlightRecorded", "AircraftGrounded"]
+    );
+    assert_eq!(submit().await.status_code(), StatusCode::BAD_REQUEST);
+
+    // Nor can a PIREP left for review be amended to it
+    let res = server
+        .put("/api/tenants/tenant-a/pirep-rules")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({"auto_accept": false}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+    let mut flight = json!({
+        "aircraft_id": "ac-elsewhere",
+        "departure_icao": "ENGM",
+        "arrival_icao": "ENBR",
+        "flight_time_hours": 3.0
+    });
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&flight)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::CREATED);
+    let pirep_id = res.json::<Value>()["pirep_id"].as_str().unwrap().to_string();
+    flight["aircraft_id"] = json!(aircraft_id);
+    let res = server
+        .post(&format!("/api/pireps/{}/amend", pirep_id))
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&flight)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+    let path = format!("/api/tenants/tenant-a/fleet/{}/maintenance", aircraft_id);
+    for (key, body, expected) in [
+        ("Bearer pi_key", json!({"inspections": ["A-check"]}), StatusCode::FORBIDDEN),
+        ("Bearer ta_key", json!({"inspections": ["C-check"]}), StatusCode::BAD_REQUEST),
+        ("Bearer ta_key", json!({"inspections": ["a-check"], "notes": "Done at ENBR"}), StatusCode::NO_CONTENT),
+    ] {
+        let res = server
+            .post(&path)
+            .add_header(AUTHORIZATION, HeaderValue::from_str(key).unwrap())
+            .json(&body)
+            .await;
+        assert_eq!(res.status_code(), expected, "{}", body);
+    }
+    let events = aircraft_repo.load(&aircraft_id).await.unwrap();
+    assert_eq!(events.last().unwrap().event.event_type, "AircraftMaintenanceCompleted");
+
+    // Released aircraft fly again
+    assert_eq!(submit().await.status_code(), StatusCode::CREATED);
+    let res = server
+        .post(&format!("/api/pireps/{}/amend", pirep_id))
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&flight)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+}
+
+#[tokio::test]
+async fn aircraft_past_an_interval_of_a_new_program_are_not_flown() {
+    let (server, aircraft_repo) = setup_test_app().await;
+    let aircraft_id = add_aircraft(&server, "LN-ALB", "B738").await;
+    let res = server
+        .put("/api/tenants/tenant-a/pirep-rules")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({"auto_accept": true}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+
+    let submit = || {
+        server
+            .post("/api/pireps")
+            .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+            .json(&json!({
+                "aircraft_id": aircraft_id,
+                "departure_icao": "ENGM",
+                "arrival_icao": "ENBR",
+                "flight_time_hours": 6.0
+            }))
+    };
+    assert_eq!(submit().await.status_code(), StatusCode::CREATED);
+    recorded_events(&aircraft_repo, &aircraft_id, 2).await;
+
+    // The airframe has 6 hours when a 5 hour A-check is configured
+    let res = server
+        .put("/api/tenants/tenant-a/maintenance-program")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({"inspections": [{"name": "A-check", "every_hours": 5.0}]}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+    assert_eq!(submit().await.status_code(), StatusCode::BAD_REQUEST);
+
+    let res = server
+        .post(&format!("/api/tenants/tenant-a/fleet/{}/maintenance", aircraft_id))
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({"inspections": ["A-check"]}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+    assert_eq!(submit().await.status_code(), StatusCode::CREATED);
+}
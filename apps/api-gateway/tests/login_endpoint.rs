@@ -3,9 +3,11 @@ use axum::Router;
 use axum_test::TestServer;
 use core_lib::{
     Cache, EventPublisher, Repository,
+    services::DomainServices,
     adapters::{
         postgres_repository::PostgresEventRepository,
-        in_memory_cache::InMemoryCache, in_memory_event_bus::InMemoryEventBus,
+        fs_blob_store::FsBlobStore, in_memory_cache::InMemoryCache,
+        in_memory_event_bus::InMemoryEventBus,
     },
 };
 use http::StatusCode;
@@ -29,16 +31,24 @@ struct LoginResponse {
 fn setup_test_app() -> TestServer {
     let user_repo: Arc<dyn Repository> = Arc::new(core_lib::adapters::in_memory_repository::InMemoryEventRepository::default());
     let tenant_repo: Arc<dyn Repository> = Arc::new(core_lib::adapters::in_memory_repository::InMemoryEventRepository::default());
+    let pirep_repo: Arc<dyn Repository> = Arc::new(core_lib::adapters::in_memory_repository::InMemoryEventRepository::default());
+    let aircraft_repo: Arc<dyn Repository> = Arc::new(core_lib::adapters::in_memory_repository::InMemoryEventRepository::default());
     let event_bus: Arc<dyn EventPublisher> = Arc::new(InMemoryEventBus::default());
     let cache: Arc<dyn Cache> = Arc::new(InMemoryCache::default());
 
     let app_state = AppState {
         user_repo: user_repo.clone(),
         tenant_repo: tenant_repo.clone(),
+        pirep_repo: pirep_repo.clone(),
+        aircraft_repo,
         event_bus: event_bus.clone(),
         cache: cache.clone(),
+        blob_store: Arc::new(FsBlobStore::new(
+            std::env::temp_dir().join(format!("albatross-blobs-{}", Uuid::new_v4())),
+        )),
         pg_pool: None, // Tests don't use PostgreSQL, so this is None
         redis_client: None,
+        services: DomainServices::default(),
     };
 
     let app: Router = create_app(app_state);
@@ -61,16 +71,24 @@ async fn setup_test_app_with_postgres() -> (TestServer, testcontainers::Containe
 
     let user_repo: Arc<dyn Repository> = Arc::new(PostgresEventRepository::new(pg_pool.clone()));
     let tenant_repo: Arc<dyn Repository> = Arc::new(PostgresEventRepository::new(pg_pool.clone()));
+    let pirep_repo: Arc<dyn Repository> = Arc::new(PostgresEventRepository::new(pg_pool.clone()));
+    let aircraft_repo: Arc<dyn Repository> = Arc::new(PostgresEventRepository::new(pg_pool.clone()));
     let event_bus: Arc<dyn EventPublisher> = Arc::new(InMemoryEventBus::default());
     let cache: Arc<dyn Cache> = Arc::new(InMemoryCache::default());
 
     let app_state = AppState {
         user_repo: user_repo.clone(),
         tenant_repo: tenant_repo.clone(),
+        pirep_repo: pirep_repo.clone(),
+        aircraft_repo,
         event_bus: event_bus.clone(),
         cache: cache.clone(),
+        blob_store: Arc::new(FsBlobStore::new(
+            std::env::temp_dir().join(format!("albatross-blobs-{}", Uuid::new_v4())),
+        )),
         pg_pool: Some(pg_pool.clone()),
         redis_client: None,
+        services: DomainServices::default(),
     };
 
     let app: Router = create_app(app_state);
@@ -0,0 +1,616 @@
+use api_gateway::{AppState, application::commands::CreateTenantHandler, create_app};
+use axum_test::TestServer;
+use core_lib::{
+    Cache, CommandHandler, EventPublisher, Repository,
+    adapters::{
+        fs_blob_store::FsBlobStore, in_memory_cache::InMemoryCache,
+        in_memory_event_bus::InMemoryEventBus,
+        in_memory_repository::InMemoryEventRepository,
+    },
+    services::DomainServices,
+};
+use http::{HeaderValue, StatusCode, header::AUTHORIZATION};
+use prost::Message;
+use proto::{
+    pirep::{PirepAmended, PirepValidated},
+    tenant::CreateTenant,
+};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+// In-memory app for tenant-a with two pilots (pi_key, pi2_key), its admin (ta_key), the admin
+// of another tenant (tb_key) and a platform admin (pa_key)
+async fn setup_test_app() -> (TestServer, Arc<dyn Repository>) {
+    let user_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let tenant_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let pirep_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let aircraft_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let event_bus: Arc<dyn EventPublisher> = Arc::new(InMemoryEventBus::default());
+    let cache: Arc<dyn Cache> = Arc::new(InMemoryCache::default());
+
+    for (k, v) in [
+        ("pa_key", r#"{"user_id":"user-pa","tenant_id":null,"role":"PlatformAdmin"}"#),
+        ("pi_key", r#"{"user_id":"user-pi1","tenant_id":"tenant-a","role":"Pilot"}"#),
+        ("pi2_key", r#"{"user_id":"user-pi2","tenant_id":"tenant-a","role":"Pilot"}"#),
+        ("ta_key", r#"{"user_id":"user-ta1","tenant_id":"tenant-a","role":"TenantAdmin"}"#),
+        ("tb_key", r#"{"user_id":"user-tb1","tenant_id":"tenant-b","role":"TenantAdmin"}"#),
+    ] {
+        cache.set(k, v.as_bytes(), Some(3600)).await.expect("cache set");
+    }
+
+    CreateTenantHandler::new(tenant_repo.clone(), event_bus.clone(), DomainServices::default())
+        .handle(CreateTenant {
+            tenant_id: "tenant-a".to_string(),
+            name: "Tenant A".to_string(),
+        })
+        .await
+        .expect("create tenant");
+
+    let app_state = AppState {
+        user_repo,
+        tenant_repo,
+        pirep_repo: pirep_repo.clone(),
+        aircraft_repo,
+        event_bus,
+        cache,
+        blob_store: Arc::new(FsBlobStore::new(
+            std::env::temp_dir().join(format!("albatross-blobs-{}", uuid::Uuid::new_v4())),
+        )),
+        pg_pool: None,
+        redis_client: None,
+        services: DomainServices::default(),
+    };
+
+    let server = TestServer::new(create_app(app_state)).expect("Failed to create TestServer");
+    (server, pirep_repo)
+}
+
+fn flight() -> Value {
+    json!({
+        "aircraft_id": "ac-1",
+        "departure_icao": "enbr",
+        "arrival_icao": "ENGM",
+        "flight_number": "ALB101",
+        "flight_time_hours": 0.9
+    })
+}
+
+#[tokio::test]
+async fn pilot_submits_pirep_in_own_tenant() {
+    let (server, pirep_repo) = setup_test_app().await;
+
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&flight())
+        .await;
+    assert_eq!(res.status_code(), StatusCode::CREATED);
+    let pirep_id = res.json::<Value>()["pirep_id"].as_str().unwrap().to_string();
+
+    let events = pirep_repo.load(&pirep_id).await.unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].event.event_type, "PirepSubmitted");
+    assert_eq!(events[0].tenant_id.as_deref(), Some("tenant-a"));
+}
+
+#[tokio::test]
+async fn submit_pirep_validates_and_requires_tenant() {
+    let (server, _pirep_repo) = setup_test_app().await;
+
+    let mut invalid = flight();
+    invalid["flight_time_hours"] = json!(0.0);
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&invalid)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+    let mut unknown_airport = flight();
+    unknown_airport["arrival_icao"] = json!("XXXX");
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&unknown_airport)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+    // Platform admins have no tenant to file the PIREP in
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pa_key"))
+        .json(&flight())
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+    let res = server.post("/api/pireps").json(&flight()).await;
+    assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn tenant_admin_rules_auto_accept_or_flag_submissions() {
+    let (server, pirep_repo) = setup_test_app().await;
+
+    let rules = json!({
+        "auto_accept": true,
+        "max_landing_rate_fpm": 600.0,
+        "route_aircraft": [
+            {"departure_icao": "ENBR", "arrival_icao": "ENGM", "aircraft_ids": ["ac-1"]}
+        ]
+    });
+    let res = server
+        .put("/api/tenants/tenant-a/pirep-rules")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&rules)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+
+    // Pilots can read the rules they are checked against
+    let res = server
+        .get("/api/tenants/tenant-a/pirep-rules")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    assert_eq!(res.json::<Value>()["rules"]["max_landing_rate_fpm"], json!(600.0));
+
+    let mut smooth = flight();
+    smooth["landing_rate_fpm"] = json!(180.0);
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&smooth)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::CREATED);
+    let pirep_id = res.json::<Value>()["pirep_id"].as_str().unwrap().to_string();
+    let events = pirep_repo.load(&pirep_id).await.unwrap();
+    let types: Vec<&str> = events.iter().map(|e| e.event.event_type.as_str()).collect();
+    assert_eq!(types, vec!["PirepSubmitted", "PirepValidated"]);
+    let validated = PirepValidated::decode(events[1].event.payload.as_slice()).unwrap();
+    assert!(validated.auto_accepted);
+
+    let mut hard = flight();
+    hard["landing_rate_fpm"] = json!(950.0);
+    hard["aircraft_id"] = json!("ac-2");
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&hard)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::CREATED);
+    let pirep_id = res.json::<Value>()["pirep_id"].as_str().unwrap().to_string();
+    let events = pirep_repo.load(&pirep_id).await.unwrap();
+    let validated = PirepValidated::decode(events[1].event.payload.as_slice()).unwrap();
+    assert!(!validated.auto_accepted);
+    let failed: Vec<&str> = validated
+        .results
+        .iter()
+        .filter(|r| !r.passed)
+        .map(|r| r.rule.as_str())
+        .collect();
+    assert_eq!(failed, vec!["landing_rate", "route_aircraft"]);
+
+    // Amending checks the rules again: the right aircraft, but still too hard a landing
+    hard["aircraft_id"] = json!("ac-1");
+    let res = server
+        .post(&format!("/api/pireps/{}/amend", pirep_id))
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&hard)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+    let events = pirep_repo.load(&pirep_id).await.unwrap();
+    let types: Vec<&str> = events.iter().map(|e| e.event.event_type.as_str()).collect();
+    assert_eq!(
+        types,
+        vec!["PirepSubmitted", "PirepValidated", "PirepAmended", "PirepValidated"]
+    );
+    let validated = PirepValidated::decode(events[3].event.payload.as_slice()).unwrap();
+    assert!(!validated.auto_accepted);
+    let failed: Vec<&str> = validated
+        .results
+        .iter()
+        .filter(|r| !r.passed)
+        .map(|r| r.rule.as_str())
+        .collect();
+    assert_eq!(failed, vec!["landing_rate"]);
+}
+
+#[tokio::test]
+async fn only_tenant_admins_configure_valid_rules() {
+    let (server, _pirep_repo) = setup_test_app().await;
+    let rules = json!({"auto_accept": true, "max_landing_rate_fpm": 600.0});
+
+    let res = server
+        .put("/api/tenants/tenant-a/pirep-rules")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&rules)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+    let res = server
+        .put("/api/tenants/tenant-a/pirep-rules")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({"min_block_speed_kts": 500.0, "max_block_speed_kts": 200.0}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+    // Platform admins manage any tenant, but only existing ones
+    let res = server
+        .put("/api/tenants/tenant-zz/pirep-rules")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pa_key"))
+        .json(&rules)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+}
+
+// Parked at ENBR, airborne, touchdown at ENGM at -150 fpm, parked
+const TRACK_CSV: &str = "\
+time,lat,lon,alt,gs,vs,on_ground
+2026-03-01T10:00:00Z,60.2934,5.2181,170,0,0,1
+2026-03-01T10:10:00Z,60.2940,5.2300,170,140,0,1
+2026-03-01T10:11:00Z,60.2950,5.3000,1500,170,1800,0
+2026-03-01T10:55:00Z,60.1939,11.1004,680,125,-150,1
+2026-03-01T11:00:00Z,60.1939,11.1004,680,0,0,1
+";
+
+#[tokio::test]
+async fn pilot_imports_track_and_staff_review_it() {
+    let (server, _pirep_repo) = setup_test_app().await;
+
+    let res = server
+        .post("/api/flight-logs?format=csv")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .text(TRACK_CSV)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::CREATED);
+    let body = res.json::<Value>();
+    assert_eq!(body["points"], json!(5));
+    assert_eq!(body["summary"]["block_time_hours"], json!(1.0));
+    assert_eq!(body["summary"]["landing_rate_fpm"], json!(150.0));
+    assert_eq!(body["summary"]["departure_icao"], json!("ENBR"));
+    assert_eq!(body["summary"]["arrival_icao"], json!("ENGM"));
+    let track_key = body["track"]["key"].as_str().unwrap().to_string();
+    assert!(track_key.starts_with("tenants/tenant-a/tracks/user-pi1/"));
+
+    let mut pirep = flight();
+    pirep["track_key"] = json!(track_key);
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&pirep)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::CREATED);
+    let pirep_id = res.json::<Value>()["pirep_id"].as_str().unwrap().to_string();
+
+    let res = server
+        .get(&format!("/api/pireps/{}/track", pirep_id))
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    assert_eq!(res.header("content-type"), "text/csv");
+    assert_eq!(res.text(), TRACK_CSV);
+
+    // Tracks of other pilots cannot be attached
+    let mut stolen = flight();
+    stolen["track_key"] = json!("tenants/tenant-a/tracks/user-ta1/track.csv");
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&stolen)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn import_rejects_unreadable_tracks() {
+    let (server, _pirep_repo) = setup_test_app().await;
+
+    let res = server
+        .post("/api/flight-logs?format=csv")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .text("time,lat\n2026-03-01T10:00:00Z,60.0\n")
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+    assert!(res.json::<Value>()["error"].as_str().unwrap().contains("lon"));
+
+    let res = server
+        .post("/api/flight-logs")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .text(TRACK_CSV)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    // Platform admins have no tenant to file PIREPs in
+    let res = server
+        .post("/api/flight-logs?format=csv")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pa_key"))
+        .text(TRACK_CSV)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+}
+
+// Minimal SimBrief OFP for the ENBR-ENGM flight
+const OFP_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<OFP>
+  <params><request_id>98765432</request_id><units>kgs</units></params>
+  <general><route>TUSKA T310 BAVAD</route><route_distance>187</route_distance></general>
+  <origin><icao_code>ENBR</icao_code></origin>
+  <destination><icao_code>ENGM</icao_code></destination>
+  <alternate><icao_code>ESSA</icao_code></alternate>
+  <fuel><plan_ramp>6850</plan_ramp><enroute_burn>2480</enroute_burn></fuel>
+  <weights><est_zfw>58210</est_zfw></weights>
+  <times><est_block>3600</est_block><est_out>1772359200</est_out></times>
+</OFP>"#;
+
+#[tokio::test]
+async fn pilot_attaches_simbrief_flight_plan() {
+    let (server, pirep_repo) = setup_test_app().await;
+
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&flight())
+        .await;
+    let pirep_id = res.json::<Value>()["pirep_id"].as_str().unwrap().to_string();
+    let url = format!("/api/pireps/{}/flight-plan", pirep_id);
+
+    let res = server
+        .post(&url)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .text(OFP_XML)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let plan = &res.json::<Value>()["flight_plan"];
+    assert_eq!(plan["alternate_icao"], json!("ESSA"));
+    assert_eq!(plan["block_fuel"], json!(6850.0));
+    assert_eq!(plan["est_block_hours"], json!(1.0));
+
+    let events = pirep_repo.load(&pirep_id).await.unwrap();
+    assert_eq!(events.last().unwrap().event.event_type, "PirepFlightPlanAttached");
+
+    // Only the pilot attaches plans, and only OFPs
+    let res = server
+        .post(&url)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .text(OFP_XML)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED);
+    let res = server
+        .post(&url)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .text(TRACK_CSV)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+    let res = server
+        .post("/api/pireps/unknown/flight-plan")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .text(OFP_XML)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn flight_plan_rule_checks_plans_submitted_or_attached() {
+    let (server, pirep_repo) = setup_test_app().await;
+    let rules = json!({"auto_accept": true, "max_plan_block_deviation_pct": 20.0});
+    let res = server
+        .put("/api/tenants/tenant-a/pirep-rules")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&rules)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+
+    // 0.9 h flown against 1.0 h planned
+    let mut planned = flight();
+    planned["simbrief_ofp"] = json!(OFP_XML);
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&planned)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::CREATED);
+    let pirep_id = res.json::<Value>()["pirep_id"].as_str().unwrap().to_string();
+    let events = pirep_repo.load(&pirep_id).await.unwrap();
+    let types: Vec<&str> = events.iter().map(|e| e.event.event_type.as_str()).collect();
+    assert_eq!(types, vec!["PirepSubmitted", "PirepFlightPlanAttached", "PirepValidated"]);
+    let validated = PirepValidated::decode(events[2].event.payload.as_slice()).unwrap();
+    assert!(validated.auto_accepted);
+
+    // Without a plan the PIREP waits for one or for a reviewer
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&flight())
+        .await;
+    let pirep_id = res.json::<Value>()["pirep_id"].as_str().unwrap().to_string();
+    let events = pirep_repo.load(&pirep_id).await.unwrap();
+    let validated = PirepValidated::decode(events[1].event.payload.as_slice()).unwrap();
+    assert!(!validated.auto_accepted);
+
+    let res = server
+        .post(&format!("/api/pireps/{}/flight-plan", pirep_id))
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .text(OFP_XML)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let events = pirep_repo.load(&pirep_id).await.unwrap();
+    let last = events.last().unwrap();
+    assert_eq!(last.event.event_type, "PirepValidated");
+    let validated = PirepValidated::decode(last.event.payload.as_slice()).unwrap();
+    assert!(validated.auto_accepted);
+    assert_eq!(validated.results[0].rule, "flight_plan");
+
+    let mut unreadable = flight();
+    unreadable["simbrief_ofp"] = json!("not an OFP");
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&unreadable)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+}
+
+// Submits flight() as the first pilot and returns the PIREP ID
+async fn submit(server: &TestServer) -> String {
+    let res = server
+        .post("/api/pireps")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&flight())
+        .await;
+    assert_eq!(res.status_code(), StatusCode::CREATED);
+    res.json::<Value>()["pirep_id"].as_str().unwrap().to_string()
+}
+
+async fn event_types(pirep_repo: &Arc<dyn Repository>, pirep_id: &str) -> Vec<String> {
+    let events = pirep_repo.load(pirep_id).await.unwrap();
+    events.into_iter().map(|e| e.event.event_type).collect()
+}
+
+#[tokio::test]
+async fn tenant_admins_review_pireps() {
+    let (server, pirep_repo) = setup_test_app().await;
+    let pirep_id = submit(&server).await;
+    let approve = format!("/api/pireps/{}/approve", pirep_id);
+
+    // Only admins of the PIREP's tenant review it
+    for key in ["Bearer pi_key", "Bearer pi2_key", "Bearer tb_key"] {
+        let res = server
+            .post(&approve)
+            .add_header(AUTHORIZATION, HeaderValue::from_str(key).unwrap())
+            .json(&json!({}))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::FORBIDDEN, "{}", key);
+    }
+    let res = server
+        .post(&approve)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({"comment": "Nice landing"}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+    assert_eq!(event_types(&pirep_repo, &pirep_id).await, vec!["PirepSubmitted", "PirepApproved"]);
+
+    // Decided PIREPs stay decided
+    for (action, body) in [("approve", json!({})), ("reject", json!({"reason": "Too late"}))] {
+        let res = server
+            .post(&format!("/api/pireps/{}/{}", pirep_id, action))
+            .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+            .json(&body)
+            .await;
+        assert_eq!(res.status_code(), StatusCode::BAD_REQUEST, "{}", action);
+    }
+
+    // Changes are requested with a reason, and a PIREP sent back can still be rejected
+    let pirep_id = submit(&server).await;
+    let url = |action: &str| format!("/api/pireps/{}/{}", pirep_id, action);
+    for (action, body, expected) in [
+        ("request-changes", json!({}), StatusCode::BAD_REQUEST),
+        ("request-changes", json!({"reason": "Wrong aircraft"}), StatusCode::NO_CONTENT),
+        ("approve", json!({}), StatusCode::BAD_REQUEST),
+        ("reject", json!({"reason": "Never fixed"}), StatusCode::NO_CONTENT),
+    ] {
+        let res = server
+            .post(&url(action))
+            .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+            .json(&body)
+            .await;
+        assert_eq!(res.status_code(), expected, "{} {}", action, body);
+    }
+    assert_eq!(
+        event_types(&pirep_repo, &pirep_id).await,
+        vec!["PirepSubmitted", "PirepChangesRequested", "PirepRejected"]
+    );
+
+    // Platform admins review any tenant's PIREPs
+    let pirep_id = submit(&server).await;
+    let res = server
+        .post(&format!("/api/pireps/{}/approve", pirep_id))
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pa_key"))
+        .json(&json!({}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+
+    let res = server
+        .post("/api/pireps/unknown/approve")
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&json!({}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn pilots_amend_and_cancel_their_pireps() {
+    let (server, pirep_repo) = setup_test_app().await;
+    let pirep_id = submit(&server).await;
+    let amend = format!("/api/pireps/{}/amend", pirep_id);
+    let cancel = format!("/api/pireps/{}/cancel", pirep_id);
+    let mut amended = flight();
+    amended["arrival_icao"] = json!("enva");
+
+    // Only the submitting pilot changes the PIREP
+    for key in ["Bearer pi2_key", "Bearer ta_key"] {
+        let res = server
+            .post(&amend)
+            .add_header(AUTHORIZATION, HeaderValue::from_str(key).unwrap())
+            .json(&amended)
+            .await;
+        assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED, "{}", key);
+        let res = server
+            .post(&cancel)
+            .add_header(AUTHORIZATION, HeaderValue::from_str(key).unwrap())
+            .json(&json!({}))
+            .await;
+        assert_eq!(res.status_code(), StatusCode::UNAUTHORIZED, "{}", key);
+    }
+    let res = server
+        .post(&amend)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pa_key"))
+        .json(&amended)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+    let res = server
+        .post(&amend)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&amended)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+    let events = pirep_repo.load(&pirep_id).await.unwrap();
+    let last = events.last().unwrap();
+    assert_eq!(last.event.event_type, "PirepAmended");
+    let amendment = PirepAmended::decode(last.event.payload.as_slice()).unwrap();
+    assert_eq!(amendment.arrival_icao, "ENVA");
+
+    let mut invalid = flight();
+    invalid["flight_time_hours"] = json!(0.0);
+    let res = server
+        .post(&amend)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&invalid)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+    let res = server
+        .post(&cancel)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&json!({"reason": "Filed twice"}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NO_CONTENT);
+
+    // Cancelled PIREPs can no longer be changed
+    let res = server
+        .post(&amend)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&amended)
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+    let res = server
+        .post(&cancel)
+        .add_header(AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .json(&json!({}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        event_types(&pirep_repo, &pirep_id).await,
+        vec!["PirepSubmitted", "PirepAmended", "PirepCancelled"]
+    );
+}
@@ -3,9 +3,11 @@ use axum::http::{HeaderValue, StatusCode};
 use axum_test::TestServer;
 use core_lib::{
     adapters::{
-        in_memory_cache::InMemoryCache, in_memory_event_bus::InMemoryEventBus,
+        fs_blob_store::FsBlobStore, in_memory_cache::InMemoryCache,
+        in_memory_event_bus::InMemoryEventBus,
         in_memory_repository::InMemoryEventRepository,
     },
+    services::DomainServices,
     Cache, EventPublisher, Repository,
 };
 use serde_json::Value;
@@ -43,6 +45,14 @@ async fn setup_pg() -> PgPool {
         .execute(&pool).await.expect("index username");
     sqlx::query("CREATE INDEX idx_users_email ON users(email)")
         .execute(&pool).await.expect("index email");
+    sqlx::query("CREATE TABLE pireps (pirep_id VARCHAR(36) PRIMARY KEY, tenant_id VARCHAR(36) NOT NULL, user_id VARCHAR(36) NOT NULL, aircraft_id VARCHAR(255) NOT NULL, departure_icao VARCHAR(255) NOT NULL, arrival_icao VARCHAR(255) NOT NULL, flight_number VARCHAR(255) NOT NULL DEFAULT '', flight_time_hours DOUBLE PRECISION NOT NULL, remarks TEXT NOT NULL DEFAULT '', landing_rate_fpm DOUBLE PRECISION NULL, route_distance_nm DOUBLE PRECISION NULL, rule_results JSONB NULL, flagged BOOLEAN NOT NULL DEFAULT FALSE, track_key VARCHAR(1024) NULL, flight_plan JSONB NULL, status VARCHAR(30) NOT NULL, status_note TEXT NULL, reviewer_id VARCHAR(36) NULL, submitted_at TIMESTAMPTZ NOT NULL, reviewed_at TIMESTAMPTZ NULL, created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(), updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW())")
+        .execute(&pool).await.expect("create pireps");
+    sqlx::query("CREATE TABLE aircraft (aircraft_id VARCHAR(36) PRIMARY KEY, tenant_id VARCHAR(36) NOT NULL, registration VARCHAR(10) NOT NULL, icao_type VARCHAR(4) NOT NULL, name VARCHAR(255) NOT NULL DEFAULT '', home_base_icao VARCHAR(255) NOT NULL, status VARCHAR(20) NOT NULL, status_note TEXT NULL, location_icao VARCHAR(255) NOT NULL, location_since TIMESTAMPTZ NOT NULL, location_pirep_id VARCHAR(36) NULL, airframe_hours DOUBLE PRECISION NOT NULL DEFAULT 0, cycles INTEGER NOT NULL DEFAULT 0, added_at TIMESTAMPTZ NOT NULL, retired_at TIMESTAMPTZ NULL, created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(), updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW())")
+        .execute(&pool).await.expect("create aircraft");
+    sqlx::query("CREATE TABLE aircraft_relocations (aircraft_id VARCHAR(36) NOT NULL, tenant_id VARCHAR(36) NOT NULL, from_icao VARCHAR(255) NOT NULL, to_icao VARCHAR(255) NOT NULL, reason TEXT NOT NULL, relocated_by VARCHAR(36) NOT NULL, relocated_at TIMESTAMPTZ NOT NULL, created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(), PRIMARY KEY (aircraft_id, relocated_at))")
+        .execute(&pool).await.expect("create aircraft_relocations");
+    sqlx::query("CREATE TABLE aircraft_maintenance (aircraft_id VARCHAR(36) NOT NULL, tenant_id VARCHAR(36) NOT NULL, kind VARCHAR(16) NOT NULL, inspections JSONB NOT NULL, notes TEXT NOT NULL DEFAULT '', completed_by VARCHAR(36) NULL, airframe_hours DOUBLE PRECISION NOT NULL, cycles INTEGER NOT NULL, released BOOLEAN NOT NULL DEFAULT FALSE, occurred_at TIMESTAMPTZ NOT NULL, created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(), PRIMARY KEY (aircraft_id, kind, occurred_at))")
+        .execute(&pool).await.expect("create aircraft_maintenance");
     pool
 }
 
@@ -66,6 +76,44 @@ async fn seed_data(pool: &PgPool) {
         .execute(pool)
         .await
         .expect("insert users");
+
+    // PIREPs: two by Pilot A (one approved, one flagged by the rules), one in tenant B
+    sqlx::query(r#"INSERT INTO pireps (pirep_id, tenant_id, user_id, aircraft_id, departure_icao, arrival_icao, flight_time_hours, flagged, status, submitted_at)
+        VALUES
+        ('pirep-a1','tenant-a','user-pi1','ac-1','ENBR','ENGM',0.9,FALSE,'APPROVED','2026-01-10T10:00:00Z'),
+        ('pirep-a2','tenant-a','user-pi1','ac-1','ENGM','ESSA',1.1,TRUE,'PENDING','2026-02-10T10:00:00Z'),
+        ('pirep-a3','tenant-a','user-ta1','ac-2','ENGM','EKCH',1.2,FALSE,'PENDING','2026-02-11T10:00:00Z'),
+        ('pirep-b1','tenant-b','user-ta2','ac-9','EGLL','EHAM',1.0,FALSE,'PENDING','2026-02-12T10:00:00Z')"#)
+        .execute(pool)
+        .await
+        .expect("insert pireps");
+
+    // Fleet: three aircraft in tenant A (one retired), one in tenant B. LN-ALB flew
+    // pirep-a1 to Oslo; LN-ALC was ferried there for maintenance. LN-ALB had its
+    // A-check after being grounded for it.
+    sqlx::query(r#"INSERT INTO aircraft (aircraft_id, tenant_id, registration, icao_type, home_base_icao, status, location_icao, location_since, location_pirep_id, airframe_hours, cycles, added_at, retired_at)
+        VALUES
+        ('ac-1','tenant-a','LN-ALB','B738','ENBR','ACTIVE','ENGM','2026-01-10T10:00:00Z','pirep-a1',512.5,230,'2026-01-01T00:00:00Z',NULL),
+        ('ac-2','tenant-a','LN-ALC','DH8D','ENBR','MAINTENANCE','ENGM','2026-01-20T00:00:00Z',NULL,0,0,'2026-01-02T00:00:00Z',NULL),
+        ('ac-3','tenant-a','LN-OLD','B733','ENGM','RETIRED','ENGM','2025-01-01T00:00:00Z',NULL,0,0,'2025-01-01T00:00:00Z','2025-12-31T00:00:00Z'),
+        ('ac-9','tenant-b','G-BRVO','A320','EGLL','ACTIVE','EGLL','2026-01-01T00:00:00Z',NULL,0,0,'2026-01-01T00:00:00Z',NULL)"#)
+        .execute(pool)
+        .await
+        .expect("insert aircraft");
+    sqlx::query(r#"INSERT INTO aircraft_relocations (aircraft_id, tenant_id, from_icao, to_icao, reason, relocated_by, relocated_at)
+        VALUES
+        ('ac-2','tenant-a','ENVA','ENBR','Location correction','user-ta1','2026-01-15T00:00:00Z'),
+        ('ac-2','tenant-a','ENBR','ENGM','Ferry for C-check','user-ta1','2026-01-20T00:00:00Z')"#)
+        .execute(pool)
+        .await
+        .expect("insert aircraft relocations");
+    sqlx::query(r#"INSERT INTO aircraft_maintenance (aircraft_id, tenant_id, kind, inspections, notes, completed_by, airframe_hours, cycles, released, occurred_at)
+        VALUES
+        ('ac-1','tenant-a','GROUNDED','["A-check"]','',NULL,501.0,225,FALSE,'2026-01-08T12:00:00Z'),
+        ('ac-1','tenant-a','COMPLETED','["A-check"]','No findings','user-ta1',501.0,225,TRUE,'2026-01-09T08:00:00Z')"#)
+        .execute(pool)
+        .await
+        .expect("insert aircraft maintenance");
 }
 
 /// Pre-populate cache with API key -> AuthenticatedUser JSON
@@ -87,16 +135,24 @@ async fn build_server() -> (TestServer, Arc<dyn Cache>, PgPool) {
 
     let user_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
     let tenant_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let pirep_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+    let aircraft_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
     let event_bus: Arc<dyn EventPublisher> = Arc::new(InMemoryEventBus::default());
     let cache: Arc<dyn Cache> = Arc::new(InMemoryCache::default());
 
     let state = AppState {
         user_repo,
         tenant_repo,
+        pirep_repo,
+        aircraft_repo,
         event_bus,
         cache: cache.clone(),
+        blob_store: Arc::new(FsBlobStore::new(
+            std::env::temp_dir().join(format!("albatross-blobs-{}", uuid::Uuid::new_v4())),
+        )),
         pg_pool: Some(pool.clone()),
         redis_client: None,
+        services: DomainServices::default(),
     };
 
     let app = create_app(state);
@@ -261,3 +317,216 @@ async fn pilot_cannot_register_user() {
 
     assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
 }
+
+#[tokio::test]
+async fn tenant_admin_filters_tenant_pireps() {
+    let (server, _cache, _pool) = build_server().await;
+
+    let res = server
+        .get("/api/pireps")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let body: Value = res.json();
+    let ids: Vec<&str> = body["data"].as_array().unwrap().iter().map(|p| p["pirep_id"].as_str().unwrap()).collect();
+    assert_eq!(ids, vec!["pirep-a3", "pirep-a2", "pirep-a1"]); // Newest first
+
+    let res = server
+        .get("/api/pireps?user_id=user-pi1&status=pending&from=2026-02-01T00:00:00Z")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let body: Value = res.json();
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    assert_eq!(body["data"][0]["pirep_id"].as_str().unwrap(), "pirep-a2");
+
+    // The review queue: PIREPs the rules flagged
+    let res = server
+        .get("/api/pireps?flagged=true")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let body: Value = res.json();
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    assert_eq!(body["data"][0]["pirep_id"].as_str().unwrap(), "pirep-a2");
+
+    let res = server
+        .get("/api/pireps?tenant_id=tenant-b")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+    let res = server
+        .get("/api/pireps?status=landed")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn pilot_sees_only_own_pireps() {
+    let (server, _cache, _pool) = build_server().await;
+
+    let res = server
+        .get("/api/pireps")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let body: Value = res.json();
+    assert_eq!(body["data"].as_array().unwrap().len(), 2);
+
+    let res = server
+        .get("/api/pireps?user_id=user-ta1")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+
+    let res = server
+        .get("/api/pireps/pirep-a1")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let body: Value = res.json();
+    assert_eq!(body["status"].as_str().unwrap(), "APPROVED");
+
+    let res = server
+        .get("/api/pireps/pirep-a3")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn platform_admin_lists_pireps_across_tenants() {
+    let (server, _cache, _pool) = build_server().await;
+
+    let res = server
+        .get("/api/pireps")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pa_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let body: Value = res.json();
+    assert_eq!(body["data"].as_array().unwrap().len(), 4);
+
+    let res = server
+        .get("/api/pireps/pirep-missing")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pa_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn tenant_members_list_their_fleet() {
+    let (server, _cache, _pool) = build_server().await;
+    let registrations = |body: &Value| {
+        body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|a| a["registration"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // Retired aircraft are left out unless asked for
+    let res = server
+        .get("/api/tenants/tenant-a/fleet")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    assert_eq!(registrations(&res.json()), vec!["LN-ALB", "LN-ALC"]);
+
+    let res = server
+        .get("/api/tenants/tenant-a/fleet?status=retired")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .await;
+    assert_eq!(registrations(&res.json()), vec!["LN-OLD"]);
+
+    let res = server
+        .get("/api/tenants/tenant-a/fleet?home_base_icao=enbr&icao_type=dh8d")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .await;
+    assert_eq!(registrations(&res.json()), vec!["LN-ALC"]);
+
+    let res = server
+        .get("/api/tenants/tenant-a/fleet?location_icao=engm&icao_type=dh8d")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .await;
+    assert_eq!(registrations(&res.json()), vec!["LN-ALC"]);
+
+    let res = server
+        .get("/api/tenants/tenant-a/fleet?status=flying")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::BAD_REQUEST);
+
+    let res = server
+        .get("/api/tenants/tenant-a/fleet/ac-2")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let body: Value = res.json();
+    assert_eq!(body["status"], "MAINTENANCE");
+    assert_eq!(body["home_base_icao"], "ENBR");
+    assert_eq!(body["location_icao"], "ENGM");
+
+    // Relocations are listed newest first with who made them and why
+    let res = server
+        .get("/api/tenants/tenant-a/fleet/ac-2/relocations")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let body: Value = res.json();
+    let relocations = body["data"].as_array().unwrap();
+    assert_eq!(relocations.len(), 2);
+    assert_eq!(relocations[0]["to_icao"], "ENGM");
+    assert_eq!(relocations[0]["reason"], "Ferry for C-check");
+    assert_eq!(relocations[0]["relocated_by"], "user-ta1");
+
+    // Groundings and the maintenance that released the aircraft, newest first
+    let res = server
+        .get("/api/tenants/tenant-a/fleet/ac-1")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    let body: Value = res.json();
+    assert_eq!(body["airframe_hours"], 512.5);
+    assert_eq!(body["cycles"], 230);
+    let res = server
+        .get("/api/tenants/tenant-a/fleet/ac-1/maintenance")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::OK);
+    let body: Value = res.json();
+    let history = body["data"].as_array().unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history[0]["kind"], "COMPLETED");
+    assert_eq!(history[0]["inspections"], serde_json::json!(["A-check"]));
+    assert_eq!(history[0]["completed_by"], "user-ta1");
+    assert_eq!(history[0]["released"], true);
+    assert_eq!(history[1]["kind"], "GROUNDED");
+
+    let res = server
+        .get("/api/tenants/tenant-a/fleet/ac-9/relocations")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pa_key"))
+        .await;
+    assert_eq!(res.json::<Value>()["data"].as_array().unwrap().len(), 0);
+
+    // Other tenants' fleets are off limits, and their aircraft are not found in this one
+    let res = server
+        .get("/api/tenants/tenant-b/fleet")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pi_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::FORBIDDEN);
+    let res = server
+        .get("/api/tenants/tenant-a/fleet/ac-9")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer pa_key"))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::NOT_FOUND);
+
+    // A registration in use is not given to a second aircraft
+    let res = server
+        .post("/api/tenants/tenant-a/fleet")
+        .add_header(axum::http::header::AUTHORIZATION, HeaderValue::from_static("Bearer ta_key"))
+        .json(&serde_json::json!({"registration": "ln-alb", "icao_type": "B738", "home_base_icao": "ENGM"}))
+        .await;
+    assert_eq!(res.status_code(), StatusCode::CONFLICT);
+}
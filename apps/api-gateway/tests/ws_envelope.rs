@@ -34,17 +34,15 @@ async fn test_ws_event_envelope() {
             match message {
                 Ok(Message::Text(text)) => {
                     let parsed: serde_json::Value = serde_json::from_str(&text).unwrap();
-                    if parsed["type"] == "event" {
-                        // Check for envelope structure
-                        if let Some(payload) = parsed.get("payload") {
-                            if payload.get("event_type").is_some() &&
-                               payload.get("ts").is_some() &&
-                               payload.get("data").is_some() &&
-                               payload.get("meta").is_some() {
-                                received_envelope = true;
-                                break;
-                            }
-                        }
+                    // Check for envelope structure
+                    if parsed["type"] == "event" &&
+                       let Some(payload) = parsed.get("payload") &&
+                       payload.get("event_type").is_some() &&
+                       payload.get("ts").is_some() &&
+                       payload.get("data").is_some() &&
+                       payload.get("meta").is_some() {
+                        received_envelope = true;
+                        break;
                     }
                 }
                 Ok(Message::Close(_)) => break,
@@ -1,19 +1,34 @@
-use core_lib::adapters::rabbitmq_event_bus::RabbitMqEventBus;
+use core_lib::adapters::rabbitmq_event_bus::{
+    AIRCRAFT_QUEUE, AIRCRAFT_ROUTING_KEY, FLIGHT_RECORDER_QUEUE, PIREP_QUEUE, PIREP_ROUTING_KEY,
+    RabbitMqConfig, RabbitMqEventBus, RabbitMqTopology, TENANT_QUEUE, TENANT_ROUTING_KEY,
+    USER_QUEUE, USER_ROUTING_KEY, projection_topology,
+};
+use core_lib::adapters::in_memory_event_bus::InMemoryEventBus;
+use core_lib::adapters::postgres_event_bus::PostgresEventBus;
+use core_lib::adapters::postgres_event_partitions::PostgresEventPartitions;
+use core_lib::adapters::postgres_lock::PostgresLockService;
+use core_lib::adapters::postgres_repository::PostgresEventRepository;
+use core_lib::flight_recorder::FlightRecorder;
 use core_lib::adapters::redis_event_bus::RedisEventBus;
-use core_lib::{CoreError, EventPublisher}; // Remove EventSubscriber import
-use dotenvy::dotenv;
-use futures_util::StreamExt; // Required for consumer.next()
-use lapin::{
-    Channel,
-    Consumer, // Import lapin types
-    options::{
-        BasicAckOptions, BasicConsumeOptions, BasicNackOptions, QueueBindOptions,
-        QueueDeclareOptions,
-    },
-    types::FieldTable,
+use core_lib::lock::HeldLock;
+use core_lib::services::DomainServices;
+use core_lib::{
+    CoreError, EventPublisher, EventSubscriber, HandlerOutcome, LockService, MessageHandler,
+    ReceivedEvent, Repository, Subscription,
 };
+use async_trait::async_trait;
+use dotenvy::dotenv;
 use prost::Message;
 use proto::{
+    aircraft::{
+        AircraftAdded, AircraftBaseTransferred, AircraftFlightRecorded, AircraftGrounded,
+        AircraftMaintenanceCompleted, AircraftRelocated, AircraftRetired, AircraftStatus,
+        AircraftStatusChanged,
+    },
+    pirep::{
+        PirepAmended, PirepApproved, PirepCancelled, PirepChangesRequested,
+        PirepFlightPlanAttached, PirepRejected, PirepStatus, PirepSubmitted, PirepValidated,
+    },
     tenant::TenantCreated,
     user::{ApiKeyGenerated, ApiKeyRevoked, Role, UserRegistered}, // Added ApiKey events
 };
@@ -21,11 +36,12 @@ use sqlx::PgPool;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::Row;
 use std::env;
+use std::path::PathBuf;
 use std::sync::Arc;
-// use tokio::sync::broadcast::error::RecvError; // Not needed for lapin consumer stream
+use std::time::Duration;
 // use tokio_postgres::NoTls; // No longer needed directly here
 use tracing::{Level, error, info, warn};
-use chrono::Utc;
+use chrono::{DateTime, Months, Utc};
 use serde_json::json;
 use tracing_subscriber::FmtSubscriber;
 use uuid::Uuid; // Needed for parsing UUIDs in handlers
@@ -37,6 +53,30 @@ type BoxError = Box<dyn std::error::Error + Send + Sync>;
 
 // Migrations are now handled by api-gateway (command side)
 
+// Event partitions: upcoming months are created ahead of time and, with EVENT_ARCHIVE_DIR
+// set, months older than EVENT_ARCHIVE_AFTER_DAYS are moved to files. One replica at a time.
+const PARTITION_MAINTENANCE_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const PARTITION_MAINTENANCE_LOCK: &str = "event-partition-maintenance";
+const PARTITION_MAINTENANCE_LOCK_TTL: Duration = Duration::from_secs(60);
+const PARTITION_MONTHS_AHEAD: u32 = 3;
+const DEFAULT_ARCHIVE_AFTER_DAYS: i64 = 365;
+
+// Builds the RabbitMQ topology from the environment:
+// RABBITMQ_EXCHANGE_NAME, RABBITMQ_PREFETCH and RABBITMQ_DEAD_LETTER_EXCHANGE (optional).
+// The API gateway reads the same variables and must see the same values.
+fn rabbitmq_topology() -> Result<RabbitMqTopology, BoxError> {
+    let exchange_name =
+        env::var("RABBITMQ_EXCHANGE_NAME").unwrap_or_else(|_| "albatross_exchange".to_string());
+    let prefetch = match env::var("RABBITMQ_PREFETCH") {
+        Ok(value) => Some(value.parse::<u16>().map_err(|e| {
+            format!("RABBITMQ_PREFETCH must be a number: {}", e)
+        })?),
+        Err(_) => None,
+    };
+    let dead_letter_exchange = env::var("RABBITMQ_DEAD_LETTER_EXCHANGE").ok();
+    Ok(projection_topology(exchange_name, prefetch, dead_letter_exchange))
+}
+
 #[tokio::main]
 async fn main() -> Result<(), BoxError> {
     // Load environment variables from .env file
@@ -56,16 +96,8 @@ async fn main() -> Result<(), BoxError> {
 
     // --- Configuration ---
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-    let rabbitmq_url = env::var("RABBITMQ_URL").expect("RABBITMQ_URL must be set");
-    let redis_url = env::var("REDIS_URL").expect("REDIS_URL must be set");
-    let exchange_name =
-        env::var("RABBITMQ_EXCHANGE_NAME").unwrap_or_else(|_| "albatross_exchange".to_string());
-    // Queue names can be derived or configured
-    let tenant_queue = "projection_worker_tenant_queue";
-    let user_queue = "projection_worker_user_queue";
-    // Routing keys should match what the publisher uses (e.g., "tenant.*", "user.*")
-    let tenant_routing_key = "tenant.*"; // Listen for all tenant events
-    let user_routing_key = "user.*"; // Listen for all user events
+    // "rabbitmq" (default) or "postgres" (LISTEN/NOTIFY, no broker needed)
+    let event_bus_kind = env::var("EVENT_BUS").unwrap_or_else(|_| "rabbitmq".to_string());
 
     // --- Database Setup ---
     // Migrations are now handled by api-gateway. Verify tables exist.
@@ -75,7 +107,7 @@ async fn main() -> Result<(), BoxError> {
         .await?;
 
     // Verify required tables exist (created by api-gateway migrations)
-    let tables = sqlx::query("SELECT tablename FROM pg_tables WHERE schemaname = 'public' AND tablename IN ('events', 'tenants', 'users', 'user_api_keys')")
+    let tables = sqlx::query("SELECT tablename FROM pg_tables WHERE schemaname = 'public' AND tablename IN ('events', 'tenants', 'users', 'user_api_keys', 'pireps')")
         .fetch_all(&temp_pool)
         .await?;
 
@@ -84,8 +116,8 @@ async fn main() -> Result<(), BoxError> {
         .map(|row| row.get::<String, &str>("tablename"))
         .collect();
 
-    if existing_tables.len() < 4 {
-        error!("Required tables missing. Expected: events, tenants, users, user_api_keys. Found: {:?}. Ensure api-gateway has started and created the database schema.", existing_tables);
+    if existing_tables.len() < 5 {
+        error!("Required tables missing. Expected: events, tenants, users, user_api_keys, pireps. Found: {:?}. Ensure api-gateway has started and created the database schema.", existing_tables);
         return Err("Database schema not ready - start api-gateway first".into());
     } else {
         info!("Database schema verification successful - all required tables exist: {:?}", existing_tables);
@@ -100,215 +132,229 @@ async fn main() -> Result<(), BoxError> {
     );
     info!("Database connection pool established.");
 
+    // --- Event Partition Maintenance ---
+    let archive = match env::var("EVENT_ARCHIVE_DIR") {
+        Ok(dir) => {
+            let after_days = match env::var("EVENT_ARCHIVE_AFTER_DAYS") {
+                Ok(value) => value.parse::<i64>().map_err(|e| {
+                    format!("EVENT_ARCHIVE_AFTER_DAYS must be a number: {}", e)
+                })?,
+                Err(_) => DEFAULT_ARCHIVE_AFTER_DAYS,
+            };
+            info!("Archiving event partitions older than {} days to {}.", after_days, dir);
+            Some((PathBuf::from(dir), chrono::Duration::days(after_days)))
+        }
+        Err(_) => {
+            info!("EVENT_ARCHIVE_DIR not set (event partitions are kept in the database)");
+            None
+        }
+    };
+    tokio::spawn(maintain_event_partitions(
+        PostgresEventPartitions::new(db_pool.as_ref().clone()),
+        Arc::new(PostgresLockService::new(db_pool.as_ref().clone())),
+        archive,
+    ));
+
     // --- Event Bus Setup ---
-    // Subscriber Bus (used for creating channels)
-    let subscriber_bus = Arc::new(
-        RabbitMqEventBus::new(&rabbitmq_url, &exchange_name) // Pass exchange name
-            .await
-            .expect("Failed to connect to RabbitMQ"),
-    );
-    info!("Connected to RabbitMQ.");
+    // The bus is also published to by the flight recorder, which changes aircraft
+    let (subscriber_bus, event_bus): (Arc<dyn EventSubscriber>, Arc<dyn EventPublisher>) = match event_bus_kind.as_str() {
+        "postgres" => {
+            let bus = Arc::new(PostgresEventBus::new(db_pool.as_ref().clone()));
+            info!("Using Postgres LISTEN/NOTIFY event bus.");
+            (bus.clone(), bus)
+        }
+        "rabbitmq" => {
+            let rabbitmq_url = env::var("RABBITMQ_URL").expect("RABBITMQ_URL must be set");
+            let topology = rabbitmq_topology()?;
+            // Reconnects and re-declares the topology on failure
+            let bus = RabbitMqEventBus::connect(RabbitMqConfig::new(rabbitmq_url, topology))
+                .await
+                .expect("Failed to connect to RabbitMQ");
+            info!("Connected to RabbitMQ.");
+            let bus = Arc::new(bus);
+            (bus.clone(), bus)
+        }
+        other => {
+            return Err(format!("Unknown EVENT_BUS '{}' (expected 'rabbitmq' or 'postgres')", other).into());
+        }
+    };
 
-    // Publisher (Redis for Notifications)
-    let notification_publisher: Arc<dyn EventPublisher> = Arc::new(
-        RedisEventBus::new(&redis_url, None) // Provide None for optional channel_prefix
-            .await
-            .expect("Failed to connect to Redis"),
-    );
-    info!("Connected to Redis for publishing notifications.");
+    // Publisher (Redis for Notifications). Without Redis, notifications are dropped.
+    let notification_publisher: Arc<dyn EventPublisher> = match env::var("REDIS_URL") {
+        Ok(redis_url) => {
+            let publisher = RedisEventBus::new(&redis_url, None) // Provide None for optional channel_prefix
+                .await
+                .expect("Failed to connect to Redis");
+            info!("Connected to Redis for publishing notifications.");
+            Arc::new(publisher)
+        }
+        Err(_) => {
+            warn!("REDIS_URL not set (real-time notifications disabled)");
+            Arc::new(InMemoryEventBus::default())
+        }
+    };
 
-    // --- Setup Consumers ---
-    let tenant_consumer: Consumer = setup_consumer(
-        // Add explicit type annotation
-        subscriber_bus.create_subscriber_channel().await?,
-        &exchange_name,
-        tenant_queue,
-        tenant_routing_key, // Use routing key for binding
-        "tenant_consumer_tag",
-    )
-    .await?;
+    // --- Setup Subscriptions ---
+    // Approved PIREPs are added to the airframe totals of the aircraft they were flown with
+    let events: Arc<dyn Repository> = Arc::new(PostgresEventRepository::new(db_pool.as_ref().clone()));
+    let flight_recorder: Arc<dyn MessageHandler> = Arc::new(FlightRecorder::new(
+        events.clone(),
+        events.clone(),
+        events,
+        event_bus,
+        DomainServices::default(),
+    ));
+
+    let handler: Arc<dyn MessageHandler> = Arc::new(ProjectionHandler {
+        db_pool,
+        publisher: notification_publisher,
+    });
+
+    let tenant_subscription = subscriber_bus
+        .subscribe(
+            Subscription::new(TENANT_QUEUE, TENANT_ROUTING_KEY),
+            Arc::clone(&handler),
+        )
+        .await?;
     info!("Tenant event consumer ready.");
 
-    let user_consumer: Consumer = setup_consumer(
-        // Add explicit type annotation
-        subscriber_bus.create_subscriber_channel().await?,
-        &exchange_name,
-        user_queue,
-        user_routing_key, // Use routing key for binding
-        "user_consumer_tag",
-    )
-    .await?;
+    let user_subscription = subscriber_bus
+        .subscribe(
+            Subscription::new(USER_QUEUE, USER_ROUTING_KEY),
+            Arc::clone(&handler),
+        )
+        .await?;
     info!("User event consumer ready.");
 
+    let pirep_subscription = subscriber_bus
+        .subscribe(
+            Subscription::new(PIREP_QUEUE, PIREP_ROUTING_KEY),
+            Arc::clone(&handler),
+        )
+        .await?;
+    info!("PIREP event consumer ready.");
+
+    let aircraft_subscription = subscriber_bus
+        .subscribe(
+            Subscription::new(AIRCRAFT_QUEUE, AIRCRAFT_ROUTING_KEY),
+            Arc::clone(&handler),
+        )
+        .await?;
+    info!("Aircraft event consumer ready.");
+
+    let flight_recorder_subscription = subscriber_bus
+        .subscribe(
+            Subscription::new(FLIGHT_RECORDER_QUEUE, PIREP_ROUTING_KEY),
+            flight_recorder,
+        )
+        .await?;
+    info!("Flight recorder ready.");
+
     info!("Projection Worker started successfully. Listening for events...");
 
-    // --- Main Event Consumption Loop ---
-    let mut tenant_consumer = tenant_consumer; // Make mutable for next()
-    let mut user_consumer = user_consumer; // Make mutable for next()
+    // Events are handled by the subscriptions, which survive broker restarts.
+    // They only stop on an unrecoverable error.
+    tokio::select! {
+        _ = tenant_subscription.closed() => warn!("Tenant consumer stopped."),
+        _ = user_subscription.closed() => warn!("User consumer stopped."),
+        _ = pirep_subscription.closed() => warn!("PIREP consumer stopped."),
+        _ = aircraft_subscription.closed() => warn!("Aircraft consumer stopped."),
+        _ = flight_recorder_subscription.closed() => warn!("Flight recorder stopped."),
+    }
+
+    info!("Projection Worker event loop finished."); // Should only happen on consumer shutdown
+
+    Ok(())
+}
+
+// --- Event Partition Maintenance ---
 
+// Periodically creates upcoming event partitions and archives old ones. Replicas that
+// don't get the lock skip the run; a run is abandoned if the lock is lost.
+async fn maintain_event_partitions(
+    partitions: PostgresEventPartitions,
+    locks: Arc<dyn LockService>,
+    archive: Option<(PathBuf, chrono::Duration)>,
+) {
+    let mut interval = tokio::time::interval(PARTITION_MAINTENANCE_INTERVAL);
     loop {
-        tokio::select! {
-            biased; // Ensure fairness between queues
-
-            // --- Tenant Events ---
-            maybe_delivery = tenant_consumer.next() => {
-                if let Some(delivery_result) = maybe_delivery {
-                    match delivery_result {
-                        Ok(delivery) => {
-                            info!("Received tenant event delivery.");
-                            // Attempt to get event type from headers again
-                            let event_type = delivery
-                                .properties
-                                .kind().clone() // Get message type from properties
-                                .map(|s| s.to_string())
-                                .unwrap_or_else(|| {
-                                    warn!("Received message without message type");
-                                    String::new()
-                                });
-                            let payload = delivery.data.clone(); // Clone payload for handler
-
-                            if event_type.is_empty() {
-                                error!("Cannot handle message without event type. NACKing.");
-                                if let Err(nack_err) = delivery.nack(BasicNackOptions { requeue: false, ..Default::default() }).await {
-                                     error!("Failed to NACK message without type: {}", nack_err);
-                                }
-                                continue; // Skip to next iteration
-                            }
-
-                            match handle_event(payload, event_type.clone(), Arc::clone(&db_pool), Arc::clone(&notification_publisher)).await {
-                                Ok(_) => {
-                                    if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
-                                        error!("Failed to ACK tenant event: {}", e);
-                                    } else {
-                                         info!("Tenant event processed and ACKed.");
-                                    }
-                                }
-                                Err(e) => {
-                                    error!("Error handling tenant event: {}. NACKing...", e);
-                                    // Nack without requeueing to avoid poison messages
-                                    if let Err(nack_err) = delivery.nack(BasicNackOptions { requeue: false, ..Default::default() }).await {
-                                        error!("Failed to NACK tenant event: {}", nack_err);
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            error!("Error receiving tenant delivery: {}", e);
-                            // Consider reconnection logic here
-                            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-                        }
-                    }
-                } else {
-                     warn!("Tenant consumer stream ended.");
-                     break; // Or implement reconnection logic
+        interval.tick().await;
+        let lease = match locks
+            .try_acquire(PARTITION_MAINTENANCE_LOCK, PARTITION_MAINTENANCE_LOCK_TTL)
+            .await
+        {
+            Ok(Some(lease)) => lease,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("Failed to take the partition maintenance lock: {}", e);
+                continue;
+            }
+        };
+        let held = HeldLock::new(Arc::clone(&locks), lease);
+
+        let run = async {
+            let now = Utc::now();
+            partitions
+                .create_partitions(now, now + Months::new(PARTITION_MONTHS_AHEAD))
+                .await?;
+            if let Some((dir, after)) = &archive {
+                let archived = partitions.archive_before(now - *after, dir).await?;
+                if !archived.is_empty() {
+                    info!("Archived {} event partitions.", archived.len());
                 }
-            },
-
-             // --- User Events --- (Similar logic as Tenant Events)
-            maybe_delivery = user_consumer.next() => {
-                if let Some(delivery_result) = maybe_delivery {
-                    match delivery_result {
-                        Ok(delivery) => {
-                            info!("Received user event delivery.");
-                             // Attempt to get event type from headers again
-                            let event_type = delivery
-                                .properties
-                                .kind().clone() // Get message type from properties
-                                .map(|s| s.to_string())
-                                .unwrap_or_else(|| {
-                                    warn!("Received message without message type");
-                                    String::new()
-                                });
-                            let payload = delivery.data.clone();
-
-                             if event_type.is_empty() {
-                                error!("Cannot handle message without event type. NACKing.");
-                                if let Err(nack_err) = delivery.nack(BasicNackOptions { requeue: false, ..Default::default() }).await {
-                                     error!("Failed to NACK message without type: {}", nack_err);
-                                }
-                                continue; // Skip to next iteration
-                            }
-
-                            match handle_event(payload, event_type.clone(), Arc::clone(&db_pool), Arc::clone(&notification_publisher)).await {
-                                Ok(_) => {
-                                    if let Err(e) = delivery.ack(BasicAckOptions::default()).await {
-                                        error!("Failed to ACK user event: {}", e);
-                                    } else {
-                                         info!("User event processed and ACKed.");
-                                    }
-                                }
-                                Err(e) => {
-                                    error!("Error handling user event: {}. NACKing...", e);
-                                    if let Err(nack_err) = delivery.nack(BasicNackOptions { requeue: false, ..Default::default() }).await {
-                                        error!("Failed to NACK user event: {}", nack_err);
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            error!("Error receiving user delivery: {}", e);
-                            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
-                        }
-                    }
-                } else {
-                     warn!("User consumer stream ended.");
-                     break; // Or implement reconnection logic
+            }
+            Ok::<(), CoreError>(())
+        };
+        tokio::select! {
+            result = run => {
+                if let Err(e) = result {
+                    error!("Event partition maintenance failed: {}", e);
                 }
-            },
+            }
+            _ = held.lost() => warn!("Lost the partition maintenance lock; run abandoned."),
+        }
+        if let Err(e) = held.release().await {
+            warn!("Failed to release the partition maintenance lock: {}", e);
         }
     }
+}
 
-    info!("Projection Worker event loop finished."); // Should only happen on break/error
+// --- Subscription Handler ---
 
-    Ok(())
+// Projects every event received from the bus. Failures are NACKed without
+// requeueing to avoid poison messages.
+struct ProjectionHandler {
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
 }
 
-// --- Consumer Setup Helper ---
-async fn setup_consumer(
-    channel: Channel,
-    exchange: &str,
-    queue_name: &str,
-    routing_key: &str,
-    consumer_tag: &str,
-) -> Result<Consumer, lapin::Error> {
-    // Declare the queue
-    channel
-        .queue_declare(
-            queue_name,
-            QueueDeclareOptions {
-                durable: true, // Ensure queue survives restarts
-                ..Default::default()
-            },
-            FieldTable::default(),
-        )
-        .await?;
-    info!("Queue '{}' declared.", queue_name);
-
-    // Bind the queue to the exchange
-    channel
-        .queue_bind(
-            queue_name,
-            exchange,
-            routing_key, // Use the specific routing key for binding
-            QueueBindOptions::default(),
-            FieldTable::default(),
-        )
-        .await?;
-    info!(
-        "Queue '{}' bound to exchange '{}' with key '{}'.",
-        queue_name, exchange, routing_key
-    );
+#[async_trait]
+impl MessageHandler for ProjectionHandler {
+    async fn handle(&self, event: ReceivedEvent) -> HandlerOutcome {
+        info!("Received event delivery on {}.", event.topic);
+        if event.event_type.is_empty() {
+            error!("Cannot handle message without event type. NACKing.");
+            return HandlerOutcome::Nack;
+        }
 
-    // Start consuming messages
-    let consumer = channel
-        .basic_consume(
-            queue_name,
-            consumer_tag, // Unique consumer tag
-            BasicConsumeOptions::default(),
-            FieldTable::default(),
+        match handle_event(
+            event.payload,
+            event.event_type,
+            Arc::clone(&self.db_pool),
+            Arc::clone(&self.publisher),
         )
-        .await?;
-    Ok(consumer)
+        .await
+        {
+            Ok(_) => {
+                info!("Event processed and ACKed.");
+                HandlerOutcome::Ack
+            }
+            Err(e) => {
+                error!("Error handling event from {}: {}. NACKing...", event.topic, e);
+                HandlerOutcome::Nack
+            }
+        }
+    }
 }
 
 // --- Event Handling Logic ---
@@ -354,7 +400,139 @@ async fn handle_event(
             }
             Err(e) => error!("Failed to decode ApiKeyRevoked: {}", e),
         },
-        // TODO: Add other event types (PasswordChanged, PIREPSubmitted, etc.)
+        "PirepSubmitted" => match PirepSubmitted::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_pirep_submitted(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode PirepSubmitted: {}", e),
+        },
+        "PirepApproved" => match PirepApproved::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_pirep_approved(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode PirepApproved: {}", e),
+        },
+        "PirepRejected" => match PirepRejected::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_pirep_rejected(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode PirepRejected: {}", e),
+        },
+        "PirepChangesRequested" => match PirepChangesRequested::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_pirep_changes_requested(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode PirepChangesRequested: {}", e),
+        },
+        "PirepAmended" => match PirepAmended::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_pirep_amended(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode PirepAmended: {}", e),
+        },
+        "PirepCancelled" => match PirepCancelled::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_pirep_cancelled(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode PirepCancelled: {}", e),
+        },
+        "PirepValidated" => match PirepValidated::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_pirep_validated(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode PirepValidated: {}", e),
+        },
+        "PirepFlightPlanAttached" => match PirepFlightPlanAttached::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_pirep_flight_plan_attached(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode PirepFlightPlanAttached: {}", e),
+        },
+        "AircraftAdded" => match AircraftAdded::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_aircraft_added(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode AircraftAdded: {}", e),
+        },
+        "AircraftRetired" => match AircraftRetired::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_aircraft_retired(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode AircraftRetired: {}", e),
+        },
+        "AircraftStatusChanged" => match AircraftStatusChanged::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_aircraft_status_changed(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode AircraftStatusChanged: {}", e),
+        },
+        "AircraftBaseTransferred" => match AircraftBaseTransferred::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_aircraft_base_transferred(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode AircraftBaseTransferred: {}", e),
+        },
+        "AircraftRelocated" => match AircraftRelocated::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_aircraft_relocated(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode AircraftRelocated: {}", e),
+        },
+        "AircraftFlightRecorded" => match AircraftFlightRecorded::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_aircraft_flight_recorded(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode AircraftFlightRecorded: {}", e),
+        },
+        "AircraftGrounded" => match AircraftGrounded::decode(payload.as_slice()) {
+            Ok(event) => {
+                handle_aircraft_grounded(event, Arc::clone(&db_pool), Arc::clone(&publisher))
+                    .await
+                    .map_err(BoxError::from)?
+            }
+            Err(e) => error!("Failed to decode AircraftGrounded: {}", e),
+        },
+        "AircraftMaintenanceCompleted" => {
+            match AircraftMaintenanceCompleted::decode(payload.as_slice()) {
+                Ok(event) => handle_aircraft_maintenance_completed(
+                    event,
+                    Arc::clone(&db_pool),
+                    Arc::clone(&publisher),
+                )
+                .await
+                .map_err(BoxError::from)?,
+                Err(e) => error!("Failed to decode AircraftMaintenanceCompleted: {}", e),
+            }
+        }
+        // TODO: Add other event types (PasswordChanged, etc.)
         _ => {
             warn!("Received unknown event type: {}", event_type);
         }
@@ -636,3 +814,804 @@ async fn handle_api_key_revoked(
 
     Ok(())
 }
+
+// --- PIREP Projections ---
+
+// Read model status, e.g. "CHANGES_REQUESTED" for PIREP_STATUS_CHANGES_REQUESTED
+fn pirep_status_name(status: PirepStatus) -> &'static str {
+    let name = status.as_str_name();
+    name.strip_prefix("PIREP_STATUS_").unwrap_or(name)
+}
+
+// Events carry RFC 3339 timestamps; fall back to the projection time for bad ones
+fn parse_event_timestamp(timestamp: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|ts| ts.with_timezone(&Utc))
+        .unwrap_or_else(|e| {
+            warn!("Invalid event timestamp '{}': {}. Using current time.", timestamp, e);
+            Utc::now()
+        })
+}
+
+// Notifies everyone watching the tenant's PIREPs (tenant:{tid}:pireps)
+async fn publish_pirep_notification<E: serde::Serialize>(
+    publisher: &Arc<dyn EventPublisher>,
+    event_type: &str,
+    event: &E,
+    pirep_id: &str,
+    tenant_id: &str,
+) -> Result<(), CoreError> {
+    publish_tenant_notification(publisher, "pireps", event_type, event, pirep_id, tenant_id).await
+}
+
+// Sends an event envelope to a tenant channel (tenant:{tid}:{channel})
+async fn publish_tenant_notification<E: serde::Serialize>(
+    publisher: &Arc<dyn EventPublisher>,
+    channel: &str,
+    event_type: &str,
+    event: &E,
+    aggregate_id: &str,
+    tenant_id: &str,
+) -> Result<(), CoreError> {
+    let notification_topic = format!("tenant:{}:{}", tenant_id, channel);
+    let envelope = json!({
+        "event_type": event_type,
+        "ts": Utc::now().to_rfc3339(),
+        "data": event,
+        "meta": {
+            "tenant_id": tenant_id,
+            "aggregate_id": aggregate_id,
+            "version": serde_json::Value::Null
+        }
+    });
+    let notification_payload = serde_json::to_vec(&envelope).map_err(|e| {
+        CoreError::Serialization(format!("Failed to serialize {} envelope: {}", event_type, e))
+    })?;
+    publisher
+        .publish(&notification_topic, event_type, &notification_payload)
+        .await?;
+    info!(
+        "Published {} notification to topic: {}",
+        event_type, notification_topic
+    );
+    Ok(())
+}
+
+async fn handle_pirep_submitted(
+    event: PirepSubmitted,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!(
+        "Projecting PirepSubmitted: ID = {}, Pilot = {}, Tenant = {}",
+        event.pirep_id, event.user_id, event.tenant_id
+    );
+
+    // Runtime query: the offline query data only covers the original projections.
+    // A redelivered event leaves the existing row alone. A zero landing rate was not reported,
+    // and a zero distance comes from PIREPs filed before airports were checked.
+    sqlx::query(
+        "INSERT INTO pireps (pirep_id, tenant_id, user_id, aircraft_id, departure_icao, arrival_icao,
+                             flight_number, flight_time_hours, remarks, status, submitted_at,
+                             landing_rate_fpm, track_key, route_distance_nm)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, NULLIF($12, 0), $13, NULLIF($14, 0))
+         ON CONFLICT (pirep_id) DO NOTHING",
+    )
+    .bind(&event.pirep_id)
+    .bind(&event.tenant_id)
+    .bind(&event.user_id)
+    .bind(&event.aircraft_id)
+    .bind(&event.departure_icao)
+    .bind(&event.arrival_icao)
+    .bind(&event.flight_number)
+    .bind(event.flight_time_hours)
+    .bind(&event.remarks)
+    .bind(pirep_status_name(PirepStatus::Pending))
+    .bind(parse_event_timestamp(&event.timestamp))
+    .bind(event.landing_rate_fpm)
+    .bind(event.track.as_ref().map(|track| track.key.as_str()))
+    .bind(event.route_distance_nm)
+    .execute(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    info!("PIREP {} inserted into read model.", event.pirep_id);
+
+    publish_pirep_notification(&publisher, "PirepSubmitted", &event, &event.pirep_id, &event.tenant_id)
+        .await
+}
+
+// Records a review decision and returns the PIREP's tenant (None if the PIREP is unknown)
+async fn project_pirep_review(
+    db_pool: &PgPool,
+    pirep_id: &str,
+    status: PirepStatus,
+    reviewer_id: &str,
+    note: &str,
+    timestamp: &str,
+) -> Result<Option<String>, CoreError> {
+    sqlx::query_scalar::<_, String>(
+        "UPDATE pireps
+         SET status = $2, status_note = NULLIF($3, ''), reviewer_id = $4, reviewed_at = $5
+         WHERE pirep_id = $1
+         RETURNING tenant_id",
+    )
+    .bind(pirep_id)
+    .bind(pirep_status_name(status))
+    .bind(note)
+    .bind(reviewer_id)
+    .bind(parse_event_timestamp(timestamp))
+    .fetch_optional(db_pool)
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))
+}
+
+async fn handle_pirep_approved(
+    event: PirepApproved,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!(
+        "Projecting PirepApproved: ID = {}, Reviewer = {}",
+        event.pirep_id, event.reviewer_id
+    );
+    let tenant_id = project_pirep_review(
+        &db_pool,
+        &event.pirep_id,
+        PirepStatus::Approved,
+        &event.reviewer_id,
+        &event.comment,
+        &event.timestamp,
+    )
+    .await?;
+    match tenant_id {
+        Some(tenant_id) => {
+            project_aircraft_location(&db_pool, &event.pirep_id).await?;
+            publish_pirep_notification(&publisher, "PirepApproved", &event, &event.pirep_id, &tenant_id)
+                .await
+        }
+        None => {
+            warn!("PIREP {} not found in read model; approval not projected.", event.pirep_id);
+            Ok(())
+        }
+    }
+}
+
+async fn handle_pirep_rejected(
+    event: PirepRejected,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!(
+        "Projecting PirepRejected: ID = {}, Reviewer = {}",
+        event.pirep_id, event.reviewer_id
+    );
+    let tenant_id = project_pirep_review(
+        &db_pool,
+        &event.pirep_id,
+        PirepStatus::Rejected,
+        &event.reviewer_id,
+        &event.reason,
+        &event.timestamp,
+    )
+    .await?;
+    match tenant_id {
+        Some(tenant_id) => {
+            publish_pirep_notification(&publisher, "PirepRejected", &event, &event.pirep_id, &tenant_id)
+                .await
+        }
+        None => {
+            warn!("PIREP {} not found in read model; rejection not projected.", event.pirep_id);
+            Ok(())
+        }
+    }
+}
+
+async fn handle_pirep_changes_requested(
+    event: PirepChangesRequested,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!(
+        "Projecting PirepChangesRequested: ID = {}, Reviewer = {}",
+        event.pirep_id, event.reviewer_id
+    );
+    let tenant_id = project_pirep_review(
+        &db_pool,
+        &event.pirep_id,
+        PirepStatus::ChangesRequested,
+        &event.reviewer_id,
+        &event.reason,
+        &event.timestamp,
+    )
+    .await?;
+    match tenant_id {
+        Some(tenant_id) => {
+            publish_pirep_notification(
+                &publisher,
+                "PirepChangesRequested",
+                &event,
+                &event.pirep_id,
+                &tenant_id,
+            )
+            .await
+        }
+        None => {
+            warn!("PIREP {} not found in read model; change request not projected.", event.pirep_id);
+            Ok(())
+        }
+    }
+}
+
+async fn handle_pirep_amended(
+    event: PirepAmended,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!("Projecting PirepAmended: ID = {}", event.pirep_id);
+
+    // Amending puts the PIREP back in the review queue; the reviewer's note and the rule
+    // results of the old details no longer apply. A PirepValidated follows if the tenant has rules.
+    let tenant_id = sqlx::query_scalar::<_, String>(
+        "UPDATE pireps
+         SET aircraft_id = $2, departure_icao = $3, arrival_icao = $4, flight_number = $5,
+             flight_time_hours = $6, remarks = $7, status = $8, status_note = NULL,
+             landing_rate_fpm = NULLIF($9, 0), route_distance_nm = NULLIF($10, 0),
+             rule_results = NULL, flagged = FALSE
+         WHERE pirep_id = $1
+         RETURNING tenant_id",
+    )
+    .bind(&event.pirep_id)
+    .bind(&event.aircraft_id)
+    .bind(&event.departure_icao)
+    .bind(&event.arrival_icao)
+    .bind(&event.flight_number)
+    .bind(event.flight_time_hours)
+    .bind(&event.remarks)
+    .bind(pirep_status_name(PirepStatus::Pending))
+    .bind(event.landing_rate_fpm)
+    .bind(event.route_distance_nm)
+    .fetch_optional(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    match tenant_id {
+        Some(tenant_id) => {
+            publish_pirep_notification(&publisher, "PirepAmended", &event, &event.pirep_id, &tenant_id)
+                .await
+        }
+        None => {
+            warn!("PIREP {} not found in read model; amendment not projected.", event.pirep_id);
+            Ok(())
+        }
+    }
+}
+
+async fn handle_pirep_cancelled(
+    event: PirepCancelled,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!("Projecting PirepCancelled: ID = {}", event.pirep_id);
+
+    let tenant_id = sqlx::query_scalar::<_, String>(
+        "UPDATE pireps
+         SET status = $2, status_note = NULLIF($3, '')
+         WHERE pirep_id = $1
+         RETURNING tenant_id",
+    )
+    .bind(&event.pirep_id)
+    .bind(pirep_status_name(PirepStatus::Cancelled))
+    .bind(&event.reason)
+    .fetch_optional(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    match tenant_id {
+        Some(tenant_id) => {
+            publish_pirep_notification(&publisher, "PirepCancelled", &event, &event.pirep_id, &tenant_id)
+                .await
+        }
+        None => {
+            warn!("PIREP {} not found in read model; cancellation not projected.", event.pirep_id);
+            Ok(())
+        }
+    }
+}
+
+async fn handle_pirep_validated(
+    event: PirepValidated,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!(
+        "Projecting PirepValidated: ID = {}, Auto-accepted = {}",
+        event.pirep_id, event.auto_accepted
+    );
+
+    let results = serde_json::to_value(&event.results).map_err(|e| {
+        CoreError::Serialization(format!("Failed to serialize rule results: {}", e))
+    })?;
+    // Reviewers see why a PIREP was flagged without opening the results
+    let failures = event
+        .results
+        .iter()
+        .filter(|r| !r.passed)
+        .map(|r| r.message.as_str())
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    // An auto-accepted PIREP is approved as of the evaluation, with no reviewer
+    let tenant_id = sqlx::query_scalar::<_, String>(
+        "UPDATE pireps
+         SET rule_results = $2, flagged = NOT $3,
+             status = CASE WHEN $3 THEN $4 ELSE status END,
+             reviewed_at = CASE WHEN $3 THEN $5 ELSE reviewed_at END,
+             status_note = NULLIF($6, '')
+         WHERE pirep_id = $1
+         RETURNING tenant_id",
+    )
+    .bind(&event.pirep_id)
+    .bind(results)
+    .bind(event.auto_accepted)
+    .bind(pirep_status_name(PirepStatus::Approved))
+    .bind(parse_event_timestamp(&event.timestamp))
+    .bind(&failures)
+    .fetch_optional(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    match tenant_id {
+        Some(tenant_id) => {
+            if event.auto_accepted {
+                project_aircraft_location(&db_pool, &event.pirep_id).await?;
+            }
+            publish_pirep_notification(&publisher, "PirepValidated", &event, &event.pirep_id, &tenant_id)
+                .await
+        }
+        None => {
+            warn!("PIREP {} not found in read model; validation not projected.", event.pirep_id);
+            Ok(())
+        }
+    }
+}
+
+// Moves the PIREP's aircraft to its arrival airport. Only a PIREP submitted after the
+// aircraft got to where it is moves it, so a late approval of an older flight or one
+// flown before a relocation leaves the aircraft alone. PIREPs flown in aircraft that are
+// not in the fleet change nothing.
+async fn project_aircraft_location(db_pool: &PgPool, pirep_id: &str) -> Result<(), CoreError> {
+    let moved = sqlx::query(
+        "UPDATE aircraft
+         SET location_icao = p.arrival_icao, location_since = p.submitted_at,
+             location_pirep_id = p.pirep_id
+         FROM pireps p
+         WHERE p.pirep_id = $1
+           AND aircraft.aircraft_id = p.aircraft_id AND aircraft.tenant_id = p.tenant_id
+           AND aircraft.location_since <= p.submitted_at",
+    )
+    .bind(pirep_id)
+    .execute(db_pool)
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    if moved.rows_affected() > 0 {
+        info!("Aircraft of PIREP {} moved to its arrival airport.", pirep_id);
+    }
+    Ok(())
+}
+
+async fn handle_pirep_flight_plan_attached(
+    event: PirepFlightPlanAttached,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!("Projecting PirepFlightPlanAttached: ID = {}", event.pirep_id);
+
+    let plan = serde_json::to_value(&event.plan).map_err(|e| {
+        CoreError::Serialization(format!("Failed to serialize flight plan: {}", e))
+    })?;
+    let tenant_id = sqlx::query_scalar::<_, String>(
+        "UPDATE pireps
+         SET flight_plan = $2
+         WHERE pirep_id = $1
+         RETURNING tenant_id",
+    )
+    .bind(&event.pirep_id)
+    .bind(plan)
+    .fetch_optional(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    match tenant_id {
+        Some(tenant_id) => {
+            publish_pirep_notification(
+                &publisher,
+                "PirepFlightPlanAttached",
+                &event,
+                &event.pirep_id,
+                &tenant_id,
+            )
+            .await
+        }
+        None => {
+            warn!("PIREP {} not found in read model; flight plan not projected.", event.pirep_id);
+            Ok(())
+        }
+    }
+}
+
+// --- Fleet Projections ---
+
+// Read model status, e.g. "MAINTENANCE" for AIRCRAFT_STATUS_MAINTENANCE
+fn aircraft_status_name(status: AircraftStatus) -> &'static str {
+    let name = status.as_str_name();
+    name.strip_prefix("AIRCRAFT_STATUS_").unwrap_or(name)
+}
+
+async fn handle_aircraft_added(
+    event: AircraftAdded,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!(
+        "Projecting AircraftAdded: ID = {}, Registration = {}, Tenant = {}",
+        event.aircraft_id, event.registration, event.tenant_id
+    );
+
+    // A redelivered event leaves the existing row alone. New aircraft are at their home base.
+    sqlx::query(
+        "INSERT INTO aircraft (aircraft_id, tenant_id, registration, icao_type, name,
+                               home_base_icao, status, added_at, location_icao, location_since)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $6, $8)
+         ON CONFLICT (aircraft_id) DO NOTHING",
+    )
+    .bind(&event.aircraft_id)
+    .bind(&event.tenant_id)
+    .bind(&event.registration)
+    .bind(&event.icao_type)
+    .bind(&event.name)
+    .bind(&event.home_base_icao)
+    .bind(aircraft_status_name(AircraftStatus::Active))
+    .bind(parse_event_timestamp(&event.timestamp))
+    .execute(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    info!("Aircraft {} inserted into read model.", event.aircraft_id);
+
+    publish_tenant_notification(
+        &publisher,
+        "fleet",
+        "AircraftAdded",
+        &event,
+        &event.aircraft_id,
+        &event.tenant_id,
+    )
+    .await
+}
+
+async fn handle_aircraft_retired(
+    event: AircraftRetired,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!("Projecting AircraftRetired: ID = {}", event.aircraft_id);
+
+    let updated = sqlx::query(
+        "UPDATE aircraft
+         SET status = $2, status_note = NULLIF($3, ''), retired_at = $4
+         WHERE aircraft_id = $1",
+    )
+    .bind(&event.aircraft_id)
+    .bind(aircraft_status_name(AircraftStatus::Retired))
+    .bind(&event.reason)
+    .bind(parse_event_timestamp(&event.timestamp))
+    .execute(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    if updated.rows_affected() == 0 {
+        warn!("Aircraft {} not found in read model; retirement not projected.", event.aircraft_id);
+        return Ok(());
+    }
+    publish_tenant_notification(
+        &publisher,
+        "fleet",
+        "AircraftRetired",
+        &event,
+        &event.aircraft_id,
+        &event.tenant_id,
+    )
+    .await
+}
+
+async fn handle_aircraft_status_changed(
+    event: AircraftStatusChanged,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!(
+        "Projecting AircraftStatusChanged: ID = {}, Status = {:?}",
+        event.aircraft_id,
+        event.status()
+    );
+
+    let updated = sqlx::query(
+        "UPDATE aircraft
+         SET status = $2, status_note = NULLIF($3, '')
+         WHERE aircraft_id = $1",
+    )
+    .bind(&event.aircraft_id)
+    .bind(aircraft_status_name(event.status()))
+    .bind(&event.reason)
+    .execute(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    if updated.rows_affected() == 0 {
+        warn!("Aircraft {} not found in read model; status change not projected.", event.aircraft_id);
+        return Ok(());
+    }
+    publish_tenant_notification(
+        &publisher,
+        "fleet",
+        "AircraftStatusChanged",
+        &event,
+        &event.aircraft_id,
+        &event.tenant_id,
+    )
+    .await
+}
+
+async fn handle_aircraft_base_transferred(
+    event: AircraftBaseTransferred,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!(
+        "Projecting AircraftBaseTransferred: ID = {}, {} -> {}",
+        event.aircraft_id, event.from_icao, event.to_icao
+    );
+
+    let updated = sqlx::query(
+        "UPDATE aircraft
+         SET home_base_icao = $2
+         WHERE aircraft_id = $1",
+    )
+    .bind(&event.aircraft_id)
+    .bind(&event.to_icao)
+    .execute(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    if updated.rows_affected() == 0 {
+        warn!("Aircraft {} not found in read model; base transfer not projected.", event.aircraft_id);
+        return Ok(());
+    }
+    publish_tenant_notification(
+        &publisher,
+        "fleet",
+        "AircraftBaseTransferred",
+        &event,
+        &event.aircraft_id,
+        &event.tenant_id,
+    )
+    .await
+}
+
+async fn handle_aircraft_relocated(
+    event: AircraftRelocated,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!(
+        "Projecting AircraftRelocated: ID = {}, {} -> {}, By = {}",
+        event.aircraft_id, event.from_icao, event.to_icao, event.relocated_by
+    );
+    let relocated_at = parse_event_timestamp(&event.timestamp);
+
+    // The relocation history; a redelivered event is recorded once
+    sqlx::query(
+        "INSERT INTO aircraft_relocations (aircraft_id, tenant_id, from_icao, to_icao, reason,
+                                           relocated_by, relocated_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)
+         ON CONFLICT (aircraft_id, relocated_at) DO NOTHING",
+    )
+    .bind(&event.aircraft_id)
+    .bind(&event.tenant_id)
+    .bind(&event.from_icao)
+    .bind(&event.to_icao)
+    .bind(&event.reason)
+    .bind(&event.relocated_by)
+    .bind(relocated_at)
+    .execute(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    // A PIREP submitted after the relocation may already have moved the aircraft on
+    let updated = sqlx::query(
+        "UPDATE aircraft
+         SET location_icao = $2, location_since = $3, location_pirep_id = NULL
+         WHERE aircraft_id = $1 AND location_since <= $3",
+    )
+    .bind(&event.aircraft_id)
+    .bind(&event.to_icao)
+    .bind(relocated_at)
+    .execute(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    if updated.rows_affected() == 0 {
+        warn!(
+            "Aircraft {} not found in read model or moved since; location not changed.",
+            event.aircraft_id
+        );
+    }
+    publish_tenant_notification(
+        &publisher,
+        "fleet",
+        "AircraftRelocated",
+        &event,
+        &event.aircraft_id,
+        &event.tenant_id,
+    )
+    .await
+}
+
+async fn handle_aircraft_flight_recorded(
+    event: AircraftFlightRecorded,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!(
+        "Projecting AircraftFlightRecorded: ID = {}, PIREP = {}, Hours = {}, Cycles = {}",
+        event.aircraft_id, event.pirep_id, event.airframe_hours, event.cycles
+    );
+
+    // The event carries the totals; a redelivered older flight does not roll them back
+    let updated = sqlx::query(
+        "UPDATE aircraft
+         SET airframe_hours = $2, cycles = $3
+         WHERE aircraft_id = $1 AND cycles < $3",
+    )
+    .bind(&event.aircraft_id)
+    .bind(event.airframe_hours)
+    .bind(event.cycles as i32)
+    .execute(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    if updated.rows_affected() == 0 {
+        warn!(
+            "Aircraft {} not found in read model or already has these totals.",
+            event.aircraft_id
+        );
+        return Ok(());
+    }
+    publish_tenant_notification(
+        &publisher,
+        "fleet",
+        "AircraftFlightRecorded",
+        &event,
+        &event.aircraft_id,
+        &event.tenant_id,
+    )
+    .await
+}
+
+async fn handle_aircraft_grounded(
+    event: AircraftGrounded,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!(
+        "Projecting AircraftGrounded: ID = {}, Due = {:?}",
+        event.aircraft_id, event.inspections
+    );
+    let grounded_at = parse_event_timestamp(&event.timestamp);
+
+    // The maintenance history; a redelivered event is recorded once
+    sqlx::query(
+        "INSERT INTO aircraft_maintenance (aircraft_id, tenant_id, kind, inspections,
+                                           airframe_hours, cycles, occurred_at)
+         VALUES ($1, $2, 'GROUNDED', $3, $4, $5, $6)
+         ON CONFLICT (aircraft_id, kind, occurred_at) DO NOTHING",
+    )
+    .bind(&event.aircraft_id)
+    .bind(&event.tenant_id)
+    .bind(json!(event.inspections))
+    .bind(event.airframe_hours)
+    .bind(event.cycles as i32)
+    .bind(grounded_at)
+    .execute(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    let updated = sqlx::query(
+        "UPDATE aircraft
+         SET status = $2, status_note = $3
+         WHERE aircraft_id = $1",
+    )
+    .bind(&event.aircraft_id)
+    .bind(aircraft_status_name(AircraftStatus::Grounded))
+    .bind(format!("Due: {}", event.inspections.join(", ")))
+    .execute(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    if updated.rows_affected() == 0 {
+        warn!("Aircraft {} not found in read model; grounding not projected.", event.aircraft_id);
+        return Ok(());
+    }
+    publish_tenant_notification(
+        &publisher,
+        "fleet",
+        "AircraftGrounded",
+        &event,
+        &event.aircraft_id,
+        &event.tenant_id,
+    )
+    .await
+}
+
+async fn handle_aircraft_maintenance_completed(
+    event: AircraftMaintenanceCompleted,
+    db_pool: Arc<PgPool>,
+    publisher: Arc<dyn EventPublisher>,
+) -> Result<(), CoreError> {
+    info!(
+        "Projecting AircraftMaintenanceCompleted: ID = {}, Done = {:?}, Released = {}",
+        event.aircraft_id, event.inspections, event.released
+    );
+
+    sqlx::query(
+        "INSERT INTO aircraft_maintenance (aircraft_id, tenant_id, kind, inspections, notes,
+                                           completed_by, airframe_hours, cycles, released,
+                                           occurred_at)
+         VALUES ($1, $2, 'COMPLETED', $3, $4, $5, $6, $7, $8, $9)
+         ON CONFLICT (aircraft_id, kind, occurred_at) DO NOTHING",
+    )
+    .bind(&event.aircraft_id)
+    .bind(&event.tenant_id)
+    .bind(json!(event.inspections))
+    .bind(&event.notes)
+    .bind(&event.completed_by)
+    .bind(event.airframe_hours)
+    .bind(event.cycles as i32)
+    .bind(event.released)
+    .bind(parse_event_timestamp(&event.timestamp))
+    .execute(db_pool.as_ref())
+    .await
+    .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+    if event.released {
+        let updated = sqlx::query(
+            "UPDATE aircraft
+             SET status = $2, status_note = NULL
+             WHERE aircraft_id = $1 AND status = $3",
+        )
+        .bind(&event.aircraft_id)
+        .bind(aircraft_status_name(AircraftStatus::Active))
+        .bind(aircraft_status_name(AircraftStatus::Grounded))
+        .execute(db_pool.as_ref())
+        .await
+        .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+        if updated.rows_affected() == 0 {
+            warn!(
+                "Aircraft {} not found in read model or not grounded; release not projected.",
+                event.aircraft_id
+            );
+        }
+    }
+    publish_tenant_notification(
+        &publisher,
+        "fleet",
+        "AircraftMaintenanceCompleted",
+        &event,
+        &event.aircraft_id,
+        &event.tenant_id,
+    )
+    .await
+}
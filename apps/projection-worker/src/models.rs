@@ -35,3 +35,24 @@ pub struct ApiKeyDetails {
     pub revoked_at: Option<DateTime<Utc>>,
     pub last_used_at: Option<DateTime<Utc>>,
 }
+
+// Represents the 'pireps' read model table
+#[derive(FromRow, Debug)]
+pub struct PirepDetails {
+    pub pirep_id: String,
+    pub tenant_id: String,
+    pub user_id: String,
+    pub aircraft_id: String,
+    pub departure_icao: String,
+    pub arrival_icao: String,
+    pub flight_number: String,
+    pub flight_time_hours: f64,
+    pub remarks: String,
+    pub status: String, // PirepStatus name without the PIREP_STATUS_ prefix
+    pub status_note: Option<String>,
+    pub reviewer_id: Option<String>,
+    pub submitted_at: DateTime<Utc>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
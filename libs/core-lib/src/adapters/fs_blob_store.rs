@@ -0,0 +1,381 @@
+use super::content_sha256;
+use crate::{Blob, BlobAccess, BlobLimits, BlobMetadata, BlobStore, CoreError};
+use async_trait::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+
+// Stored next to each blob, under `meta/`
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredMetadata {
+    content_type: String,
+    size: u64,
+    sha256: String,
+}
+
+#[derive(Debug, Clone)]
+struct Presigner {
+    base_url: String,
+    secret: Vec<u8>,
+}
+
+/// Local filesystem implementation of the BlobStore port.
+///
+/// Content lives under `{root}/blobs/{key}` and metadata under `{root}/meta/{key}.json`;
+/// both are written to a temporary file and renamed into place. Presigned URLs point at
+/// `{base_url}/{key}` and carry an HMAC that the serving route checks with
+/// `verify_presigned`.
+#[derive(Debug, Clone)]
+pub struct FsBlobStore {
+    root: PathBuf,
+    limits: BlobLimits,
+    presigner: Option<Presigner>,
+}
+
+impl FsBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            limits: BlobLimits::default(),
+            presigner: None,
+        }
+    }
+
+    pub fn with_limits(mut self, limits: BlobLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Enables `presign`, signing URLs under `base_url` with `secret`.
+    pub fn with_presigning(mut self, base_url: &str, secret: &[u8]) -> Self {
+        self.presigner = Some(Presigner {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            secret: secret.to_vec(),
+        });
+        self
+    }
+
+    fn blob_path(&self, key: &str) -> PathBuf {
+        self.root.join("blobs").join(key)
+    }
+
+    fn metadata_path(&self, key: &str) -> PathBuf {
+        self.root.join("meta").join(format!("{}.json", key))
+    }
+
+    fn presigner(&self) -> Result<&Presigner, CoreError> {
+        self.presigner.as_ref().ok_or_else(|| {
+            CoreError::Configuration(
+                "Presigned URLs are not enabled for this blob store".to_string(),
+            )
+        })
+    }
+
+    /// Checks the `expires` and `signature` query parameters of a presigned URL.
+    pub fn verify_presigned(
+        &self,
+        key: &str,
+        access: BlobAccess,
+        expires: i64,
+        signature: &str,
+    ) -> Result<(), CoreError> {
+        let presigner = self.presigner()?;
+        if expires < Utc::now().timestamp() {
+            return Err(CoreError::Unauthorized(
+                "Presigned URL has expired".to_string(),
+            ));
+        }
+        let signature = hex::decode(signature)
+            .map_err(|_| CoreError::Unauthorized("Malformed URL signature".to_string()))?;
+        presign_mac(&presigner.secret, key, access, expires)
+            .verify_slice(&signature)
+            .map_err(|_| CoreError::Unauthorized("Invalid URL signature".to_string()))
+    }
+
+    async fn read_metadata(&self, key: &str) -> Result<Option<BlobMetadata>, CoreError> {
+        let json = match tokio::fs::read(self.metadata_path(key)).await {
+            Ok(json) => json,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(CoreError::Infrastructure(Box::new(e))),
+        };
+        let stored: StoredMetadata = serde_json::from_slice(&json).map_err(|e| {
+            CoreError::Deserialization(format!("Corrupt metadata for blob '{}': {}", key, e))
+        })?;
+        Ok(Some(BlobMetadata {
+            key: key.to_string(),
+            content_type: stored.content_type,
+            size: stored.size,
+            sha256: stored.sha256,
+        }))
+    }
+}
+
+fn access_name(access: BlobAccess) -> &'static str {
+    match access {
+        BlobAccess::Read => "read",
+        BlobAccess::Write => "write",
+    }
+}
+
+fn presign_mac(secret: &[u8], key: &str, access: BlobAccess, expires: i64) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}\n{}\n{}", access_name(access), key, expires).as_bytes());
+    mac
+}
+
+// Writes through a temporary file in the same directory so readers never see a partial file.
+async fn write_atomically(path: &Path, data: &[u8]) -> Result<(), CoreError> {
+    let parent = path.parent().ok_or_else(|| {
+        CoreError::Internal(format!("No parent directory for {}", path.display()))
+    })?;
+    tokio::fs::create_dir_all(parent)
+        .await
+        .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+    let temp_path = parent.join(format!(".{}.tmp", Uuid::new_v4()));
+    tokio::fs::write(&temp_path, data)
+        .await
+        .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+    if let Err(e) = tokio::fs::rename(&temp_path, path).await {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(CoreError::Infrastructure(Box::new(e)));
+    }
+    Ok(())
+}
+
+async fn remove_if_exists(path: &Path) -> Result<(), CoreError> {
+    match tokio::fs::remove_file(path).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(CoreError::Infrastructure(Box::new(e))),
+    }
+}
+
+#[async_trait]
+impl BlobStore for FsBlobStore {
+    async fn put(
+        &self,
+        key: &str,
+        content_type: &str,
+        data: &[u8],
+    ) -> Result<BlobMetadata, CoreError> {
+        self.limits.check(key, content_type, data.len() as u64)?;
+        let metadata = BlobMetadata {
+            key: key.to_string(),
+            content_type: content_type.to_string(),
+            size: data.len() as u64,
+            sha256: content_sha256(data),
+        };
+        let stored = StoredMetadata {
+            content_type: metadata.content_type.clone(),
+            size: metadata.size,
+            sha256: metadata.sha256.clone(),
+        };
+        let json = serde_json::to_vec(&stored).map_err(|e| {
+            CoreError::Serialization(format!("Failed to serialize blob metadata: {}", e))
+        })?;
+
+        // Content first: metadata is what makes a blob visible
+        write_atomically(&self.blob_path(key), data).await?;
+        write_atomically(&self.metadata_path(key), &json).await?;
+        Ok(metadata)
+    }
+
+    async fn get(&self, key: &str) -> Result<Blob, CoreError> {
+        crate::validate_blob_key(key)?;
+        let not_found = || CoreError::NotFound(format!("Blob '{}'", key));
+        let metadata = self.read_metadata(key).await?.ok_or_else(not_found)?;
+        let data = match tokio::fs::read(self.blob_path(key)).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Err(not_found()),
+            Err(e) => return Err(CoreError::Infrastructure(Box::new(e))),
+        };
+        Ok(Blob { metadata, data })
+    }
+
+    async fn head(&self, key: &str) -> Result<Option<BlobMetadata>, CoreError> {
+        crate::validate_blob_key(key)?;
+        self.read_metadata(key).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), CoreError> {
+        crate::validate_blob_key(key)?;
+        remove_if_exists(&self.metadata_path(key)).await?;
+        remove_if_exists(&self.blob_path(key)).await
+    }
+
+    async fn presign(
+        &self,
+        key: &str,
+        access: BlobAccess,
+        expires_in: Duration,
+    ) -> Result<String, CoreError> {
+        crate::validate_blob_key(key)?;
+        let presigner = self.presigner()?;
+        let expires = Utc::now().timestamp() + expires_in.as_secs() as i64;
+        let signature = presign_mac(&presigner.secret, key, access, expires).finalize();
+        Ok(format!(
+            "{}/{}?access={}&expires={}&signature={}",
+            presigner.base_url,
+            key,
+            access_name(access),
+            expires,
+            hex::encode(signature.into_bytes())
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store() -> (FsBlobStore, PathBuf) {
+        let root = std::env::temp_dir().join(format!("albatross-blobs-{}", Uuid::new_v4()));
+        (FsBlobStore::new(&root), root)
+    }
+
+    // Splits a presigned URL into its key and query parameters.
+    fn parse_presigned(url: &str, base_url: &str) -> (String, String, i64, String) {
+        let rest = url.strip_prefix(base_url).unwrap().trim_start_matches('/');
+        let (key, query) = rest.split_once('?').unwrap();
+        let params: std::collections::HashMap<_, _> = query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .collect();
+        (
+            key.to_string(),
+            params["access"].to_string(),
+            params["expires"].parse().unwrap(),
+            params["signature"].to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_put_get_head_delete() {
+        let (store, root) = temp_store();
+        let metadata = store
+            .put("tenants/t1/logo.png", "image/png", b"png-bytes")
+            .await
+            .unwrap();
+        assert_eq!(metadata.size, 9);
+        assert_eq!(metadata.sha256, content_sha256(b"png-bytes"));
+
+        let blob = store.get("tenants/t1/logo.png").await.unwrap();
+        assert_eq!(blob.data, b"png-bytes");
+        assert_eq!(blob.metadata, metadata);
+        assert_eq!(
+            store.head("tenants/t1/logo.png").await.unwrap(),
+            Some(metadata)
+        );
+
+        // Replacing keeps a single blob
+        store
+            .put("tenants/t1/logo.png", "image/jpeg", b"jpg")
+            .await
+            .unwrap();
+        let blob = store.get("tenants/t1/logo.png").await.unwrap();
+        assert_eq!(blob.metadata.content_type, "image/jpeg");
+        assert_eq!(blob.data, b"jpg");
+
+        store.delete("tenants/t1/logo.png").await.unwrap();
+        store.delete("tenants/t1/logo.png").await.unwrap();
+        assert!(matches!(
+            store.get("tenants/t1/logo.png").await,
+            Err(CoreError::NotFound(_))
+        ));
+        assert_eq!(store.head("tenants/t1/logo.png").await.unwrap(), None);
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_limits_and_keys_are_enforced() {
+        let (store, root) = temp_store();
+        let store = store.with_limits(BlobLimits {
+            max_size: 4,
+            content_types: vec!["image/png".to_string()],
+        });
+
+        for (key, content_type, data) in [
+            ("avatars/a.png", "image/png", &b"too large"[..]),
+            ("avatars/a.gif", "image/gif", &b"gif"[..]),
+            ("../escape.png", "image/png", &b"png"[..]),
+            ("/absolute.png", "image/png", &b"png"[..]),
+            ("avatars//a.png", "image/png", &b"png"[..]),
+        ] {
+            assert!(
+                matches!(
+                    store.put(key, content_type, data).await,
+                    Err(CoreError::Validation(_))
+                ),
+                "{} should be rejected",
+                key
+            );
+        }
+        assert!(store
+            .put("avatars/a.png", "image/png", b"png")
+            .await
+            .is_ok());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_presigned_urls_are_verified() {
+        let (store, root) = temp_store();
+        assert!(matches!(
+            store
+                .presign("a.png", BlobAccess::Read, Duration::from_secs(60))
+                .await,
+            Err(CoreError::Configuration(_))
+        ));
+
+        let base_url = "https://files.example.com/blobs";
+        let store = store.with_presigning(base_url, b"secret");
+        let url = store
+            .presign(
+                "pireps/p1/screenshot.png",
+                BlobAccess::Read,
+                Duration::from_secs(60),
+            )
+            .await
+            .unwrap();
+        let (key, access, expires, signature) = parse_presigned(&url, base_url);
+        assert_eq!(key, "pireps/p1/screenshot.png");
+        assert_eq!(access, "read");
+        assert!(store
+            .verify_presigned(&key, BlobAccess::Read, expires, &signature)
+            .is_ok());
+
+        // Any other key, access, expiry or secret fails
+        for result in [
+            store.verify_presigned(
+                "pireps/p2/screenshot.png",
+                BlobAccess::Read,
+                expires,
+                &signature,
+            ),
+            store.verify_presigned(&key, BlobAccess::Write, expires, &signature),
+            store.verify_presigned(&key, BlobAccess::Read, expires + 1, &signature),
+            store
+                .clone()
+                .with_presigning(base_url, b"other")
+                .verify_presigned(&key, BlobAccess::Read, expires, &signature),
+        ] {
+            assert!(matches!(result, Err(CoreError::Unauthorized(_))));
+        }
+        let expired = presign_mac(b"secret", &key, BlobAccess::Read, 1).finalize();
+        assert!(matches!(
+            store.verify_presigned(
+                &key,
+                BlobAccess::Read,
+                1,
+                &hex::encode(expired.into_bytes())
+            ),
+            Err(CoreError::Unauthorized(_))
+        ));
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -1,24 +1,94 @@
 use crate::{Cache, CoreError};
 use async_trait::async_trait;
+use dashmap::DashMap;
 use moka::future::Cache as MokaCache;
-use std::time::Duration;
+use moka::notification::RemovalCause;
+use moka::Expiry;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// A cached value together with its own time-to-live and the tags it was stored under.
+#[derive(Clone, Debug)]
+struct CacheEntry {
+    value: Vec<u8>,
+    ttl: Duration,
+    tags: Vec<String>,
+}
+
+/// Expires each entry after the TTL it was stored with, restarting the clock on overwrite.
+struct PerEntryTtl;
+
+impl Expiry<String, CacheEntry> for PerEntryTtl {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        entry: &CacheEntry,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(entry.ttl)
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        entry: &CacheEntry,
+        _updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(entry.ttl)
+    }
+}
+
+// Tag -> keys stored under it.
+type TagIndex = Arc<DashMap<String, HashSet<String>>>;
 
 /// In-memory implementation of the Cache port using Moka.
 /// Suitable for testing and single-executable mode.
 #[derive(Clone, Debug)]
 pub struct InMemoryCache {
-    cache: MokaCache<String, Vec<u8>>,
+    cache: MokaCache<String, CacheEntry>,
+    tags: TagIndex,
+    default_ttl: Duration,
 }
 
 impl InMemoryCache {
-    /// Creates a new InMemoryCache with specific capacity and default TTL settings.
+    /// Creates a new InMemoryCache with specific capacity and a TTL for entries stored without one.
     pub fn new(max_capacity: u64, default_ttl_seconds: u64) -> Self {
+        let tags: TagIndex = Arc::new(DashMap::new());
+        let index = tags.clone();
         let cache = MokaCache::builder()
             .max_capacity(max_capacity)
-            .time_to_live(Duration::from_secs(default_ttl_seconds))
-            // Consider adding time_to_idle as well if needed
+            .expire_after(PerEntryTtl)
+            .eviction_listener(move |key: Arc<String>, entry: CacheEntry, cause| {
+                // An overwrite re-registers the key itself; removing it here could drop the new tags.
+                if cause != RemovalCause::Replaced {
+                    unindex(&index, &key, &entry.tags);
+                }
+            })
             .build();
-        Self { cache }
+        Self {
+            cache,
+            tags,
+            default_ttl: Duration::from_secs(default_ttl_seconds),
+        }
+    }
+
+    fn entry(&self, value: &[u8], ttl_seconds: Option<u64>, tags: &[&str]) -> CacheEntry {
+        CacheEntry {
+            value: value.to_vec(),
+            ttl: ttl_seconds.map_or(self.default_ttl, Duration::from_secs),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+}
+
+fn unindex(index: &DashMap<String, HashSet<String>>, key: &str, tags: &[String]) {
+    for tag in tags {
+        index.remove_if_mut(tag, |_, keys| {
+            keys.remove(key);
+            keys.is_empty()
+        });
     }
 }
 
@@ -32,19 +102,17 @@ impl Default for InMemoryCache {
 #[async_trait]
 impl Cache for InMemoryCache {
     async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, CoreError> {
-        Ok(self.cache.get(key).await)
+        Ok(self.cache.get(key).await.map(|entry| entry.value))
     }
 
     async fn set(
         &self,
         key: &str,
         value: &[u8],
-        _ttl_seconds: Option<u64>, // Prefixed with underscore as it's unused in this adapter
+        ttl_seconds: Option<u64>,
     ) -> Result<(), CoreError> {
-        // Moka's async cache sets TTL at build time.
-        // The `insert` method respects the pre-configured TTL.
-        // We ignore the `ttl_seconds` parameter for this specific adapter.
-        self.cache.insert(key.to_string(), value.to_vec()).await;
+        let entry = self.entry(value, ttl_seconds, &[]);
+        self.cache.insert(key.to_string(), entry).await;
         Ok(())
     }
 
@@ -52,48 +120,52 @@ impl Cache for InMemoryCache {
         self.cache.invalidate(key).await;
         Ok(())
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tokio::time::sleep;
-
-    #[tokio::test]
-    async fn test_set_and_get() {
-        let cache = InMemoryCache::default();
-        let key = "test_key";
-        let value = b"test_value".to_vec();
-
-        cache.set(key, &value, None).await.unwrap();
-        let retrieved = cache.get(key).await.unwrap();
 
-        assert_eq!(retrieved, Some(value));
+    async fn set_tagged(
+        &self,
+        key: &str,
+        value: &[u8],
+        ttl_seconds: Option<u64>,
+        tags: &[&str],
+    ) -> Result<(), CoreError> {
+        for tag in tags {
+            self.tags
+                .entry(tag.to_string())
+                .or_default()
+                .insert(key.to_string());
+        }
+        let entry = self.entry(value, ttl_seconds, tags);
+        self.cache.insert(key.to_string(), entry).await;
+        Ok(())
     }
 
-    #[tokio::test]
-    async fn test_get_non_existent() {
-        let cache = InMemoryCache::default();
-        let key = "non_existent_key";
-
-        let retrieved = cache.get(key).await.unwrap();
-        assert_eq!(retrieved, None);
+    async fn invalidate_tag(&self, tag: &str) -> Result<(), CoreError> {
+        if let Some((_, keys)) = self.tags.remove(tag) {
+            for key in keys {
+                self.cache.invalidate(&key).await;
+            }
+        }
+        Ok(())
     }
 
-    #[tokio::test]
-    async fn test_delete() {
-        let cache = InMemoryCache::default();
-        let key = "delete_key";
-        let value = b"delete_value".to_vec();
-
-        cache.set(key, &value, None).await.unwrap();
-        let retrieved_before = cache.get(key).await.unwrap();
-        assert_eq!(retrieved_before, Some(value));
-
-        cache.delete(key).await.unwrap();
-        let retrieved_after = cache.get(key).await.unwrap();
-        assert_eq!(retrieved_after, None);
+    async fn invalidate_prefix(&self, prefix: &str) -> Result<(), CoreError> {
+        let keys: Vec<Arc<String>> = self
+            .cache
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .map(|(key, _)| key)
+            .collect();
+        for key in keys {
+            self.cache.invalidate(key.as_str()).await;
+        }
+        Ok(())
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::sleep;
 
     #[tokio::test]
     async fn test_entry_expires_based_on_default_ttl() {
@@ -103,7 +175,6 @@ mod tests {
         let key = "ttl_key";
         let value = b"ttl_value".to_vec();
 
-        // Set the value (ttl_seconds parameter is ignored by this adapter)
         cache.set(key, &value, None).await.unwrap();
         let retrieved_before = cache.get(key).await.unwrap();
         assert_eq!(
@@ -123,18 +194,7 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_overwrite() {
-        let cache = InMemoryCache::default();
-        let key = "overwrite_key";
-        let value1 = b"value1".to_vec();
-        let value2 = b"value2".to_vec();
-
-        cache.set(key, &value1, None).await.unwrap();
-        let retrieved1 = cache.get(key).await.unwrap();
-        assert_eq!(retrieved1, Some(value1));
-
-        cache.set(key, &value2, None).await.unwrap();
-        let retrieved2 = cache.get(key).await.unwrap();
-        assert_eq!(retrieved2, Some(value2));
+    async fn test_conformance() {
+        crate::conformance::cache_conformance(&InMemoryCache::default()).await;
     }
 }
@@ -1,58 +1,30 @@
-use crate::{CoreError, EventPublisher, EventSubscriber};
+use crate::adapters::deliver_locally;
+use crate::{
+    CoreError, EventPublisher, EventSubscriber, MessageHandler, ReceivedEvent, Subscription,
+    SubscriptionHandle,
+};
 use async_trait::async_trait;
-use dashmap::DashMap;
-use std::fmt::Debug;
 use std::sync::Arc;
-use tokio::sync::broadcast::{self, Sender};
-
-/// Represents a message published on the in-memory bus.
-#[derive(Clone, Debug)]
-pub struct InMemoryMessage {
-    pub topic: String,
-    pub event_type: String,
-    pub payload: Vec<u8>,
-}
+use tokio::sync::broadcast::{self, error::RecvError, Sender};
+use tracing::warn;
 
-/// In-memory implementation of the EventPublisher and EventSubscriber ports using Tokio broadcast channels.
+/// In-memory implementation of the EventPublisher and EventSubscriber ports using a Tokio broadcast channel.
 /// Suitable for testing and single-executable mode.
 ///
-/// Note: Tokio broadcast channels are multi-producer, multi-consumer, but if a receiver
-/// lags behind, it might miss messages. This might be acceptable for some testing scenarios
-/// but differs from persistent queues like RabbitMQ.
+/// Note: Every subscription receives every message and filters by its pattern. If a subscription
+/// lags behind by more than the channel capacity, it misses messages (a warning is logged).
+/// This might be acceptable for some testing scenarios but differs from persistent queues like RabbitMQ.
 #[derive(Debug, Clone)]
 pub struct InMemoryEventBus {
-    // Store: Topic Name -> Broadcast Sender
-    // We only store the Sender; Receivers are created on demand for subscribers.
-    channels: Arc<DashMap<String, Sender<InMemoryMessage>>>,
-    // Capacity for each new broadcast channel created.
-    channel_capacity: usize,
+    // Only the Sender is kept; each subscription gets its own Receiver.
+    sender: Sender<ReceivedEvent>,
 }
 
 impl InMemoryEventBus {
-    /// Creates a new InMemoryEventBus with a specific capacity for broadcast channels.
+    /// Creates a new InMemoryEventBus with a specific capacity for the broadcast channel.
     pub fn new(channel_capacity: usize) -> Self {
-        Self {
-            channels: Arc::new(DashMap::new()),
-            channel_capacity,
-        }
-    }
-
-    /// Gets or creates a broadcast sender for a given topic.
-    fn get_or_create_sender(&self, topic: &str) -> Sender<InMemoryMessage> {
-        self.channels
-            .entry(topic.to_string())
-            .or_insert_with(|| {
-                let (sender, _) = broadcast::channel(self.channel_capacity);
-                sender
-            })
-            .value()
-            .clone()
-    }
-
-    /// Subscribes to a specific topic, returning a broadcast receiver.
-    /// This is intended for internal use by adapters or test setups that need direct subscription.
-    pub fn subscribe(&self, topic: &str) -> broadcast::Receiver<InMemoryMessage> {
-        self.get_or_create_sender(topic).subscribe()
+        let (sender, _) = broadcast::channel(channel_capacity);
+        Self { sender }
     }
 }
 
@@ -71,73 +43,73 @@ impl EventPublisher for InMemoryEventBus {
         event_type: &str,
         event_payload: &[u8],
     ) -> Result<(), CoreError> {
-        let sender = self.get_or_create_sender(topic);
-        let message = InMemoryMessage {
+        let message = ReceivedEvent {
             topic: topic.to_string(),
             event_type: event_type.to_string(),
             payload: event_payload.to_vec(),
         };
 
-        // Sending on a broadcast channel returns the number of *active* subscribers.
-        // An error occurs only if there are *no* active subscribers.
+        // Sending on a broadcast channel fails only if there are *no* active subscribers.
         // In many pub/sub scenarios (like event sourcing), publishing to a topic
         // with no current subscribers is not an error.
-        match sender.send(message) {
-            Ok(_) => Ok(()),
-            Err(_) => {
-                // Log this situation? It means no one is listening *right now*.
-                // For an event bus, this is usually okay.
-                // println!("Warning: Published to topic '{}' with no active subscribers.", topic);
-                Ok(())
-            }
-        }
+        let _ = self.sender.send(message);
+        Ok(())
     }
 }
 
-// Basic implementation for the marker trait EventSubscriber.
-// The actual subscription logic happens within the components (like projection workers)
-// that *use* this bus, by calling the `subscribe` method.
 #[async_trait]
 impl EventSubscriber for InMemoryEventBus {
-    // This trait implementation doesn't actively *do* anything here.
-    // Components needing to subscribe will hold an instance of InMemoryEventBus
-    // and call its `subscribe(topic)` method to get a receiver.
+    async fn subscribe(
+        &self,
+        subscription: Subscription,
+        handler: Arc<dyn MessageHandler>,
+    ) -> Result<SubscriptionHandle, CoreError> {
+        // Subscribe before spawning so messages published after this call returns are delivered.
+        let mut receiver = self.sender.subscribe();
+        let task = tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if subscription.matches(&event.topic) => {
+                        deliver_locally(&subscription.name, handler.as_ref(), event).await;
+                    }
+                    Ok(_) => {}
+                    Err(RecvError::Lagged(missed)) => warn!(
+                        "Subscription '{}' lagged behind and missed {} messages",
+                        subscription.name, missed
+                    ),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+        Ok(SubscriptionHandle::new(task))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::HandlerOutcome;
+    use tokio::sync::mpsc::{self, UnboundedReceiver};
     use tokio::time::{timeout, Duration};
 
-    #[tokio::test]
-    async fn test_publish_and_subscribe() {
-        let bus = InMemoryEventBus::default();
-        let topic = "test-topic";
-        let event_type = "TestEvent";
-        let payload = b"hello world".to_vec();
-
-        // Subscribe *before* publishing
-        let mut receiver1 = bus.subscribe(topic);
-        let mut receiver2 = bus.subscribe(topic);
-
-        let publish_result = bus.publish(topic, event_type, &payload).await;
-        assert!(publish_result.is_ok());
-
-        // Check receiver 1
-        let recv_result1 = timeout(Duration::from_millis(100), receiver1.recv()).await;
-        assert!(recv_result1.is_ok(), "Receiver 1 timed out");
-        let message1 = recv_result1.unwrap().unwrap();
-        assert_eq!(message1.topic, topic);
-        assert_eq!(message1.event_type, event_type);
-        assert_eq!(message1.payload, payload);
-
-        // Check receiver 2
-        let recv_result2 = timeout(Duration::from_millis(100), receiver2.recv()).await;
-        assert!(recv_result2.is_ok(), "Receiver 2 timed out");
-        let message2 = recv_result2.unwrap().unwrap();
-        assert_eq!(message2.topic, topic);
-        assert_eq!(message2.event_type, event_type);
-        assert_eq!(message2.payload, payload);
+    // Subscribes a handler that forwards every delivered message to a channel and acks it.
+    async fn collect(
+        bus: &InMemoryEventBus,
+        pattern: &str,
+    ) -> (SubscriptionHandle, UnboundedReceiver<ReceivedEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let handler = move |event: ReceivedEvent| {
+            let tx = tx.clone();
+            async move {
+                let _ = tx.send(event);
+                HandlerOutcome::Ack
+            }
+        };
+        let handle = bus
+            .subscribe(Subscription::new("test", pattern), Arc::new(handler))
+            .await
+            .unwrap();
+        (handle, rx)
     }
 
     #[tokio::test]
@@ -165,12 +137,11 @@ mod tests {
         assert!(publish_result.is_ok());
 
         // Subscribe *after* publishing
-        let mut receiver = bus.subscribe(topic);
+        let (_handle, mut receiver) = collect(&bus, topic).await;
 
-        // Attempt to receive - should timeout or return RecvError::Lagged initially
-        // because broadcast doesn't guarantee delivery of past messages.
+        // Past messages are not replayed
         let recv_result = timeout(Duration::from_millis(50), receiver.recv()).await;
-        assert!(recv_result.is_err(), "Should have timed out or lagged");
+        assert!(recv_result.is_err(), "Should have timed out");
 
         // Now publish again
         let payload2 = b"second message".to_vec();
@@ -187,38 +158,8 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_multiple_topics() {
+    async fn test_conformance() {
         let bus = InMemoryEventBus::default();
-        let topic1 = "topic-1";
-        let topic2 = "topic-2";
-        let event_type = "MultiEvent";
-        let payload1 = b"message 1".to_vec();
-        let payload2 = b"message 2".to_vec();
-
-        let mut receiver1 = bus.subscribe(topic1);
-        let mut receiver2 = bus.subscribe(topic2);
-
-        // Publish to topic 1
-        bus.publish(topic1, event_type, &payload1).await.unwrap();
-
-        // Publish to topic 2
-        bus.publish(topic2, event_type, &payload2).await.unwrap();
-
-        // Check receiver 1 gets message 1
-        let recv_result1 = timeout(Duration::from_millis(100), receiver1.recv()).await;
-        assert!(recv_result1.is_ok(), "Receiver 1 timed out");
-        assert_eq!(recv_result1.unwrap().unwrap().payload, payload1);
-
-        // Check receiver 2 gets message 2
-        let recv_result2 = timeout(Duration::from_millis(100), receiver2.recv()).await;
-        assert!(recv_result2.is_ok(), "Receiver 2 timed out");
-        assert_eq!(recv_result2.unwrap().unwrap().payload, payload2);
-
-        // Check receiver 1 doesn't get message 2 (should timeout)
-        let recv_result1_again = timeout(Duration::from_millis(50), receiver1.recv()).await;
-        assert!(
-            recv_result1_again.is_err(),
-            "Receiver 1 got message meant for topic 2"
-        );
+        crate::conformance::publisher_conformance(&bus, &bus, Default::default()).await;
     }
 }
@@ -0,0 +1,152 @@
+use crate::{CoreError, LockLease, LockService};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+#[derive(Debug, Default)]
+struct LockState {
+    // Current holder and the instant its lease expires
+    holder: Option<(String, Instant)>,
+    // Kept after release so tokens keep increasing
+    last_token: u64,
+}
+
+/// In-memory implementation of the LockService port.
+/// Suitable for testing and single-executable mode; clones share the same locks.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryLockService {
+    locks: Arc<Mutex<HashMap<String, LockState>>>,
+}
+
+impl InMemoryLockService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// Returns the state of `name` with an expired holder cleared.
+fn live_state<'a>(locks: &'a mut HashMap<String, LockState>, name: &str) -> &'a mut LockState {
+    let state = locks.entry(name.to_string()).or_default();
+    if matches!(&state.holder, Some((_, expires_at)) if *expires_at <= Instant::now()) {
+        state.holder = None;
+    }
+    state
+}
+
+fn holds(state: &LockState, lease: &LockLease) -> bool {
+    matches!(&state.holder, Some((holder, _)) if *holder == lease.holder)
+}
+
+#[async_trait]
+impl LockService for InMemoryLockService {
+    async fn try_acquire(&self, name: &str, ttl: Duration) -> Result<Option<LockLease>, CoreError> {
+        let mut locks = self
+            .locks
+            .lock()
+            .expect("InMemoryLockService mutex poisoned");
+        let state = live_state(&mut locks, name);
+        if state.holder.is_some() {
+            return Ok(None);
+        }
+
+        let holder = Uuid::new_v4().to_string();
+        state.last_token += 1;
+        state.holder = Some((holder.clone(), Instant::now() + ttl));
+        Ok(Some(LockLease {
+            name: name.to_string(),
+            holder,
+            token: state.last_token,
+            ttl,
+        }))
+    }
+
+    async fn renew(&self, lease: &LockLease) -> Result<bool, CoreError> {
+        let mut locks = self
+            .locks
+            .lock()
+            .expect("InMemoryLockService mutex poisoned");
+        let state = live_state(&mut locks, &lease.name);
+        if !holds(state, lease) {
+            return Ok(false);
+        }
+        state.holder = Some((lease.holder.clone(), Instant::now() + lease.ttl));
+        Ok(true)
+    }
+
+    async fn release(&self, lease: &LockLease) -> Result<(), CoreError> {
+        let mut locks = self
+            .locks
+            .lock()
+            .expect("InMemoryLockService mutex poisoned");
+        let state = live_state(&mut locks, &lease.name);
+        if holds(state, lease) {
+            state.holder = None;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::sleep;
+
+    #[tokio::test]
+    async fn test_lock_is_exclusive_until_released() {
+        let locks = InMemoryLockService::new();
+        let ttl = Duration::from_secs(10);
+
+        let lease = locks.try_acquire("rebuild", ttl).await.unwrap().unwrap();
+        assert!(locks.try_acquire("rebuild", ttl).await.unwrap().is_none());
+        // Other names are independent
+        assert!(locks.try_acquire("scheduler", ttl).await.unwrap().is_some());
+
+        locks.release(&lease).await.unwrap();
+        let next = locks.try_acquire("rebuild", ttl).await.unwrap().unwrap();
+        assert!(next.token > lease.token);
+    }
+
+    #[tokio::test]
+    async fn test_expired_lease_is_taken_over_and_cannot_renew() {
+        let locks = InMemoryLockService::new();
+        let stale = locks
+            .try_acquire("rebuild", Duration::from_millis(50))
+            .await
+            .unwrap()
+            .unwrap();
+        sleep(Duration::from_millis(80)).await;
+
+        let current = locks
+            .try_acquire("rebuild", Duration::from_secs(10))
+            .await
+            .unwrap()
+            .expect("Expired lock should be free");
+        assert!(current.token > stale.token);
+        assert!(!locks.renew(&stale).await.unwrap());
+
+        // Releasing the stale lease leaves the current holder in place
+        locks.release(&stale).await.unwrap();
+        assert!(locks.renew(&current).await.unwrap());
+        assert!(locks
+            .try_acquire("rebuild", Duration::from_secs(10))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_renew_extends_lease() {
+        let locks = InMemoryLockService::new();
+        let lease = locks
+            .try_acquire("rebuild", Duration::from_millis(100))
+            .await
+            .unwrap()
+            .unwrap();
+        sleep(Duration::from_millis(60)).await;
+        assert!(locks.renew(&lease).await.unwrap());
+        sleep(Duration::from_millis(60)).await;
+        assert!(locks.renew(&lease).await.unwrap());
+    }
+}
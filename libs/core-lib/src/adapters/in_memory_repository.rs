@@ -1,23 +1,33 @@
-use crate::{CoreError, Repository}; // Removed Aggregate
+use crate::{validate_event_batch, CoreError, Repository, StoredEvent, TenantEventQuery};
 use async_trait::async_trait;
-use cqrs_es::persist::SerializedEvent;
 use dashmap::DashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
+// Events of one aggregate, each tagged with its global insertion position
+type EventStream = Vec<(u64, StoredEvent)>;
+
 /// In-memory implementation of the Repository port for testing and single-executable mode.
 /// Stores events associated with their aggregate ID and current version.
 #[derive(Debug, Clone, Default)]
 pub struct InMemoryEventRepository {
-    // Store: Aggregate ID -> (Current Version, Vec<StoredEventData>)
-    store: Arc<DashMap<String, (usize, Vec<SerializedEvent>)>>,
+    // Store: Aggregate ID -> (Current Version, EventStream)
+    store: Arc<DashMap<String, (usize, EventStream)>>,
+    // Global insertion counter, used to keep a stable order across aggregates
+    position: Arc<AtomicU64>,
 }
 
 #[async_trait]
 impl Repository for InMemoryEventRepository {
     /// Load events for a specific aggregate instance.
-    async fn load(&self, aggregate_id: &str) -> Result<Vec<SerializedEvent>, CoreError> {
+    async fn load(&self, aggregate_id: &str) -> Result<Vec<StoredEvent>, CoreError> {
         match self.store.get(aggregate_id) {
-            Some(entry) => Ok(entry.value().1.clone()), // Clone the StoredEventData vector
+            Some(entry) => Ok(entry
+                .value()
+                .1
+                .iter()
+                .map(|(_, event)| event.clone())
+                .collect()),
             None => Ok(Vec::new()),
         }
     }
@@ -27,11 +37,12 @@ impl Repository for InMemoryEventRepository {
         &self,
         aggregate_id: &str,
         expected_version: usize,
-        events: &[(String, Vec<u8>)], // Takes (event_type, payload) tuples
+        events: &[StoredEvent],
     ) -> Result<(), CoreError> {
         if events.is_empty() {
             return Ok(());
         }
+        validate_event_batch(aggregate_id, expected_version, events)?;
 
         let mut entry = self
             .store
@@ -49,46 +60,67 @@ impl Repository for InMemoryEventRepository {
         }
 
         // Append new events and update version
-        let mut next_sequence = *current_version + 1;
-        for (event_type, payload) in events {
-            existing_events.push(SerializedEvent::new(
-                aggregate_id.to_string(),
-                next_sequence,
-                "".to_string(),
-                event_type.clone(),
-                "".to_string(),
-                payload.clone(),
-                vec![],
-            ));
-            next_sequence += 1;
+        for event in events {
+            let position = self.position.fetch_add(1, Ordering::SeqCst);
+            existing_events.push((position, event.clone()));
         }
-        *current_version = next_sequence - 1; // Update version to the last sequence number used
+        *current_version = expected_version + events.len();
 
         Ok(())
     }
+
+    /// Load events of all aggregates belonging to a tenant.
+    async fn load_by_tenant(
+        &self,
+        tenant_id: &str,
+        query: &TenantEventQuery,
+    ) -> Result<Vec<StoredEvent>, CoreError> {
+        let mut matching: EventStream = self
+            .store
+            .iter()
+            .flat_map(|entry| entry.value().1.clone())
+            .filter(|(_, stored)| {
+                stored.tenant_id.as_deref() == Some(tenant_id)
+                    && query
+                        .aggregate_type
+                        .as_ref()
+                        .is_none_or(|t| &stored.event.aggregate_type == t)
+                    && query.since.is_none_or(|since| stored.timestamp >= since)
+            })
+            .collect();
+        matching.sort_by(|(pa, a), (pb, b)| a.timestamp.cmp(&b.timestamp).then(pa.cmp(pb)));
+
+        let limit = query.limit.unwrap_or(usize::MAX);
+        Ok(matching
+            .into_iter()
+            .take(limit)
+            .map(|(_, stored)| stored)
+            .collect())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::domain::user::{User, UserCommand, UserEvent};
-    use cqrs_es::Aggregate;
+    use crate::services::DomainServices;
+    use chrono::Utc;
+    use cqrs_es::persist::SerializedEvent;
+    use cqrs_es::{Aggregate, DomainEvent};
     use prost::Message;
-    use proto::user::{PasswordChanged, RegisterUser, UserRegistered};
+    use proto::user::RegisterUser;
 
-    // Helper to serialize events for saving (same as in postgres tests)
-    fn serialize_events(events: &[UserEvent]) -> Vec<(String, Vec<u8>)> {
+    // Helper to serialize events for saving, numbering them after `version`
+    fn serialize_events(
+        aggregate_id: &str,
+        version: usize,
+        tenant_id: Option<&str>,
+        events: &[UserEvent],
+    ) -> Vec<StoredEvent> {
         events
             .iter()
-            .map(|event| {
-                let event_type = match event {
-                    UserEvent::Registered(_) => "UserRegistered",
-                    UserEvent::PasswordChanged(_) => "PasswordChanged",
-                    UserEvent::ApiKeyGenerated(_) => "ApiKeyGenerated",
-                    UserEvent::ApiKeyRevoked(_) => "ApiKeyRevoked", // Added
-                    UserEvent::LoggedIn(_) => "UserLoggedIn",
-                }
-                .to_string();
+            .zip(version + 1..)
+            .map(|(event, sequence)| {
                 let payload = match event {
                     UserEvent::Registered(e) => e.encode_to_vec(),
                     UserEvent::PasswordChanged(e) => e.encode_to_vec(),
@@ -96,7 +128,19 @@ mod tests {
                     UserEvent::ApiKeyRevoked(e) => e.encode_to_vec(), // Added
                     UserEvent::LoggedIn(e) => e.encode_to_vec(),
                 };
-                (event_type, payload)
+                StoredEvent::new(
+                    SerializedEvent::new(
+                        aggregate_id.to_string(),
+                        sequence,
+                        User::TYPE.to_string(),
+                        event.event_type(),
+                        event.event_version(),
+                        payload,
+                        vec![],
+                    ),
+                    tenant_id.map(str::to_string),
+                    Utc::now(),
+                )
             })
             .collect()
     }
@@ -118,127 +162,35 @@ mod tests {
         });
         let default_user = User::default();
         let events_domain = default_user
-            .handle(command, &())
+            .handle(command, &DomainServices::default())
             .await
             .expect("Handle failed");
         // --- End Test Setup ---
 
-        let events_to_save = serialize_events(&events_domain);
+        let events_to_save =
+            serialize_events(&aggregate_id, 0, Some("tenant-inmem-test"), &events_domain);
 
         let result = repo.save(&aggregate_id, 0, &events_to_save).await;
         assert!(result.is_ok());
 
         let loaded_events = repo.load(&aggregate_id).await.unwrap();
         assert_eq!(loaded_events.len(), 1);
-        assert_eq!(loaded_events[0].sequence, 1);
-        assert_eq!(loaded_events[0].event_type, events_to_save[0].0);
-        assert_eq!(loaded_events[0].payload, events_to_save[0].1);
+        assert_eq!(loaded_events[0], events_to_save[0]);
+        assert_eq!(loaded_events[0].event.sequence, 1);
+        assert_eq!(loaded_events[0].event.aggregate_type, "user");
+        assert_eq!(loaded_events[0].event.event_version, "0.1.0");
+        assert_eq!(loaded_events[0].tenant_id.as_deref(), Some("tenant-inmem-test"));
 
         // Check internal state
         let entry = repo.store.get(&aggregate_id).unwrap();
         assert_eq!(entry.value().0, 1); // Version should be 1
     }
 
-    #[tokio::test]
-    async fn test_append_events() {
-        let repo = InMemoryEventRepository::default(); // No generic type needed
-        let aggregate_id = "agg-2".to_string();
-
-        // --- Setup initial event ---
-        let event1_domain = UserEvent::Registered(UserRegistered {
-            user_id: aggregate_id.clone(),
-            username: "test-inmem".to_string(),
-            email: "inmem@test.com".to_string(),
-            role: proto::user::Role::Pilot as i32,
-            tenant_id: None,
-            password_hash: "test-hash".to_string(),
-            timestamp: "0".to_string(),
-        });
-        let events_to_save1 = serialize_events(&[event1_domain]);
-        repo.save(&aggregate_id, 0, &events_to_save1).await.unwrap();
-        // --- End setup ---
-
-        // --- Setup second event ---
-        let event2_domain = UserEvent::PasswordChanged(PasswordChanged {
-            user_id: aggregate_id.clone(),
-            timestamp: "1".to_string(),
-        });
-        let events_to_save2 = serialize_events(&[event2_domain]);
-        // --- End setup ---
-
-        let result = repo.save(&aggregate_id, 1, &events_to_save2).await; // Expected version is 1
-        assert!(result.is_ok());
-
-        let loaded_events = repo.load(&aggregate_id).await.unwrap();
-        assert_eq!(loaded_events.len(), 2);
-        assert_eq!(loaded_events[0].sequence, 1);
-        assert_eq!(loaded_events[1].sequence, 2);
-        assert_eq!(loaded_events[1].event_type, events_to_save2[0].0);
-        assert_eq!(loaded_events[1].payload, events_to_save2[0].1);
-
-        let entry = repo.store.get(&aggregate_id).unwrap();
-        assert_eq!(entry.value().0, 2); // Version should be 2
-    }
-
-    #[tokio::test]
-    async fn test_concurrency_error() {
-        let repo = InMemoryEventRepository::default(); // No generic type needed
-        let aggregate_id = "agg-3".to_string();
-        // --- Setup initial event ---
-        let event1_domain = UserEvent::Registered(UserRegistered {
-            user_id: aggregate_id.clone(),
-            username: "concurr-user".to_string(),
-            email: "concurr@test.com".to_string(),
-            role: proto::user::Role::Pilot as i32,
-            tenant_id: None,
-            password_hash: "test-hash".to_string(),
-            timestamp: "0".to_string(),
-        });
-        let events_to_save1 = serialize_events(&[event1_domain]);
-        repo.save(&aggregate_id, 0, &events_to_save1).await.unwrap(); // Version is now 1
-                                                                      // --- End setup ---
-
-        // --- Setup second event ---
-        let event2_domain = UserEvent::PasswordChanged(PasswordChanged {
-            user_id: aggregate_id.clone(),
-            timestamp: "1".to_string(),
-        });
-        let events_to_save2 = serialize_events(&[event2_domain]);
-        // --- End setup ---
-
-        // Try to save with incorrect expected version (0 instead of 1)
-        let result = repo.save(&aggregate_id, 0, &events_to_save2).await;
-
-        assert!(result.is_err());
-        match result.err().unwrap() {
-            CoreError::Concurrency { expected, actual } => {
-                assert_eq!(expected, 0);
-                assert_eq!(actual, 1);
-            }
-            _ => panic!("Expected Concurrency error"),
-        }
-
-        // Ensure events were not saved
-        let loaded_events = repo.load(&aggregate_id).await.unwrap();
-        assert_eq!(loaded_events.len(), 1); // Should only contain the initial event
-        let entry = repo.store.get(&aggregate_id).unwrap();
-        assert_eq!(entry.value().0, 1); // Version should still be 1
-    }
-
-    #[tokio::test]
-    async fn test_load_non_existent_aggregate() {
-        let repo = InMemoryEventRepository::default();
-        let aggregate_id = "agg-non-existent".to_string();
-
-        let loaded_events = repo.load(&aggregate_id).await.unwrap();
-        assert!(loaded_events.is_empty());
-    }
-
     #[tokio::test]
     async fn test_save_empty_event_list() {
         let repo = InMemoryEventRepository::default();
         let aggregate_id = "agg-empty".to_string();
-        let empty_events: Vec<(String, Vec<u8>)> = vec![];
+        let empty_events: Vec<StoredEvent> = vec![];
 
         let result = repo.save(&aggregate_id, 0, &empty_events).await;
         assert!(result.is_ok());
@@ -246,4 +198,9 @@ mod tests {
         // Ensure nothing was actually stored
         assert!(!repo.store.contains_key(&aggregate_id));
     }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        crate::conformance::repository_conformance(&InMemoryEventRepository::default()).await;
+    }
 }
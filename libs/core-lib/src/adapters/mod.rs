@@ -1,11 +1,74 @@
 // Declare modules within the adapters directory
+pub mod fs_blob_store;
 pub mod in_memory_cache;
 pub mod in_memory_event_bus;
+pub mod in_memory_lock;
 pub mod in_memory_repository;
+pub mod postgres_event_bus;
+pub mod postgres_event_partitions;
+pub mod postgres_lock;
 pub mod postgres_repository;
 pub mod rabbitmq_event_bus;
 pub mod redis_cache;
 pub mod redis_event_bus;
+pub mod redis_lock;
+pub mod s3_blob_store;
+pub mod tiered_cache;
+
+use crate::{HandlerOutcome, MessageHandler, ReceivedEvent};
+use std::time::Duration;
+
+// Hex-encoded SHA-256 of blob content, as recorded in `BlobMetadata`
+pub(crate) fn content_sha256(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(data))
+}
+
+// Delay before redelivering a requeued message on transports without a broker-side queue
+const LOCAL_REQUEUE_DELAY: Duration = Duration::from_millis(100);
+
+// Redeliveries of a requeued message before it is given up on, so a message that
+// always fails cannot hold up the subscription
+const LOCAL_MAX_REDELIVERIES: u32 = 5;
+
+// Delivers a message on transports that cannot hand it back to the broker
+// (in-memory, Redis Pub/Sub, Postgres): `Requeue` redelivers it locally up to
+// `LOCAL_MAX_REDELIVERIES` times, `Nack` drops it.
+pub(crate) async fn deliver_locally(
+    subscription_name: &str,
+    handler: &dyn MessageHandler,
+    event: ReceivedEvent,
+) {
+    let mut redeliveries = 0;
+    loop {
+        match handler.handle(event.clone()).await {
+            HandlerOutcome::Ack => return,
+            HandlerOutcome::Nack => {
+                tracing::warn!(
+                    "Subscription '{}' rejected {} on {}; dropping it",
+                    subscription_name,
+                    event.event_type,
+                    event.topic
+                );
+                return;
+            }
+            HandlerOutcome::Requeue if redeliveries >= LOCAL_MAX_REDELIVERIES => {
+                tracing::error!(
+                    "Subscription '{}' requeued {} on {} {} times; dropping it",
+                    subscription_name,
+                    event.event_type,
+                    event.topic,
+                    redeliveries + 1
+                );
+                return;
+            }
+            HandlerOutcome::Requeue => {
+                redeliveries += 1;
+                tokio::time::sleep(LOCAL_REQUEUE_DELAY).await;
+            }
+        }
+    }
+}
 
 // TODO: Add feature flags (e.g., "postgres", "rabbitmq", "redis", "in_memory_infra")
 //       to conditionally compile these adapters and allow selection at runtime
@@ -19,3 +82,31 @@ pub mod redis_event_bus;
 // pub use in_memory_cache::InMemoryCache;
 // pub use in_memory_event_bus::InMemoryEventBus;
 // pub use in_memory_repository::InMemoryEventRepository;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_requeued_message_is_dropped_after_max_redeliveries() {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let counter = attempts.clone();
+        let handler = move |_event: ReceivedEvent| {
+            let counter = counter.clone();
+            async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                HandlerOutcome::Requeue
+            }
+        };
+        let event = ReceivedEvent {
+            topic: "user.created".to_string(),
+            event_type: "UserCreated".to_string(),
+            payload: b"poison".to_vec(),
+        };
+
+        deliver_locally("poison", &handler, event).await;
+        assert_eq!(attempts.load(Ordering::SeqCst), LOCAL_MAX_REDELIVERIES + 1);
+    }
+}
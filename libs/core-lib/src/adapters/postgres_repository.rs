@@ -1,27 +1,199 @@
-use crate::{CoreError, Repository};
+use super::postgres_event_partitions::read_archive;
+use crate::{validate_event_batch, CoreError, Repository, StoredEvent, TenantEventQuery};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use cqrs_es::persist::SerializedEvent;
-use sqlx::{PgPool, Row};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+
+const EVENT_COLUMNS: &str =
+    "aggregate_id, sequence, aggregate_type, event_type, event_version, payload, metadata, tenant_id, timestamp";
 
 // Define a structure to represent stored events matching DB schema
 #[derive(sqlx::FromRow, Debug)]
 struct EventRow {
+    aggregate_id: String,
     sequence: i64,
+    aggregate_type: String,
     event_type: String,
+    event_version: String,
     payload: Vec<u8>,
+    metadata: Vec<u8>,
+    tenant_id: Option<String>,
+    timestamp: DateTime<Utc>,
+}
+
+impl From<EventRow> for StoredEvent {
+    fn from(row: EventRow) -> Self {
+        StoredEvent::new(
+            SerializedEvent::new(
+                row.aggregate_id,
+                row.sequence as usize,
+                row.aggregate_type,
+                row.event_type,
+                row.event_version,
+                row.payload,
+                row.metadata,
+            ),
+            row.tenant_id,
+            row.timestamp,
+        )
+    }
+}
+
+// Postgres error code for unique constraint violations
+const UNIQUE_VIOLATION: &str = "23505";
+
+/// Quotes an SQL identifier so any schema or table name can be spliced into a query.
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// An events table and its companion tables, which share its name as a prefix:
+/// `{table}_streams` (head version of each stream), `{table}_archives` (partitions
+/// moved to files) and `{table}_archive_streams` (streams with events in each archive).
+#[derive(Debug, Clone)]
+pub(crate) struct EventTables {
+    schema: String,
+    pub(crate) table: String,
+}
+
+impl EventTables {
+    pub(crate) fn new(schema: &str, table: &str) -> Self {
+        Self {
+            schema: schema.to_string(),
+            table: table.to_string(),
+        }
+    }
+
+    /// Schema-qualified, quoted name of a table in the same schema.
+    pub(crate) fn qualified(&self, name: &str) -> String {
+        format!("{}.{}", quote_identifier(&self.schema), quote_identifier(name))
+    }
+
+    pub(crate) fn events(&self) -> String {
+        self.qualified(&self.table)
+    }
+
+    pub(crate) fn streams(&self) -> String {
+        self.qualified(&format!("{}_streams", self.table))
+    }
+
+    pub(crate) fn archives(&self) -> String {
+        self.qualified(&format!("{}_archives", self.table))
+    }
+
+    pub(crate) fn archive_streams(&self) -> String {
+        self.qualified(&format!("{}_archive_streams", self.table))
+    }
 }
 
 /// PostgreSQL implementation of the Repository port using sqlx.
+///
+/// Events of one save are written with a single statement that moves the stream's head
+/// version in `{table}_streams` and inserts the batch with `INSERT ... SELECT FROM UNNEST`.
+/// Conflicting writers are detected on the head row rather than by reading the current
+/// version first. Events of partitions archived by
+/// [`PostgresEventPartitions`](super::postgres_event_partitions::PostgresEventPartitions)
+/// are read back from their files.
 #[derive(Debug, Clone)]
 pub struct PostgresEventRepository {
-    // No longer generic
     pool: PgPool,
+    tables: EventTables,
 }
 
 impl PostgresEventRepository {
-    #[allow(dead_code)]
+    /// Uses the `public.events` table created by the gateway migrations.
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self::with_table(pool, "public", "events")
+    }
+
+    /// Uses an events table with the same columns in another schema or under another name.
+    /// Its `_streams`, `_archives` and `_archive_streams` tables must exist alongside it,
+    /// as the migrations create them for `events`.
+    pub fn with_table(pool: PgPool, schema: &str, table: &str) -> Self {
+        Self {
+            pool,
+            tables: EventTables::new(schema, table),
+        }
+    }
+
+    async fn current_version(&self, aggregate_id: &str) -> Result<usize, CoreError> {
+        let query = format!(
+            "SELECT version FROM {} WHERE aggregate_id = $1",
+            self.tables.streams()
+        );
+        let version: Option<i64> = sqlx::query_scalar(&query)
+            .bind(aggregate_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+        Ok(version.unwrap_or(0) as usize)
+    }
+
+    // Events of `aggregate_id` that were moved to archive files, oldest archive first.
+    async fn load_archived(&self, aggregate_id: &str) -> Result<Vec<StoredEvent>, CoreError> {
+        let query = format!(
+            r#"
+            SELECT a.path FROM {archive_streams} s
+            JOIN {archives} a ON a.partition_name = s.partition_name
+            WHERE s.aggregate_id = $1
+            ORDER BY a.range_start
+            "#,
+            archive_streams = self.tables.archive_streams(),
+            archives = self.tables.archives()
+        );
+        let paths: Vec<String> = sqlx::query_scalar(&query)
+            .bind(aggregate_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+        let mut events = Vec::new();
+        for path in paths {
+            let aggregate_id = aggregate_id.to_string();
+            let archived =
+                read_archive(path.into(), move |e| e.aggregate_id == aggregate_id).await?;
+            for event in archived {
+                events.push(StoredEvent::try_from(event)?);
+            }
+        }
+        events.sort_by_key(|e| e.event.sequence);
+        Ok(events)
+    }
+
+    // Tenant events in archive files that may match `query`, ordered like live events.
+    async fn load_archived_by_tenant(
+        &self,
+        tenant_id: &str,
+        query: &TenantEventQuery,
+    ) -> Result<Vec<StoredEvent>, CoreError> {
+        let archives_query = format!(
+            "SELECT path FROM {} WHERE $1::TIMESTAMPTZ IS NULL OR range_end > $1 ORDER BY range_start",
+            self.tables.archives()
+        );
+        let paths: Vec<String> = sqlx::query_scalar(&archives_query)
+            .bind(query.since)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+        let mut events = Vec::new();
+        for path in paths {
+            let tenant_id = tenant_id.to_string();
+            let query = query.clone();
+            let archived = read_archive(path.into(), move |e| {
+                e.tenant_id.as_deref() == Some(tenant_id.as_str())
+                    && query
+                        .aggregate_type
+                        .as_ref()
+                        .is_none_or(|t| *t == e.aggregate_type)
+                    && query.since.is_none_or(|since| e.timestamp >= since)
+            })
+            .await?;
+            events.extend(archived);
+        }
+        events.sort_by_key(|e| (e.timestamp, e.id));
+        events.into_iter().map(StoredEvent::try_from).collect()
     }
 }
 
@@ -29,11 +201,14 @@ impl PostgresEventRepository {
 impl Repository for PostgresEventRepository {
     // Implement non-generic trait
     /// Load events for a specific aggregate instance.
-    async fn load(&self, aggregate_id: &str) -> Result<Vec<SerializedEvent>, CoreError> {
-        let table_name = "events";
+    /// Archived events come first; archived partitions always precede the live ones.
+    async fn load(&self, aggregate_id: &str) -> Result<Vec<StoredEvent>, CoreError> {
+        let mut events = self.load_archived(aggregate_id).await?;
+
         let query = format!(
-            "SELECT sequence, event_type, payload FROM {} WHERE aggregate_id = $1 ORDER BY sequence ASC",
-            table_name
+            "SELECT {} FROM {} WHERE aggregate_id = $1 ORDER BY sequence ASC",
+            EVENT_COLUMNS,
+            self.tables.events()
         );
 
         let rows: Vec<EventRow> = sqlx::query_as(&query)
@@ -42,20 +217,7 @@ impl Repository for PostgresEventRepository {
             .await
             .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
 
-        let events = rows
-            .into_iter()
-            .map(|row| {
-                SerializedEvent::new(
-                    aggregate_id.to_string(),
-                    row.sequence as usize,
-                    "".to_string(),
-                    row.event_type,
-                    "".to_string(),
-                    row.payload,
-                    vec![],
-                )
-            })
-            .collect();
+        events.extend(rows.into_iter().map(StoredEvent::from));
         Ok(events)
     }
 
@@ -64,73 +226,136 @@ impl Repository for PostgresEventRepository {
         &self,
         aggregate_id: &str,
         expected_version: usize,
-        events: &[(String, Vec<u8>)], // Takes (event_type, payload) tuples
+        events: &[StoredEvent],
     ) -> Result<(), CoreError> {
         if events.is_empty() {
             return Ok(());
         }
+        validate_event_batch(aggregate_id, expected_version, events)?;
+
+        // One statement for the whole batch. A new stream gets a head row, whose primary
+        // key rejects a concurrent creation; an existing head only moves if it is still at
+        // the expected version. The events are inserted only if the head moved.
+        let insert_query = format!(
+            r#"
+            WITH created AS (
+                INSERT INTO {streams} (aggregate_id, version)
+                SELECT $11, $12 WHERE $10 = 0
+                RETURNING 1
+            ), advanced AS (
+                UPDATE {streams} SET version = $12
+                WHERE aggregate_id = $11 AND version = $10 AND $10 > 0
+                RETURNING 1
+            )
+            INSERT INTO {events} ({columns})
+            SELECT * FROM UNNEST(
+                $1::VARCHAR[], $2::BIGINT[], $3::VARCHAR[], $4::VARCHAR[], $5::VARCHAR[],
+                $6::BYTEA[], $7::BYTEA[], $8::VARCHAR[], $9::TIMESTAMPTZ[]
+            )
+            WHERE EXISTS (SELECT 1 FROM created) OR EXISTS (SELECT 1 FROM advanced)
+            "#,
+            streams = self.tables.streams(),
+            events = self.tables.events(),
+            columns = EVENT_COLUMNS
+        );
 
-        let table_name = "events";
-        let mut tx = self
-            .pool
-            .begin()
-            .await
-            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+        let mut aggregate_ids = Vec::with_capacity(events.len());
+        let mut sequences = Vec::with_capacity(events.len());
+        let mut aggregate_types = Vec::with_capacity(events.len());
+        let mut event_types = Vec::with_capacity(events.len());
+        let mut event_versions = Vec::with_capacity(events.len());
+        let mut payloads = Vec::with_capacity(events.len());
+        let mut metadata = Vec::with_capacity(events.len());
+        let mut tenant_ids = Vec::with_capacity(events.len());
+        let mut timestamps = Vec::with_capacity(events.len());
+        for stored in events {
+            let event = &stored.event;
+            aggregate_ids.push(event.aggregate_id.as_str());
+            sequences.push(event.sequence as i64);
+            aggregate_types.push(event.aggregate_type.as_str());
+            event_types.push(event.event_type.as_str());
+            event_versions.push(event.event_version.as_str());
+            payloads.push(event.payload.as_slice());
+            metadata.push(event.metadata.as_slice());
+            tenant_ids.push(stored.tenant_id.as_deref());
+            timestamps.push(stored.timestamp);
+        }
 
-        // 1. Get current version
-        let current_version_query = format!(
-            "SELECT MAX(sequence) FROM {} WHERE aggregate_id = $1",
-            table_name
-        );
-        let current_version_row = sqlx::query(&current_version_query)
+        let result = sqlx::query(&insert_query)
+            .bind(aggregate_ids)
+            .bind(sequences)
+            .bind(aggregate_types)
+            .bind(event_types)
+            .bind(event_versions)
+            .bind(payloads)
+            .bind(metadata)
+            .bind(tenant_ids)
+            .bind(timestamps)
+            .bind(expected_version as i64)
             .bind(aggregate_id)
-            .fetch_optional(&mut *tx)
-            .await
-            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
-
-        let current_version: usize = match current_version_row {
-            Some(row) => row
-                .try_get::<Option<i64>, _>(0)
-                .map_err(|e| CoreError::Infrastructure(Box::new(e)))?
-                .map(|v| v as usize)
-                .unwrap_or(0),
-            None => 0,
-        };
-
-        // 2. Optimistic concurrency check
-        if current_version != expected_version {
-            tx.rollback()
-                .await
-                .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
-            return Err(CoreError::Concurrency {
+            .bind((expected_version + events.len()) as i64)
+            .execute(&self.pool)
+            .await;
+
+        match result {
+            Ok(done) if done.rows_affected() == events.len() as u64 => Ok(()),
+            // The head was not at the expected version
+            Ok(_) => Err(CoreError::Concurrency {
                 expected: expected_version,
-                actual: current_version,
-            });
+                actual: self.current_version(aggregate_id).await?,
+            }),
+            Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some(UNIQUE_VIOLATION) => {
+                Err(CoreError::Concurrency {
+                    expected: expected_version,
+                    actual: self.current_version(aggregate_id).await?,
+                })
+            }
+            Err(e) => Err(CoreError::Infrastructure(Box::new(e))),
         }
+    }
 
-        // 3. Insert new events
-        let mut next_sequence = current_version + 1;
-        for (event_type, payload) in events {
-            let insert_query = format!(
-                "INSERT INTO {} (aggregate_id, sequence, event_type, payload) VALUES ($1, $2, $3, $4)",
-                table_name
-            );
-            sqlx::query(&insert_query)
-                .bind(aggregate_id)
-                .bind(next_sequence as i64)
-                .bind(event_type)
-                .bind(payload)
-                .execute(&mut *tx)
-                .await
-                .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
-            next_sequence += 1;
+    /// Load events of all aggregates belonging to a tenant.
+    /// Served by the `(tenant_id, timestamp, id)` index. Archives overlapping the
+    /// requested window are scanned in full, so reads reaching into them are slow.
+    async fn load_by_tenant(
+        &self,
+        tenant_id: &str,
+        query: &TenantEventQuery,
+    ) -> Result<Vec<StoredEvent>, CoreError> {
+        let mut events = self.load_archived_by_tenant(tenant_id, query).await?;
+        if let Some(limit) = query.limit {
+            events.truncate(limit);
+        }
+        let remaining = query.limit.map(|limit| limit - events.len());
+        if remaining == Some(0) {
+            return Ok(events);
         }
 
-        // 4. Commit transaction
-        tx.commit()
+        let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(format!(
+            "SELECT {} FROM {} WHERE tenant_id = ",
+            EVENT_COLUMNS,
+            self.tables.events()
+        ));
+        builder.push_bind(tenant_id);
+        if let Some(aggregate_type) = &query.aggregate_type {
+            builder.push(" AND aggregate_type = ").push_bind(aggregate_type);
+        }
+        if let Some(since) = query.since {
+            builder.push(" AND timestamp >= ").push_bind(since);
+        }
+        builder.push(" ORDER BY timestamp ASC, id ASC");
+        if let Some(limit) = remaining {
+            builder.push(" LIMIT ").push_bind(limit as i64);
+        }
+
+        let rows: Vec<EventRow> = builder
+            .build_query_as()
+            .fetch_all(&self.pool)
             .await
             .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
-        Ok(())
+
+        events.extend(rows.into_iter().map(StoredEvent::from));
+        Ok(events)
     }
 }
 
@@ -139,7 +364,9 @@ impl Repository for PostgresEventRepository {
 mod tests {
     use super::*;
     use crate::domain::user::{User, UserCommand, UserEvent};
-    use cqrs_es::Aggregate;
+    use crate::services::DomainServices;
+    use chrono::TimeZone;
+    use cqrs_es::{Aggregate, DomainEvent};
     use prost::Message;
     use proto::user::{PasswordChanged, RegisterUser, UserRegistered};
     use sqlx::postgres::PgPoolOptions;
@@ -175,19 +402,19 @@ mod tests {
         (pool, node)
     }
 
-    // Helper to serialize events for saving
-    fn serialize_events(events: &[UserEvent]) -> Vec<(String, Vec<u8>)> {
+    // Helper to serialize events for saving, numbering them after `version`.
+    // Uses a whole-second timestamp so it survives the round trip through TIMESTAMPTZ.
+    fn serialize_events(
+        aggregate_id: &str,
+        version: usize,
+        tenant_id: Option<&str>,
+        events: &[UserEvent],
+    ) -> Vec<StoredEvent> {
+        let timestamp = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
         events
             .iter()
-            .map(|event| {
-                let event_type = match event {
-                    UserEvent::Registered(_) => "UserRegistered",
-                    UserEvent::PasswordChanged(_) => "PasswordChanged",
-                    UserEvent::ApiKeyGenerated(_) => "ApiKeyGenerated",
-                    UserEvent::ApiKeyRevoked(_) => "ApiKeyRevoked", // Added
-                    UserEvent::LoggedIn(_) => "UserLoggedIn",
-                }
-                .to_string();
+            .zip(version + 1..)
+            .map(|(event, sequence)| {
                 let payload = match event {
                     UserEvent::Registered(e) => e.encode_to_vec(),
                     UserEvent::PasswordChanged(e) => e.encode_to_vec(),
@@ -195,7 +422,19 @@ mod tests {
                     UserEvent::ApiKeyRevoked(e) => e.encode_to_vec(), // Added
                     UserEvent::LoggedIn(e) => e.encode_to_vec(),
                 };
-                (event_type, payload)
+                StoredEvent::new(
+                    SerializedEvent::new(
+                        aggregate_id.to_string(),
+                        sequence,
+                        User::TYPE.to_string(),
+                        event.event_type(),
+                        event.event_version(),
+                        payload,
+                        vec![],
+                    ),
+                    tenant_id.map(str::to_string),
+                    timestamp,
+                )
             })
             .collect()
     }
@@ -218,12 +457,13 @@ mod tests {
         });
         let default_user = User::default();
         let events_domain = default_user
-            .handle(command, &())
+            .handle(command, &DomainServices::default())
             .await
             .expect("Handle failed");
         // --- End Test Setup ---
 
-        let events_to_save = serialize_events(&events_domain);
+        let events_to_save =
+            serialize_events(&user_id, 0, Some("tenant-pg-test"), &events_domain);
 
         // Save events (version 0)
         let save_result = repo.save(&user_id, 0, &events_to_save).await;
@@ -233,12 +473,13 @@ mod tests {
         let loaded_stored_events = repo.load(&user_id).await.expect("Load failed");
 
         assert_eq!(events_to_save.len(), loaded_stored_events.len());
-        assert_eq!(loaded_stored_events[0].sequence, 1);
-        assert_eq!(loaded_stored_events[0].event_type, events_to_save[0].0);
-        assert_eq!(loaded_stored_events[0].payload, events_to_save[0].1);
+        assert_eq!(loaded_stored_events[0], events_to_save[0]);
+        assert_eq!(loaded_stored_events[0].event.sequence, 1);
+        assert_eq!(loaded_stored_events[0].event.aggregate_type, "user");
+        assert_eq!(loaded_stored_events[0].event.event_version, "0.1.0");
 
         // Optional: Deserialize and compare specific fields
-        let deserialized_event = UserRegistered::decode(loaded_stored_events[0].payload.as_slice())
+        let deserialized_event = UserRegistered::decode(loaded_stored_events[0].event.payload.as_slice())
             .expect("Decode failed");
         assert_eq!(deserialized_event.user_id, user_id);
         assert_eq!(deserialized_event.username, "test-pg");
@@ -250,47 +491,60 @@ mod tests {
             .await
             .unwrap();
         assert_eq!(count, events_to_save.len() as i64);
+
+        // Tenant-wide query sees the event
+        let tenant_events = repo
+            .load_by_tenant("tenant-pg-test", &TenantEventQuery::default())
+            .await
+            .expect("Tenant load failed");
+        assert!(tenant_events.iter().any(|e| e.event.aggregate_id == user_id));
     }
 
     #[tokio::test]
-    async fn test_concurrency_error_postgres() {
+    async fn test_conformance_postgres() {
         let (pool, _node) = setup_db().await;
-        let repo = PostgresEventRepository::new(pool);
-
-        let user_id = Uuid::new_v4().to_string();
-        // --- Test Setup: Generate events ---
-        let event1_domain = UserEvent::Registered(UserRegistered {
-            user_id: user_id.clone(),
-            username: "concurrent".to_string(),
-            email: "con@test.com".to_string(),
-            role: proto::user::Role::Pilot as i32,
-            tenant_id: None,
-            password_hash: "test-hash".to_string(),
-            timestamp: "now".to_string(),
-        });
-        let event2_domain = UserEvent::PasswordChanged(PasswordChanged {
-            user_id: user_id.clone(),
-            timestamp: "later".to_string(),
-        });
-        // --- End Test Setup ---
-
-        let events_to_save1 = serialize_events(&[event1_domain]);
-        let events_to_save2 = serialize_events(&[event2_domain]);
+        crate::conformance::repository_conformance(&PostgresEventRepository::new(pool)).await;
+    }
 
-        // Save initial event (version 0 -> 1)
-        let save_result1 = repo.save(&user_id, 0, &events_to_save1).await;
-        assert!(save_result1.is_ok());
+    #[tokio::test]
+    async fn test_custom_schema_and_table_postgres() {
+        let (pool, _node) = setup_db().await;
+        sqlx::query("CREATE SCHEMA archive").execute(&pool).await.unwrap();
+        for (table, like) in [
+            ("user events", "events"),
+            ("user events_streams", "events_streams"),
+            ("user events_archives", "events_archives"),
+            ("user events_archive_streams", "events_archive_streams"),
+        ] {
+            sqlx::query(&format!(
+                r#"CREATE TABLE archive."{}" (LIKE public.{} INCLUDING ALL)"#,
+                table, like
+            ))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        let repo = PostgresEventRepository::with_table(pool.clone(), "archive", "user events");
 
-        // Try to save another event with wrong expected version (0 instead of 1)
-        let save_result2 = repo.save(&user_id, 0, &events_to_save2).await;
+        let user_id = Uuid::new_v4().to_string();
+        let events = serialize_events(
+            &user_id,
+            0,
+            None,
+            &[UserEvent::PasswordChanged(PasswordChanged {
+                user_id: user_id.clone(),
+                timestamp: "now".to_string(),
+            })],
+        );
+        repo.save(&user_id, 0, &events).await.expect("Save failed");
+        assert_eq!(repo.load(&user_id).await.unwrap(), events);
 
-        assert!(save_result2.is_err());
-        match save_result2.err().unwrap() {
-            CoreError::Concurrency { expected, actual } => {
-                assert_eq!(expected, 0);
-                assert_eq!(actual, 1);
-            }
-            e => panic!("Expected Concurrency error, got {:?}", e),
-        }
+        // Nothing was written to the default table
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events WHERE aggregate_id = $1")
+            .bind(&user_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
     }
 }
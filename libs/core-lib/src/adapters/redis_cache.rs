@@ -3,6 +3,28 @@ use async_trait::async_trait;
 use redis::{aio::MultiplexedConnection, AsyncCommands, Client};
 use tracing::info;
 
+/// Prefix of the Redis sets that hold the keys stored under a tag.
+const TAG_KEY_PREFIX: &str = "cache:tag:";
+/// Number of keys deleted per DEL when invalidating a prefix.
+const DELETE_BATCH: usize = 500;
+
+fn tag_key(tag: &str) -> String {
+    format!("{}{}", TAG_KEY_PREFIX, tag)
+}
+
+/// Builds a SCAN MATCH pattern for keys starting with `prefix`, escaping glob metacharacters.
+fn prefix_pattern(prefix: &str) -> String {
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('*');
+    pattern
+}
+
 /// Redis implementation of the Cache port using redis-rs.
 #[derive(Clone, Debug)]
 pub struct RedisCache {
@@ -26,6 +48,23 @@ impl RedisCache {
             default_ttl_seconds,
         })
     }
+
+    /// Deletes every entry stored under `tag` along with the tag set, returning the deleted keys.
+    pub(crate) async fn remove_tag(&self, tag: &str) -> Result<Vec<String>, CoreError> {
+        let mut conn = self.connection.clone();
+        let tag_key = tag_key(tag);
+        let keys: Vec<String> = conn
+            .smembers(&tag_key)
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+        let mut doomed = keys.clone();
+        doomed.push(tag_key);
+        conn.del(doomed)
+            .await
+            .map(|_: usize| ())
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+        Ok(keys)
+    }
 }
 
 #[async_trait]
@@ -59,6 +98,90 @@ impl Cache for RedisCache {
             .map(|_: usize| ()) // Map Ok(usize) to Ok(())
             .map_err(|e| CoreError::Infrastructure(Box::new(e)))
     }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Vec<u8>>>, CoreError> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut conn = self.connection.clone();
+        // MGET always replies with an array, even for a single key
+        redis::cmd("MGET")
+            .arg(keys)
+            .query_async(&mut conn)
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, &[u8])],
+        ttl_seconds: Option<u64>,
+    ) -> Result<(), CoreError> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.connection.clone();
+        let ttl = ttl_seconds.unwrap_or(self.default_ttl_seconds);
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for (key, value) in entries {
+            pipe.set_ex(*key, *value, ttl).ignore();
+        }
+        pipe.query_async(&mut conn)
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))
+    }
+
+    async fn set_tagged(
+        &self,
+        key: &str,
+        value: &[u8],
+        ttl_seconds: Option<u64>,
+        tags: &[&str],
+    ) -> Result<(), CoreError> {
+        let mut conn = self.connection.clone();
+        let ttl = ttl_seconds.unwrap_or(self.default_ttl_seconds);
+        let mut pipe = redis::pipe();
+        pipe.atomic().set_ex(key, value, ttl).ignore();
+        for tag in tags {
+            let tag_key = tag_key(tag);
+            pipe.sadd(&tag_key, key).ignore();
+            // Keep the tag set alive as long as its longest-lived member:
+            // NX sets a TTL on a new set, GT only ever extends it.
+            pipe.cmd("EXPIRE").arg(&tag_key).arg(ttl).arg("NX").ignore();
+            pipe.cmd("EXPIRE").arg(&tag_key).arg(ttl).arg("GT").ignore();
+        }
+        pipe.query_async(&mut conn)
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))
+    }
+
+    async fn invalidate_tag(&self, tag: &str) -> Result<(), CoreError> {
+        self.remove_tag(tag).await.map(|_| ())
+    }
+
+    async fn invalidate_prefix(&self, prefix: &str) -> Result<(), CoreError> {
+        let mut scan_conn = self.connection.clone();
+        let mut keys: Vec<String> = Vec::new();
+        {
+            let mut iter = scan_conn
+                .scan_match::<_, String>(prefix_pattern(prefix))
+                .await
+                .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+            while let Some(key) = iter.next_item().await {
+                keys.push(key);
+            }
+        }
+
+        let mut conn = self.connection.clone();
+        for batch in keys.chunks(DELETE_BATCH) {
+            conn.del(batch)
+                .await
+                .map(|_: usize| ())
+                .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+        }
+        Ok(())
+    }
 }
 
 // --- Integration Tests ---
@@ -97,75 +220,6 @@ mod tests {
         (connection, node, redis_url)
     }
 
-    #[tokio::test]
-    async fn test_set_and_get_redis() {
-        let (conn, _node, _url) = setup_redis().await;
-        let cache = RedisCache {
-            connection: conn,
-            default_ttl_seconds: 3600,
-        };
-        let key = "test_key_redis";
-        let value = b"test_value_redis".to_vec();
-
-        cache.set(key, &value, None).await.expect("SET failed");
-        let retrieved = cache.get(key).await.expect("GET failed");
-        assert_eq!(retrieved, Some(value));
-    }
-
-    #[tokio::test]
-    async fn test_get_non_existent_redis() {
-        let (conn, _node, _url) = setup_redis().await;
-        let cache = RedisCache {
-            connection: conn,
-            default_ttl_seconds: 3600,
-        };
-        let key = "non_existent_key_redis";
-        let retrieved = cache.get(key).await.expect("GET failed");
-        assert_eq!(retrieved, None);
-    }
-
-    #[tokio::test]
-    async fn test_delete_redis() {
-        let (conn, _node, _url) = setup_redis().await;
-        let cache = RedisCache {
-            connection: conn,
-            default_ttl_seconds: 3600,
-        };
-        let key = "delete_key_redis";
-        let value = b"delete_value_redis".to_vec();
-
-        cache.set(key, &value, None).await.expect("SET failed");
-        let retrieved_before = cache.get(key).await.expect("GET before delete failed");
-        assert_eq!(retrieved_before, Some(value));
-
-        cache.delete(key).await.expect("DELETE failed"); // Should now return Ok(())
-        let retrieved_after = cache.get(key).await.expect("GET after delete failed");
-        assert_eq!(retrieved_after, None);
-    }
-
-    #[tokio::test]
-    async fn test_set_with_ttl_redis() {
-        let (conn, _node, _url) = setup_redis().await;
-        let cache = RedisCache {
-            connection: conn,
-            default_ttl_seconds: 3600,
-        };
-        let key = "ttl_key_redis";
-        let value = b"ttl_value_redis".to_vec();
-        let ttl_seconds = 1;
-
-        cache
-            .set(key, &value, Some(ttl_seconds))
-            .await
-            .expect("SET with TTL failed");
-        let retrieved_before = cache.get(key).await.expect("GET before expiry failed");
-        assert_eq!(retrieved_before, Some(value.clone()));
-
-        sleep(Duration::from_millis(1100)).await;
-        let retrieved_after = cache.get(key).await.expect("GET after expiry failed");
-        assert_eq!(retrieved_after, None, "Cache entry should have expired");
-    }
-
     #[tokio::test]
     async fn test_set_with_default_ttl_redis() {
         let default_ttl = 1;
@@ -191,4 +245,20 @@ mod tests {
             "Cache entry should have expired based on default TTL"
         );
     }
+
+    #[test]
+    fn test_prefix_pattern_escapes_glob_characters() {
+        assert_eq!(prefix_pattern("q:v1:users:"), "q:v1:users:*");
+        assert_eq!(prefix_pattern("a*b?[c]"), "a\\*b\\?\\[c\\]*");
+    }
+
+    #[tokio::test]
+    async fn test_conformance_redis() {
+        let (conn, _node, _url) = setup_redis().await;
+        let cache = RedisCache {
+            connection: conn,
+            default_ttl_seconds: 3600,
+        };
+        crate::conformance::cache_conformance(&cache).await;
+    }
 }
@@ -1,11 +1,20 @@
-use crate::{CoreError, EventPublisher, EventSubscriber};
+use crate::adapters::deliver_locally;
+use crate::{
+    CoreError, EventPublisher, EventSubscriber, MessageHandler, ReceivedEvent, Subscription,
+    SubscriptionHandle,
+};
 use async_trait::async_trait;
-use redis::{aio::MultiplexedConnection, aio::PubSub, AsyncCommands, Client};
-use tracing::info;
+use futures_util::StreamExt;
+use redis::{aio::MultiplexedConnection, AsyncCommands, Client};
+use std::sync::Arc;
+use tracing::{info, warn};
 
 // TODO: Define configuration struct for connection details, channel prefix, etc.
 
 /// Redis implementation of EventPublisher and EventSubscriber using Pub/Sub.
+///
+/// Pub/Sub only carries the payload: received events have an empty `event_type`,
+/// and messages published while no subscriber is connected are lost.
 #[derive(Clone)]
 pub struct RedisEventBus {
     publish_connection: MultiplexedConnection,
@@ -30,21 +39,22 @@ impl RedisEventBus {
         })
     }
 
-    /// Creates a new PubSub connection for subscribing.
-    #[allow(dead_code)]
-    // Return the concrete async PubSub type from redis::aio
-    pub async fn create_subscriber_connection(&self) -> Result<PubSub, CoreError> {
-        let client = Client::open(self.redis_url.as_str())
-            .map_err(|e| CoreError::Configuration(format!("Invalid Redis URL: {}", e)))?;
-        client
-            .get_async_pubsub()
-            .await
-            .map_err(|e| CoreError::Infrastructure(Box::new(e)))
-    }
-
     fn get_channel_name(&self, topic: &str) -> String {
         format!("{}{}", self.channel_prefix, topic)
     }
+
+    // Redis glob covering an AMQP-style pattern. `*` in a glob also matches dots,
+    // so received messages are filtered again with `Subscription::matches`. `#` may
+    // match zero words, so everything from the first `#` on (including the dot
+    // before it) becomes a single glob `*`.
+    fn get_channel_pattern(&self, pattern: &str) -> String {
+        let words: Vec<&str> = pattern.split('.').collect();
+        let glob = match words.iter().position(|word| *word == "#") {
+            Some(hash) => format!("{}*", words[..hash].join(".")),
+            None => pattern.to_string(),
+        };
+        self.get_channel_name(&glob)
+    }
 }
 
 #[async_trait]
@@ -66,17 +76,58 @@ impl EventPublisher for RedisEventBus {
 }
 
 #[async_trait]
-impl EventSubscriber for RedisEventBus {}
+impl EventSubscriber for RedisEventBus {
+    async fn subscribe(
+        &self,
+        subscription: Subscription,
+        handler: Arc<dyn MessageHandler>,
+    ) -> Result<SubscriptionHandle, CoreError> {
+        let client = Client::open(self.redis_url.as_str())
+            .map_err(|e| CoreError::Configuration(format!("Invalid Redis URL: {}", e)))?;
+        let mut pubsub = client
+            .get_async_pubsub()
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+        pubsub
+            .psubscribe(self.get_channel_pattern(&subscription.pattern))
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+        info!(
+            "Redis subscription '{}' listening on '{}'",
+            subscription.name, subscription.pattern
+        );
+
+        let prefix = self.channel_prefix.clone();
+        let task = tokio::spawn(async move {
+            let mut messages = pubsub.into_on_message();
+            while let Some(msg) = messages.next().await {
+                let Some(topic) = msg.get_channel_name().strip_prefix(prefix.as_str()) else {
+                    continue;
+                };
+                if !subscription.matches(topic) {
+                    continue;
+                }
+                let event = ReceivedEvent {
+                    topic: topic.to_string(),
+                    event_type: String::new(),
+                    payload: msg.get_payload_bytes().to_vec(),
+                };
+                deliver_locally(&subscription.name, handler.as_ref(), event).await;
+            }
+            warn!("Redis subscription '{}' stream ended", subscription.name);
+        });
+        Ok(SubscriptionHandle::new(task))
+    }
+}
 
 // --- Integration Tests ---
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::conformance::{publisher_conformance, PublisherConformance};
     use testcontainers::runners::AsyncRunner;
     use testcontainers::ContainerAsync;
     use testcontainers_modules::redis::Redis as RedisImage;
-    use tokio::time::{timeout, Duration};
-    use tokio_stream::StreamExt;
 
     async fn setup_redis_pubsub() -> (
         RedisEventBus,
@@ -100,54 +151,13 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_publish_and_subscribe_redis() {
+    async fn test_conformance_redis() {
         let (bus, _node, _url) = setup_redis_pubsub().await;
-        let topic = "test-topic-redis";
-        let event_type = "TestEventRedis";
-        let payload = b"hello redis pubsub".to_vec();
-        let channel_name = bus.get_channel_name(topic);
-
-        // Setup subscriber connection (returns redis::aio::PubSub)
-        let mut pubsub_conn = bus
-            .create_subscriber_connection()
-            .await
-            .expect("Failed to create pubsub conn");
-        // subscribe is async on PubSub
-        pubsub_conn
-            .subscribe(&channel_name)
-            .await
-            .expect("Failed to subscribe");
-
-        // Get the message stream using into_on_message()
-        let mut message_stream = pubsub_conn.into_on_message();
-
-        let (tx, mut rx) = tokio::sync::mpsc::channel::<Vec<u8>>(1);
-
-        // Spawn a task to listen to the stream
-        tokio::spawn(async move {
-            if let Some(msg) = message_stream.next().await {
-                let received_payload: Vec<u8> = msg.get_payload().expect("Failed to get payload");
-                tx.send(received_payload)
-                    .await
-                    .expect("Failed to send payload back to test");
-            } else {
-                eprintln!("Message stream ended unexpectedly");
-            }
-        });
-
-        tokio::time::sleep(Duration::from_millis(100)).await; // Give subscriber time
-
-        let publish_result = bus.publish(topic, event_type, &payload).await;
-        assert!(
-            publish_result.is_ok(),
-            "Publish failed: {:?}",
-            publish_result.err()
-        );
-
-        match timeout(Duration::from_secs(5), rx.recv()).await {
-            Ok(Some(received_payload)) => assert_eq!(received_payload, payload),
-            Ok(None) => panic!("Consumer channel closed unexpectedly"),
-            Err(_) => panic!("Test timed out waiting for message"),
-        }
+        let options = PublisherConformance {
+            // Pub/Sub only carries the payload
+            carries_event_type: false,
+            ..Default::default()
+        };
+        publisher_conformance(&bus, &bus, options).await;
     }
 }
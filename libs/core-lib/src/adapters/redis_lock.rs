@@ -0,0 +1,176 @@
+use crate::{CoreError, LockLease, LockService};
+use async_trait::async_trait;
+use redis::{aio::MultiplexedConnection, Client, Script};
+use std::time::Duration;
+use tracing::info;
+use uuid::Uuid;
+
+// Sets the lock if it is free and only then takes the next fencing token, so tokens
+// increase in the order the lock was acquired.
+const ACQUIRE_SCRIPT: &str = r#"
+if redis.call('SET', KEYS[1], ARGV[1], 'NX', 'PX', ARGV[2]) then
+    return redis.call('INCR', KEYS[2])
+end
+return false
+"#;
+
+// Extends the lock only if it still belongs to the caller.
+const RENEW_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+return 0
+"#;
+
+// Deletes the lock only if it still belongs to the caller.
+const RELEASE_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+/// Redis implementation of the LockService port.
+///
+/// A lock is a key set with `SET NX PX` whose value identifies the holder; renewals and
+/// releases compare the value first. Fencing tokens come from a per-lock `INCR` counter
+/// that is never reset, incremented in the same script as a successful `SET`.
+#[derive(Clone, Debug)]
+pub struct RedisLockService {
+    connection: MultiplexedConnection,
+}
+
+impl RedisLockService {
+    pub async fn new(redis_url: &str) -> Result<Self, CoreError> {
+        let client = Client::open(redis_url)
+            .map_err(|e| CoreError::Configuration(format!("Invalid Redis URL: {}", e)))?;
+        let connection = client
+            .get_multiplexed_tokio_connection()
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+        info!("Redis Lock Service connected.");
+        Ok(Self { connection })
+    }
+}
+
+fn lock_key(name: &str) -> String {
+    format!("lock:{}", name)
+}
+
+fn fence_key(name: &str) -> String {
+    format!("lock:{}:fence", name)
+}
+
+fn millis(ttl: Duration) -> u64 {
+    (ttl.as_millis() as u64).max(1)
+}
+
+#[async_trait]
+impl LockService for RedisLockService {
+    async fn try_acquire(&self, name: &str, ttl: Duration) -> Result<Option<LockLease>, CoreError> {
+        let mut conn = self.connection.clone();
+        let holder = Uuid::new_v4().to_string();
+        let token: Option<u64> = Script::new(ACQUIRE_SCRIPT)
+            .key(lock_key(name))
+            .key(fence_key(name))
+            .arg(&holder)
+            .arg(millis(ttl))
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+
+        Ok(token.map(|token| LockLease {
+            name: name.to_string(),
+            holder,
+            token,
+            ttl,
+        }))
+    }
+
+    async fn renew(&self, lease: &LockLease) -> Result<bool, CoreError> {
+        let mut conn = self.connection.clone();
+        let renewed: i64 = Script::new(RENEW_SCRIPT)
+            .key(lock_key(&lease.name))
+            .arg(&lease.holder)
+            .arg(millis(lease.ttl))
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+        Ok(renewed == 1)
+    }
+
+    async fn release(&self, lease: &LockLease) -> Result<(), CoreError> {
+        let mut conn = self.connection.clone();
+        let _: i64 = Script::new(RELEASE_SCRIPT)
+            .key(lock_key(&lease.name))
+            .arg(&lease.holder)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+        Ok(())
+    }
+}
+
+// --- Integration Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testcontainers::runners::AsyncRunner;
+    use testcontainers::ContainerAsync;
+    use testcontainers_modules::redis::Redis as RedisImage;
+    use tokio::time::sleep;
+
+    async fn setup_redis() -> (RedisLockService, ContainerAsync<RedisImage>) {
+        let node = RedisImage::default()
+            .start()
+            .await
+            .expect("Failed to start Redis container");
+        let port = node
+            .get_host_port_ipv4(6379)
+            .await
+            .expect("Failed to get host port");
+        let locks = RedisLockService::new(&format!("redis://localhost:{}/", port))
+            .await
+            .expect("Failed to connect to testcontainer Redis");
+        (locks, node)
+    }
+
+    #[tokio::test]
+    async fn test_lock_is_exclusive_until_released_redis() {
+        let (locks, _node) = setup_redis().await;
+        let ttl = Duration::from_secs(10);
+
+        let lease = locks.try_acquire("rebuild", ttl).await.unwrap().unwrap();
+        assert!(locks.try_acquire("rebuild", ttl).await.unwrap().is_none());
+        assert!(locks.renew(&lease).await.unwrap());
+
+        locks.release(&lease).await.unwrap();
+        let next = locks.try_acquire("rebuild", ttl).await.unwrap().unwrap();
+        assert!(next.token > lease.token);
+        // The refused attempt did not take a token
+        assert_eq!(next.token, lease.token + 1);
+    }
+
+    #[tokio::test]
+    async fn test_expired_lease_is_taken_over_redis() {
+        let (locks, _node) = setup_redis().await;
+        let stale = locks
+            .try_acquire("rebuild", Duration::from_millis(100))
+            .await
+            .unwrap()
+            .unwrap();
+        sleep(Duration::from_millis(200)).await;
+
+        let current = locks
+            .try_acquire("rebuild", Duration::from_secs(10))
+            .await
+            .unwrap()
+            .expect("Expired lock should be free");
+        assert!(current.token > stale.token);
+        assert!(!locks.renew(&stale).await.unwrap());
+
+        // Releasing the stale lease leaves the current holder in place
+        locks.release(&stale).await.unwrap();
+        assert!(locks.renew(&current).await.unwrap());
+    }
+}
@@ -0,0 +1,364 @@
+use crate::adapters::in_memory_cache::InMemoryCache;
+use crate::adapters::redis_cache::RedisCache;
+use crate::{Cache, CoreError};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use redis::aio::{MultiplexedConnection, PubSub};
+use redis::Client;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Pub/Sub channel carrying L1 evictions between instances.
+const INVALIDATION_CHANNEL: &str = "cache:invalidate";
+const RESUBSCRIBE_INITIAL_DELAY: Duration = Duration::from_millis(100);
+const RESUBSCRIBE_MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// An L1 eviction broadcast to the other instances sharing the L2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Invalidation {
+    Key(String),
+    Prefix(String),
+}
+
+impl Invalidation {
+    // Wire format: "<origin> <k|p> <key or prefix>"
+    fn encode(&self, origin: &str) -> String {
+        match self {
+            Invalidation::Key(key) => format!("{} k {}", origin, key),
+            Invalidation::Prefix(prefix) => format!("{} p {}", origin, prefix),
+        }
+    }
+
+    fn decode(message: &str) -> Option<(&str, Invalidation)> {
+        let mut parts = message.splitn(3, ' ');
+        let origin = parts.next()?;
+        let kind = parts.next()?;
+        let value = parts.next()?.to_string();
+        match kind {
+            "k" => Some((origin, Invalidation::Key(value))),
+            "p" => Some((origin, Invalidation::Prefix(value))),
+            _ => None,
+        }
+    }
+
+    async fn apply(&self, l1: &InMemoryCache) -> Result<(), CoreError> {
+        match self {
+            Invalidation::Key(key) => l1.delete(key).await,
+            Invalidation::Prefix(prefix) => l1.invalidate_prefix(prefix).await,
+        }
+    }
+}
+
+/// Two-tier implementation of the Cache port: a per-instance Moka L1 in front of a shared Redis L2.
+///
+/// Reads are served from L1 when possible and fall back to Redis, filling L1 on the way.
+/// Writes and deletes go to both tiers and are broadcast over Redis Pub/Sub so that other
+/// instances evict their L1 copy. L1 entries never outlive `l1_ttl_seconds`, which bounds
+/// staleness if a broadcast is missed; after a lost Pub/Sub connection the whole L1 is cleared.
+#[derive(Debug)]
+pub struct TieredCache {
+    l1: InMemoryCache,
+    l2: RedisCache,
+    publisher: MultiplexedConnection,
+    origin: String,
+    l1_ttl_seconds: u64,
+    l2_ttl_seconds: u64,
+    listener: JoinHandle<()>,
+}
+
+impl TieredCache {
+    /// Connects to Redis and starts listening for evictions from other instances.
+    pub async fn new(
+        redis_url: &str,
+        l1_capacity: u64,
+        l1_ttl_seconds: u64,
+        l2_ttl_seconds: u64,
+    ) -> Result<Self, CoreError> {
+        let l2 = RedisCache::new(redis_url, l2_ttl_seconds).await?;
+        let client = Client::open(redis_url)
+            .map_err(|e| CoreError::Configuration(format!("Invalid Redis URL: {}", e)))?;
+        let publisher = client
+            .get_multiplexed_tokio_connection()
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+        // Subscribe before returning so no eviction published afterwards is missed.
+        let pubsub = subscribe(&client).await?;
+
+        let l1 = InMemoryCache::new(l1_capacity, l1_ttl_seconds);
+        let origin = Uuid::new_v4().to_string();
+        let listener = tokio::spawn(listen(client, pubsub, l1.clone(), origin.clone()));
+        info!("Tiered cache connected (instance {}).", origin);
+
+        Ok(Self {
+            l1,
+            l2,
+            publisher,
+            origin,
+            l1_ttl_seconds,
+            l2_ttl_seconds,
+            listener,
+        })
+    }
+
+    // L1 copies expire no later than the L2 entry and never later than the L1 TTL.
+    fn l1_ttl(&self, ttl_seconds: Option<u64>) -> Option<u64> {
+        Some(
+            ttl_seconds
+                .unwrap_or(self.l2_ttl_seconds)
+                .min(self.l1_ttl_seconds),
+        )
+    }
+
+    async fn broadcast(&self, invalidations: &[Invalidation]) -> Result<(), CoreError> {
+        if invalidations.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self.publisher.clone();
+        let mut pipe = redis::pipe();
+        for invalidation in invalidations {
+            pipe.publish(INVALIDATION_CHANNEL, invalidation.encode(&self.origin))
+                .ignore();
+        }
+        pipe.query_async(&mut conn)
+            .await
+            .map_err(|e| CoreError::Infrastructure(Box::new(e)))
+    }
+}
+
+impl Drop for TieredCache {
+    fn drop(&mut self) {
+        self.listener.abort();
+    }
+}
+
+async fn subscribe(client: &Client) -> Result<PubSub, CoreError> {
+    let mut pubsub = client
+        .get_async_pubsub()
+        .await
+        .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+    pubsub
+        .subscribe(INVALIDATION_CHANNEL)
+        .await
+        .map_err(|e| CoreError::Infrastructure(Box::new(e)))?;
+    Ok(pubsub)
+}
+
+// Applies evictions from other instances to the local L1, resubscribing if the connection drops.
+async fn listen(client: Client, mut pubsub: PubSub, l1: InMemoryCache, origin: String) {
+    loop {
+        let mut messages = pubsub.into_on_message();
+        while let Some(msg) = messages.next().await {
+            let Ok(payload) = msg.get_payload::<String>() else {
+                continue;
+            };
+            match Invalidation::decode(&payload) {
+                Some((sender, _)) if sender == origin => {}
+                Some((_, invalidation)) => {
+                    if let Err(e) = invalidation.apply(&l1).await {
+                        warn!("Failed to apply cache invalidation '{}': {}", payload, e);
+                    }
+                }
+                None => warn!("Ignoring malformed cache invalidation '{}'", payload),
+            }
+        }
+        drop(messages);
+        warn!("Cache invalidation subscription lost; resubscribing");
+
+        let mut delay = RESUBSCRIBE_INITIAL_DELAY;
+        pubsub = loop {
+            tokio::time::sleep(delay).await;
+            match subscribe(&client).await {
+                Ok(pubsub) => break pubsub,
+                Err(e) => {
+                    warn!("Cache invalidation resubscribe failed: {}", e);
+                    delay = (delay * 2).min(RESUBSCRIBE_MAX_DELAY);
+                }
+            }
+        };
+        // Evictions published while disconnected are lost, so start from an empty L1.
+        let _ = l1.invalidate_prefix("").await;
+        info!("Cache invalidation subscription restored; L1 cleared");
+    }
+}
+
+#[async_trait]
+impl Cache for TieredCache {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, CoreError> {
+        if let Some(value) = self.l1.get(key).await? {
+            return Ok(Some(value));
+        }
+        let value = self.l2.get(key).await?;
+        if let Some(value) = &value {
+            self.l1.set(key, value, None).await?;
+        }
+        Ok(value)
+    }
+
+    async fn set(
+        &self,
+        key: &str,
+        value: &[u8],
+        ttl_seconds: Option<u64>,
+    ) -> Result<(), CoreError> {
+        self.l2.set(key, value, ttl_seconds).await?;
+        self.l1.set(key, value, self.l1_ttl(ttl_seconds)).await?;
+        self.broadcast(&[Invalidation::Key(key.to_string())]).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), CoreError> {
+        self.l2.delete(key).await?;
+        self.l1.delete(key).await?;
+        self.broadcast(&[Invalidation::Key(key.to_string())]).await
+    }
+
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Vec<u8>>>, CoreError> {
+        let mut values = self.l1.get_many(keys).await?;
+        let missing: Vec<usize> = (0..keys.len()).filter(|&i| values[i].is_none()).collect();
+        if missing.is_empty() {
+            return Ok(values);
+        }
+
+        let missing_keys: Vec<&str> = missing.iter().map(|&i| keys[i]).collect();
+        let fetched = self.l2.get_many(&missing_keys).await?;
+        for (i, value) in missing.into_iter().zip(fetched) {
+            if let Some(value) = &value {
+                self.l1.set(keys[i], value, None).await?;
+            }
+            values[i] = value;
+        }
+        Ok(values)
+    }
+
+    async fn set_many(
+        &self,
+        entries: &[(&str, &[u8])],
+        ttl_seconds: Option<u64>,
+    ) -> Result<(), CoreError> {
+        self.l2.set_many(entries, ttl_seconds).await?;
+        self.l1.set_many(entries, self.l1_ttl(ttl_seconds)).await?;
+        let invalidations: Vec<Invalidation> = entries
+            .iter()
+            .map(|(key, _)| Invalidation::Key(key.to_string()))
+            .collect();
+        self.broadcast(&invalidations).await
+    }
+
+    async fn set_tagged(
+        &self,
+        key: &str,
+        value: &[u8],
+        ttl_seconds: Option<u64>,
+        tags: &[&str],
+    ) -> Result<(), CoreError> {
+        self.l2.set_tagged(key, value, ttl_seconds, tags).await?;
+        self.l1
+            .set_tagged(key, value, self.l1_ttl(ttl_seconds), tags)
+            .await?;
+        self.broadcast(&[Invalidation::Key(key.to_string())]).await
+    }
+
+    async fn invalidate_tag(&self, tag: &str) -> Result<(), CoreError> {
+        // Other instances only know the keys they filled from L2, not their tags,
+        // so the tag is resolved here and broadcast as individual keys.
+        let keys = self.l2.remove_tag(tag).await?;
+        self.l1.invalidate_tag(tag).await?;
+        let mut invalidations = Vec::with_capacity(keys.len());
+        for key in keys {
+            self.l1.delete(&key).await?;
+            invalidations.push(Invalidation::Key(key));
+        }
+        self.broadcast(&invalidations).await
+    }
+
+    async fn invalidate_prefix(&self, prefix: &str) -> Result<(), CoreError> {
+        self.l2.invalidate_prefix(prefix).await?;
+        self.l1.invalidate_prefix(prefix).await?;
+        self.broadcast(&[Invalidation::Prefix(prefix.to_string())])
+            .await
+    }
+}
+
+// --- Integration Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testcontainers::runners::AsyncRunner;
+    use testcontainers::ContainerAsync;
+    use testcontainers_modules::redis::Redis as RedisImage;
+    use tokio::time::sleep;
+
+    async fn setup_redis() -> (ContainerAsync<RedisImage>, String) {
+        let node = RedisImage::default()
+            .start()
+            .await
+            .expect("Failed to start Redis container");
+        let port = node
+            .get_host_port_ipv4(6379)
+            .await
+            .expect("Failed to get host port");
+        (node, format!("redis://localhost:{}/", port))
+    }
+
+    #[test]
+    fn test_invalidation_round_trip() {
+        let key = Invalidation::Key("q:v1:users:self:u 1".to_string());
+        let prefix = Invalidation::Prefix("q:v1:users:tenant:t1:".to_string());
+
+        assert_eq!(
+            Invalidation::decode(&key.encode("a")),
+            Some(("a", key.clone()))
+        );
+        assert_eq!(
+            Invalidation::decode(&prefix.encode("b")),
+            Some(("b", prefix))
+        );
+        assert_eq!(Invalidation::decode("a x key"), None);
+        assert_eq!(Invalidation::decode("garbage"), None);
+    }
+
+    #[tokio::test]
+    async fn test_conformance_tiered() {
+        let (_node, url) = setup_redis().await;
+        let cache = TieredCache::new(&url, 100, 60, 3600).await.unwrap();
+        crate::conformance::cache_conformance(&cache).await;
+    }
+
+    #[tokio::test]
+    async fn test_delete_evicts_other_instances() {
+        let (_node, url) = setup_redis().await;
+        let a = TieredCache::new(&url, 100, 60, 3600).await.unwrap();
+        let b = TieredCache::new(&url, 100, 60, 3600).await.unwrap();
+
+        a.set("api_key", b"user", None).await.unwrap();
+        // B fills its L1 from Redis
+        assert_eq!(b.get("api_key").await.unwrap(), Some(b"user".to_vec()));
+
+        a.delete("api_key").await.unwrap();
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(b.get("api_key").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_and_tag_invalidation_reach_other_instances() {
+        let (_node, url) = setup_redis().await;
+        let a = TieredCache::new(&url, 100, 60, 3600).await.unwrap();
+        let b = TieredCache::new(&url, 100, 60, 3600).await.unwrap();
+
+        a.set_tagged("users:1", b"v1", None, &["tenant:t1"])
+            .await
+            .unwrap();
+        assert_eq!(b.get("users:1").await.unwrap(), Some(b"v1".to_vec()));
+
+        a.set_tagged("users:1", b"v2", None, &["tenant:t1"])
+            .await
+            .unwrap();
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(b.get("users:1").await.unwrap(), Some(b"v2".to_vec()));
+
+        a.invalidate_tag("tenant:t1").await.unwrap();
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(b.get("users:1").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,673 @@
+//! Behavioural test suites for the ports in this crate.
+//!
+//! Every adapter of a port is expected to behave the same way, so instead of each adapter
+//! carrying its own copy of the same tests, its test module runs the shared suite against
+//! an instance:
+//!
+//! ```ignore
+//! #[tokio::test]
+//! async fn test_conformance() {
+//!     let repo = InMemoryEventRepository::default();
+//!     core_lib::conformance::repository_conformance(&repo).await;
+//! }
+//! ```
+//!
+//! Built for this crate's tests; other crates enable the `conformance` feature, e.g. as
+//! `core-lib = { workspace = true, features = ["conformance"] }` under `[dev-dependencies]`.
+//!
+//! The suites panic on the first deviation. They only touch keys, streams and topics under
+//! a random namespace, so they can run against a shared backend and next to other tests.
+
+use crate::{
+    Cache, CoreError, EventPublisher, EventSubscriber, HandlerOutcome, OutgoingEvent,
+    ReceivedEvent, Repository, StoredEvent, Subscription, SubscriptionHandle, TenantEventQuery,
+};
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
+use cqrs_es::persist::SerializedEvent;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::time::{sleep, timeout};
+use uuid::Uuid;
+
+// Random word used to keep one run's data apart from everything else in the backend
+fn namespace() -> String {
+    format!("conformance{}", Uuid::new_v4().simple())
+}
+
+// --- Repository ---
+
+// Builds events for `aggregate_id` numbered after `version`, one minute apart from `start`.
+// Timestamps are whole seconds so they survive stores with coarser precision.
+fn stored_events(
+    aggregate_id: &str,
+    aggregate_type: &str,
+    version: usize,
+    count: usize,
+    tenant_id: Option<&str>,
+    start: DateTime<Utc>,
+) -> Vec<StoredEvent> {
+    (version + 1..=version + count)
+        .map(|sequence| {
+            StoredEvent::new(
+                SerializedEvent::new(
+                    aggregate_id.to_string(),
+                    sequence,
+                    aggregate_type.to_string(),
+                    format!("Event{}", sequence),
+                    "1.0.0".to_string(),
+                    format!("payload {} of {}", sequence, aggregate_id).into_bytes(),
+                    format!(r#"{{"sequence":"{}"}}"#, sequence).into_bytes(),
+                ),
+                tenant_id.map(str::to_string),
+                start + ChronoDuration::minutes(sequence as i64),
+            )
+        })
+        .collect()
+}
+
+fn assert_concurrency(
+    result: Result<(), CoreError>,
+    expected_version: usize,
+    actual_version: usize,
+    context: &str,
+) {
+    match result {
+        Err(CoreError::Concurrency { expected, actual }) => {
+            assert_eq!(
+                expected, expected_version,
+                "{}: wrong expected version",
+                context
+            );
+            assert_eq!(actual, actual_version, "{}: wrong actual version", context);
+        }
+        other => panic!("{}: expected a concurrency error, got {:?}", context, other),
+    }
+}
+
+/// Runs the [`Repository`] conformance suite against `repo`.
+///
+/// Checks round trips of every stored field, optimistic concurrency (including two writers
+/// racing for the same version), batch validation and tenant-wide reads.
+pub async fn repository_conformance<R: Repository + ?Sized>(repo: &R) {
+    let ns = namespace();
+    let start = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+    let tenant = Uuid::new_v4().to_string();
+    let stream = Uuid::new_v4().to_string();
+
+    // Unknown streams are empty, and saving nothing creates nothing
+    assert!(
+        repo.load(&stream).await.expect("load failed").is_empty(),
+        "Unknown aggregate should have no events"
+    );
+    repo.save(&stream, 0, &[]).await.expect("Empty save failed");
+    assert!(
+        repo.load(&stream).await.expect("load failed").is_empty(),
+        "Empty save should not store anything"
+    );
+
+    // Round trip of a new stream, then an append
+    let first = stored_events(&stream, &ns, 0, 2, Some(&tenant), start);
+    repo.save(&stream, 0, &first)
+        .await
+        .expect("Initial save failed");
+    assert_eq!(
+        repo.load(&stream).await.expect("load failed"),
+        first,
+        "Loaded events should equal the saved ones"
+    );
+    let second = stored_events(&stream, &ns, 2, 3, Some(&tenant), start);
+    repo.save(&stream, 2, &second).await.expect("Append failed");
+    let loaded = repo.load(&stream).await.expect("load failed");
+    assert_eq!(
+        loaded,
+        [first.clone(), second.clone()].concat(),
+        "Appended events should follow the existing ones in sequence order"
+    );
+
+    // Stale and future expected versions are rejected without writing anything
+    let stale = stored_events(&stream, &ns, 1, 1, Some(&tenant), start);
+    assert_concurrency(repo.save(&stream, 1, &stale).await, 1, 5, "Stale version");
+    let ahead = stored_events(&stream, &ns, 7, 1, Some(&tenant), start);
+    assert_concurrency(repo.save(&stream, 7, &ahead).await, 7, 5, "Version ahead");
+    let fresh = stored_events(&stream, &ns, 0, 1, Some(&tenant), start);
+    assert_concurrency(repo.save(&stream, 0, &fresh).await, 0, 5, "Existing stream");
+    assert_eq!(
+        repo.load(&stream).await.expect("load failed").len(),
+        5,
+        "Rejected saves should not store events"
+    );
+
+    // Batches must be numbered consecutively and belong to the stream they are saved to
+    let invalid = Uuid::new_v4().to_string();
+    let gap = stored_events(&invalid, &ns, 1, 1, None, start);
+    assert!(
+        matches!(
+            repo.save(&invalid, 0, &gap).await,
+            Err(CoreError::Validation(_))
+        ),
+        "Non-consecutive sequence should be a validation error"
+    );
+    let mut foreign = stored_events(&invalid, &ns, 0, 2, None, start);
+    foreign[1].event.aggregate_id = stream.clone();
+    assert!(
+        matches!(
+            repo.save(&invalid, 0, &foreign).await,
+            Err(CoreError::Validation(_))
+        ),
+        "Event of another aggregate should be a validation error"
+    );
+    assert!(
+        repo.load(&invalid).await.expect("load failed").is_empty(),
+        "Invalid batches should not store events"
+    );
+
+    // Two writers racing for the same version: exactly one wins
+    let contended = Uuid::new_v4().to_string();
+    let a = stored_events(&contended, &ns, 0, 2, None, start);
+    let b = stored_events(
+        &contended,
+        &ns,
+        0,
+        1,
+        None,
+        start + ChronoDuration::hours(1),
+    );
+    let (result_a, result_b) =
+        futures_util::future::join(repo.save(&contended, 0, &a), repo.save(&contended, 0, &b))
+            .await;
+    let loaded = repo.load(&contended).await.expect("load failed");
+    match (result_a, result_b) {
+        (Ok(()), Err(CoreError::Concurrency { expected: 0, .. })) => assert_eq!(loaded, a),
+        (Err(CoreError::Concurrency { expected: 0, .. }), Ok(())) => assert_eq!(loaded, b),
+        other => panic!("Exactly one concurrent save should win, got {:?}", other),
+    }
+
+    // Tenant reads are ordered by time across streams, whatever the save order
+    let other_type = format!("{}other", ns);
+    let late = Uuid::new_v4().to_string();
+    let early = Uuid::new_v4().to_string();
+    let typed = Uuid::new_v4().to_string();
+    let foreign_tenant = Uuid::new_v4().to_string();
+    let late_events = stored_events(
+        &late,
+        &ns,
+        0,
+        1,
+        Some(&tenant),
+        start + ChronoDuration::days(2),
+    );
+    let early_events = stored_events(
+        &early,
+        &ns,
+        0,
+        1,
+        Some(&tenant),
+        start + ChronoDuration::days(1),
+    );
+    let typed_events = stored_events(
+        &typed,
+        &other_type,
+        0,
+        1,
+        Some(&tenant),
+        start + ChronoDuration::days(3),
+    );
+    let foreign_events = stored_events(
+        &foreign_tenant,
+        &ns,
+        0,
+        1,
+        Some(&Uuid::new_v4().to_string()),
+        start,
+    );
+    for (id, events) in [
+        (&late, &late_events),
+        (&early, &early_events),
+        (&typed, &typed_events),
+        (&foreign_tenant, &foreign_events),
+    ] {
+        repo.save(id, 0, events).await.expect("Save failed");
+    }
+
+    let everything = [
+        first,
+        second,
+        early_events.clone(),
+        late_events.clone(),
+        typed_events.clone(),
+    ]
+    .concat();
+    let all = repo
+        .load_by_tenant(&tenant, &TenantEventQuery::default())
+        .await
+        .expect("load_by_tenant failed");
+    assert_eq!(
+        all, everything,
+        "Tenant events should be ordered by timestamp"
+    );
+
+    let since = repo
+        .load_by_tenant(
+            &tenant,
+            &TenantEventQuery {
+                since: Some(start + ChronoDuration::days(2)),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("load_by_tenant failed");
+    assert_eq!(
+        since,
+        [late_events, typed_events.clone()].concat(),
+        "`since` should be inclusive and exclude older events"
+    );
+
+    let of_type = repo
+        .load_by_tenant(
+            &tenant,
+            &TenantEventQuery {
+                aggregate_type: Some(other_type),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("load_by_tenant failed");
+    assert_eq!(of_type, typed_events, "Aggregate type filter not applied");
+
+    let limited = repo
+        .load_by_tenant(
+            &tenant,
+            &TenantEventQuery {
+                limit: Some(3),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("load_by_tenant failed");
+    assert_eq!(
+        limited,
+        everything[..3],
+        "Limit should keep the oldest events"
+    );
+}
+
+// --- Cache ---
+
+/// Runs the [`Cache`] conformance suite against `cache`.
+///
+/// Checks reads, writes and deletes, per-entry expiry, batch access and tag and prefix
+/// invalidation. The cache's default TTL must be at least a minute, and the suite waits
+/// a couple of seconds for entries to expire.
+pub async fn cache_conformance<C: Cache + ?Sized>(cache: &C) {
+    let ns = namespace();
+    let key = |name: &str| format!("{}:{}", ns, name);
+
+    // Set, get, overwrite, delete
+    let value_key = key("value");
+    assert_eq!(
+        cache.get(&value_key).await.expect("get failed"),
+        None,
+        "Missing key should read as None"
+    );
+    cache
+        .set(&value_key, b"v1", None)
+        .await
+        .expect("set failed");
+    assert_eq!(
+        cache.get(&value_key).await.expect("get failed"),
+        Some(b"v1".to_vec())
+    );
+    cache
+        .set(&value_key, b"v2", None)
+        .await
+        .expect("set failed");
+    assert_eq!(
+        cache.get(&value_key).await.expect("get failed"),
+        Some(b"v2".to_vec()),
+        "Overwrite should replace the value"
+    );
+    cache.delete(&value_key).await.expect("delete failed");
+    assert_eq!(
+        cache.get(&value_key).await.expect("get failed"),
+        None,
+        "Delete had no effect"
+    );
+    cache
+        .delete(&value_key)
+        .await
+        .expect("Deleting a missing key should succeed");
+
+    // An entry's own TTL overrides the default, and overwriting resets it
+    let short = key("ttl:short");
+    let long = key("ttl:long");
+    let reset = key("ttl:reset");
+    cache
+        .set(&short, b"short", Some(1))
+        .await
+        .expect("set failed");
+    cache.set(&long, b"long", None).await.expect("set failed");
+    cache.set(&reset, b"v1", Some(1)).await.expect("set failed");
+    cache.set(&reset, b"v2", None).await.expect("set failed");
+    assert_eq!(
+        cache.get(&short).await.expect("get failed"),
+        Some(b"short".to_vec())
+    );
+    sleep(Duration::from_millis(1500)).await;
+    assert_eq!(
+        cache.get(&short).await.expect("get failed"),
+        None,
+        "Entry should expire after its own TTL"
+    );
+    assert_eq!(
+        cache.get(&long).await.expect("get failed"),
+        Some(b"long".to_vec()),
+        "Entry without a TTL should use the default"
+    );
+    assert_eq!(
+        cache.get(&reset).await.expect("get failed"),
+        Some(b"v2".to_vec()),
+        "Overwriting should reset the TTL"
+    );
+
+    // Batch reads keep one slot per requested key, in order
+    let (a, b, missing) = (key("many:a"), key("many:b"), key("many:missing"));
+    cache
+        .set_many(
+            &[(a.as_str(), b"1".as_slice()), (b.as_str(), b"2".as_slice())],
+            None,
+        )
+        .await
+        .expect("set_many failed");
+    assert_eq!(
+        cache
+            .get_many(&[&a, &missing, &b])
+            .await
+            .expect("get_many failed"),
+        vec![Some(b"1".to_vec()), None, Some(b"2".to_vec())]
+    );
+    assert!(
+        cache
+            .get_many(&[])
+            .await
+            .expect("get_many failed")
+            .is_empty(),
+        "Empty batch should read as empty"
+    );
+
+    // Tag invalidation drops every entry carrying the tag, and only those
+    let (tag_1, tag_2) = (key("tag:1"), key("tag:2"));
+    let (one, both, two) = (key("tagged:1"), key("tagged:both"), key("tagged:2"));
+    cache
+        .set_tagged(&one, b"1", None, &[&tag_1])
+        .await
+        .expect("set_tagged failed");
+    cache
+        .set_tagged(&both, b"12", Some(60), &[&tag_1, &tag_2])
+        .await
+        .expect("set_tagged failed");
+    cache
+        .set_tagged(&two, b"2", None, &[&tag_2])
+        .await
+        .expect("set_tagged failed");
+    cache
+        .invalidate_tag(&tag_1)
+        .await
+        .expect("invalidate_tag failed");
+    assert_eq!(
+        cache
+            .get_many(&[&one, &both, &two])
+            .await
+            .expect("get_many failed"),
+        vec![None, None, Some(b"2".to_vec())],
+        "Tag invalidation should drop exactly the tagged entries"
+    );
+    cache
+        .invalidate_tag(&key("tag:unknown"))
+        .await
+        .expect("Invalidating an unknown tag should succeed");
+
+    // Prefix invalidation, with glob characters taken literally
+    let (p1, p2) = (key("q*:t1:offset:0"), key("q*:t1:offset:50"));
+    let (other, lookalike) = (key("q*:t2:offset:0"), key("qx:t1:offset:0"));
+    for (k, v) in [
+        (&p1, b"p1"),
+        (&p2, b"p2"),
+        (&other, b"o1"),
+        (&lookalike, b"o2"),
+    ] {
+        cache.set(k, v, None).await.expect("set failed");
+    }
+    cache
+        .invalidate_prefix(&key("q*:t1:"))
+        .await
+        .expect("invalidate_prefix failed");
+    assert_eq!(
+        cache
+            .get_many(&[&p1, &p2, &other, &lookalike])
+            .await
+            .expect("get_many failed"),
+        vec![None, None, Some(b"o1".to_vec()), Some(b"o2".to_vec())],
+        "Prefix invalidation should drop exactly the entries under the prefix"
+    );
+}
+
+// --- Publisher ---
+
+/// Transport differences the [`publisher_conformance`] suite allows for.
+#[derive(Debug, Clone)]
+pub struct PublisherConformance {
+    /// Whether delivered events carry the published event type. Redis Pub/Sub only
+    /// carries the payload, so its subscribers see an empty type.
+    pub carries_event_type: bool,
+    /// How long to wait for an expected delivery.
+    pub delivery_timeout: Duration,
+    /// How long to wait before concluding that a message is not delivered.
+    pub quiet_period: Duration,
+}
+
+impl Default for PublisherConformance {
+    fn default() -> Self {
+        Self {
+            carries_event_type: true,
+            delivery_timeout: Duration::from_secs(5),
+            quiet_period: Duration::from_millis(300),
+        }
+    }
+}
+
+async fn next_event(
+    receiver: &mut UnboundedReceiver<ReceivedEvent>,
+    wait: Duration,
+    what: &str,
+) -> ReceivedEvent {
+    timeout(wait, receiver.recv())
+        .await
+        .unwrap_or_else(|_| panic!("Timed out waiting for {}", what))
+        .expect("Subscription channel closed")
+}
+
+async fn assert_quiet(receiver: &mut UnboundedReceiver<ReceivedEvent>, wait: Duration, what: &str) {
+    if let Ok(Some(event)) = timeout(wait, receiver.recv()).await {
+        panic!("{} received unexpected message {:?}", what, event);
+    }
+}
+
+// Subscribes a handler that forwards every delivered message to a channel and acks it
+async fn collect(
+    subscriber: &dyn EventSubscriber,
+    name: &str,
+    pattern: &str,
+) -> (SubscriptionHandle, UnboundedReceiver<ReceivedEvent>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let handler = move |event: ReceivedEvent| {
+        let tx = tx.clone();
+        async move {
+            let _ = tx.send(event);
+            HandlerOutcome::Ack
+        }
+    };
+    let handle = subscriber
+        .subscribe(Subscription::new(name, pattern), Arc::new(handler))
+        .await
+        .unwrap_or_else(|e| panic!("Subscribing to {} failed: {:?}", pattern, e));
+    (handle, rx)
+}
+
+/// Runs the publish/subscribe conformance suite: `publisher` publishes and `subscriber`
+/// consumes, which may be the same bus or two connections to the same transport.
+///
+/// Checks delivery of topic, type and payload, `*` and `#` pattern matching, fan-out to
+/// separately named subscriptions, batch ordering, redelivery on `Requeue` and that
+/// cancelled subscriptions stop receiving. Every message published is routed to at
+/// least one subscription, so transports that reject unroutable messages pass.
+pub async fn publisher_conformance(
+    publisher: &dyn EventPublisher,
+    subscriber: &dyn EventSubscriber,
+    options: PublisherConformance,
+) {
+    let ns = namespace();
+    let wait = options.delivery_timeout;
+    let quiet = options.quiet_period;
+    let expected_type = |event_type: &str| {
+        if options.carries_event_type {
+            event_type.to_string()
+        } else {
+            String::new()
+        }
+    };
+
+    let (_users, mut users) = collect(
+        subscriber,
+        &format!("{}-users", ns),
+        &format!("{}.user.*", ns),
+    )
+    .await;
+    let (_all, mut all) = collect(subscriber, &format!("{}-all", ns), &format!("{}.#", ns)).await;
+    let (_fanout, mut fanout) = collect(
+        subscriber,
+        &format!("{}-fanout", ns),
+        &format!("{}.user.*", ns),
+    )
+    .await;
+
+    // `*` matches exactly one word, `#` any number; separately named subscriptions each
+    // get their own copy
+    let user_topic = format!("{}.user.u1", ns);
+    publisher
+        .publish(&user_topic, "UserRegistered", b"registered")
+        .await
+        .expect("Publish failed");
+    for (receiver, name) in [
+        (&mut users, "`*` subscription"),
+        (&mut fanout, "second subscription"),
+    ] {
+        let event = next_event(receiver, wait, name).await;
+        assert_eq!(event.topic, user_topic, "{} got the wrong topic", name);
+        assert_eq!(
+            event.event_type,
+            expected_type("UserRegistered"),
+            "{} got the wrong type",
+            name
+        );
+        assert_eq!(
+            event.payload, b"registered",
+            "{} got the wrong payload",
+            name
+        );
+    }
+    assert_eq!(
+        next_event(&mut all, wait, "`#` subscription").await.topic,
+        user_topic
+    );
+
+    let deep_topic = format!("{}.user.u1.keys", ns);
+    let root_topic = ns.clone();
+    for topic in [&deep_topic, &root_topic] {
+        publisher
+            .publish(topic, "Unrelated", b"x")
+            .await
+            .expect("Publish failed");
+        assert_eq!(
+            next_event(&mut all, wait, topic).await.topic,
+            *topic,
+            "`#` should match any number of words"
+        );
+    }
+    assert_quiet(&mut users, quiet, "`*` subscription").await;
+    assert_quiet(&mut fanout, quiet, "Second `*` subscription").await;
+
+    // Batches arrive in order
+    let batch: Vec<OutgoingEvent> = (1..=5u8)
+        .map(|n| {
+            OutgoingEvent::new(
+                format!("{}.user.u{}", ns, n),
+                format!("Event{}", n),
+                vec![n],
+            )
+        })
+        .collect();
+    publisher
+        .publish_batch(&batch)
+        .await
+        .expect("Batch publish failed");
+    for expected in &batch {
+        let event = next_event(&mut users, wait, &expected.topic).await;
+        assert_eq!(event.topic, expected.topic, "Batch delivered out of order");
+        assert_eq!(event.event_type, expected_type(&expected.event_type));
+        assert_eq!(event.payload, expected.payload);
+    }
+
+    // `Requeue` redelivers until the handler settles; cancelled subscriptions stop
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let counter = attempts.clone();
+    let (settled_tx, mut settled) = mpsc::unbounded_channel();
+    let handler = move |event: ReceivedEvent| {
+        let counter = counter.clone();
+        let settled_tx = settled_tx.clone();
+        async move {
+            if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                HandlerOutcome::Requeue
+            } else {
+                let _ = settled_tx.send(event);
+                HandlerOutcome::Ack
+            }
+        }
+    };
+    let retry_topic = format!("{}.retry.r1", ns);
+    let retrying = subscriber
+        .subscribe(
+            Subscription::new(format!("{}-retry", ns), format!("{}.retry.*", ns)),
+            Arc::new(handler),
+        )
+        .await
+        .expect("Subscribe failed");
+    publisher
+        .publish(&retry_topic, "Retried", b"again")
+        .await
+        .expect("Publish failed");
+    assert_eq!(
+        next_event(&mut settled, wait, "redelivery").await.payload,
+        b"again"
+    );
+    sleep(quiet).await;
+    assert_eq!(
+        attempts.load(Ordering::SeqCst),
+        2,
+        "Message should be handled once more after a requeue, then acked"
+    );
+
+    retrying.cancel();
+    timeout(wait, retrying.closed())
+        .await
+        .expect("Subscription did not stop after cancel");
+    // Transports that drop the queue with its consumer may report this one as unroutable
+    let _ = publisher.publish(&retry_topic, "Retried", b"late").await;
+    sleep(quiet).await;
+    assert_eq!(
+        attempts.load(Ordering::SeqCst),
+        2,
+        "Cancelled subscription should not receive messages"
+    );
+}
@@ -0,0 +1,1003 @@
+use crate::domain::airport::AirportDatabase;
+use crate::domain::maintenance::{applies_to, due_inspections, MaintenanceProgram, Usage};
+use crate::framework::{EventCodec, TenantScoped};
+use crate::services::DomainServices;
+use crate::{Command, CoreError, DomainEvent, Event};
+use cqrs_es::Aggregate;
+use prost::Message;
+pub use proto::aircraft::aircraft_command::AircraftCommand;
+pub use proto::aircraft::AircraftStatus;
+use proto::aircraft::{
+    AddAircraft, AircraftAdded, AircraftBaseTransferred, AircraftFlightRecorded, AircraftGrounded,
+    AircraftMaintenanceCompleted, AircraftRelocated, AircraftRetired, AircraftStatusChanged,
+    ChangeAircraftStatus, CompleteMaintenance, RecordAircraftFlight, RelocateAircraft,
+    RetireAircraft, TransferAircraftBase,
+};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+// --- Aircraft Aggregate ---
+
+/// One airframe in a tenant's fleet.
+#[derive(Debug, Default, Clone)]
+pub struct Aircraft {
+    id: String,
+    version: usize,
+    tenant_id: String,
+    registration: String,
+    icao_type: String, // Designator in the tenant's aircraft type catalog
+    home_base_icao: String,
+    name: String,
+    status: AircraftStatus,
+    // Airframe totals from approved PIREPs, each counted once
+    usage: Usage,
+    recorded_pireps: HashSet<String>,
+    // Totals when each inspection was last done, and the inspections now due
+    inspections_done: BTreeMap<String, Usage>,
+    inspections_due: BTreeSet<String>,
+}
+
+// --- Commands ---
+
+impl Command for AddAircraft {}
+impl Command for RetireAircraft {}
+impl Command for ChangeAircraftStatus {}
+impl Command for TransferAircraftBase {}
+impl Command for RelocateAircraft {}
+impl Command for RecordAircraftFlight {}
+impl Command for CompleteMaintenance {}
+
+// --- Events ---
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AircraftEvent {
+    Added(AircraftAdded),
+    Retired(AircraftRetired),
+    StatusChanged(AircraftStatusChanged),
+    BaseTransferred(AircraftBaseTransferred),
+    Relocated(AircraftRelocated),
+    FlightRecorded(AircraftFlightRecorded),
+    Grounded(AircraftGrounded),
+    MaintenanceCompleted(AircraftMaintenanceCompleted),
+}
+
+impl DomainEvent for AircraftEvent {
+    fn event_type(&self) -> String {
+        match self {
+            AircraftEvent::Added(_) => "AircraftAdded".to_string(),
+            AircraftEvent::Retired(_) => "AircraftRetired".to_string(),
+            AircraftEvent::StatusChanged(_) => "AircraftStatusChanged".to_string(),
+            AircraftEvent::BaseTransferred(_) => "AircraftBaseTransferred".to_string(),
+            AircraftEvent::Relocated(_) => "AircraftRelocated".to_string(),
+            AircraftEvent::FlightRecorded(_) => "AircraftFlightRecorded".to_string(),
+            AircraftEvent::Grounded(_) => "AircraftGrounded".to_string(),
+            AircraftEvent::MaintenanceCompleted(_) => "AircraftMaintenanceCompleted".to_string(),
+        }
+    }
+
+    fn event_version(&self) -> String {
+        "0.1.0".to_string()
+    }
+}
+
+impl EventCodec for AircraftEvent {
+    fn encode_payload(&self) -> Vec<u8> {
+        match self {
+            AircraftEvent::Added(e) => e.encode_to_vec(),
+            AircraftEvent::Retired(e) => e.encode_to_vec(),
+            AircraftEvent::StatusChanged(e) => e.encode_to_vec(),
+            AircraftEvent::BaseTransferred(e) => e.encode_to_vec(),
+            AircraftEvent::Relocated(e) => e.encode_to_vec(),
+            AircraftEvent::FlightRecorded(e) => e.encode_to_vec(),
+            AircraftEvent::Grounded(e) => e.encode_to_vec(),
+            AircraftEvent::MaintenanceCompleted(e) => e.encode_to_vec(),
+        }
+    }
+
+    fn decode_payload(event_type: &str, payload: &[u8]) -> Result<Self, CoreError> {
+        let decoded = match event_type {
+            "AircraftAdded" => AircraftAdded::decode(payload).map(AircraftEvent::Added),
+            "AircraftRetired" => AircraftRetired::decode(payload).map(AircraftEvent::Retired),
+            "AircraftStatusChanged" => {
+                AircraftStatusChanged::decode(payload).map(AircraftEvent::StatusChanged)
+            }
+            "AircraftBaseTransferred" => {
+                AircraftBaseTransferred::decode(payload).map(AircraftEvent::BaseTransferred)
+            }
+            "AircraftRelocated" => AircraftRelocated::decode(payload).map(AircraftEvent::Relocated),
+            "AircraftFlightRecorded" => {
+                AircraftFlightRecorded::decode(payload).map(AircraftEvent::FlightRecorded)
+            }
+            "AircraftGrounded" => AircraftGrounded::decode(payload).map(AircraftEvent::Grounded),
+            "AircraftMaintenanceCompleted" => AircraftMaintenanceCompleted::decode(payload)
+                .map(AircraftEvent::MaintenanceCompleted),
+            other => {
+                return Err(CoreError::Deserialization(format!(
+                    "Unknown aircraft event type: {}",
+                    other
+                )))
+            }
+        };
+        decoded.map_err(|e| {
+            CoreError::Deserialization(format!("Failed to decode {}: {}", event_type, e))
+        })
+    }
+}
+
+impl Event for AircraftAdded {}
+impl Event for AircraftRetired {}
+impl Event for AircraftStatusChanged {}
+impl Event for AircraftBaseTransferred {}
+impl Event for AircraftRelocated {}
+impl Event for AircraftFlightRecorded {}
+impl Event for AircraftGrounded {}
+impl Event for AircraftMaintenanceCompleted {}
+
+// --- Errors ---
+
+#[derive(thiserror::Error, Debug)]
+pub enum AircraftError {
+    #[error("Core Error: {0}")]
+    Core(#[from] CoreError),
+    #[error("Aircraft already added (ID: {0})")]
+    AlreadyExists(String),
+    #[error("Aircraft not found (ID: {0})")]
+    NotFound(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("Aircraft {0} is retired")]
+    Retired(String),
+}
+
+// --- Aggregate Implementation ---
+
+impl Aggregate for Aircraft {
+    const TYPE: &'static str = "aircraft";
+    type Command = AircraftCommand;
+    type Event = AircraftEvent;
+    type Error = AircraftError;
+    type Services = DomainServices;
+
+    fn aggregate_id(&self) -> &str {
+        &self.id
+    }
+
+    fn version(&self) -> usize {
+        self.version
+    }
+
+    fn apply(&mut self, event: Self::Event) {
+        match event {
+            AircraftEvent::Added(AircraftAdded {
+                aircraft_id,
+                tenant_id,
+                registration,
+                icao_type,
+                home_base_icao,
+                name,
+                ..
+            }) => {
+                self.id = aircraft_id;
+                self.tenant_id = tenant_id;
+                self.registration = registration;
+                self.icao_type = icao_type;
+                self.home_base_icao = home_base_icao;
+                self.name = name;
+                self.status = AircraftStatus::Active;
+            }
+            AircraftEvent::Retired(_) => self.status = AircraftStatus::Retired,
+            AircraftEvent::StatusChanged(AircraftStatusChanged { status, .. }) => {
+                self.status = AircraftStatus::try_from(status).unwrap_or_default();
+            }
+            AircraftEvent::BaseTransferred(AircraftBaseTransferred { to_icao, .. }) => {
+                self.home_base_icao = to_icao;
+            }
+            // Where the aircraft is follows from PIREPs too, so only the read model tracks it
+            AircraftEvent::Relocated(_) => {}
+            AircraftEvent::FlightRecorded(AircraftFlightRecorded {
+                pirep_id,
+                airframe_hours,
+                cycles,
+                ..
+            }) => {
+                self.recorded_pireps.insert(pirep_id);
+                self.usage = Usage {
+                    hours: airframe_hours,
+                    cycles,
+                };
+            }
+            AircraftEvent::Grounded(AircraftGrounded { inspections, .. }) => {
+                self.inspections_due = inspections.into_iter().collect();
+                self.status = AircraftStatus::Grounded;
+            }
+            AircraftEvent::MaintenanceCompleted(AircraftMaintenanceCompleted {
+                inspections,
+                airframe_hours,
+                cycles,
+                released,
+                ..
+            }) => {
+                for inspection in inspections {
+                    self.inspections_due.remove(&inspection);
+                    self.inspections_done.insert(
+                        inspection,
+                        Usage {
+                            hours: airframe_hours,
+                            cycles,
+                        },
+                    );
+                }
+                if released {
+                    self.status = AircraftStatus::Active;
+                }
+            }
+        }
+        self.version += 1;
+    }
+
+    async fn handle(
+        &self,
+        command: Self::Command,
+        services: &Self::Services,
+    ) -> Result<Vec<Self::Event>, Self::Error> {
+        // Only adding works on an aircraft that does not exist yet. Aircraft of other
+        // tenants are reported as missing, and retired ones can no longer be changed.
+        if !matches!(command, AircraftCommand::Add(_)) {
+            let (aircraft_id, tenant_id) = match &command {
+                AircraftCommand::Retire(c) => (&c.aircraft_id, &c.tenant_id),
+                AircraftCommand::ChangeStatus(c) => (&c.aircraft_id, &c.tenant_id),
+                AircraftCommand::TransferBase(c) => (&c.aircraft_id, &c.tenant_id),
+                AircraftCommand::Relocate(c) => (&c.aircraft_id, &c.tenant_id),
+                AircraftCommand::RecordFlight(c) => (&c.aircraft_id, &c.tenant_id),
+                AircraftCommand::CompleteMaintenance(c) => (&c.aircraft_id, &c.tenant_id),
+                AircraftCommand::Add(c) => (&c.aircraft_id, &c.tenant_id),
+            };
+            if self.version == 0 || *tenant_id != self.tenant_id {
+                return Err(AircraftError::NotFound(aircraft_id.clone()));
+            }
+            if self.status == AircraftStatus::Retired {
+                return Err(AircraftError::Retired(self.registration.clone()));
+            }
+        }
+
+        match command {
+            AircraftCommand::Add(cmd) => self.handle_add(cmd, services),
+            AircraftCommand::Retire(cmd) => self.handle_retire(cmd, services),
+            AircraftCommand::ChangeStatus(cmd) => self.handle_change_status(cmd, services),
+            AircraftCommand::TransferBase(cmd) => self.handle_transfer_base(cmd, services),
+            AircraftCommand::Relocate(cmd) => self.handle_relocate(cmd, services),
+            AircraftCommand::RecordFlight(cmd) => self.handle_record_flight(cmd, services),
+            AircraftCommand::CompleteMaintenance(cmd) => {
+                self.handle_complete_maintenance(cmd, services)
+            }
+        }
+    }
+}
+
+impl TenantScoped for Aircraft {
+    fn owning_tenant(&self) -> Option<&str> {
+        (!self.tenant_id.is_empty()).then_some(self.tenant_id.as_str())
+    }
+}
+
+/// Normalizes a registration ("ln-alb" becomes "LN-ALB") and checks its characters.
+pub fn normalize_registration(registration: &str) -> Result<String, AircraftError> {
+    let registration = registration.trim().to_uppercase();
+    let well_formed = (2..=10).contains(&registration.len())
+        && registration
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        && !registration.starts_with('-')
+        && !registration.ends_with('-');
+    if !well_formed {
+        return Err(AircraftError::InvalidInput(format!(
+            "'{}' is not a registration (2-10 letters, digits or dashes)",
+            registration
+        )));
+    }
+    Ok(registration)
+}
+
+// Bases and locations have to be airports known to the airport database
+fn known_airport(airports: &AirportDatabase, icao: &str) -> Result<String, AircraftError> {
+    airports
+        .get(icao)
+        .map(|airport| airport.icao.clone())
+        .ok_or_else(|| AircraftError::InvalidInput(format!("Unknown airport: {}", icao)))
+}
+
+impl Aircraft {
+    // --- Public Getters ---
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+    pub fn tenant_id(&self) -> &str {
+        &self.tenant_id
+    }
+    pub fn registration(&self) -> &str {
+        &self.registration
+    }
+    pub fn icao_type(&self) -> &str {
+        &self.icao_type
+    }
+    pub fn home_base_icao(&self) -> &str {
+        &self.home_base_icao
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn status(&self) -> AircraftStatus {
+        self.status
+    }
+    pub fn airframe_hours(&self) -> f64 {
+        self.usage.hours
+    }
+    pub fn cycles(&self) -> u32 {
+        self.usage.cycles
+    }
+    pub fn inspections_due(&self) -> impl Iterator<Item = &str> {
+        self.inspections_due.iter().map(String::as_str)
+    }
+
+    /// Inspections to be done before the aircraft flies again under `program`: those
+    /// it was grounded for, and any interval it has already passed, such as one of a
+    /// program configured after its last flight.
+    pub fn inspections_due_under(&self, program: &MaintenanceProgram) -> Vec<String> {
+        let mut due = self.inspections_due.clone();
+        due.extend(due_inspections(
+            program,
+            &self.icao_type,
+            self.usage,
+            &self.inspections_done,
+        ));
+        due.into_iter().collect()
+    }
+
+    // --- Command Handlers ---
+
+    fn handle_add(
+        &self,
+        command: AddAircraft,
+        services: &DomainServices,
+    ) -> Result<Vec<AircraftEvent>, AircraftError> {
+        if command.aircraft_id.is_empty()
+            || command.tenant_id.is_empty()
+            || command.icao_type.trim().is_empty()
+        {
+            return Err(AircraftError::InvalidInput(
+                "Missing required fields".into(),
+            ));
+        }
+        let registration = normalize_registration(&command.registration)?;
+        let home_base_icao = known_airport(services.airports, &command.home_base_icao)?;
+        if self.version > 0 {
+            return Err(AircraftError::AlreadyExists(self.id.clone()));
+        }
+
+        Ok(vec![AircraftEvent::Added(AircraftAdded {
+            aircraft_id: command.aircraft_id,
+            tenant_id: command.tenant_id,
+            registration,
+            icao_type: command.icao_type.trim().to_uppercase(),
+            home_base_icao,
+            name: command.name.trim().to_string(),
+            timestamp: services.clock.timestamp(),
+        })])
+    }
+
+    fn handle_retire(
+        &self,
+        command: RetireAircraft,
+        services: &DomainServices,
+    ) -> Result<Vec<AircraftEvent>, AircraftError> {
+        Ok(vec![AircraftEvent::Retired(AircraftRetired {
+            aircraft_id: self.id.clone(),
+            tenant_id: self.tenant_id.clone(),
+            reason: command.reason,
+            timestamp: services.clock.timestamp(),
+        })])
+    }
+
+    fn handle_change_status(
+        &self,
+        command: ChangeAircraftStatus,
+        services: &DomainServices,
+    ) -> Result<Vec<AircraftEvent>, AircraftError> {
+        if self.status == AircraftStatus::Grounded {
+            return Err(AircraftError::InvalidInput(format!(
+                "Aircraft {} is grounded until its maintenance is completed",
+                self.registration
+            )));
+        }
+        let status = match AircraftStatus::try_from(command.status) {
+            Ok(
+                status @ (AircraftStatus::Active
+                | AircraftStatus::Maintenance
+                | AircraftStatus::Stored),
+            ) => status,
+            Ok(AircraftStatus::Retired) => {
+                return Err(AircraftError::InvalidInput(
+                    "Use retire to take an aircraft out of the fleet".into(),
+                ))
+            }
+            _ => {
+                return Err(AircraftError::InvalidInput(
+                    "Status must be active, maintenance or stored".into(),
+                ))
+            }
+        };
+        if status == self.status {
+            return Err(AircraftError::InvalidInput(format!(
+                "Aircraft {} is already {}",
+                self.registration,
+                status_label(status)
+            )));
+        }
+
+        Ok(vec![AircraftEvent::StatusChanged(AircraftStatusChanged {
+            aircraft_id: self.id.clone(),
+            tenant_id: self.tenant_id.clone(),
+            status: status as i32,
+            reason: command.reason,
+            timestamp: services.clock.timestamp(),
+        })])
+    }
+
+    fn handle_transfer_base(
+        &self,
+        command: TransferAircraftBase,
+        services: &DomainServices,
+    ) -> Result<Vec<AircraftEvent>, AircraftError> {
+        let to_icao = known_airport(services.airports, &command.home_base_icao)?;
+        if to_icao == self.home_base_icao {
+            return Err(AircraftError::InvalidInput(format!(
+                "Aircraft {} is already based at {}",
+                self.registration, to_icao
+            )));
+        }
+
+        Ok(vec![AircraftEvent::BaseTransferred(
+            AircraftBaseTransferred {
+                aircraft_id: self.id.clone(),
+                tenant_id: self.tenant_id.clone(),
+                from_icao: self.home_base_icao.clone(),
+                to_icao,
+                timestamp: services.clock.timestamp(),
+            },
+        )])
+    }
+
+    fn handle_relocate(
+        &self,
+        command: RelocateAircraft,
+        services: &DomainServices,
+    ) -> Result<Vec<AircraftEvent>, AircraftError> {
+        if command.relocated_by.is_empty() {
+            return Err(AircraftError::InvalidInput(
+                "Missing required fields".into(),
+            ));
+        }
+        let reason = command.reason.trim();
+        if reason.is_empty() {
+            return Err(AircraftError::InvalidInput(
+                "A reason is required to relocate an aircraft".into(),
+            ));
+        }
+        let to_icao = known_airport(services.airports, &command.to_icao)?;
+        if command.from_icao.eq_ignore_ascii_case(&to_icao) {
+            return Err(AircraftError::InvalidInput(format!(
+                "Aircraft {} is already at {}",
+                self.registration, to_icao
+            )));
+        }
+
+        Ok(vec![AircraftEvent::Relocated(AircraftRelocated {
+            aircraft_id: self.id.clone(),
+            tenant_id: self.tenant_id.clone(),
+            from_icao: command.from_icao.to_uppercase(),
+            to_icao,
+            reason: reason.to_string(),
+            relocated_by: command.relocated_by,
+            timestamp: services.clock.timestamp(),
+        })])
+    }
+
+    fn handle_record_flight(
+        &self,
+        command: RecordAircraftFlight,
+        services: &DomainServices,
+    ) -> Result<Vec<AircraftEvent>, AircraftError> {
+        if command.pirep_id.is_empty() {
+            return Err(AircraftError::InvalidInput(
+                "Missing required fields".into(),
+            ));
+        }
+        if !(command.flight_time_hours.is_finite() && command.flight_time_hours >= 0.0) {
+            return Err(AircraftError::InvalidInput(
+                "Flight time must be zero or more hours".into(),
+            ));
+        }
+        // Recording the same PIREP again changes nothing
+        if self.recorded_pireps.contains(&command.pirep_id) {
+            return Ok(vec![]);
+        }
+
+        let usage = Usage {
+            hours: self.usage.hours + command.flight_time_hours,
+            cycles: self.usage.cycles + 1,
+        };
+        let timestamp = services.clock.timestamp();
+        let mut events = vec![AircraftEvent::FlightRecorded(AircraftFlightRecorded {
+            aircraft_id: self.id.clone(),
+            tenant_id: self.tenant_id.clone(),
+            pirep_id: command.pirep_id,
+            flight_time_hours: command.flight_time_hours,
+            airframe_hours: usage.hours,
+            cycles: usage.cycles,
+            timestamp: timestamp.clone(),
+        })];
+
+        // Grounded again whenever another inspection falls due
+        let program = command.program.unwrap_or_default();
+        let mut due = self.inspections_due.clone();
+        let newly_due = due_inspections(&program, &self.icao_type, usage, &self.inspections_done)
+            .into_iter()
+            .filter(|inspection| due.insert(inspection.clone()))
+            .count();
+        if newly_due > 0 {
+            events.push(AircraftEvent::Grounded(AircraftGrounded {
+                aircraft_id: self.id.clone(),
+                tenant_id: self.tenant_id.clone(),
+                inspections: due.into_iter().collect(),
+                airframe_hours: usage.hours,
+                cycles: usage.cycles,
+                timestamp,
+            }));
+        }
+        Ok(events)
+    }
+
+    fn handle_complete_maintenance(
+        &self,
+        command: CompleteMaintenance,
+        services: &DomainServices,
+    ) -> Result<Vec<AircraftEvent>, AircraftError> {
+        if command.completed_by.is_empty() || command.inspections.is_empty() {
+            return Err(AircraftError::InvalidInput(
+                "Missing required fields".into(),
+            ));
+        }
+
+        // Inspections of the program for this type, or ones due under an older program.
+        // Names are matched ignoring case and recorded as the program spells them.
+        let program = command.program.unwrap_or_default();
+        let mut inspections = BTreeSet::new();
+        for name in &command.inspections {
+            let name = name.trim();
+            let known = program
+                .inspections
+                .iter()
+                .filter(|inspection| applies_to(inspection, &self.icao_type))
+                .map(|inspection| &inspection.name)
+                .chain(&self.inspections_due)
+                .find(|known| known.eq_ignore_ascii_case(name))
+                .ok_or_else(|| {
+                    AircraftError::InvalidInput(format!(
+                        "{} is not an inspection of the maintenance program for {}",
+                        name, self.icao_type
+                    ))
+                })?;
+            inspections.insert(known.clone());
+        }
+
+        let released =
+            self.status == AircraftStatus::Grounded && self.inspections_due.is_subset(&inspections);
+        Ok(vec![AircraftEvent::MaintenanceCompleted(
+            AircraftMaintenanceCompleted {
+                aircraft_id: self.id.clone(),
+                tenant_id: self.tenant_id.clone(),
+                inspections: inspections.into_iter().collect(),
+                notes: command.notes.trim().to_string(),
+                completed_by: command.completed_by,
+                airframe_hours: self.usage.hours,
+                cycles: self.usage.cycles,
+                released,
+                timestamp: services.clock.timestamp(),
+            },
+        )])
+    }
+}
+
+// Human-readable status for error messages
+fn status_label(status: AircraftStatus) -> &'static str {
+    match status {
+        AircraftStatus::Unspecified => "not in the fleet",
+        AircraftStatus::Active => "active",
+        AircraftStatus::Maintenance => "in maintenance",
+        AircraftStatus::Stored => "stored",
+        AircraftStatus::Retired => "retired",
+        AircraftStatus::Grounded => "grounded",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::maintenance::{InspectionInterval, MaintenanceProgram};
+
+    fn add(registration: &str, home_base_icao: &str) -> AircraftCommand {
+        AircraftCommand::Add(AddAircraft {
+            aircraft_id: "ac-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            registration: registration.to_string(),
+            icao_type: "b738".to_string(),
+            home_base_icao: home_base_icao.to_string(),
+            name: "Roald Amundsen".to_string(),
+        })
+    }
+
+    fn change_status(tenant_id: &str, status: AircraftStatus) -> AircraftCommand {
+        AircraftCommand::ChangeStatus(ChangeAircraftStatus {
+            aircraft_id: "ac-1".to_string(),
+            tenant_id: tenant_id.to_string(),
+            status: status as i32,
+            reason: "A-check".to_string(),
+        })
+    }
+
+    fn transfer_base(home_base_icao: &str) -> AircraftCommand {
+        AircraftCommand::TransferBase(TransferAircraftBase {
+            aircraft_id: "ac-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            home_base_icao: home_base_icao.to_string(),
+        })
+    }
+
+    fn relocate(from_icao: &str, to_icao: &str, reason: &str) -> AircraftCommand {
+        AircraftCommand::Relocate(RelocateAircraft {
+            aircraft_id: "ac-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            to_icao: to_icao.to_string(),
+            reason: reason.to_string(),
+            relocated_by: "admin-1".to_string(),
+            from_icao: from_icao.to_string(),
+        })
+    }
+
+    fn program() -> MaintenanceProgram {
+        MaintenanceProgram {
+            inspections: vec![
+                InspectionInterval {
+                    name: "A-check".to_string(),
+                    every_hours: Some(10.0),
+                    every_cycles: None,
+                    icao_types: vec![],
+                },
+                InspectionInterval {
+                    name: "Landing gear".to_string(),
+                    every_hours: None,
+                    every_cycles: Some(3),
+                    icao_types: vec!["B738".to_string()],
+                },
+            ],
+        }
+    }
+
+    fn record_flight(pirep_id: &str, flight_time_hours: f64) -> AircraftCommand {
+        AircraftCommand::RecordFlight(RecordAircraftFlight {
+            aircraft_id: "ac-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            pirep_id: pirep_id.to_string(),
+            flight_time_hours,
+            program: Some(program()),
+        })
+    }
+
+    fn complete_maintenance(inspections: &[&str]) -> AircraftCommand {
+        AircraftCommand::CompleteMaintenance(CompleteMaintenance {
+            aircraft_id: "ac-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            inspections: inspections.iter().map(|i| i.to_string()).collect(),
+            notes: " All good ".to_string(),
+            completed_by: "admin-1".to_string(),
+            program: Some(program()),
+        })
+    }
+
+    fn retire() -> AircraftCommand {
+        AircraftCommand::Retire(RetireAircraft {
+            aircraft_id: "ac-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            reason: "Sold".to_string(),
+        })
+    }
+
+    // Handles `command`, checks the events survive encoding and applies them
+    async fn execute(
+        aggregate: &mut Aircraft,
+        command: AircraftCommand,
+    ) -> Result<(), AircraftError> {
+        let events = aggregate
+            .handle(command, &DomainServices::default())
+            .await?;
+        for event in events {
+            let decoded =
+                AircraftEvent::decode_payload(&event.event_type(), &event.encode_payload())
+                    .unwrap();
+            assert_eq!(decoded, event);
+            aggregate.apply(event);
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_aircraft() {
+        let mut aircraft = Aircraft::default();
+        execute(&mut aircraft, add(" ln-alb ", "engm"))
+            .await
+            .unwrap();
+
+        assert_eq!(aircraft.id(), "ac-1");
+        assert_eq!(aircraft.owning_tenant(), Some("tenant-1"));
+        assert_eq!(aircraft.registration(), "LN-ALB");
+        assert_eq!(aircraft.icao_type(), "B738");
+        assert_eq!(aircraft.home_base_icao(), "ENGM");
+        assert_eq!(aircraft.name(), "Roald Amundsen");
+        assert_eq!(aircraft.status(), AircraftStatus::Active);
+
+        assert!(matches!(
+            execute(&mut aircraft, add("LN-ALB", "ENGM")).await,
+            Err(AircraftError::AlreadyExists(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_add_aircraft_validates_input() {
+        let mut aircraft = Aircraft::default();
+        for (registration, home_base_icao) in [
+            ("LN ALB", "ENGM"),
+            ("-LNALB", "ENGM"),
+            ("L", "ENGM"),
+            ("LN-ALB", "XXXX"),
+        ] {
+            assert!(
+                matches!(
+                    execute(&mut aircraft, add(registration, home_base_icao)).await,
+                    Err(AircraftError::InvalidInput(_))
+                ),
+                "{} at {}",
+                registration,
+                home_base_icao
+            );
+        }
+        assert_eq!(aircraft.version(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_status_and_base_changes() {
+        let mut aircraft = Aircraft::default();
+        assert!(matches!(
+            execute(&mut aircraft, retire()).await,
+            Err(AircraftError::NotFound(_))
+        ));
+        execute(&mut aircraft, add("LN-ALB", "ENGM")).await.unwrap();
+
+        // Another tenant cannot touch the aircraft
+        assert!(matches!(
+            execute(
+                &mut aircraft,
+                change_status("tenant-2", AircraftStatus::Stored)
+            )
+            .await,
+            Err(AircraftError::NotFound(_))
+        ));
+
+        execute(
+            &mut aircraft,
+            change_status("tenant-1", AircraftStatus::Maintenance),
+        )
+        .await
+        .unwrap();
+        assert_eq!(aircraft.status(), AircraftStatus::Maintenance);
+        for status in [
+            AircraftStatus::Maintenance,
+            AircraftStatus::Retired,
+            AircraftStatus::Unspecified,
+        ] {
+            assert!(matches!(
+                execute(&mut aircraft, change_status("tenant-1", status)).await,
+                Err(AircraftError::InvalidInput(_))
+            ));
+        }
+
+        execute(&mut aircraft, transfer_base("enbr")).await.unwrap();
+        assert_eq!(aircraft.home_base_icao(), "ENBR");
+        assert!(matches!(
+            execute(&mut aircraft, transfer_base("ENBR")).await,
+            Err(AircraftError::InvalidInput(_))
+        ));
+
+        // Retiring is final
+        execute(&mut aircraft, retire()).await.unwrap();
+        assert_eq!(aircraft.status(), AircraftStatus::Retired);
+        for command in [
+            retire(),
+            transfer_base("ENGM"),
+            change_status("tenant-1", AircraftStatus::Active),
+        ] {
+            assert!(matches!(
+                execute(&mut aircraft, command).await,
+                Err(AircraftError::Retired(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_relocate_aircraft() {
+        let mut aircraft = Aircraft::default();
+        execute(&mut aircraft, add("LN-ALB", "ENGM")).await.unwrap();
+
+        let events = aircraft
+            .handle(
+                relocate("ENGM", "enbr", " Ferry for heavy check "),
+                &DomainServices::default(),
+            )
+            .await
+            .unwrap();
+        match &events[..] {
+            [AircraftEvent::Relocated(event)] => {
+                assert_eq!(event.from_icao, "ENGM");
+                assert_eq!(event.to_icao, "ENBR");
+                assert_eq!(event.reason, "Ferry for heavy check");
+                assert_eq!(event.relocated_by, "admin-1");
+            }
+            other => panic!("unexpected events: {:?}", other),
+        }
+        // Relocating leaves the home base alone
+        execute(&mut aircraft, relocate("ENGM", "ENBR", "Ferry"))
+            .await
+            .unwrap();
+        assert_eq!(aircraft.home_base_icao(), "ENGM");
+
+        for command in [
+            relocate("ENBR", "ENBR", "Ferry"),
+            relocate("ENBR", "XXXX", "Ferry"),
+            relocate("ENBR", "ENGM", " "),
+        ] {
+            assert!(matches!(
+                execute(&mut aircraft, command).await,
+                Err(AircraftError::InvalidInput(_))
+            ));
+        }
+        // An unknown location does not stop the relocation
+        execute(&mut aircraft, relocate("", "ENGM", "Location lost"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_flights_ground_aircraft_until_maintenance_is_completed() {
+        let mut aircraft = Aircraft::default();
+        execute(&mut aircraft, add("LN-ALB", "ENGM")).await.unwrap();
+
+        execute(&mut aircraft, record_flight("pirep-1", 4.0))
+            .await
+            .unwrap();
+        // A PIREP is only counted once
+        let events = aircraft
+            .handle(record_flight("pirep-1", 4.0), &DomainServices::default())
+            .await
+            .unwrap();
+        assert!(events.is_empty());
+        execute(&mut aircraft, record_flight("pirep-2", 3.5))
+            .await
+            .unwrap();
+        assert_eq!(aircraft.airframe_hours(), 7.5);
+        assert_eq!(aircraft.cycles(), 2);
+        assert_eq!(aircraft.status(), AircraftStatus::Active);
+
+        // The third cycle makes the landing gear due
+        execute(&mut aircraft, record_flight("pirep-3", 1.0))
+            .await
+            .unwrap();
+        assert_eq!(aircraft.status(), AircraftStatus::Grounded);
+        assert_eq!(
+            aircraft.inspections_due().collect::<Vec<_>>(),
+            vec!["Landing gear"]
+        );
+        assert!(matches!(
+            execute(
+                &mut aircraft,
+                change_status("tenant-1", AircraftStatus::Active)
+            )
+            .await,
+            Err(AircraftError::InvalidInput(_))
+        ));
+
+        // Flying on adds the A-check to what is due
+        let events = aircraft
+            .handle(record_flight("pirep-4", 2.0), &DomainServices::default())
+            .await
+            .unwrap();
+        match &events[..] {
+            [AircraftEvent::FlightRecorded(_), AircraftEvent::Grounded(event)] => {
+                assert_eq!(event.inspections, vec!["A-check", "Landing gear"]);
+                assert_eq!(event.airframe_hours, 10.5);
+                assert_eq!(event.cycles, 4);
+            }
+            other => panic!("unexpected events: {:?}", other),
+        }
+        for event in events {
+            aircraft.apply(event);
+        }
+
+        assert!(matches!(
+            execute(&mut aircraft, complete_maintenance(&["C-check"])).await,
+            Err(AircraftError::InvalidInput(_))
+        ));
+        // Still grounded while an inspection is due
+        execute(&mut aircraft, complete_maintenance(&["landing gear"]))
+            .await
+            .unwrap();
+        assert_eq!(aircraft.status(), AircraftStatus::Grounded);
+        let events = aircraft
+            .handle(
+                complete_maintenance(&["A-CHECK"]),
+                &DomainServices::default(),
+            )
+            .await
+            .unwrap();
+        match &events[..] {
+            [AircraftEvent::MaintenanceCompleted(event)] => {
+                assert_eq!(event.inspections, vec!["A-check"]);
+                assert_eq!(event.notes, "All good");
+                assert_eq!(event.airframe_hours, 10.5);
+                assert!(event.released);
+            }
+            other => panic!("unexpected events: {:?}", other),
+        }
+        for event in events {
+            aircraft.apply(event);
+        }
+        assert_eq!(aircraft.status(), AircraftStatus::Active);
+        assert_eq!(aircraft.inspections_due().count(), 0);
+
+        // Intervals count from the maintenance
+        execute(&mut aircraft, record_flight("pirep-5", 9.0))
+            .await
+            .unwrap();
+        assert_eq!(aircraft.status(), AircraftStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_inspections_due_under_a_new_program() {
+        let mut aircraft = Aircraft::default();
+        execute(&mut aircraft, add("LN-ALB", "ENGM")).await.unwrap();
+        let flight = AircraftCommand::RecordFlight(RecordAircraftFlight {
+            aircraft_id: "ac-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            pirep_id: "pirep-1".to_string(),
+            flight_time_hours: 6.0,
+            program: None,
+        });
+        execute(&mut aircraft, flight).await.unwrap();
+        assert_eq!(aircraft.status(), AircraftStatus::Active);
+
+        // Flown before the program had a 5 hour A-check
+        let program = MaintenanceProgram {
+            inspections: vec![InspectionInterval {
+                name: "A-check".to_string(),
+                every_hours: Some(5.0),
+                ..Default::default()
+            }],
+        };
+        assert_eq!(aircraft.inspections_due_under(&program), vec!["A-check"]);
+        assert!(aircraft
+            .inspections_due_under(&MaintenanceProgram::default())
+            .is_empty());
+
+        execute(&mut aircraft, complete_maintenance(&["A-check"]))
+            .await
+            .unwrap();
+        assert!(aircraft.inspections_due_under(&program).is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+//! Aircraft type catalog.
+//!
+//! The catalog is seeded from a CSV embedded at build time (`data/aircraft_types.csv`).
+//! Tenants extend it with types of their own, which take precedence over an embedded
+//! type with the same designator (see [`AircraftCatalog::with_tenant_types`]).
+
+pub use proto::aircraft::AircraftType;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+const EMBEDDED_AIRCRAFT_TYPES: &str = include_str!("../../data/aircraft_types.csv");
+
+/// Values allowed in `AircraftType::category`.
+pub const CATEGORIES: &[&str] = &["jet", "turboprop", "piston", "helicopter"];
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum AircraftTypeError {
+    #[error("Invalid aircraft type data: {0}")]
+    InvalidData(String),
+}
+
+// One row of the CSV
+#[derive(Debug, Deserialize)]
+struct AircraftTypeRecord {
+    icao_type: String,
+    name: String,
+    manufacturer: String,
+    category: String,
+    cruise_speed_kts: f64,
+    range_nm: f64,
+    seats: u32,
+    mtow_kg: f64,
+}
+
+/// Checks an aircraft type before it is added to a catalog. The designator is expected
+/// in upper case, as ICAO publishes it.
+pub fn validate_aircraft_type(aircraft_type: &AircraftType) -> Result<(), String> {
+    let designator = &aircraft_type.icao_type;
+    let well_formed = (2..=4).contains(&designator.len())
+        && designator.starts_with(|c: char| c.is_ascii_uppercase())
+        && designator
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+    if !well_formed {
+        return Err(format!(
+            "'{}' is not an ICAO type designator (2-4 letters or digits, starting with a letter)",
+            designator
+        ));
+    }
+    if aircraft_type.name.trim().is_empty() {
+        return Err(format!("{} needs a name", designator));
+    }
+    if !CATEGORIES.contains(&aircraft_type.category.as_str()) {
+        return Err(format!(
+            "Category of {} must be one of {}",
+            designator,
+            CATEGORIES.join(", ")
+        ));
+    }
+    let figures = [
+        ("cruise_speed_kts", aircraft_type.cruise_speed_kts),
+        ("range_nm", aircraft_type.range_nm),
+        ("mtow_kg", aircraft_type.mtow_kg),
+    ];
+    for (name, value) in figures {
+        if !(value.is_finite() && value > 0.0) {
+            return Err(format!(
+                "{} of {} must be a positive number",
+                name, designator
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AircraftCatalog {
+    types: BTreeMap<String, AircraftType>,
+}
+
+impl AircraftCatalog {
+    /// The types shipped with the application, parsed on first use.
+    pub fn embedded() -> &'static AircraftCatalog {
+        static CATALOG: OnceLock<AircraftCatalog> = OnceLock::new();
+        CATALOG.get_or_init(|| {
+            AircraftCatalog::from_csv(EMBEDDED_AIRCRAFT_TYPES.as_bytes())
+                .expect("embedded aircraft_types.csv is valid")
+        })
+    }
+
+    pub fn from_csv(data: &[u8]) -> Result<Self, AircraftTypeError> {
+        let mut types = BTreeMap::new();
+        for (line, record) in csv::Reader::from_reader(data).deserialize().enumerate() {
+            let invalid = |message: String| {
+                AircraftTypeError::InvalidData(format!("row {}: {}", line + 1, message))
+            };
+            let record: AircraftTypeRecord = record.map_err(|e| invalid(e.to_string()))?;
+            let aircraft_type = AircraftType {
+                icao_type: record.icao_type.trim().to_uppercase(),
+                name: record.name,
+                manufacturer: record.manufacturer,
+                category: record.category.trim().to_lowercase(),
+                cruise_speed_kts: record.cruise_speed_kts,
+                range_nm: record.range_nm,
+                seats: record.seats,
+                mtow_kg: record.mtow_kg,
+            };
+            validate_aircraft_type(&aircraft_type).map_err(invalid)?;
+            let designator = aircraft_type.icao_type.clone();
+            if types.insert(designator.clone(), aircraft_type).is_some() {
+                return Err(invalid(format!("{} listed twice", designator)));
+            }
+        }
+        Ok(Self { types })
+    }
+
+    /// This catalog with `tenant_types` added, replacing types with the same designator.
+    pub fn with_tenant_types<'a>(
+        &self,
+        tenant_types: impl IntoIterator<Item = &'a AircraftType>,
+    ) -> AircraftCatalog {
+        let mut catalog = self.clone();
+        for aircraft_type in tenant_types {
+            catalog
+                .types
+                .insert(aircraft_type.icao_type.clone(), aircraft_type.clone());
+        }
+        catalog
+    }
+
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Looks up a type by ICAO designator, ignoring case.
+    pub fn get(&self, icao_type: &str) -> Option<&AircraftType> {
+        self.types.get(&icao_type.trim().to_uppercase())
+    }
+
+    /// All types, ordered by designator.
+    pub fn types(&self) -> impl Iterator<Item = &AircraftType> {
+        self.types.values()
+    }
+
+    /// Types whose designator starts with `query`, or whose name or manufacturer
+    /// contains it, ordered by designator.
+    pub fn search(&self, query: &str) -> Vec<&AircraftType> {
+        let query = query.trim().to_uppercase();
+        self.types()
+            .filter(|t| {
+                t.icao_type.starts_with(&query)
+                    || t.name.to_uppercase().contains(&query)
+                    || t.manufacturer.to_uppercase().contains(&query)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caravan() -> AircraftType {
+        AircraftType {
+            icao_type: "C208".to_string(),
+            name: "208B Grand Caravan EX".to_string(),
+            manufacturer: "Cessna".to_string(),
+            category: "turboprop".to_string(),
+            cruise_speed_kts: 195.0,
+            range_nm: 900.0,
+            seats: 0,
+            mtow_kg: 3995.0,
+        }
+    }
+
+    #[test]
+    fn test_embedded_catalog() {
+        let catalog = AircraftCatalog::embedded();
+        assert!(catalog.len() > 40);
+
+        let b738 = catalog.get("b738").unwrap();
+        assert_eq!(b738.name, "737-800");
+        assert_eq!(b738.manufacturer, "Boeing");
+        assert_eq!(b738.category, "jet");
+        assert_eq!(b738.seats, 189);
+        assert!(catalog.get("XXXX").is_none());
+
+        let atrs: Vec<&str> = catalog
+            .search("atr")
+            .iter()
+            .map(|t| t.icao_type.as_str())
+            .collect();
+        assert_eq!(atrs, vec!["AT45", "AT76"]);
+        assert_eq!(catalog.search("").len(), catalog.len());
+    }
+
+    #[test]
+    fn test_tenant_types_extend_and_override() {
+        let embedded = AircraftCatalog::embedded();
+        let custom = AircraftType {
+            icao_type: "ZZ01".to_string(),
+            name: "Homebuilt".to_string(),
+            category: "piston".to_string(),
+            ..caravan()
+        };
+        let catalog = embedded.with_tenant_types([&caravan(), &custom]);
+
+        assert_eq!(catalog.len(), embedded.len() + 1);
+        assert_eq!(catalog.get("C208").unwrap().seats, 0);
+        assert_eq!(catalog.get("zz01").unwrap().name, "Homebuilt");
+        // The shared catalog is left alone
+        assert_eq!(embedded.get("C208").unwrap().seats, 12);
+    }
+
+    #[test]
+    fn test_validate_aircraft_type() {
+        assert!(validate_aircraft_type(&caravan()).is_ok());
+        for icao_type in ["", "C", "c208", "2080", "C2080", "C-20"] {
+            let invalid = AircraftType {
+                icao_type: icao_type.to_string(),
+                ..caravan()
+            };
+            assert!(validate_aircraft_type(&invalid).is_err(), "{}", icao_type);
+        }
+        let glider = AircraftType {
+            category: "glider".to_string(),
+            ..caravan()
+        };
+        assert!(validate_aircraft_type(&glider).is_err());
+        let parked = AircraftType {
+            cruise_speed_kts: 0.0,
+            ..caravan()
+        };
+        assert_eq!(
+            validate_aircraft_type(&parked),
+            Err("cruise_speed_kts of C208 must be a positive number".to_string())
+        );
+    }
+
+    #[test]
+    fn test_from_csv_rejects_bad_rows() {
+        let header =
+            "icao_type,name,manufacturer,category,cruise_speed_kts,range_nm,seats,mtow_kg\n";
+        let csv = |rows: &str| AircraftCatalog::from_csv(format!("{}{}", header, rows).as_bytes());
+
+        assert_eq!(
+            csv("C208,Caravan,Cessna,Turboprop,186,1070,12,3995\n")
+                .unwrap()
+                .get("c208")
+                .unwrap()
+                .category,
+            "turboprop"
+        );
+        assert!(csv("C208,Caravan,Cessna,turboprop,fast,1070,12,3995\n").is_err());
+        assert!(csv("C208,Caravan,Cessna,blimp,186,1070,12,3995\n").is_err());
+        assert!(csv(
+            "C208,Caravan,Cessna,turboprop,186,1070,12,3995\nc208,Caravan,Cessna,turboprop,186,1070,12,3995\n"
+        )
+        .is_err());
+    }
+}
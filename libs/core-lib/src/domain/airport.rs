@@ -0,0 +1,315 @@
+//! Airport reference data.
+//!
+//! The database is read from an OurAirports-style CSV embedded at build time
+//! (`data/airports.csv`). The file uses OurAirports column names, so an export
+//! from ourairports.com can be installed in its place at startup with
+//! [`AirportDatabase::install`]; other columns are ignored.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
+
+const EMBEDDED_AIRPORTS: &str = include_str!("../../data/airports.csv");
+const EARTH_RADIUS_NM: f64 = 3440.065;
+// OurAirports types of places that are not airports anyone flies to
+const SKIPPED_TYPES: [&str; 3] = ["closed", "heliport", "balloonport"];
+
+static INSTALLED: OnceLock<AirportDatabase> = OnceLock::new();
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum AirportError {
+    #[error("Invalid airport data: {0}")]
+    InvalidData(String),
+    #[error("Failed to read airport data: {0}")]
+    Read(String),
+    #[error("An airport database is already installed")]
+    AlreadyInstalled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Airport {
+    pub icao: String,
+    /// Empty for airports without an IATA code.
+    pub iata: String,
+    pub name: String,
+    pub municipality: String,
+    /// ISO 3166-1 alpha-2 code.
+    pub country: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub elevation_ft: Option<i32>,
+}
+
+impl Airport {
+    /// Great-circle distance to `other` in nautical miles.
+    pub fn distance_nm(&self, other: &Airport) -> f64 {
+        great_circle_nm(
+            self.latitude,
+            self.longitude,
+            other.latitude,
+            other.longitude,
+        )
+    }
+}
+
+// One row of the CSV, by OurAirports column name
+#[derive(Debug, Deserialize)]
+struct AirportRecord {
+    ident: String,
+    #[serde(default)]
+    iata_code: String,
+    name: String,
+    latitude_deg: f64,
+    longitude_deg: f64,
+    elevation_ft: Option<i32>,
+    iso_country: String,
+    #[serde(default)]
+    municipality: String,
+    #[serde(default, rename = "type")]
+    kind: String,
+}
+
+pub struct AirportDatabase {
+    airports: Vec<Airport>,
+    by_icao: HashMap<String, usize>,
+}
+
+impl AirportDatabase {
+    /// The airports shipped with the application, parsed on first use.
+    pub fn embedded() -> &'static AirportDatabase {
+        static DATABASE: OnceLock<AirportDatabase> = OnceLock::new();
+        DATABASE.get_or_init(|| {
+            AirportDatabase::from_csv(EMBEDDED_AIRPORTS.as_bytes())
+                .expect("embedded airports.csv is valid")
+        })
+    }
+
+    /// The airports in use: the installed database, or the embedded one.
+    pub fn global() -> &'static AirportDatabase {
+        INSTALLED.get().unwrap_or_else(Self::embedded)
+    }
+
+    /// Replaces the embedded airports for the rest of the process. Call it at startup,
+    /// before anything looks an airport up; only one database can be installed.
+    pub fn install(database: AirportDatabase) -> Result<(), AirportError> {
+        INSTALLED
+            .set(database)
+            .map_err(|_| AirportError::AlreadyInstalled)
+    }
+
+    /// Reads an OurAirports CSV export, e.g. `airports.csv` from ourairports.com.
+    pub fn from_path(path: &Path) -> Result<Self, AirportError> {
+        let data = std::fs::read(path)
+            .map_err(|e| AirportError::Read(format!("{}: {}", path.display(), e)))?;
+        Self::from_csv(&data)
+    }
+
+    /// Parses OurAirports CSV. Closed airports, heliports and balloonports are skipped.
+    pub fn from_csv(data: &[u8]) -> Result<Self, AirportError> {
+        let mut airports: Vec<Airport> = Vec::new();
+        let mut by_icao = HashMap::new();
+        for (line, record) in csv::Reader::from_reader(data).deserialize().enumerate() {
+            let record: AirportRecord = record
+                .map_err(|e| AirportError::InvalidData(format!("row {}: {}", line + 1, e)))?;
+            if SKIPPED_TYPES.contains(&record.kind.as_str()) {
+                continue;
+            }
+            let icao = record.ident.trim().to_uppercase();
+            if icao.is_empty() {
+                return Err(AirportError::InvalidData(format!(
+                    "row {}: missing ident",
+                    line + 1
+                )));
+            }
+            if !(-90.0..=90.0).contains(&record.latitude_deg)
+                || !(-180.0..=180.0).contains(&record.longitude_deg)
+            {
+                return Err(AirportError::InvalidData(format!(
+                    "row {}: coordinates of {} out of range",
+                    line + 1,
+                    icao
+                )));
+            }
+            if by_icao.insert(icao.clone(), airports.len()).is_some() {
+                return Err(AirportError::InvalidData(format!(
+                    "row {}: {} listed twice",
+                    line + 1,
+                    icao
+                )));
+            }
+            airports.push(Airport {
+                icao,
+                iata: record.iata_code.trim().to_uppercase(),
+                name: record.name,
+                municipality: record.municipality,
+                country: record.iso_country,
+                latitude: record.latitude_deg,
+                longitude: record.longitude_deg,
+                elevation_ft: record.elevation_ft,
+            });
+        }
+        Ok(Self { airports, by_icao })
+    }
+
+    pub fn len(&self) -> usize {
+        self.airports.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.airports.is_empty()
+    }
+
+    /// Looks up an airport by ICAO code, ignoring case.
+    pub fn get(&self, icao: &str) -> Option<&Airport> {
+        self.by_icao
+            .get(&icao.trim().to_uppercase())
+            .map(|index| &self.airports[*index])
+    }
+
+    /// Great-circle distance between two airports, if both are known.
+    pub fn distance_nm(&self, from_icao: &str, to_icao: &str) -> Option<f64> {
+        Some(self.get(from_icao)?.distance_nm(self.get(to_icao)?))
+    }
+
+    /// Airports matching `query`, best matches first: exact ICAO or IATA code, then
+    /// ICAO prefix, then name or municipality containing the query.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&Airport> {
+        let query = query.trim().to_uppercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let rank = |airport: &Airport| {
+            if airport.icao == query || airport.iata == query {
+                Some(0)
+            } else if airport.icao.starts_with(&query) {
+                Some(1)
+            } else if airport.name.to_uppercase().contains(&query)
+                || airport.municipality.to_uppercase().contains(&query)
+            {
+                Some(2)
+            } else {
+                None
+            }
+        };
+        let mut matches: Vec<(u8, &Airport)> = self
+            .airports
+            .iter()
+            .filter_map(|airport| rank(airport).map(|rank| (rank, airport)))
+            .collect();
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.icao.cmp(&b.1.icao)));
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(_, airport)| airport)
+            .collect()
+    }
+
+    /// The closest airport to a position, if one is within `max_distance_nm`.
+    pub fn nearest(&self, latitude: f64, longitude: f64, max_distance_nm: f64) -> Option<&Airport> {
+        self.airports
+            .iter()
+            .map(|airport| {
+                let distance =
+                    great_circle_nm(latitude, longitude, airport.latitude, airport.longitude);
+                (distance, airport)
+            })
+            .filter(|(distance, _)| *distance <= max_distance_nm)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, airport)| airport)
+    }
+}
+
+/// Great-circle distance between two coordinates in nautical miles.
+pub fn great_circle_nm(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dphi = (lat2 - lat1).to_radians();
+    let dlambda = (lon2 - lon1).to_radians();
+    let a = (dphi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (dlambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_NM * a.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_great_circle_distance() {
+        let distance = great_circle_nm(60.2934, 5.2181, 60.1939, 11.1004);
+        assert!((distance - 176.0).abs() < 1.0, "got {}", distance);
+        // Across the antimeridian
+        let distance = great_circle_nm(0.0, 179.5, 0.0, -179.5);
+        assert!((distance - 60.0).abs() < 0.1, "got {}", distance);
+    }
+
+    #[test]
+    fn test_embedded_database_lookup() {
+        let airports = AirportDatabase::embedded();
+        assert!(airports.len() > 100);
+
+        let oslo = airports.get("engm").unwrap();
+        assert_eq!(oslo.iata, "OSL");
+        assert_eq!(oslo.country, "NO");
+        assert_eq!(oslo.elevation_ft, Some(681));
+        assert!(airports.get("XXXX").is_none());
+
+        let distance = airports.distance_nm("EKCH", "EGLL").unwrap();
+        assert!((distance - 531.0).abs() < 5.0, "got {}", distance);
+        assert_eq!(airports.distance_nm("EKCH", "XXXX"), None);
+    }
+
+    #[test]
+    fn test_search_ranks_codes_before_names() {
+        let airports = AirportDatabase::embedded();
+        let icaos = |query: &str| {
+            airports
+                .search(query, 10)
+                .iter()
+                .map(|a| a.icao.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(icaos("lhr"), vec!["EGLL"]);
+        assert_eq!(icaos("EGL"), vec!["EGLC", "EGLL"]);
+        // Heathrow, Gatwick, Stansted and City all serve London
+        assert_eq!(icaos("london").len(), 4);
+        assert_eq!(airports.search("london", 2).len(), 2);
+        assert!(icaos("").is_empty());
+    }
+
+    #[test]
+    fn test_nearest_airport() {
+        let airports = AirportDatabase::embedded();
+        let near_bergen = airports.nearest(60.2940, 5.2300, 5.0).unwrap();
+        assert_eq!(near_bergen.icao, "ENBR");
+        // Mid-Atlantic
+        assert!(airports.nearest(45.0, -30.0, 5.0).is_none());
+    }
+
+    #[test]
+    fn test_from_csv_rejects_bad_rows() {
+        let header = "ident,iata_code,name,latitude_deg,longitude_deg,elevation_ft,iso_country\n";
+        let csv = |rows: &str| AirportDatabase::from_csv(format!("{}{}", header, rows).as_bytes());
+
+        let airports = csv("XAAA,,Test Field,10.0,20.0,,ZZ\n").unwrap();
+        assert_eq!(airports.get("xaaa").unwrap().elevation_ft, None);
+        assert!(csv("XAAA,,Test Field,95.0,20.0,,ZZ\n").is_err());
+        assert!(csv("XAAA,,Test Field,north,20.0,,ZZ\n").is_err());
+        assert!(csv("XAAA,,One,10.0,20.0,,ZZ\nxaaa,,Two,10.0,20.0,,ZZ\n").is_err());
+    }
+
+    #[test]
+    fn test_from_csv_reads_ourairports_export() {
+        // Column order and extra columns as in ourairports.com's airports.csv
+        let export = "\
+id,ident,type,name,latitude_deg,longitude_deg,elevation_ft,continent,iso_country,iso_region,municipality,iata_code
+2212,ENVA,large_airport,Trondheim Airport Vaernes,63.4578,10.924,56,EU,NO,NO-50,Trondheim,TRD
+2213,ENXX,closed,Old Field,63.0,10.0,,EU,NO,NO-50,,
+2214,ENHX,heliport,Helipad,63.1,10.1,,EU,NO,NO-50,,
+";
+        let airports = AirportDatabase::from_csv(export.as_bytes()).unwrap();
+        assert_eq!(airports.len(), 1);
+        let trondheim = airports.get("ENVA").unwrap();
+        assert_eq!(trondheim.iata, "TRD");
+        assert_eq!(trondheim.municipality, "Trondheim");
+    }
+}
@@ -0,0 +1,670 @@
+//! Flight track import.
+//!
+//! A pilot uploads the track recorded by their tracker or logger; [`parse_track`] reads it
+//! into [`TrackPoint`]s and [`summarize`] derives the PIREP fields from them: airports,
+//! block and air times, distance flown and the touchdown vertical speed.
+
+use super::airport::great_circle_nm;
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use quick_xml::events::Event as XmlEvent;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+
+const FEET_PER_METER: f64 = 3.28084;
+// Faster than this on the ground counts as moving (taxi), slower as parked
+const TAXI_SPEED_KTS: f64 = 3.0;
+// Without an on-ground flag, faster than this counts as airborne
+const AIRBORNE_SPEED_KTS: f64 = 50.0;
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum FlightLogError {
+    #[error("Invalid flight track: {0}")]
+    InvalidTrack(String),
+}
+
+/// Supported track exports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrackFormat {
+    /// Position samples with a header row, e.g. `time,lat,lon,alt,gs,vs,on_ground`.
+    Csv,
+    /// The same samples as an array of objects, or an object with a `points` array.
+    Json,
+    Gpx,
+    Igc,
+}
+
+impl TrackFormat {
+    /// Format by name or file extension, e.g. "gpx".
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "gpx" => Some(Self::Gpx),
+            "igc" => Some(Self::Igc),
+            _ => None,
+        }
+    }
+
+    /// Format from a MIME type; parameters such as `charset` are ignored.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        match mime.to_ascii_lowercase().as_str() {
+            "text/csv" => Some(Self::Csv),
+            "application/json" => Some(Self::Json),
+            "application/gpx+xml" => Some(Self::Gpx),
+            "application/vnd.fai.igc" => Some(Self::Igc),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Json => "application/json",
+            Self::Gpx => "application/gpx+xml",
+            Self::Igc => "application/vnd.fai.igc",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Gpx => "gpx",
+            Self::Igc => "igc",
+        }
+    }
+}
+
+/// One position sample. Optional values are derived from neighbouring samples when missing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackPoint {
+    pub time: DateTime<Utc>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude_ft: Option<f64>,
+    pub ground_speed_kts: Option<f64>,
+    /// Negative when descending.
+    pub vertical_speed_fpm: Option<f64>,
+    pub on_ground: Option<bool>,
+}
+
+/// PIREP fields derived from a track.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlightSummary {
+    /// Nearest airports to the first and last samples.
+    pub departure_icao: Option<String>,
+    pub arrival_icao: Option<String>,
+    pub block_off: DateTime<Utc>,
+    pub block_on: DateTime<Utc>,
+    /// `None` if the track never left the ground.
+    pub takeoff: Option<DateTime<Utc>>,
+    /// `None` if the track ends in the air.
+    pub touchdown: Option<DateTime<Utc>>,
+    pub block_time_hours: f64,
+    pub air_time_hours: Option<f64>,
+    /// Flown while airborne, or over the whole track if it never left the ground.
+    pub distance_nm: f64,
+    /// Vertical speed at touchdown, positive down like `landing_rate_fpm` on a PIREP.
+    pub landing_rate_fpm: Option<f64>,
+}
+
+/// Reads a track, sorted by time. Needs at least two samples with valid coordinates.
+pub fn parse_track(format: TrackFormat, data: &[u8]) -> Result<Vec<TrackPoint>, FlightLogError> {
+    let mut points = match format {
+        TrackFormat::Csv => parse_csv(data)?,
+        TrackFormat::Json => parse_json(data)?,
+        TrackFormat::Gpx => parse_gpx(data)?,
+        TrackFormat::Igc => parse_igc(data)?,
+    };
+    for point in &points {
+        let valid = point.latitude.is_finite()
+            && point.longitude.is_finite()
+            && (-90.0..=90.0).contains(&point.latitude)
+            && (-180.0..=180.0).contains(&point.longitude);
+        if !valid {
+            return Err(invalid(format!(
+                "Coordinates out of range at {}",
+                point.time.to_rfc3339()
+            )));
+        }
+    }
+    if points.len() < 2 {
+        return Err(invalid("A track needs at least two position samples"));
+    }
+    points.sort_by_key(|point| point.time);
+    Ok(points)
+}
+
+/// Derives the PIREP fields from a parsed track. `nearest_airport` maps a coordinate to
+/// the ICAO code of the closest airport, if any is close enough.
+pub fn summarize(
+    points: &[TrackPoint],
+    nearest_airport: impl Fn(f64, f64) -> Option<String>,
+) -> Result<FlightSummary, FlightLogError> {
+    let (first, last) = match (points.first(), points.last()) {
+        (Some(first), Some(last)) if points.len() >= 2 => (first, last),
+        _ => return Err(invalid("A track needs at least two position samples")),
+    };
+
+    let speeds = ground_speeds(points);
+    let airborne: Vec<bool> = points
+        .iter()
+        .zip(&speeds)
+        .map(|(point, speed)| point.on_ground.map_or(*speed >= AIRBORNE_SPEED_KTS, |g| !g))
+        .collect();
+
+    // Off blocks when the aircraft starts moving, on blocks when it stops for good
+    let first_moving = speeds.iter().position(|speed| *speed >= TAXI_SPEED_KTS);
+    let last_moving = speeds.iter().rposition(|speed| *speed >= TAXI_SPEED_KTS);
+    let block_off = first_moving.map_or(first.time, |i| points[i.saturating_sub(1)].time);
+    let block_on = last_moving.map_or(last.time, |i| points[(i + 1).min(points.len() - 1)].time);
+
+    // Takeoff at the first airborne sample, touchdown at the first ground sample after the
+    // last one; a track that ends in the air has no touchdown
+    let takeoff_index = airborne.iter().position(|a| *a);
+    let touchdown_index = airborne
+        .iter()
+        .rposition(|a| *a)
+        .map(|i| i + 1)
+        .filter(|i| *i < points.len());
+    let takeoff = takeoff_index.map(|i| points[i].time);
+    let touchdown = touchdown_index.map(|i| points[i].time);
+
+    let distance_range = takeoff_index.map_or(0, |i| i.saturating_sub(1))
+        ..touchdown_index.unwrap_or(points.len() - 1);
+    let distance_nm = distance_range
+        .map(|i| segment_nm(&points[i], &points[i + 1]))
+        .sum();
+
+    Ok(FlightSummary {
+        departure_icao: nearest_airport(first.latitude, first.longitude),
+        arrival_icao: nearest_airport(last.latitude, last.longitude),
+        block_off,
+        block_on,
+        takeoff,
+        touchdown,
+        block_time_hours: hours_between(block_off, block_on),
+        air_time_hours: takeoff.zip(touchdown).map(|(t, d)| hours_between(t, d)),
+        distance_nm,
+        landing_rate_fpm: touchdown_index
+            .and_then(|i| touchdown_vertical_speed(&points[i - 1], &points[i]))
+            .map(|vs| -vs),
+    })
+}
+
+fn invalid(message: impl Into<String>) -> FlightLogError {
+    FlightLogError::InvalidTrack(message.into())
+}
+
+fn segment_nm(a: &TrackPoint, b: &TrackPoint) -> f64 {
+    great_circle_nm(a.latitude, a.longitude, b.latitude, b.longitude)
+}
+
+fn hours_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_seconds() as f64 / 3600.0
+}
+
+// Reported ground speed, or the speed over the segment to the previous sample
+// (the next one for the first sample)
+fn ground_speeds(points: &[TrackPoint]) -> Vec<f64> {
+    (0..points.len())
+        .map(|i| {
+            points[i].ground_speed_kts.unwrap_or_else(|| {
+                let (a, b) = if i == 0 {
+                    (&points[0], &points[1])
+                } else {
+                    (&points[i - 1], &points[i])
+                };
+                let hours = hours_between(a.time, b.time);
+                if hours > 0.0 {
+                    segment_nm(a, b) / hours
+                } else {
+                    0.0
+                }
+            })
+        })
+        .collect()
+}
+
+// Reported at touchdown or just before, otherwise from the altitude change over the last segment
+fn touchdown_vertical_speed(before: &TrackPoint, at: &TrackPoint) -> Option<f64> {
+    at.vertical_speed_fpm
+        .or(before.vertical_speed_fpm)
+        .or_else(|| {
+            let minutes = (at.time - before.time).num_milliseconds() as f64 / 60_000.0;
+            match (before.altitude_ft, at.altitude_ft) {
+                (Some(from), Some(to)) if minutes > 0.0 => Some((to - from) / minutes),
+                _ => None,
+            }
+        })
+}
+
+// RFC 3339, or seconds since the Unix epoch
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    value.parse::<f64>().ok().and_then(epoch_time)
+}
+
+fn epoch_time(seconds: f64) -> Option<DateTime<Utc>> {
+    if !seconds.is_finite() {
+        return None;
+    }
+    DateTime::from_timestamp_millis((seconds * 1000.0).round() as i64)
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" => Some(true),
+        "0" | "false" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+// --- CSV ---
+
+const TIME_COLUMNS: &[&str] = &["time", "timestamp", "utc"];
+const LATITUDE_COLUMNS: &[&str] = &["lat", "latitude"];
+const LONGITUDE_COLUMNS: &[&str] = &["lon", "lng", "long", "longitude"];
+const ALTITUDE_COLUMNS: &[&str] = &["altitude_ft", "alt", "altitude"];
+const GROUND_SPEED_COLUMNS: &[&str] = &["ground_speed_kts", "gs", "groundspeed", "ground_speed"];
+const VERTICAL_SPEED_COLUMNS: &[&str] = &["vertical_speed_fpm", "vs", "vertical_speed"];
+const ON_GROUND_COLUMNS: &[&str] = &["on_ground", "onground"];
+
+fn parse_csv(data: &[u8]) -> Result<Vec<TrackPoint>, FlightLogError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(data);
+    let headers = reader
+        .headers()
+        .map_err(|e| invalid(format!("Unreadable CSV header: {}", e)))?
+        .clone();
+    let column = |names: &[&str]| {
+        headers
+            .iter()
+            .position(|h| names.contains(&h.to_ascii_lowercase().as_str()))
+    };
+    let required = |names: &[&str]| {
+        column(names).ok_or_else(|| invalid(format!("CSV has no '{}' column", names[0])))
+    };
+    let (time, latitude, longitude) = (
+        required(TIME_COLUMNS)?,
+        required(LATITUDE_COLUMNS)?,
+        required(LONGITUDE_COLUMNS)?,
+    );
+    let (altitude, ground_speed, vertical_speed, on_ground) = (
+        column(ALTITUDE_COLUMNS),
+        column(GROUND_SPEED_COLUMNS),
+        column(VERTICAL_SPEED_COLUMNS),
+        column(ON_GROUND_COLUMNS),
+    );
+
+    let mut points = Vec::new();
+    for (line, record) in reader.records().enumerate() {
+        // Line numbers count the header
+        let row = line + 2;
+        let record = record.map_err(|e| invalid(format!("CSV row {}: {}", row, e)))?;
+        let field = |index: usize| record.get(index).unwrap_or_default();
+        let number = |index: Option<usize>| -> Result<Option<f64>, FlightLogError> {
+            match index.map(field).filter(|v| !v.is_empty()) {
+                Some(value) => value
+                    .parse()
+                    .map(Some)
+                    .map_err(|_| invalid(format!("CSV row {}: '{}' is not a number", row, value))),
+                None => Ok(None),
+            }
+        };
+        points.push(TrackPoint {
+            time: parse_time(field(time))
+                .ok_or_else(|| invalid(format!("CSV row {}: invalid time", row)))?,
+            latitude: number(Some(latitude))?
+                .ok_or_else(|| invalid(format!("CSV row {}: missing latitude", row)))?,
+            longitude: number(Some(longitude))?
+                .ok_or_else(|| invalid(format!("CSV row {}: missing longitude", row)))?,
+            altitude_ft: number(altitude)?,
+            ground_speed_kts: number(ground_speed)?,
+            vertical_speed_fpm: number(vertical_speed)?,
+            on_ground: on_ground.and_then(|i| parse_flag(field(i))),
+        });
+    }
+    Ok(points)
+}
+
+// --- JSON ---
+
+#[derive(Deserialize)]
+struct JsonPoint {
+    #[serde(alias = "timestamp")]
+    time: serde_json::Value,
+    #[serde(alias = "lat")]
+    latitude: f64,
+    #[serde(alias = "lon", alias = "lng")]
+    longitude: f64,
+    #[serde(default, alias = "alt", alias = "altitude")]
+    altitude_ft: Option<f64>,
+    #[serde(default, alias = "gs", alias = "groundspeed")]
+    ground_speed_kts: Option<f64>,
+    #[serde(default, alias = "vs")]
+    vertical_speed_fpm: Option<f64>,
+    #[serde(default)]
+    on_ground: Option<bool>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonTrack {
+    Points(Vec<JsonPoint>),
+    Wrapped {
+        #[serde(alias = "track")]
+        points: Vec<JsonPoint>,
+    },
+}
+
+fn parse_json(data: &[u8]) -> Result<Vec<TrackPoint>, FlightLogError> {
+    let track: JsonTrack = serde_json::from_slice(data)
+        .map_err(|_| invalid("JSON must be an array of position samples or {\"points\": [...]}"))?;
+    let points = match track {
+        JsonTrack::Points(points) | JsonTrack::Wrapped { points } => points,
+    };
+    points
+        .into_iter()
+        .enumerate()
+        .map(|(i, point)| {
+            let time = match &point.time {
+                serde_json::Value::String(s) => parse_time(s),
+                serde_json::Value::Number(n) => n.as_f64().and_then(epoch_time),
+                _ => None,
+            };
+            Ok(TrackPoint {
+                time: time.ok_or_else(|| invalid(format!("Sample {}: invalid time", i)))?,
+                latitude: point.latitude,
+                longitude: point.longitude,
+                altitude_ft: point.altitude_ft,
+                ground_speed_kts: point.ground_speed_kts,
+                vertical_speed_fpm: point.vertical_speed_fpm,
+                on_ground: point.on_ground,
+            })
+        })
+        .collect()
+}
+
+// --- GPX ---
+
+// The trkpt being read
+struct GpxPoint {
+    latitude: f64,
+    longitude: f64,
+    elevation_m: Option<f64>,
+    time: Option<DateTime<Utc>>,
+}
+
+// Track points (`trkpt`) with `lat`/`lon` attributes and `ele` (meters) and `time` children
+fn parse_gpx(data: &[u8]) -> Result<Vec<TrackPoint>, FlightLogError> {
+    let mut reader = Reader::from_reader(data);
+    reader.config_mut().trim_text(true);
+
+    let mut points = Vec::new();
+    let mut buf = Vec::new();
+    let mut current: Option<GpxPoint> = None;
+    let mut element = Vec::new();
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| invalid(format!("Malformed GPX: {}", e)))?;
+        match event {
+            XmlEvent::Start(e) if e.local_name().as_ref() == b"trkpt" => {
+                let (mut latitude, mut longitude) = (None, None);
+                for attribute in e.attributes().flatten() {
+                    let value = std::str::from_utf8(&attribute.value)
+                        .ok()
+                        .and_then(|v| v.trim().parse::<f64>().ok());
+                    match attribute.key.local_name().as_ref() {
+                        b"lat" => latitude = value,
+                        b"lon" => longitude = value,
+                        _ => {}
+                    }
+                }
+                match (latitude, longitude) {
+                    (Some(latitude), Some(longitude)) => {
+                        current = Some(GpxPoint {
+                            latitude,
+                            longitude,
+                            elevation_m: None,
+                            time: None,
+                        })
+                    }
+                    _ => return Err(invalid("GPX trkpt without valid lat/lon")),
+                }
+            }
+            XmlEvent::Start(e) => element = e.local_name().as_ref().to_vec(),
+            XmlEvent::Text(text) => {
+                if let Some(point) = current.as_mut() {
+                    let text = text
+                        .unescape()
+                        .map_err(|e| invalid(format!("Malformed GPX: {}", e)))?;
+                    match element.as_slice() {
+                        b"ele" => point.elevation_m = text.trim().parse().ok(),
+                        b"time" => point.time = parse_time(&text),
+                        _ => {}
+                    }
+                }
+            }
+            XmlEvent::End(e) if e.local_name().as_ref() == b"trkpt" => {
+                if let Some(point) = current.take() {
+                    points.push(TrackPoint {
+                        time: point
+                            .time
+                            .ok_or_else(|| invalid("GPX trkpt without a time"))?,
+                        latitude: point.latitude,
+                        longitude: point.longitude,
+                        altitude_ft: point.elevation_m.map(|m| m * FEET_PER_METER),
+                        ground_speed_kts: None,
+                        vertical_speed_fpm: None,
+                        on_ground: None,
+                    });
+                }
+            }
+            XmlEvent::End(_) => element.clear(),
+            XmlEvent::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    Ok(points)
+}
+
+// --- IGC ---
+
+// B records (`BHHMMSSDDMMmmmNDDDMMmmmEVPPPPPGGGGG`) dated by the HFDTE header
+fn parse_igc(data: &[u8]) -> Result<Vec<TrackPoint>, FlightLogError> {
+    let text = String::from_utf8_lossy(data);
+    let mut date = None;
+    let mut day_offset = 0;
+    let mut previous: Option<NaiveTime> = None;
+    let mut points = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if let Some(header) = line.strip_prefix("HFDTE") {
+            let digits = header.trim_start_matches("DATE:");
+            date = Some(parse_igc_date(digits).ok_or_else(|| invalid("Invalid IGC date header"))?);
+        } else if line.starts_with('B') {
+            let date = date.ok_or_else(|| invalid("IGC fix before the HFDTE date header"))?;
+            let fix = parse_igc_fix(line)
+                .ok_or_else(|| invalid(format!("Invalid IGC fix '{}'", line)))?;
+            // Fixes are in time order; an earlier time means the flight passed midnight UTC
+            if previous.is_some_and(|p| fix.0 < p) {
+                day_offset += 1;
+            }
+            previous = Some(fix.0);
+            let (time, latitude, longitude, altitude_m) = fix;
+            points.push(TrackPoint {
+                time: Utc.from_utc_datetime(&(date + Duration::days(day_offset)).and_time(time)),
+                latitude,
+                longitude,
+                altitude_ft: altitude_m.map(|m| m * FEET_PER_METER),
+                ground_speed_kts: None,
+                vertical_speed_fpm: None,
+                on_ground: None,
+            });
+        }
+    }
+    Ok(points)
+}
+
+fn parse_igc_date(header: &str) -> Option<NaiveDate> {
+    let digits = header.get(0..6)?;
+    let number = |range: std::ops::Range<usize>| digits.get(range)?.parse::<u32>().ok();
+    NaiveDate::from_ymd_opt(2000 + number(4..6)? as i32, number(2..4)?, number(0..2)?)
+}
+
+// (time, latitude, longitude, altitude in meters: GNSS, or pressure if there is none)
+fn parse_igc_fix(line: &str) -> Option<(NaiveTime, f64, f64, Option<f64>)> {
+    let field = |range: std::ops::Range<usize>| line.get(range);
+    let number = |range: std::ops::Range<usize>| field(range)?.parse::<u32>().ok();
+    let time = NaiveTime::from_hms_opt(number(1..3)?, number(3..5)?, number(5..7)?)?;
+    // Degrees, then minutes with three implied decimals
+    let mut latitude = number(7..9)? as f64 + number(9..14)? as f64 / 60_000.0;
+    if field(14..15)? == "S" {
+        latitude = -latitude;
+    }
+    let mut longitude = number(15..18)? as f64 + number(18..23)? as f64 / 60_000.0;
+    if field(23..24)? == "W" {
+        longitude = -longitude;
+    }
+    let altitude = |range: std::ops::Range<usize>| field(range)?.parse::<i32>().ok();
+    let altitude_m = altitude(30..35)
+        .filter(|m| *m != 0)
+        .or(altitude(25..30))
+        .map(f64::from);
+    Some((time, latitude, longitude, altitude_m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ENBR and ENGM, about 171 nm apart
+    fn nearest(latitude: f64, longitude: f64) -> Option<String> {
+        [("ENBR", 60.2934, 5.2181), ("ENGM", 60.1939, 11.1004)]
+            .iter()
+            .find(|(_, lat, lon)| great_circle_nm(latitude, longitude, *lat, *lon) < 5.0)
+            .map(|(icao, _, _)| icao.to_string())
+    }
+
+    fn time(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 10, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn test_summarize_csv_track_with_flags() {
+        // Parked, taxi, takeoff, cruise, touchdown at -180 fpm, taxi in, parked
+        let csv = "\
+time,lat,lon,alt,gs,vs,on_ground
+2026-03-01T10:00:00Z,60.2934,5.2181,170,0,0,1
+2026-03-01T10:05:00Z,60.2934,5.2181,170,12,0,1
+2026-03-01T10:15:00Z,60.2940,5.2300,170,150,0,1
+2026-03-01T10:16:00Z,60.2950,5.3000,1500,180,2000,0
+2026-03-01T10:50:00Z,60.2000,11.0000,2000,140,-700,0
+2026-03-01T10:55:00Z,60.1939,11.1004,680,120,-180,1
+2026-03-01T11:02:00Z,60.1939,11.1004,680,10,0,1
+2026-03-01T11:05:00Z,60.1939,11.1004,680,0,0,1
+";
+        let points = parse_track(TrackFormat::Csv, csv.as_bytes()).unwrap();
+        assert_eq!(points.len(), 8);
+
+        let summary = summarize(&points, nearest).unwrap();
+        assert_eq!(summary.departure_icao.as_deref(), Some("ENBR"));
+        assert_eq!(summary.arrival_icao.as_deref(), Some("ENGM"));
+        assert_eq!(summary.block_off, time(0));
+        assert_eq!(summary.block_on, time(65));
+        assert_eq!(summary.takeoff, Some(time(16)));
+        assert_eq!(summary.touchdown, Some(time(55)));
+        assert!((summary.block_time_hours - 65.0 / 60.0).abs() < 1e-9);
+        assert!((summary.air_time_hours.unwrap() - 39.0 / 60.0).abs() < 1e-9);
+        assert!(summary.distance_nm > 160.0 && summary.distance_nm < 180.0);
+        assert_eq!(summary.landing_rate_fpm, Some(180.0));
+    }
+
+    #[test]
+    fn test_summarize_json_track_derives_speeds() {
+        // No speeds or flags: movement and flight are derived from the positions
+        let json = r#"{"points": [
+            {"time": "2026-03-01T10:00:00Z", "lat": 60.2934, "lon": 5.2181, "alt": 170},
+            {"time": "2026-03-01T10:10:00Z", "lat": 60.2934, "lon": 5.2181, "alt": 170},
+            {"time": "2026-03-01T10:12:00Z", "lat": 60.2950, "lon": 5.2300, "alt": 170},
+            {"time": "2026-03-01T10:40:00Z", "lat": 60.2000, "lon": 10.0000, "alt": 9000},
+            {"time": "2026-03-01T10:55:00Z", "lat": 60.1950, "lon": 11.0900, "alt": 800},
+            {"time": 1772362800, "lat": 60.1939, "lon": 11.1004, "alt": 680},
+            {"time": "2026-03-01T11:05:00Z", "lat": 60.1939, "lon": 11.1004, "alt": 680}
+        ]}"#;
+        let points = parse_track(TrackFormat::Json, json.as_bytes()).unwrap();
+        assert_eq!(points[5].time, time(60));
+
+        let summary = summarize(&points, nearest).unwrap();
+        assert_eq!(summary.block_off, time(10));
+        assert_eq!(summary.block_on, time(65));
+        assert_eq!(summary.takeoff, Some(time(40)));
+        assert_eq!(summary.touchdown, Some(time(60)));
+        // 120 ft lost in the last 5 minutes before touchdown
+        assert_eq!(summary.landing_rate_fpm, Some(24.0));
+    }
+
+    #[test]
+    fn test_parse_gpx() {
+        let gpx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="tracker" xmlns="http://www.topografix.com/GPX/1/1">
+  <trk><name>ENBR-ENGM</name><trkseg>
+    <trkpt lat="60.2934" lon="5.2181"><ele>52</ele><time>2026-03-01T10:00:00Z</time></trkpt>
+    <trkpt lat="60.1939" lon="11.1004"><ele>207</ele><time>2026-03-01T11:00:00Z</time></trkpt>
+  </trkseg></trk>
+</gpx>"#;
+        let points = parse_track(TrackFormat::Gpx, gpx.as_bytes()).unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[1].time, time(60));
+        assert_eq!(points[1].latitude, 60.1939);
+        assert!((points[0].altitude_ft.unwrap() - 170.6).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_parse_igc_across_midnight() {
+        let igc = "\
+AXXXLOGGER
+HFDTEDATE:280226,01
+B2359306017604N00513086EA0005000052
+B0000306011634N01106024EA0020000207
+";
+        let points = parse_track(TrackFormat::Igc, igc.as_bytes()).unwrap();
+        assert_eq!(
+            points[0].time,
+            Utc.with_ymd_and_hms(2026, 2, 28, 23, 59, 30).unwrap()
+        );
+        assert_eq!(points[1].time, time(-600) + Duration::seconds(30));
+        assert!((points[0].latitude - 60.2934).abs() < 1e-4);
+        assert!((points[1].longitude - 11.1004).abs() < 1e-4);
+        assert!((points[0].altitude_ft.unwrap() - 52.0 * FEET_PER_METER).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_invalid_tracks() {
+        let missing_column = "time,lat\n2026-03-01T10:00:00Z,60.0\n";
+        assert!(parse_track(TrackFormat::Csv, missing_column.as_bytes()).is_err());
+
+        let single = r#"[{"time": "2026-03-01T10:00:00Z", "lat": 60.0, "lon": 5.0}]"#;
+        assert_eq!(
+            parse_track(TrackFormat::Json, single.as_bytes()),
+            Err(invalid("A track needs at least two position samples"))
+        );
+
+        let out_of_range = "time,lat,lon\n2026-03-01T10:00:00Z,91,5\n2026-03-01T10:01:00Z,60,5\n";
+        assert!(parse_track(TrackFormat::Csv, out_of_range.as_bytes()).is_err());
+
+        assert!(parse_track(TrackFormat::Igc, b"B2359306017604N00513086EA0005000052\n").is_err());
+        assert_eq!(TrackFormat::from_name("GPX"), Some(TrackFormat::Gpx));
+        assert_eq!(
+            TrackFormat::from_content_type("text/csv; charset=utf-8"),
+            Some(TrackFormat::Csv)
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Tenant maintenance programs.
+//!
+//! A tenant configures a [`MaintenanceProgram`] of inspection intervals on its aggregate.
+//! Approved flights add to an aircraft's airframe hours and cycles; once an inspection
+//! is due ([`due_inspections`]) the aircraft is grounded until the inspection is done.
+
+pub use proto::aircraft::{InspectionInterval, MaintenanceProgram};
+use std::collections::{BTreeMap, HashSet};
+
+/// Airframe hours and cycles (flights), in total or when an inspection was done.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Usage {
+    pub hours: f64,
+    pub cycles: u32,
+}
+
+/// Checks a program before it is stored.
+pub fn validate_program(program: &MaintenanceProgram) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for inspection in &program.inspections {
+        let name = inspection.name.trim();
+        if name.is_empty() {
+            return Err("Inspections need a name".into());
+        }
+        if !names.insert(name.to_lowercase()) {
+            return Err(format!("{} is listed twice", name));
+        }
+        if inspection.every_hours.is_none() && inspection.every_cycles.is_none() {
+            return Err(format!("{} needs every_hours, every_cycles or both", name));
+        }
+        if inspection
+            .every_hours
+            .is_some_and(|hours| !(hours.is_finite() && hours > 0.0))
+        {
+            return Err(format!("every_hours of {} must be a positive number", name));
+        }
+        if inspection.every_cycles == Some(0) {
+            return Err(format!("every_cycles of {} must be at least 1", name));
+        }
+    }
+    Ok(())
+}
+
+/// The program with names trimmed and aircraft types in upper case, as it is stored.
+pub fn normalize_program(mut program: MaintenanceProgram) -> MaintenanceProgram {
+    for inspection in &mut program.inspections {
+        inspection.name = inspection.name.trim().to_string();
+        for icao_type in &mut inspection.icao_types {
+            *icao_type = icao_type.trim().to_uppercase();
+        }
+    }
+    program
+}
+
+/// Whether an inspection of the program applies to aircraft of `icao_type`.
+pub fn applies_to(inspection: &InspectionInterval, icao_type: &str) -> bool {
+    inspection.icao_types.is_empty()
+        || inspection
+            .icao_types
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(icao_type))
+}
+
+/// Names of the inspections an aircraft of `icao_type` with `usage` has reached, in
+/// program order. Intervals count from when each inspection was last done
+/// (`last_done`), or from a new airframe if never.
+pub fn due_inspections(
+    program: &MaintenanceProgram,
+    icao_type: &str,
+    usage: Usage,
+    last_done: &BTreeMap<String, Usage>,
+) -> Vec<String> {
+    program
+        .inspections
+        .iter()
+        .filter(|inspection| applies_to(inspection, icao_type))
+        .filter(|inspection| {
+            let since = last_done.get(&inspection.name).copied().unwrap_or_default();
+            let hours_reached = inspection
+                .every_hours
+                .is_some_and(|every| usage.hours - since.hours >= every);
+            let cycles_reached = inspection
+                .every_cycles
+                .is_some_and(|every| usage.cycles.saturating_sub(since.cycles) >= every);
+            hours_reached || cycles_reached
+        })
+        .map(|inspection| inspection.name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn program() -> MaintenanceProgram {
+        MaintenanceProgram {
+            inspections: vec![
+                InspectionInterval {
+                    name: "A-check".to_string(),
+                    every_hours: Some(500.0),
+                    every_cycles: Some(400),
+                    icao_types: vec![],
+                },
+                InspectionInterval {
+                    name: "Propeller overhaul".to_string(),
+                    every_hours: Some(3000.0),
+                    every_cycles: None,
+                    icao_types: vec!["DH8D".to_string()],
+                },
+            ],
+        }
+    }
+
+    fn usage(hours: f64, cycles: u32) -> Usage {
+        Usage { hours, cycles }
+    }
+
+    #[test]
+    fn test_due_inspections() {
+        let never = BTreeMap::new();
+        assert!(due_inspections(&program(), "B738", usage(499.9, 399), &never).is_empty());
+        // Hours or cycles, whichever comes first
+        assert_eq!(
+            due_inspections(&program(), "B738", usage(500.0, 10), &never),
+            vec!["A-check"]
+        );
+        assert_eq!(
+            due_inspections(&program(), "B738", usage(20.0, 400), &never),
+            vec!["A-check"]
+        );
+        // Type specific inspections only apply to their types
+        assert!(
+            due_inspections(&program(), "B738", usage(3000.0, 0), &never)
+                .contains(&"A-check".to_string())
+        );
+        assert_eq!(
+            due_inspections(&program(), "dh8d", usage(3000.0, 0), &never),
+            vec!["A-check", "Propeller overhaul"]
+        );
+
+        // Intervals count from the last time the inspection was done
+        let done = BTreeMap::from([("A-check".to_string(), usage(480.0, 300))]);
+        assert!(due_inspections(&program(), "B738", usage(900.0, 650), &done).is_empty());
+        assert_eq!(
+            due_inspections(&program(), "B738", usage(980.0, 650), &done),
+            vec!["A-check"]
+        );
+    }
+
+    #[test]
+    fn test_validate_program() {
+        assert!(validate_program(&program()).is_ok());
+        assert!(validate_program(&MaintenanceProgram::default()).is_ok());
+
+        let invalid = |change: fn(&mut InspectionInterval)| {
+            let mut program = program();
+            change(&mut program.inspections[0]);
+            validate_program(&program)
+        };
+        assert!(invalid(|i| i.name = " ".to_string()).is_err());
+        assert!(invalid(|i| i.name = "propeller OVERHAUL".to_string()).is_err());
+        assert!(invalid(|i| i.every_hours = Some(-1.0)).is_err());
+        assert!(invalid(|i| i.every_cycles = Some(0)).is_err());
+        assert_eq!(
+            invalid(|i| {
+                i.every_hours = None;
+                i.every_cycles = None;
+            }),
+            Err("A-check needs every_hours, every_cycles or both".to_string())
+        );
+    }
+}
@@ -1,5 +1,12 @@
 // Declare aggregate modules
+pub mod aircraft;
+pub mod aircraft_type;
+pub mod airport;
+pub mod flight_log;
+pub mod maintenance;
 pub mod pirep;
+pub mod pirep_rules;
+pub mod simbrief;
 pub mod tenant;
 pub mod user;
 
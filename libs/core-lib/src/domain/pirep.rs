@@ -1,8 +1,21 @@
+use crate::domain::airport::AirportDatabase;
+use crate::domain::pirep_rules::{
+    auto_accepts, evaluate, evaluate_flight_plan, PirepRules, RuleResult, RULE_FLIGHT_PLAN,
+};
+use crate::framework::{EventCodec, TenantScoped};
+use crate::services::DomainServices;
 use crate::{Command, CoreError, DomainEvent, Event};
 use cqrs_es::Aggregate;
+use prost::Message;
+use proto::blob::BlobRef;
 pub use proto::pirep::pirep_command::PirepCommand;
-use proto::pirep::{PirepSubmitted, SubmitPirep};
-use std::time::{SystemTime, UNIX_EPOCH};
+pub use proto::pirep::PirepStatus;
+use proto::pirep::{
+    AmendPirep, ApprovePirep, AttachFlightPlan, CancelPirep, FlightPlan, PirepAmended,
+    PirepApproved, PirepCancelled, PirepChangesRequested, PirepFlightPlanAttached, PirepRejected,
+    PirepSubmitted, PirepValidated, RejectPirep, RequestPirepChanges, SubmitPirep,
+};
+use std::fmt;
 
 // --- PIREP Aggregate ---
 
@@ -18,24 +31,109 @@ pub struct Pirep {
     flight_number: String,
     flight_time_hours: f64,
     remarks: String,
-    // status: PirepStatus, // Add status later (e.g., Pending, Approved, Rejected)
+    landing_rate_fpm: f64,
+    route_distance_nm: f64,
+    status: PirepStatus,
+    rule_results: Vec<RuleResult>, // Tenant rules checked on submission or amendment
+    validated: bool,               // The tenant had rules when it was last submitted or amended
+    track: Option<BlobRef>,        // Imported flight track, for review
+    flight_plan: Option<FlightPlan>,
+}
+
+// --- Review State Machine ---
+
+/// Something done to a PIREP after it was submitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PirepAction {
+    Approve,
+    Reject,
+    RequestChanges,
+    Amend,
+    Cancel,
+    AttachFlightPlan,
+}
+
+impl PirepAction {
+    /// Status a PIREP in `from` moves to, or `None` if the action is not allowed there.
+    ///
+    /// Reviewers decide on pending PIREPs; a PIREP sent back for changes can still be
+    /// rejected but has to be amended before it can be approved. Approved, rejected and
+    /// cancelled PIREPs are final.
+    pub fn transition(self, from: PirepStatus) -> Option<PirepStatus> {
+        use PirepStatus::*;
+        match (self, from) {
+            (PirepAction::Approve, Pending) => Some(Approved),
+            (PirepAction::Reject, Pending | ChangesRequested) => Some(Rejected),
+            (PirepAction::RequestChanges, Pending) => Some(ChangesRequested),
+            (PirepAction::Amend, Pending | ChangesRequested) => Some(Pending),
+            (PirepAction::Cancel, Pending | ChangesRequested) => Some(Cancelled),
+            // Planning data can be added until the PIREP is decided on
+            (PirepAction::AttachFlightPlan, Pending | ChangesRequested) => Some(from),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PirepAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PirepAction::Approve => "approve",
+            PirepAction::Reject => "reject",
+            PirepAction::RequestChanges => "request changes to",
+            PirepAction::Amend => "amend",
+            PirepAction::Cancel => "cancel",
+            PirepAction::AttachFlightPlan => "attach a flight plan to",
+        })
+    }
+}
+
+// Human-readable status for error messages
+fn status_label(status: PirepStatus) -> &'static str {
+    match status {
+        PirepStatus::Unspecified => "not submitted",
+        PirepStatus::Pending => "pending review",
+        PirepStatus::ChangesRequested => "awaiting changes",
+        PirepStatus::Approved => "approved",
+        PirepStatus::Rejected => "rejected",
+        PirepStatus::Cancelled => "cancelled",
+    }
 }
 
 // --- Commands ---
 
 impl Command for SubmitPirep {}
+impl Command for ApprovePirep {}
+impl Command for RejectPirep {}
+impl Command for RequestPirepChanges {}
+impl Command for AmendPirep {}
+impl Command for CancelPirep {}
+impl Command for AttachFlightPlan {}
 
 // --- Events ---
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum PirepEvent {
     Submitted(PirepSubmitted),
+    Validated(PirepValidated),
+    Approved(PirepApproved),
+    Rejected(PirepRejected),
+    ChangesRequested(PirepChangesRequested),
+    Amended(PirepAmended),
+    Cancelled(PirepCancelled),
+    FlightPlanAttached(PirepFlightPlanAttached),
 }
 
 impl DomainEvent for PirepEvent {
     fn event_type(&self) -> String {
         match self {
             PirepEvent::Submitted(_) => "PirepSubmitted".to_string(),
+            PirepEvent::Validated(_) => "PirepValidated".to_string(),
+            PirepEvent::Approved(_) => "PirepApproved".to_string(),
+            PirepEvent::Rejected(_) => "PirepRejected".to_string(),
+            PirepEvent::ChangesRequested(_) => "PirepChangesRequested".to_string(),
+            PirepEvent::Amended(_) => "PirepAmended".to_string(),
+            PirepEvent::Cancelled(_) => "PirepCancelled".to_string(),
+            PirepEvent::FlightPlanAttached(_) => "PirepFlightPlanAttached".to_string(),
         }
     }
 
@@ -44,7 +142,55 @@ impl DomainEvent for PirepEvent {
     }
 }
 
+impl EventCodec for PirepEvent {
+    fn encode_payload(&self) -> Vec<u8> {
+        match self {
+            PirepEvent::Submitted(e) => e.encode_to_vec(),
+            PirepEvent::Validated(e) => e.encode_to_vec(),
+            PirepEvent::Approved(e) => e.encode_to_vec(),
+            PirepEvent::Rejected(e) => e.encode_to_vec(),
+            PirepEvent::ChangesRequested(e) => e.encode_to_vec(),
+            PirepEvent::Amended(e) => e.encode_to_vec(),
+            PirepEvent::Cancelled(e) => e.encode_to_vec(),
+            PirepEvent::FlightPlanAttached(e) => e.encode_to_vec(),
+        }
+    }
+
+    fn decode_payload(event_type: &str, payload: &[u8]) -> Result<Self, CoreError> {
+        let decoded = match event_type {
+            "PirepSubmitted" => PirepSubmitted::decode(payload).map(PirepEvent::Submitted),
+            "PirepValidated" => PirepValidated::decode(payload).map(PirepEvent::Validated),
+            "PirepApproved" => PirepApproved::decode(payload).map(PirepEvent::Approved),
+            "PirepRejected" => PirepRejected::decode(payload).map(PirepEvent::Rejected),
+            "PirepChangesRequested" => {
+                PirepChangesRequested::decode(payload).map(PirepEvent::ChangesRequested)
+            }
+            "PirepAmended" => PirepAmended::decode(payload).map(PirepEvent::Amended),
+            "PirepCancelled" => PirepCancelled::decode(payload).map(PirepEvent::Cancelled),
+            "PirepFlightPlanAttached" => {
+                PirepFlightPlanAttached::decode(payload).map(PirepEvent::FlightPlanAttached)
+            }
+            other => {
+                return Err(CoreError::Deserialization(format!(
+                    "Unknown PIREP event type: {}",
+                    other
+                )))
+            }
+        };
+        decoded.map_err(|e| {
+            CoreError::Deserialization(format!("Failed to decode {}: {}", event_type, e))
+        })
+    }
+}
+
 impl Event for PirepSubmitted {}
+impl Event for PirepValidated {}
+impl Event for PirepApproved {}
+impl Event for PirepRejected {}
+impl Event for PirepChangesRequested {}
+impl Event for PirepAmended {}
+impl Event for PirepCancelled {}
+impl Event for PirepFlightPlanAttached {}
 
 // --- Errors ---
 
@@ -54,9 +200,17 @@ pub enum PirepError {
     Core(#[from] CoreError),
     #[error("PIREP already submitted (ID: {0})")]
     AlreadyExists(String),
+    #[error("PIREP not found (ID: {0})")]
+    NotFound(String),
     #[error("Invalid input: {0}")]
     InvalidInput(String),
-    // Add other PIREP-specific errors if needed
+    #[error("Cannot {action} a PIREP that is {}", status_label(*.status))]
+    InvalidTransition {
+        action: PirepAction,
+        status: PirepStatus,
+    },
+    #[error("Not permitted: {0}")]
+    Forbidden(String),
 }
 
 // --- Aggregate Implementation ---
@@ -66,7 +220,7 @@ impl Aggregate for Pirep {
     type Command = PirepCommand;
     type Event = PirepEvent; // Event is the concrete type here
     type Error = PirepError;
-    type Services = ();
+    type Services = DomainServices;
 
     fn aggregate_id(&self) -> &str {
         &self.id
@@ -78,7 +232,6 @@ impl Aggregate for Pirep {
 
     /// Apply state changes based on events.
     fn apply(&mut self, event: Self::Event) {
-        #[allow(clippy::match_single_binding)]
         match event {
             PirepEvent::Submitted(PirepSubmitted {
                 // Corrected casing
@@ -91,6 +244,9 @@ impl Aggregate for Pirep {
                 flight_number,
                 flight_time_hours,
                 remarks,
+                landing_rate_fpm,
+                track,
+                route_distance_nm,
                 ..
             }) => {
                 self.id = pirep_id;
@@ -102,7 +258,53 @@ impl Aggregate for Pirep {
                 self.flight_number = flight_number;
                 self.flight_time_hours = flight_time_hours;
                 self.remarks = remarks;
-                // self.status = PirepStatus::Pending; // Set initial status
+                self.landing_rate_fpm = landing_rate_fpm;
+                self.track = track;
+                self.route_distance_nm = route_distance_nm;
+                self.status = PirepStatus::Pending;
+            }
+            PirepEvent::Validated(PirepValidated {
+                results,
+                auto_accepted,
+                ..
+            }) => {
+                self.rule_results = results;
+                self.validated = true;
+                if auto_accepted {
+                    self.status = PirepStatus::Approved;
+                }
+            }
+            PirepEvent::Approved(_) => self.status = PirepStatus::Approved,
+            PirepEvent::Rejected(_) => self.status = PirepStatus::Rejected,
+            PirepEvent::ChangesRequested(_) => self.status = PirepStatus::ChangesRequested,
+            PirepEvent::Amended(PirepAmended {
+                aircraft_id,
+                departure_icao,
+                arrival_icao,
+                flight_number,
+                flight_time_hours,
+                remarks,
+                landing_rate_fpm,
+                route_distance_nm,
+                ..
+            }) => {
+                self.aircraft_id = aircraft_id;
+                self.departure_icao = departure_icao;
+                self.arrival_icao = arrival_icao;
+                self.flight_number = flight_number;
+                self.flight_time_hours = flight_time_hours;
+                self.remarks = remarks;
+                self.landing_rate_fpm = landing_rate_fpm;
+                self.route_distance_nm = route_distance_nm;
+                self.status = PirepStatus::Pending;
+                // Results of the old details no longer apply; a PirepValidated follows
+                // when the tenant has rules
+                self.rule_results.clear();
+                self.validated = false;
+            }
+            PirepEvent::Cancelled(_) => self.status = PirepStatus::Cancelled,
+            PirepEvent::FlightPlanAttached(PirepFlightPlanAttached { plan, .. }) => {
+                self.flight_plan = plan;
             }
         }
         self.version += 1;
@@ -112,33 +314,190 @@ impl Aggregate for Pirep {
     async fn handle(
         &self,
         command: Self::Command,
-        _service: &Self::Services,
+        services: &Self::Services,
     ) -> Result<Vec<Self::Event>, Self::Error> {
+        // Only submitting works on a PIREP that does not exist yet
+        if self.version == 0 && !matches!(command, PirepCommand::Submit(_)) {
+            return Err(PirepError::NotFound(match &command {
+                PirepCommand::Approve(c) => c.pirep_id.clone(),
+                PirepCommand::Reject(c) => c.pirep_id.clone(),
+                PirepCommand::RequestChanges(c) => c.pirep_id.clone(),
+                PirepCommand::Amend(c) => c.pirep_id.clone(),
+                PirepCommand::Cancel(c) => c.pirep_id.clone(),
+                PirepCommand::AttachFlightPlan(c) => c.pirep_id.clone(),
+                PirepCommand::Submit(c) => c.pirep_id.clone(),
+            }));
+        }
+
         match command {
-            PirepCommand::Submit(cmd) => self.handle_submit(cmd).await,
+            PirepCommand::Submit(cmd) => self.handle_submit(cmd, services).await,
+            PirepCommand::Approve(cmd) => self.handle_approve(cmd, services),
+            PirepCommand::Reject(cmd) => self.handle_reject(cmd, services),
+            PirepCommand::RequestChanges(cmd) => self.handle_request_changes(cmd, services),
+            PirepCommand::Amend(cmd) => self.handle_amend(cmd, services),
+            PirepCommand::Cancel(cmd) => self.handle_cancel(cmd, services),
+            PirepCommand::AttachFlightPlan(cmd) => self.handle_attach_flight_plan(cmd, services),
         }
     }
 }
 
+impl TenantScoped for Pirep {
+    fn owning_tenant(&self) -> Option<&str> {
+        (!self.tenant_id.is_empty()).then_some(self.tenant_id.as_str())
+    }
+}
+
+// Checks the flight details shared by submitting and amending
+fn validate_flight_details(
+    aircraft_id: &str,
+    departure_icao: &str,
+    arrival_icao: &str,
+    flight_time_hours: f64,
+) -> Result<(), PirepError> {
+    if aircraft_id.is_empty() || departure_icao.is_empty() || arrival_icao.is_empty() {
+        return Err(PirepError::InvalidInput("Missing required fields".into()));
+    }
+    if flight_time_hours <= 0.0 {
+        return Err(PirepError::InvalidInput(
+            "Flight time must be positive".into(),
+        ));
+    }
+    Ok(())
+}
+
+// A plan has to name the airports it was made for
+fn checked_plan(plan: FlightPlan) -> Result<FlightPlan, PirepError> {
+    Some(plan)
+        .filter(|plan| !plan.departure_icao.is_empty() && !plan.arrival_icao.is_empty())
+        .ok_or_else(|| {
+            PirepError::InvalidInput("A flight plan with origin and destination is required".into())
+        })
+}
+
+// Both airports have to be in the airport database; the distance between them is
+// recorded with the flight
+fn route_distance_nm(
+    airports: &AirportDatabase,
+    departure_icao: &str,
+    arrival_icao: &str,
+) -> Result<f64, PirepError> {
+    let airport = |icao: &str| {
+        airports
+            .get(icao)
+            .ok_or_else(|| PirepError::InvalidInput(format!("Unknown airport: {}", icao)))
+    };
+    Ok(airport(departure_icao)?.distance_nm(airport(arrival_icao)?))
+}
+
 impl Pirep {
+    // --- Public Getters ---
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+    pub fn user_id(&self) -> &str {
+        &self.user_id
+    }
+    pub fn tenant_id(&self) -> &str {
+        &self.tenant_id
+    }
+    pub fn aircraft_id(&self) -> &str {
+        &self.aircraft_id
+    }
+    pub fn status(&self) -> PirepStatus {
+        self.status
+    }
+    pub fn flight_time_hours(&self) -> f64 {
+        self.flight_time_hours
+    }
+    pub fn route_distance_nm(&self) -> f64 {
+        self.route_distance_nm
+    }
+    pub fn rule_results(&self) -> &[RuleResult] {
+        &self.rule_results
+    }
+    pub fn track(&self) -> Option<&BlobRef> {
+        self.track.as_ref()
+    }
+    pub fn flight_plan(&self) -> Option<&FlightPlan> {
+        self.flight_plan.as_ref()
+    }
+
+    // The flight as last submitted or amended, for the rules
+    fn flown(&self) -> PirepSubmitted {
+        PirepSubmitted {
+            pirep_id: self.id.clone(),
+            tenant_id: self.tenant_id.clone(),
+            user_id: self.user_id.clone(),
+            aircraft_id: self.aircraft_id.clone(),
+            departure_icao: self.departure_icao.clone(),
+            arrival_icao: self.arrival_icao.clone(),
+            flight_number: self.flight_number.clone(),
+            flight_time_hours: self.flight_time_hours,
+            landing_rate_fpm: self.landing_rate_fpm,
+            route_distance_nm: self.route_distance_nm,
+            ..Default::default()
+        }
+    }
+
+    // Status reached by `action`, or the error for an illegal transition
+    fn next_status(&self, action: PirepAction) -> Result<PirepStatus, PirepError> {
+        action
+            .transition(self.status)
+            .ok_or(PirepError::InvalidTransition {
+                action,
+                status: self.status,
+            })
+    }
+
+    // Reviewers act on other pilots' flights only
+    fn check_reviewer(&self, reviewer_id: &str) -> Result<(), PirepError> {
+        if reviewer_id.is_empty() {
+            return Err(PirepError::InvalidInput("Reviewer ID is required".into()));
+        }
+        if reviewer_id == self.user_id {
+            return Err(PirepError::Forbidden(
+                "Pilots cannot review their own PIREP".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    // Amending and cancelling are left to the pilot who flew
+    fn check_pilot(&self, user_id: &str, action: PirepAction) -> Result<(), PirepError> {
+        if user_id != self.user_id {
+            return Err(PirepError::Forbidden(format!(
+                "Only the submitting pilot can {} this PIREP",
+                action
+            )));
+        }
+        Ok(())
+    }
+
     // --- Command Handlers ---
 
-    async fn handle_submit(&self, command: SubmitPirep) -> Result<Vec<PirepEvent>, PirepError> {
+    async fn handle_submit(
+        &self,
+        command: SubmitPirep,
+        services: &DomainServices,
+    ) -> Result<Vec<PirepEvent>, PirepError> {
         // Validate command input
-        if command.pirep_id.is_empty()
-            || command.tenant_id.is_empty()
-            || command.user_id.is_empty()
-            || command.aircraft_id.is_empty()
-            || command.departure_icao.is_empty()
-            || command.arrival_icao.is_empty()
+        if command.pirep_id.is_empty() || command.tenant_id.is_empty() || command.user_id.is_empty()
         {
             return Err(PirepError::InvalidInput("Missing required fields".into()));
         }
-        if command.flight_time_hours <= 0.0 {
-            return Err(PirepError::InvalidInput(
-                "Flight time must be positive".into(),
-            ));
-        }
+        validate_flight_details(
+            &command.aircraft_id,
+            &command.departure_icao,
+            &command.arrival_icao,
+            command.flight_time_hours,
+        )?;
+        let route_distance_nm = route_distance_nm(
+            services.airports,
+            &command.departure_icao,
+            &command.arrival_icao,
+        )?;
+        // A plan made before the flight can come with it
+        let plan = command.plan.map(checked_plan).transpose()?;
 
         // Check business rules (e.g., prevent duplicate submission)
         if self.version > 0 {
@@ -146,10 +505,7 @@ impl Pirep {
         }
 
         // Create the event
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs().to_string())
-            .unwrap_or_default();
+        let timestamp = services.clock.timestamp();
 
         let event = PirepSubmitted {
             // Corrected casing
@@ -163,18 +519,303 @@ impl Pirep {
             flight_time_hours: command.flight_time_hours,
             remarks: command.remarks,
             timestamp,
+            landing_rate_fpm: command.landing_rate_fpm,
+            track: command.track,
+            route_distance_nm,
         };
 
-        Ok(vec![PirepEvent::Submitted(event)])
+        let attached = plan.clone().map(|plan| PirepFlightPlanAttached {
+            pirep_id: event.pirep_id.clone(),
+            user_id: event.user_id.clone(),
+            plan: Some(plan),
+            timestamp: event.timestamp.clone(),
+        });
+
+        // Tenants with rules get the outcome recorded right after the submission
+        let validated = command.rules.map(|rules| {
+            let facts = command.facts.unwrap_or_default();
+            let results = evaluate(&rules, &event, &facts, plan.as_ref());
+            PirepValidated {
+                pirep_id: event.pirep_id.clone(),
+                auto_accepted: auto_accepts(&rules, &results),
+                results,
+                timestamp: event.timestamp.clone(),
+            }
+        });
+
+        let mut events = vec![PirepEvent::Submitted(event)];
+        events.extend(attached.map(PirepEvent::FlightPlanAttached));
+        events.extend(validated.map(PirepEvent::Validated));
+        Ok(events)
+    }
+
+    fn handle_approve(
+        &self,
+        command: ApprovePirep,
+        services: &DomainServices,
+    ) -> Result<Vec<PirepEvent>, PirepError> {
+        self.check_reviewer(&command.reviewer_id)?;
+        self.next_status(PirepAction::Approve)?;
+        Ok(vec![PirepEvent::Approved(PirepApproved {
+            pirep_id: self.id.clone(),
+            reviewer_id: command.reviewer_id,
+            comment: command.comment,
+            timestamp: services.clock.timestamp(),
+        })])
+    }
+
+    fn handle_reject(
+        &self,
+        command: RejectPirep,
+        services: &DomainServices,
+    ) -> Result<Vec<PirepEvent>, PirepError> {
+        self.check_reviewer(&command.reviewer_id)?;
+        if command.reason.trim().is_empty() {
+            return Err(PirepError::InvalidInput(
+                "A reason is required to reject a PIREP".into(),
+            ));
+        }
+        self.next_status(PirepAction::Reject)?;
+        Ok(vec![PirepEvent::Rejected(PirepRejected {
+            pirep_id: self.id.clone(),
+            reviewer_id: command.reviewer_id,
+            reason: command.reason,
+            timestamp: services.clock.timestamp(),
+        })])
+    }
+
+    fn handle_request_changes(
+        &self,
+        command: RequestPirepChanges,
+        services: &DomainServices,
+    ) -> Result<Vec<PirepEvent>, PirepError> {
+        self.check_reviewer(&command.reviewer_id)?;
+        if command.reason.trim().is_empty() {
+            return Err(PirepError::InvalidInput(
+                "A reason is required to request changes".into(),
+            ));
+        }
+        self.next_status(PirepAction::RequestChanges)?;
+        Ok(vec![PirepEvent::ChangesRequested(PirepChangesRequested {
+            pirep_id: self.id.clone(),
+            reviewer_id: command.reviewer_id,
+            reason: command.reason,
+            timestamp: services.clock.timestamp(),
+        })])
+    }
+
+    fn handle_amend(
+        &self,
+        command: AmendPirep,
+        services: &DomainServices,
+    ) -> Result<Vec<PirepEvent>, PirepError> {
+        self.check_pilot(&command.user_id, PirepAction::Amend)?;
+        self.next_status(PirepAction::Amend)?;
+        validate_flight_details(
+            &command.aircraft_id,
+            &command.departure_icao,
+            &command.arrival_icao,
+            command.flight_time_hours,
+        )?;
+        let route_distance_nm = route_distance_nm(
+            services.airports,
+            &command.departure_icao,
+            &command.arrival_icao,
+        )?;
+        let event = PirepAmended {
+            pirep_id: self.id.clone(),
+            user_id: command.user_id,
+            aircraft_id: command.aircraft_id,
+            departure_icao: command.departure_icao,
+            arrival_icao: command.arrival_icao,
+            flight_number: command.flight_number,
+            flight_time_hours: command.flight_time_hours,
+            remarks: command.remarks,
+            timestamp: services.clock.timestamp(),
+            landing_rate_fpm: command.landing_rate_fpm,
+            route_distance_nm,
+        };
+
+        // The amended flight goes through the rules again, like a new submission
+        let validated = command.rules.map(|rules| {
+            let flown = PirepSubmitted {
+                aircraft_id: event.aircraft_id.clone(),
+                departure_icao: event.departure_icao.clone(),
+                arrival_icao: event.arrival_icao.clone(),
+                flight_number: event.flight_number.clone(),
+                flight_time_hours: event.flight_time_hours,
+                landing_rate_fpm: event.landing_rate_fpm,
+                route_distance_nm: event.route_distance_nm,
+                ..self.flown()
+            };
+            let facts = command.facts.unwrap_or_default();
+            let results = evaluate(&rules, &flown, &facts, self.flight_plan.as_ref());
+            PirepValidated {
+                pirep_id: event.pirep_id.clone(),
+                auto_accepted: auto_accepts(&rules, &results),
+                results,
+                timestamp: event.timestamp.clone(),
+            }
+        });
+
+        let mut events = vec![PirepEvent::Amended(event)];
+        events.extend(validated.map(PirepEvent::Validated));
+        Ok(events)
+    }
+
+    fn handle_cancel(
+        &self,
+        command: CancelPirep,
+        services: &DomainServices,
+    ) -> Result<Vec<PirepEvent>, PirepError> {
+        self.check_pilot(&command.user_id, PirepAction::Cancel)?;
+        self.next_status(PirepAction::Cancel)?;
+        Ok(vec![PirepEvent::Cancelled(PirepCancelled {
+            pirep_id: self.id.clone(),
+            user_id: command.user_id,
+            reason: command.reason,
+            timestamp: services.clock.timestamp(),
+        })])
+    }
+
+    fn handle_attach_flight_plan(
+        &self,
+        command: AttachFlightPlan,
+        services: &DomainServices,
+    ) -> Result<Vec<PirepEvent>, PirepError> {
+        self.check_pilot(&command.user_id, PirepAction::AttachFlightPlan)?;
+        self.next_status(PirepAction::AttachFlightPlan)?;
+        // A plan for another route is kept as is; the flight plan rule flags it
+        let plan = checked_plan(command.plan.unwrap_or_default())?;
+        let timestamp = services.clock.timestamp();
+        let validated = command
+            .rules
+            .filter(|_| self.validated)
+            .and_then(|rules| self.revalidate(&rules, &plan, &timestamp));
+
+        let mut events = vec![PirepEvent::FlightPlanAttached(PirepFlightPlanAttached {
+            pirep_id: self.id.clone(),
+            user_id: command.user_id,
+            plan: Some(plan),
+            timestamp,
+        })];
+        events.extend(validated.map(PirepEvent::Validated));
+        Ok(events)
+    }
+
+    // Checks a newly attached plan, keeping the results of the other rules from the
+    // submission. A pending PIREP that now passes every rule is accepted.
+    fn revalidate(
+        &self,
+        rules: &PirepRules,
+        plan: &FlightPlan,
+        timestamp: &str,
+    ) -> Option<PirepValidated> {
+        let checked = evaluate_flight_plan(rules, &self.flown(), Some(plan))?;
+        let mut results: Vec<RuleResult> = self
+            .rule_results
+            .iter()
+            .filter(|result| result.rule != RULE_FLIGHT_PLAN)
+            .cloned()
+            .collect();
+        results.push(checked);
+        Some(PirepValidated {
+            pirep_id: self.id.clone(),
+            auto_accepted: self.status == PirepStatus::Pending && auto_accepts(rules, &results),
+            results,
+            timestamp: timestamp.to_string(),
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::pirep_rules::{PirepRules, SubmissionFacts};
     use cqrs_es::Aggregate;
-    // Use the corrected casing from the import
-    use proto::pirep::{PirepSubmitted, SubmitPirep};
+
+    // A pending PIREP flown by user-1
+    fn submitted() -> Pirep {
+        let mut aggregate = Pirep::default();
+        aggregate.apply(PirepEvent::Submitted(PirepSubmitted {
+            pirep_id: "pirep-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            user_id: "user-1".to_string(),
+            aircraft_id: "ac-1".to_string(),
+            departure_icao: "EKCH".to_string(),
+            arrival_icao: "EGLL".to_string(),
+            flight_number: "VA123".to_string(),
+            flight_time_hours: 2.5,
+            remarks: "Smooth flight".to_string(),
+            timestamp: "0".to_string(),
+            ..Default::default()
+        }));
+        aggregate
+    }
+
+    // Handles `command` and applies the resulting events
+    async fn execute(aggregate: &mut Pirep, command: PirepCommand) -> Result<(), PirepError> {
+        let events = aggregate
+            .handle(command, &DomainServices::default())
+            .await?;
+        for event in events {
+            aggregate.apply(event);
+        }
+        Ok(())
+    }
+
+    fn approve(reviewer_id: &str) -> PirepCommand {
+        PirepCommand::Approve(ApprovePirep {
+            pirep_id: "pirep-1".to_string(),
+            reviewer_id: reviewer_id.to_string(),
+            comment: String::new(),
+        })
+    }
+
+    fn request_changes(reason: &str) -> PirepCommand {
+        PirepCommand::RequestChanges(RequestPirepChanges {
+            pirep_id: "pirep-1".to_string(),
+            reviewer_id: "staff-1".to_string(),
+            reason: reason.to_string(),
+        })
+    }
+
+    fn amend(user_id: &str, flight_time_hours: f64) -> PirepCommand {
+        PirepCommand::Amend(AmendPirep {
+            pirep_id: "pirep-1".to_string(),
+            user_id: user_id.to_string(),
+            aircraft_id: "ac-2".to_string(),
+            departure_icao: "EKCH".to_string(),
+            arrival_icao: "EGLL".to_string(),
+            flight_number: "VA123".to_string(),
+            flight_time_hours,
+            remarks: "Corrected block times".to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn cancel(user_id: &str) -> PirepCommand {
+        PirepCommand::Cancel(CancelPirep {
+            pirep_id: "pirep-1".to_string(),
+            user_id: user_id.to_string(),
+            reason: String::new(),
+        })
+    }
+
+    fn attach_flight_plan(user_id: &str, departure_icao: &str) -> PirepCommand {
+        PirepCommand::AttachFlightPlan(AttachFlightPlan {
+            pirep_id: "pirep-1".to_string(),
+            user_id: user_id.to_string(),
+            plan: Some(FlightPlan {
+                source: "simbrief".to_string(),
+                departure_icao: departure_icao.to_string(),
+                arrival_icao: "EGLL".to_string(),
+                block_fuel: 6850.0,
+                ..Default::default()
+            }),
+            rules: None,
+        })
+    }
 
     #[tokio::test]
     async fn test_submit_pirep_command() {
@@ -190,30 +831,142 @@ mod tests {
             flight_number: "VA123".to_string(),
             flight_time_hours: 2.5,
             remarks: "Smooth flight".to_string(),
+            ..Default::default()
         });
 
-        let result = aggregate.handle(command.clone(), &()).await;
+        let result = aggregate.handle(command.clone(), &DomainServices::default()).await;
         assert!(result.is_ok());
 
         let events = result.unwrap();
         assert_eq!(events.len(), 1);
 
-        #[allow(clippy::match_single_binding)]
-        match &events[0] {
-            PirepEvent::Submitted(PirepSubmitted {
-                pirep_id,
-                tenant_id,
-                user_id,
-                flight_time_hours,
-                ..
-            }) => {
-                let PirepCommand::Submit(command) = command;
+        match (&events[0], command) {
+            (
+                PirepEvent::Submitted(PirepSubmitted {
+                    pirep_id,
+                    tenant_id,
+                    user_id,
+                    flight_time_hours,
+                    ..
+                }),
+                PirepCommand::Submit(command),
+            ) => {
                 assert_eq!(pirep_id, &command.pirep_id);
                 assert_eq!(tenant_id, &command.tenant_id);
                 assert_eq!(user_id, &command.user_id);
                 assert_eq!(*flight_time_hours, command.flight_time_hours);
             }
+            other => panic!("Expected PirepSubmitted, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_keeps_imported_track() {
+        let track = BlobRef {
+            key: "tenants/tenant-1/tracks/user-1/track-1.gpx".to_string(),
+            content_type: "application/gpx+xml".to_string(),
+            size: 2048,
+            sha256: "ab".repeat(32),
+        };
+        let command = PirepCommand::Submit(SubmitPirep {
+            pirep_id: "pirep-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            user_id: "user-1".to_string(),
+            aircraft_id: "ac-1".to_string(),
+            departure_icao: "EKCH".to_string(),
+            arrival_icao: "EGLL".to_string(),
+            flight_time_hours: 2.5,
+            track: Some(track.clone()),
+            ..Default::default()
+        });
+
+        let mut aggregate = Pirep::default();
+        let events = aggregate
+            .handle(command, &DomainServices::default())
+            .await
+            .unwrap();
+        for event in events {
+            aggregate.apply(event);
+        }
+        assert_eq!(aggregate.track(), Some(&track));
+    }
+
+    // Submission of EKCH-EGLL in 2.5 h, checked against `rules`
+    fn submit_with_rules(rules: PirepRules, landing_rate_fpm: f64) -> PirepCommand {
+        PirepCommand::Submit(SubmitPirep {
+            pirep_id: "pirep-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            user_id: "user-1".to_string(),
+            aircraft_id: "ac-1".to_string(),
+            departure_icao: "EKCH".to_string(),
+            arrival_icao: "EGLL".to_string(),
+            flight_time_hours: 2.5,
+            landing_rate_fpm,
+            rules: Some(rules),
+            facts: Some(SubmissionFacts {
+                pilot_location_icao: "EKCH".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_submit_passing_rules_is_auto_accepted() {
+        let rules = PirepRules {
+            auto_accept: true,
+            max_landing_rate_fpm: Some(600.0),
+            max_block_speed_kts: Some(500.0),
+            require_departure_from_location: true,
+            ..Default::default()
+        };
+        let mut aggregate = Pirep::default();
+        execute(&mut aggregate, submit_with_rules(rules, 180.0))
+            .await
+            .unwrap();
+
+        assert_eq!(aggregate.version(), 2);
+        assert_eq!(aggregate.status(), PirepStatus::Approved);
+        assert_eq!(aggregate.rule_results().len(), 3);
+        assert!(aggregate.rule_results().iter().all(|r| r.passed));
+    }
+
+    #[tokio::test]
+    async fn test_submit_failing_rules_is_flagged_for_review() {
+        let rules = PirepRules {
+            auto_accept: true,
+            max_landing_rate_fpm: Some(600.0),
+            ..Default::default()
+        };
+        let events = Pirep::default()
+            .handle(submit_with_rules(rules, 750.0), &DomainServices::default())
+            .await
+            .unwrap();
+        match &events[..] {
+            [PirepEvent::Submitted(_), PirepEvent::Validated(validated)] => {
+                assert!(!validated.auto_accepted);
+                assert_eq!(
+                    validated.results[0].message,
+                    "Landing rate 750 fpm exceeds the 600 fpm limit"
+                );
+            }
+            other => panic!(
+                "Expected PirepSubmitted and PirepValidated, got {:?}",
+                other
+            ),
         }
+
+        let mut aggregate = Pirep::default();
+        for event in events {
+            let decoded =
+                PirepEvent::decode_payload(&event.event_type(), &event.encode_payload()).unwrap();
+            assert_eq!(decoded, event);
+            aggregate.apply(event);
+        }
+        // Left for a reviewer, who can still approve it
+        assert_eq!(aggregate.status(), PirepStatus::Pending);
+        execute(&mut aggregate, approve("staff-1")).await.unwrap();
+        assert_eq!(aggregate.status(), PirepStatus::Approved);
     }
 
     #[tokio::test]
@@ -231,6 +984,7 @@ mod tests {
             flight_time_hours: 2.5,
             remarks: "Smooth flight".to_string(),
             timestamp: "0".to_string(),
+            ..Default::default()
         }));
 
         let command = PirepCommand::Submit(SubmitPirep {
@@ -244,9 +998,10 @@ mod tests {
             flight_number: "VA456".to_string(),
             flight_time_hours: 2.1,
             remarks: "Return flight".to_string(),
+            ..Default::default()
         });
 
-        let result = aggregate.handle(command, &()).await;
+        let result = aggregate.handle(command, &DomainServices::default()).await;
         assert!(result.is_err());
         match result.err().unwrap() {
             PirepError::AlreadyExists(id) => assert_eq!(id, "pirep-1"),
@@ -270,8 +1025,9 @@ mod tests {
             flight_number: "VA123".to_string(),
             flight_time_hours: 2.5,
             remarks: "Smooth flight".to_string(),
+            ..Default::default()
         });
-        let result_no_id = aggregate.handle(command_no_id, &()).await;
+        let result_no_id = aggregate.handle(command_no_id, &DomainServices::default()).await;
         assert!(result_no_id.is_err());
         match result_no_id.err().unwrap() {
             PirepError::InvalidInput(msg) => assert!(msg.contains("Missing required fields")),
@@ -290,8 +1046,9 @@ mod tests {
             flight_number: "VA123".to_string(),
             flight_time_hours: 0.0, // Invalid
             remarks: "Smooth flight".to_string(),
+            ..Default::default()
         });
-        let result_zero_time = aggregate.handle(command_zero_time, &()).await;
+        let result_zero_time = aggregate.handle(command_zero_time, &DomainServices::default()).await;
         assert!(result_zero_time.is_err());
         match result_zero_time.err().unwrap() {
             PirepError::InvalidInput(msg) => assert!(msg.contains("Flight time must be positive")),
@@ -299,6 +1056,83 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_submit_records_route_distance_of_known_airports() {
+        let submit = |arrival_icao: &str| {
+            PirepCommand::Submit(SubmitPirep {
+                pirep_id: "pirep-1".to_string(),
+                tenant_id: "tenant-1".to_string(),
+                user_id: "user-1".to_string(),
+                aircraft_id: "ac-1".to_string(),
+                departure_icao: "EKCH".to_string(),
+                arrival_icao: arrival_icao.to_string(),
+                flight_time_hours: 2.5,
+                ..Default::default()
+            })
+        };
+
+        match execute(&mut Pirep::default(), submit("XXXX")).await {
+            Err(PirepError::InvalidInput(msg)) => assert_eq!(msg, "Unknown airport: XXXX"),
+            other => panic!("Expected InvalidInput, got {:?}", other),
+        }
+
+        let mut aggregate = Pirep::default();
+        execute(&mut aggregate, submit("EGLL")).await.unwrap();
+        let distance = aggregate.route_distance_nm();
+        assert!((distance - 531.0).abs() < 5.0, "got {}", distance);
+
+        // Amending the route updates the distance
+        let to_oslo = PirepCommand::Amend(AmendPirep {
+            pirep_id: "pirep-1".to_string(),
+            user_id: "user-1".to_string(),
+            aircraft_id: "ac-1".to_string(),
+            departure_icao: "EKCH".to_string(),
+            arrival_icao: "ENGM".to_string(),
+            flight_time_hours: 1.2,
+            ..Default::default()
+        });
+        execute(&mut aggregate, to_oslo).await.unwrap();
+        assert!(aggregate.route_distance_nm() < distance);
+        let to_nowhere = PirepCommand::Amend(AmendPirep {
+            pirep_id: "pirep-1".to_string(),
+            user_id: "user-1".to_string(),
+            aircraft_id: "ac-1".to_string(),
+            departure_icao: "XXXX".to_string(),
+            arrival_icao: "ENGM".to_string(),
+            flight_time_hours: 1.2,
+            ..Default::default()
+        });
+        assert!(matches!(
+            execute(&mut aggregate, to_nowhere).await,
+            Err(PirepError::InvalidInput(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_airports_come_from_the_services() {
+        let header = "id,ident,type,name,latitude_deg,longitude_deg,elevation_ft,continent,iso_country,iso_region,municipality,iata_code\n";
+        let rows = "1,EKCH,large_airport,Copenhagen,55.618,12.656,17,EU,DK,DK-84,Copenhagen,CPH\n\
+                    2,XXXX,small_airport,Test Field,55.0,12.0,10,EU,DK,DK-84,,\n";
+        let airports = AirportDatabase::from_csv(format!("{}{}", header, rows).as_bytes()).unwrap();
+        let services = DomainServices::default().with_airports(Box::leak(Box::new(airports)));
+
+        let command = PirepCommand::Submit(SubmitPirep {
+            pirep_id: "pirep-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            user_id: "user-1".to_string(),
+            aircraft_id: "ac-1".to_string(),
+            departure_icao: "EKCH".to_string(),
+            arrival_icao: "XXXX".to_string(),
+            flight_time_hours: 0.5,
+            ..Default::default()
+        });
+        let events = Pirep::default().handle(command, &services).await.unwrap();
+        match &events[..] {
+            [PirepEvent::Submitted(submitted)] => assert!(submitted.route_distance_nm > 0.0),
+            other => panic!("Expected PirepSubmitted, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_apply_pirep_submitted() {
         let mut aggregate = Pirep::default();
@@ -314,6 +1148,7 @@ mod tests {
             flight_time_hours: 2.5,
             remarks: "Smooth flight".to_string(),
             timestamp: "12345".to_string(),
+            ..Default::default()
         };
 
         assert_eq!(aggregate.version(), 0);
@@ -325,4 +1160,366 @@ mod tests {
         assert_eq!(aggregate.user_id, event.user_id);
         assert_eq!(aggregate.flight_time_hours, event.flight_time_hours);
     }
+
+    #[test]
+    fn test_transition_table() {
+        use PirepAction::*;
+        use PirepStatus::*;
+        let expected = [
+            (Approve, Pending, Some(Approved)),
+            (Approve, ChangesRequested, None),
+            (Reject, Pending, Some(Rejected)),
+            (Reject, ChangesRequested, Some(Rejected)),
+            (RequestChanges, Pending, Some(ChangesRequested)),
+            (RequestChanges, ChangesRequested, None),
+            (Amend, Pending, Some(Pending)),
+            (Amend, ChangesRequested, Some(Pending)),
+            (Cancel, Pending, Some(Cancelled)),
+            (Cancel, ChangesRequested, Some(Cancelled)),
+            (AttachFlightPlan, Pending, Some(Pending)),
+            (AttachFlightPlan, ChangesRequested, Some(ChangesRequested)),
+        ];
+        for (action, from, to) in expected {
+            assert_eq!(action.transition(from), to, "{:?} from {:?}", action, from);
+        }
+        // Final and unsubmitted PIREPs accept nothing
+        for from in [Unspecified, Approved, Rejected, Cancelled] {
+            for action in [
+                Approve,
+                Reject,
+                RequestChanges,
+                Amend,
+                Cancel,
+                AttachFlightPlan,
+            ] {
+                assert_eq!(
+                    action.transition(from),
+                    None,
+                    "{:?} from {:?}",
+                    action,
+                    from
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_approve_pirep_is_final() {
+        let mut aggregate = submitted();
+        execute(&mut aggregate, approve("staff-1")).await.unwrap();
+        assert_eq!(aggregate.status(), PirepStatus::Approved);
+        assert_eq!(aggregate.version(), 2);
+
+        match execute(&mut aggregate, cancel("user-1")).await {
+            Err(err @ PirepError::InvalidTransition { .. }) => {
+                assert_eq!(err.to_string(), "Cannot cancel a PIREP that is approved")
+            }
+            other => panic!("Expected InvalidTransition, got {:?}", other),
+        }
+        assert!(matches!(
+            execute(&mut aggregate, approve("staff-1")).await,
+            Err(PirepError::InvalidTransition { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reject_pirep_requires_reason() {
+        let mut aggregate = submitted();
+        let reject = |reason: &str| {
+            PirepCommand::Reject(RejectPirep {
+                pirep_id: "pirep-1".to_string(),
+                reviewer_id: "staff-1".to_string(),
+                reason: reason.to_string(),
+            })
+        };
+
+        assert!(matches!(
+            execute(&mut aggregate, reject("  ")).await,
+            Err(PirepError::InvalidInput(_))
+        ));
+        execute(&mut aggregate, reject("Flight not found in tracker"))
+            .await
+            .unwrap();
+        assert_eq!(aggregate.status(), PirepStatus::Rejected);
+    }
+
+    #[tokio::test]
+    async fn test_changes_requested_then_amended() {
+        let mut aggregate = submitted();
+        assert!(matches!(
+            execute(&mut aggregate, request_changes("")).await,
+            Err(PirepError::InvalidInput(_))
+        ));
+        execute(&mut aggregate, request_changes("Block times look off"))
+            .await
+            .unwrap();
+        assert_eq!(aggregate.status(), PirepStatus::ChangesRequested);
+
+        // Has to be amended before it can be approved
+        assert!(matches!(
+            execute(&mut aggregate, approve("staff-1")).await,
+            Err(PirepError::InvalidTransition { .. })
+        ));
+        assert!(matches!(
+            execute(&mut aggregate, amend("user-1", 0.0)).await,
+            Err(PirepError::InvalidInput(_))
+        ));
+        execute(&mut aggregate, amend("user-1", 2.1)).await.unwrap();
+        assert_eq!(aggregate.status(), PirepStatus::Pending);
+        assert_eq!(aggregate.flight_time_hours(), 2.1);
+        assert_eq!(aggregate.aircraft_id, "ac-2");
+
+        execute(&mut aggregate, approve("staff-1")).await.unwrap();
+        assert_eq!(aggregate.status(), PirepStatus::Approved);
+    }
+
+    #[tokio::test]
+    async fn test_amend_checks_the_rules_again() {
+        let rules = PirepRules {
+            auto_accept: true,
+            max_landing_rate_fpm: Some(600.0),
+            ..Default::default()
+        };
+        let mut aggregate = Pirep::default();
+        execute(&mut aggregate, submit_with_rules(rules.clone(), 750.0))
+            .await
+            .unwrap();
+        assert!(!aggregate.rule_results()[0].passed);
+        execute(&mut aggregate, request_changes("Landing rate looks wrong"))
+            .await
+            .unwrap();
+
+        // Fixed: accepted without review
+        let mut fixed = amend("user-1", 2.5);
+        if let PirepCommand::Amend(command) = &mut fixed {
+            command.landing_rate_fpm = 180.0;
+            command.rules = Some(rules.clone());
+        }
+        execute(&mut aggregate, fixed).await.unwrap();
+        assert_eq!(aggregate.status(), PirepStatus::Approved);
+        assert!(aggregate.rule_results()[0].passed);
+
+        // Amended to a landing rate over the limit: flagged for review
+        let mut aggregate = submitted();
+        let mut hard = amend("user-1", 2.5);
+        if let PirepCommand::Amend(command) = &mut hard {
+            command.landing_rate_fpm = 750.0;
+            command.rules = Some(rules);
+        }
+        let events = aggregate
+            .handle(hard, &DomainServices::default())
+            .await
+            .unwrap();
+        match &events[..] {
+            [PirepEvent::Amended(_), PirepEvent::Validated(validated)] => {
+                assert!(!validated.auto_accepted);
+                assert_eq!(
+                    validated.results[0].message,
+                    "Landing rate 750 fpm exceeds the 600 fpm limit"
+                );
+            }
+            other => panic!("Expected PirepAmended and PirepValidated, got {:?}", other),
+        }
+        for event in events {
+            aggregate.apply(event);
+        }
+        assert_eq!(aggregate.status(), PirepStatus::Pending);
+        assert!(!aggregate.rule_results()[0].passed);
+    }
+
+    #[tokio::test]
+    async fn test_only_the_pilot_amends_and_cancels() {
+        let mut aggregate = submitted();
+        assert!(matches!(
+            execute(&mut aggregate, amend("user-2", 2.0)).await,
+            Err(PirepError::Forbidden(_))
+        ));
+        assert!(matches!(
+            execute(&mut aggregate, cancel("user-2")).await,
+            Err(PirepError::Forbidden(_))
+        ));
+
+        execute(&mut aggregate, cancel("user-1")).await.unwrap();
+        assert_eq!(aggregate.status(), PirepStatus::Cancelled);
+        assert!(matches!(
+            execute(&mut aggregate, amend("user-1", 2.0)).await,
+            Err(PirepError::InvalidTransition { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_pilot_attaches_flight_plan_until_decided() {
+        let mut aggregate = submitted();
+        assert!(matches!(
+            execute(&mut aggregate, attach_flight_plan("user-2", "EKCH")).await,
+            Err(PirepError::Forbidden(_))
+        ));
+        assert!(matches!(
+            execute(&mut aggregate, attach_flight_plan("user-1", "")).await,
+            Err(PirepError::InvalidInput(_))
+        ));
+
+        execute(&mut aggregate, attach_flight_plan("user-1", "EKCH"))
+            .await
+            .unwrap();
+        assert_eq!(aggregate.status(), PirepStatus::Pending);
+        assert_eq!(aggregate.flight_plan().unwrap().block_fuel, 6850.0);
+
+        execute(&mut aggregate, approve("staff-1")).await.unwrap();
+        assert!(matches!(
+            execute(&mut aggregate, attach_flight_plan("user-1", "EKCH")).await,
+            Err(PirepError::InvalidTransition { .. })
+        ));
+    }
+
+    // Auto-accepts flights within 20% of a plan's block time
+    fn plan_rules() -> PirepRules {
+        PirepRules {
+            auto_accept: true,
+            max_plan_block_deviation_pct: Some(20.0),
+            ..Default::default()
+        }
+    }
+
+    // EKCH-EGLL planned at 2.4 h
+    fn planned() -> FlightPlan {
+        FlightPlan {
+            departure_icao: "EKCH".to_string(),
+            arrival_icao: "EGLL".to_string(),
+            est_block_hours: 2.4,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plan_submitted_with_pirep_is_checked() {
+        let mut command = submit_with_rules(plan_rules(), 180.0);
+        if let PirepCommand::Submit(submit) = &mut command {
+            submit.plan = Some(planned());
+        }
+        let events = Pirep::default()
+            .handle(command, &DomainServices::default())
+            .await
+            .unwrap();
+        assert!(matches!(
+            events.as_slice(),
+            [
+                PirepEvent::Submitted(_),
+                PirepEvent::FlightPlanAttached(_),
+                PirepEvent::Validated(PirepValidated {
+                    auto_accepted: true,
+                    ..
+                })
+            ]
+        ));
+
+        let mut aggregate = Pirep::default();
+        for event in events {
+            aggregate.apply(event);
+        }
+        assert_eq!(aggregate.status(), PirepStatus::Approved);
+        assert_eq!(aggregate.flight_plan(), Some(&planned()));
+    }
+
+    #[tokio::test]
+    async fn test_attached_plan_is_checked_again() {
+        // Submitted without a plan, so the plan rule leaves it for review
+        let mut aggregate = Pirep::default();
+        execute(&mut aggregate, submit_with_rules(plan_rules(), 180.0))
+            .await
+            .unwrap();
+        assert_eq!(aggregate.status(), PirepStatus::Pending);
+        assert!(!aggregate.rule_results()[0].passed);
+
+        let attach = |plan: FlightPlan| {
+            PirepCommand::AttachFlightPlan(AttachFlightPlan {
+                pirep_id: "pirep-1".to_string(),
+                user_id: "user-1".to_string(),
+                plan: Some(plan),
+                rules: Some(plan_rules()),
+            })
+        };
+        // A plan for another route still fails
+        let elsewhere = FlightPlan {
+            arrival_icao: "EGKK".to_string(),
+            ..planned()
+        };
+        execute(&mut aggregate, attach(elsewhere)).await.unwrap();
+        assert_eq!(aggregate.status(), PirepStatus::Pending);
+        assert_eq!(aggregate.rule_results().len(), 1);
+        assert!(!aggregate.rule_results()[0].passed);
+
+        execute(&mut aggregate, attach(planned())).await.unwrap();
+        assert_eq!(aggregate.status(), PirepStatus::Approved);
+        assert_eq!(aggregate.rule_results().len(), 1);
+        assert!(aggregate.rule_results()[0].passed);
+
+        // PIREPs submitted before the tenant had rules are left to review
+        let mut unchecked = submitted();
+        execute(&mut unchecked, attach(planned())).await.unwrap();
+        assert_eq!(unchecked.status(), PirepStatus::Pending);
+        assert!(unchecked.rule_results().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pilot_cannot_review_own_pirep() {
+        let mut aggregate = submitted();
+        assert!(matches!(
+            execute(&mut aggregate, approve("user-1")).await,
+            Err(PirepError::Forbidden(_))
+        ));
+        assert!(matches!(
+            execute(&mut aggregate, approve("")).await,
+            Err(PirepError::InvalidInput(_))
+        ));
+        assert_eq!(aggregate.status(), PirepStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_review_of_unknown_pirep_is_not_found() {
+        let mut aggregate = Pirep::default();
+        match execute(&mut aggregate, approve("staff-1")).await {
+            Err(PirepError::NotFound(id)) => assert_eq!(id, "pirep-1"),
+            other => panic!("Expected NotFound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_review_events_round_trip_through_codec() {
+        let events = [
+            PirepEvent::Approved(PirepApproved {
+                pirep_id: "pirep-1".to_string(),
+                reviewer_id: "staff-1".to_string(),
+                comment: "Nice landing".to_string(),
+                timestamp: "1".to_string(),
+            }),
+            PirepEvent::ChangesRequested(PirepChangesRequested {
+                pirep_id: "pirep-1".to_string(),
+                reviewer_id: "staff-1".to_string(),
+                reason: "Wrong aircraft".to_string(),
+                timestamp: "2".to_string(),
+            }),
+            PirepEvent::Cancelled(PirepCancelled {
+                pirep_id: "pirep-1".to_string(),
+                user_id: "user-1".to_string(),
+                reason: String::new(),
+                timestamp: "3".to_string(),
+            }),
+            PirepEvent::FlightPlanAttached(PirepFlightPlanAttached {
+                pirep_id: "pirep-1".to_string(),
+                user_id: "user-1".to_string(),
+                plan: Some(FlightPlan {
+                    departure_icao: "EKCH".to_string(),
+                    arrival_icao: "EGLL".to_string(),
+                    ..Default::default()
+                }),
+                timestamp: "4".to_string(),
+            }),
+        ];
+        for event in events {
+            let decoded =
+                PirepEvent::decode_payload(&event.event_type(), &event.encode_payload()).unwrap();
+            assert_eq!(decoded, event);
+        }
+    }
 }
@@ -0,0 +1,572 @@
+//! Per-tenant PIREP validation rules.
+//!
+//! A tenant configures [`PirepRules`] on its aggregate; every submission is then
+//! checked with [`evaluate`], which reports one [`RuleResult`] per configured rule.
+//! A PIREP that passes all of them can be accepted without review.
+
+use proto::pirep::{FlightPlan, PirepSubmitted};
+pub use proto::pirep::{PirepRules, RouteAircraft, RuleResult, SubmissionFacts};
+
+// Rule names reported in `RuleResult::rule`
+pub const RULE_LANDING_RATE: &str = "landing_rate";
+pub const RULE_BLOCK_TIME: &str = "block_time";
+pub const RULE_ROUTE_AIRCRAFT: &str = "route_aircraft";
+pub const RULE_DEPARTURE_LOCATION: &str = "departure_location";
+pub const RULE_AIRCRAFT_LOCATION: &str = "aircraft_location";
+pub const RULE_FLIGHT_PLAN: &str = "flight_plan";
+
+/// Checks a rule configuration before it is stored.
+pub fn validate_rules(rules: &PirepRules) -> Result<(), String> {
+    let limits = [
+        ("max_landing_rate_fpm", rules.max_landing_rate_fpm),
+        ("min_block_speed_kts", rules.min_block_speed_kts),
+        ("max_block_speed_kts", rules.max_block_speed_kts),
+        (
+            "max_plan_block_deviation_pct",
+            rules.max_plan_block_deviation_pct,
+        ),
+    ];
+    for (name, limit) in limits {
+        if limit.is_some_and(|value| !(value.is_finite() && value > 0.0)) {
+            return Err(format!("{} must be a positive number", name));
+        }
+    }
+    if let (Some(min), Some(max)) = (rules.min_block_speed_kts, rules.max_block_speed_kts) {
+        if min > max {
+            return Err("min_block_speed_kts cannot exceed max_block_speed_kts".into());
+        }
+    }
+    for route in &rules.route_aircraft {
+        if route.departure_icao.is_empty() || route.arrival_icao.is_empty() {
+            return Err("Route aircraft rules need a departure and an arrival".into());
+        }
+        if route.aircraft_ids.is_empty() {
+            return Err(format!(
+                "No aircraft allowed on {}-{}",
+                route.departure_icao, route.arrival_icao
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Runs every configured rule against a submitted PIREP and its flight plan, if any.
+pub fn evaluate(
+    rules: &PirepRules,
+    pirep: &PirepSubmitted,
+    facts: &SubmissionFacts,
+    plan: Option<&FlightPlan>,
+) -> Vec<RuleResult> {
+    let mut results = Vec::new();
+    if let Some(max) = rules.max_landing_rate_fpm {
+        results.push(check_landing_rate(max, pirep.landing_rate_fpm));
+    }
+    if rules.min_block_speed_kts.is_some() || rules.max_block_speed_kts.is_some() {
+        results.push(check_block_time(rules, pirep));
+    }
+    if !rules.route_aircraft.is_empty() {
+        results.push(check_route_aircraft(&rules.route_aircraft, pirep));
+    }
+    if rules.require_departure_from_location {
+        results.push(check_departure_location(pirep, &facts.pilot_location_icao));
+    }
+    if rules.require_departure_from_aircraft_location {
+        results.push(check_aircraft_location(
+            pirep,
+            &facts.aircraft_location_icao,
+        ));
+    }
+    results.extend(evaluate_flight_plan(rules, pirep, plan));
+    results
+}
+
+/// Runs the flight plan rule alone, if it is configured; for a plan attached after the
+/// PIREP was validated.
+pub fn evaluate_flight_plan(
+    rules: &PirepRules,
+    pirep: &PirepSubmitted,
+    plan: Option<&FlightPlan>,
+) -> Option<RuleResult> {
+    rules
+        .max_plan_block_deviation_pct
+        .map(|max| check_flight_plan(max, pirep, plan))
+}
+
+/// Whether a PIREP with these results is accepted without review.
+pub fn auto_accepts(rules: &PirepRules, results: &[RuleResult]) -> bool {
+    rules.auto_accept && results.iter().all(|result| result.passed)
+}
+
+fn result(rule: &str, passed: bool, message: String) -> RuleResult {
+    RuleResult {
+        rule: rule.to_string(),
+        passed,
+        message,
+    }
+}
+
+fn check_landing_rate(max: f64, landing_rate_fpm: f64) -> RuleResult {
+    if landing_rate_fpm <= 0.0 {
+        return result(RULE_LANDING_RATE, false, "Landing rate not reported".into());
+    }
+    if landing_rate_fpm > max {
+        return result(
+            RULE_LANDING_RATE,
+            false,
+            format!(
+                "Landing rate {:.0} fpm exceeds the {:.0} fpm limit",
+                landing_rate_fpm, max
+            ),
+        );
+    }
+    result(
+        RULE_LANDING_RATE,
+        true,
+        format!("Landing rate {:.0} fpm", landing_rate_fpm),
+    )
+}
+
+fn check_block_time(rules: &PirepRules, pirep: &PirepSubmitted) -> RuleResult {
+    let distance = pirep.route_distance_nm;
+    if distance <= 0.0 {
+        return result(
+            RULE_BLOCK_TIME,
+            false,
+            "Route distance unknown; block time not checked".into(),
+        );
+    }
+    let speed = distance / pirep.flight_time_hours;
+    if let Some(max) = rules.max_block_speed_kts.filter(|max| speed > *max) {
+        return result(
+            RULE_BLOCK_TIME,
+            false,
+            format!(
+                "Block time {:.1} h is too short for {:.0} nm ({:.0} kts average, at most {:.0})",
+                pirep.flight_time_hours, distance, speed, max
+            ),
+        );
+    }
+    if let Some(min) = rules.min_block_speed_kts.filter(|min| speed < *min) {
+        return result(
+            RULE_BLOCK_TIME,
+            false,
+            format!(
+                "Block time {:.1} h is too long for {:.0} nm ({:.0} kts average, at least {:.0})",
+                pirep.flight_time_hours, distance, speed, min
+            ),
+        );
+    }
+    result(
+        RULE_BLOCK_TIME,
+        true,
+        format!(
+            "Block time {:.1} h for {:.0} nm ({:.0} kts average)",
+            pirep.flight_time_hours, distance, speed
+        ),
+    )
+}
+
+fn check_route_aircraft(routes: &[RouteAircraft], pirep: &PirepSubmitted) -> RuleResult {
+    let route = format!("{}-{}", pirep.departure_icao, pirep.arrival_icao);
+    let restriction = routes.iter().find(|r| {
+        r.departure_icao.eq_ignore_ascii_case(&pirep.departure_icao)
+            && r.arrival_icao.eq_ignore_ascii_case(&pirep.arrival_icao)
+    });
+    match restriction {
+        None => result(
+            RULE_ROUTE_AIRCRAFT,
+            true,
+            format!("No aircraft restriction on {}", route),
+        ),
+        Some(r)
+            if r.aircraft_ids
+                .iter()
+                .any(|id| id.eq_ignore_ascii_case(&pirep.aircraft_id)) =>
+        {
+            result(
+                RULE_ROUTE_AIRCRAFT,
+                true,
+                format!("Aircraft {} is allowed on {}", pirep.aircraft_id, route),
+            )
+        }
+        Some(_) => result(
+            RULE_ROUTE_AIRCRAFT,
+            false,
+            format!("Aircraft {} is not allowed on {}", pirep.aircraft_id, route),
+        ),
+    }
+}
+
+fn check_departure_location(pirep: &PirepSubmitted, pilot_location_icao: &str) -> RuleResult {
+    if pilot_location_icao.is_empty() {
+        return result(
+            RULE_DEPARTURE_LOCATION,
+            true,
+            "No previous flight; any departure allowed".into(),
+        );
+    }
+    if !pirep
+        .departure_icao
+        .eq_ignore_ascii_case(pilot_location_icao)
+    {
+        return result(
+            RULE_DEPARTURE_LOCATION,
+            false,
+            format!(
+                "Departed {} but the pilot is at {}",
+                pirep.departure_icao, pilot_location_icao
+            ),
+        );
+    }
+    result(
+        RULE_DEPARTURE_LOCATION,
+        true,
+        format!("Departed from the pilot's location {}", pilot_location_icao),
+    )
+}
+
+fn check_aircraft_location(pirep: &PirepSubmitted, aircraft_location_icao: &str) -> RuleResult {
+    // Aircraft in the fleet always have a location, starting at their home base
+    if aircraft_location_icao.is_empty() {
+        return result(
+            RULE_AIRCRAFT_LOCATION,
+            false,
+            format!(
+                "Aircraft {} is not in the fleet; location unknown",
+                pirep.aircraft_id
+            ),
+        );
+    }
+    if !pirep
+        .departure_icao
+        .eq_ignore_ascii_case(aircraft_location_icao)
+    {
+        return result(
+            RULE_AIRCRAFT_LOCATION,
+            false,
+            format!(
+                "Departed {} but aircraft {} is at {}",
+                pirep.departure_icao, pirep.aircraft_id, aircraft_location_icao
+            ),
+        );
+    }
+    result(
+        RULE_AIRCRAFT_LOCATION,
+        true,
+        format!(
+            "Departed from the aircraft's location {}",
+            aircraft_location_icao
+        ),
+    )
+}
+
+fn check_flight_plan(
+    max_deviation_pct: f64,
+    pirep: &PirepSubmitted,
+    plan: Option<&FlightPlan>,
+) -> RuleResult {
+    let Some(plan) = plan else {
+        return result(RULE_FLIGHT_PLAN, false, "No flight plan attached".into());
+    };
+    let flown = format!("{}-{}", pirep.departure_icao, pirep.arrival_icao);
+    if !plan
+        .departure_icao
+        .eq_ignore_ascii_case(&pirep.departure_icao)
+        || !plan.arrival_icao.eq_ignore_ascii_case(&pirep.arrival_icao)
+    {
+        return result(
+            RULE_FLIGHT_PLAN,
+            false,
+            format!(
+                "Planned {}-{} but flew {}",
+                plan.departure_icao, plan.arrival_icao, flown
+            ),
+        );
+    }
+    let planned = plan.est_block_hours;
+    if planned <= 0.0 {
+        return result(
+            RULE_FLIGHT_PLAN,
+            false,
+            "Flight plan has no block time; block time not checked".into(),
+        );
+    }
+    let deviation_pct = (pirep.flight_time_hours - planned).abs() / planned * 100.0;
+    if deviation_pct > max_deviation_pct {
+        return result(
+            RULE_FLIGHT_PLAN,
+            false,
+            format!(
+                "Block time {:.1} h is {:.0}% off the planned {:.1} h (at most {:.0}%)",
+                pirep.flight_time_hours, deviation_pct, planned, max_deviation_pct
+            ),
+        );
+    }
+    result(
+        RULE_FLIGHT_PLAN,
+        true,
+        format!(
+            "Flew the planned {} in {:.1} h (planned {:.1} h)",
+            flown, pirep.flight_time_hours, planned
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // EKCH-EGLL (530 nm), 2.0 h block, landing at 250 fpm
+    fn pirep() -> PirepSubmitted {
+        PirepSubmitted {
+            pirep_id: "pirep-1".to_string(),
+            tenant_id: "tenant-1".to_string(),
+            user_id: "user-1".to_string(),
+            aircraft_id: "ac-1".to_string(),
+            departure_icao: "EKCH".to_string(),
+            arrival_icao: "EGLL".to_string(),
+            flight_time_hours: 2.0,
+            landing_rate_fpm: 250.0,
+            route_distance_nm: 530.0,
+            ..Default::default()
+        }
+    }
+
+    // Planned EKCH-EGLL in 1.9 h
+    fn plan() -> FlightPlan {
+        FlightPlan {
+            departure_icao: "EKCH".to_string(),
+            arrival_icao: "EGLL".to_string(),
+            est_block_hours: 1.9,
+            ..Default::default()
+        }
+    }
+
+    fn facts(pilot_location_icao: &str) -> SubmissionFacts {
+        SubmissionFacts {
+            pilot_location_icao: pilot_location_icao.to_string(),
+            aircraft_location_icao: "EKCH".to_string(),
+        }
+    }
+
+    fn failures(results: &[RuleResult]) -> Vec<&str> {
+        results
+            .iter()
+            .filter(|r| !r.passed)
+            .map(|r| r.rule.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_no_rules_configured_reports_nothing() {
+        let rules = PirepRules::default();
+        assert!(evaluate(&rules, &pirep(), &facts(""), None).is_empty());
+    }
+
+    #[test]
+    fn test_all_rules_pass() {
+        let rules = PirepRules {
+            auto_accept: true,
+            max_landing_rate_fpm: Some(600.0),
+            min_block_speed_kts: Some(150.0),
+            max_block_speed_kts: Some(500.0),
+            route_aircraft: vec![RouteAircraft {
+                departure_icao: "ekch".to_string(),
+                arrival_icao: "egll".to_string(),
+                aircraft_ids: vec!["AC-1".to_string()],
+            }],
+            require_departure_from_location: true,
+            require_departure_from_aircraft_location: true,
+            max_plan_block_deviation_pct: Some(10.0),
+        };
+        let results = evaluate(&rules, &pirep(), &facts("EKCH"), Some(&plan()));
+        assert_eq!(results.len(), 6);
+        assert!(failures(&results).is_empty(), "{:?}", results);
+        assert!(auto_accepts(&rules, &results));
+
+        // Without auto_accept the results are only advisory
+        let manual = PirepRules {
+            auto_accept: false,
+            ..rules
+        };
+        assert!(!auto_accepts(&manual, &results));
+    }
+
+    #[test]
+    fn test_landing_rate_limit() {
+        let rules = PirepRules {
+            max_landing_rate_fpm: Some(200.0),
+            ..Default::default()
+        };
+        let results = evaluate(&rules, &pirep(), &facts(""), None);
+        assert_eq!(failures(&results), vec![RULE_LANDING_RATE]);
+        assert_eq!(
+            results[0].message,
+            "Landing rate 250 fpm exceeds the 200 fpm limit"
+        );
+
+        let unreported = PirepSubmitted {
+            landing_rate_fpm: 0.0,
+            ..pirep()
+        };
+        let results = evaluate(&rules, &unreported, &facts(""), None);
+        assert_eq!(results[0].message, "Landing rate not reported");
+    }
+
+    #[test]
+    fn test_block_time_relative_to_distance() {
+        let rules = PirepRules {
+            min_block_speed_kts: Some(150.0),
+            max_block_speed_kts: Some(500.0),
+            ..Default::default()
+        };
+        // 530 nm in 2 h is plausible; in 0.5 h it is not, and 8 h is too long
+        assert!(failures(&evaluate(&rules, &pirep(), &facts(""), None)).is_empty());
+        let short = PirepSubmitted {
+            flight_time_hours: 0.5,
+            ..pirep()
+        };
+        let results = evaluate(&rules, &short, &facts(""), None);
+        assert!(
+            results[0].message.contains("too short"),
+            "{}",
+            results[0].message
+        );
+        let long = PirepSubmitted {
+            flight_time_hours: 8.0,
+            ..pirep()
+        };
+        let results = evaluate(&rules, &long, &facts(""), None);
+        assert!(
+            results[0].message.contains("too long"),
+            "{}",
+            results[0].message
+        );
+
+        // Without a distance the rule cannot pass on its own
+        let unknown = PirepSubmitted {
+            route_distance_nm: 0.0,
+            ..pirep()
+        };
+        let results = evaluate(&rules, &unknown, &facts(""), None);
+        assert_eq!(failures(&results), vec![RULE_BLOCK_TIME]);
+    }
+
+    #[test]
+    fn test_route_aircraft_and_departure_location() {
+        let rules = PirepRules {
+            route_aircraft: vec![RouteAircraft {
+                departure_icao: "EKCH".to_string(),
+                arrival_icao: "EGLL".to_string(),
+                aircraft_ids: vec!["ac-2".to_string()],
+            }],
+            require_departure_from_location: true,
+            ..Default::default()
+        };
+        let results = evaluate(&rules, &pirep(), &facts("ENGM"), None);
+        assert_eq!(
+            failures(&results),
+            vec![RULE_ROUTE_AIRCRAFT, RULE_DEPARTURE_LOCATION]
+        );
+        assert_eq!(
+            results[0].message,
+            "Aircraft ac-1 is not allowed on EKCH-EGLL"
+        );
+        assert_eq!(results[1].message, "Departed EKCH but the pilot is at ENGM");
+
+        // Other routes are open, and a first flight may depart anywhere
+        let other_route = PirepSubmitted {
+            arrival_icao: "ESSA".to_string(),
+            ..pirep()
+        };
+        assert!(failures(&evaluate(&rules, &other_route, &facts(""), None)).is_empty());
+    }
+
+    #[test]
+    fn test_aircraft_location() {
+        let rules = PirepRules {
+            require_departure_from_aircraft_location: true,
+            ..Default::default()
+        };
+        assert!(failures(&evaluate(&rules, &pirep(), &facts(""), None)).is_empty());
+
+        let elsewhere = SubmissionFacts {
+            aircraft_location_icao: "ENGM".to_string(),
+            ..facts("")
+        };
+        let results = evaluate(&rules, &pirep(), &elsewhere, None);
+        assert_eq!(failures(&results), vec![RULE_AIRCRAFT_LOCATION]);
+        assert_eq!(
+            results[0].message,
+            "Departed EKCH but aircraft ac-1 is at ENGM"
+        );
+
+        // Aircraft outside the fleet cannot be placed
+        let unknown = SubmissionFacts {
+            aircraft_location_icao: String::new(),
+            ..facts("")
+        };
+        let results = evaluate(&rules, &pirep(), &unknown, None);
+        assert_eq!(failures(&results), vec![RULE_AIRCRAFT_LOCATION]);
+    }
+
+    #[test]
+    fn test_flown_against_flight_plan() {
+        let rules = PirepRules {
+            max_plan_block_deviation_pct: Some(10.0),
+            ..Default::default()
+        };
+        // 2.0 h against a planned 1.9 h is about 5% off
+        let results = evaluate(&rules, &pirep(), &facts(""), Some(&plan()));
+        assert!(failures(&results).is_empty(), "{:?}", results);
+
+        let results = evaluate(&rules, &pirep(), &facts(""), None);
+        assert_eq!(failures(&results), vec![RULE_FLIGHT_PLAN]);
+        assert_eq!(results[0].message, "No flight plan attached");
+
+        let diverted = PirepSubmitted {
+            arrival_icao: "EGKK".to_string(),
+            ..pirep()
+        };
+        let results = evaluate(&rules, &diverted, &facts(""), Some(&plan()));
+        assert_eq!(results[0].message, "Planned EKCH-EGLL but flew EKCH-EGKK");
+
+        let slow = PirepSubmitted {
+            flight_time_hours: 2.5,
+            ..pirep()
+        };
+        let results = evaluate(&rules, &slow, &facts(""), Some(&plan()));
+        assert_eq!(
+            results[0].message,
+            "Block time 2.5 h is 32% off the planned 1.9 h (at most 10%)"
+        );
+
+        // Only the plan rule is run on its own
+        assert!(evaluate_flight_plan(&PirepRules::default(), &pirep(), None).is_none());
+    }
+
+    #[test]
+    fn test_validate_rules() {
+        assert!(validate_rules(&PirepRules::default()).is_ok());
+        let negative = PirepRules {
+            max_landing_rate_fpm: Some(-1.0),
+            ..Default::default()
+        };
+        assert!(validate_rules(&negative).is_err());
+        let inverted = PirepRules {
+            min_block_speed_kts: Some(500.0),
+            max_block_speed_kts: Some(150.0),
+            ..Default::default()
+        };
+        assert!(validate_rules(&inverted).is_err());
+        let empty_route = PirepRules {
+            route_aircraft: vec![RouteAircraft {
+                departure_icao: "EKCH".to_string(),
+                arrival_icao: "EGLL".to_string(),
+                aircraft_ids: vec![],
+            }],
+            ..Default::default()
+        };
+        assert!(validate_rules(&empty_route).is_err());
+    }
+}
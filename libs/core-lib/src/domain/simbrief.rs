@@ -0,0 +1,221 @@
+//! SimBrief OFP import.
+//!
+//! Pilots download the OFP (operational flight plan) of a SimBrief dispatch as XML and
+//! upload it; [`parse_ofp`] turns it into a [`FlightPlan`] that is attached to the PIREP
+//! of the flight. Nothing is fetched from SimBrief itself.
+
+use chrono::DateTime;
+pub use proto::pirep::FlightPlan;
+use quick_xml::events::Event as XmlEvent;
+use quick_xml::Reader;
+use std::collections::HashMap;
+
+pub const SOURCE_SIMBRIEF: &str = "simbrief";
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum SimbriefError {
+    #[error("Invalid SimBrief OFP: {0}")]
+    InvalidOfp(String),
+}
+
+fn invalid(message: impl Into<String>) -> SimbriefError {
+    SimbriefError::InvalidOfp(message.into())
+}
+
+/// Reads an OFP XML file (`<OFP>` root, as downloaded from SimBrief).
+pub fn parse_ofp(xml: &[u8]) -> Result<FlightPlan, SimbriefError> {
+    let fields = read_sections(xml)?;
+    let text = |path: &str| fields.get(path).map(String::as_str).unwrap_or_default();
+    let number = |path: &str| -> Result<f64, SimbriefError> {
+        match text(path) {
+            "" => Ok(0.0),
+            value => value
+                .parse::<f64>()
+                .map_err(|_| invalid(format!("{} is not a number: '{}'", path, value))),
+        }
+    };
+    // Times are Unix seconds
+    let time = |path: &str| -> Result<String, SimbriefError> {
+        match text(path) {
+            "" => Ok(String::new()),
+            value => value
+                .parse::<i64>()
+                .ok()
+                .and_then(|seconds| DateTime::from_timestamp(seconds, 0))
+                .map(|time| time.to_rfc3339())
+                .ok_or_else(|| invalid(format!("{} is not a timestamp: '{}'", path, value))),
+        }
+    };
+
+    let departure_icao = text("origin/icao_code").to_uppercase();
+    let arrival_icao = text("destination/icao_code").to_uppercase();
+    if departure_icao.is_empty() || arrival_icao.is_empty() {
+        return Err(invalid("Origin and destination are required"));
+    }
+
+    let callsign = match text("atc/callsign") {
+        "" => format!(
+            "{}{}",
+            text("general/icao_airline"),
+            text("general/flight_number")
+        ),
+        callsign => callsign.to_string(),
+    };
+    let weight_unit = match text("params/units").to_ascii_lowercase().as_str() {
+        "lbs" | "lb" => "lb",
+        _ => "kg",
+    };
+
+    Ok(FlightPlan {
+        source: SOURCE_SIMBRIEF.to_string(),
+        ofp_id: text("params/request_id").to_string(),
+        callsign,
+        departure_icao,
+        arrival_icao,
+        alternate_icao: text("alternate/icao_code").to_uppercase(),
+        aircraft_icao: text("aircraft/icaocode").to_uppercase(),
+        route: text("general/route").to_string(),
+        cruise_altitude_ft: number("general/initial_altitude")? as i32,
+        route_distance_nm: number("general/route_distance")?,
+        weight_unit: weight_unit.to_string(),
+        block_fuel: number("fuel/plan_ramp")?,
+        trip_fuel: number("fuel/enroute_burn")?,
+        zero_fuel_weight: number("weights/est_zfw")?,
+        est_out: time("times/est_out")?,
+        est_off: time("times/est_off")?,
+        est_on: time("times/est_on")?,
+        est_in: time("times/est_in")?,
+        est_block_hours: number("times/est_block")? / 3600.0,
+        est_air_hours: number("times/est_time_enroute")? / 3600.0,
+    })
+}
+
+// Text of every `<OFP><section><field>` as "section/field". Only the first occurrence
+// counts, so the first of several alternates is the planned one.
+fn read_sections(xml: &[u8]) -> Result<HashMap<String, String>, SimbriefError> {
+    let mut reader = Reader::from_reader(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut fields = HashMap::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| invalid(format!("Malformed XML: {}", e)))?;
+        match event {
+            XmlEvent::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).into_owned();
+                if path.is_empty() && name != "OFP" {
+                    return Err(invalid(format!(
+                        "Expected an <OFP> document, got <{}>",
+                        name
+                    )));
+                }
+                path.push(name);
+            }
+            XmlEvent::Text(text) if path.len() == 3 => {
+                let value = text
+                    .unescape()
+                    .map_err(|e| invalid(format!("Malformed XML: {}", e)))?;
+                fields
+                    .entry(format!("{}/{}", path[1], path[2]))
+                    .or_insert_with(|| value.trim().to_string());
+            }
+            XmlEvent::End(_) => {
+                path.pop();
+            }
+            XmlEvent::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+    if fields.is_empty() {
+        return Err(invalid("Expected an <OFP> document"));
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trimmed OFP of a dispatch, as downloaded from SimBrief
+    const OFP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<OFP>
+  <fetch><userid>123456</userid><status>Success</status></fetch>
+  <params>
+    <request_id>98765432</request_id>
+    <time_generated>1772355600</time_generated>
+    <units>kgs</units>
+  </params>
+  <general>
+    <icao_airline>ALB</icao_airline>
+    <flight_number>101</flight_number>
+    <initial_altitude>34000</initial_altitude>
+    <route_distance>187</route_distance>
+    <route>TUSKA T310 BAVAD</route>
+  </general>
+  <origin><icao_code>ENBR</icao_code><name>Bergen Flesland</name></origin>
+  <destination><icao_code>ENGM</icao_code><name>Oslo Gardermoen</name></destination>
+  <alternate><icao_code>ESSA</icao_code></alternate>
+  <alternate><icao_code>ENTO</icao_code></alternate>
+  <aircraft><icaocode>B738</icaocode><reg>LN-ALB</reg></aircraft>
+  <fuel><plan_ramp>6850</plan_ramp><enroute_burn>2480</enroute_burn></fuel>
+  <weights><est_zfw>58210</est_zfw></weights>
+  <times>
+    <est_time_enroute>2700</est_time_enroute>
+    <est_block>3600</est_block>
+    <est_out>1772359200</est_out>
+    <est_off>1772359800</est_off>
+    <est_on>1772362500</est_on>
+    <est_in>1772362800</est_in>
+  </times>
+  <atc><callsign>ALB101</callsign></atc>
+  <text><plan_html>&lt;pre&gt;...&lt;/pre&gt;</plan_html></text>
+</OFP>"#;
+
+    #[test]
+    fn test_parse_ofp() {
+        let plan = parse_ofp(OFP.as_bytes()).unwrap();
+        assert_eq!(plan.source, "simbrief");
+        assert_eq!(plan.ofp_id, "98765432");
+        assert_eq!(plan.callsign, "ALB101");
+        assert_eq!(plan.departure_icao, "ENBR");
+        assert_eq!(plan.arrival_icao, "ENGM");
+        assert_eq!(plan.alternate_icao, "ESSA");
+        assert_eq!(plan.aircraft_icao, "B738");
+        assert_eq!(plan.route, "TUSKA T310 BAVAD");
+        assert_eq!(plan.cruise_altitude_ft, 34000);
+        assert_eq!(plan.route_distance_nm, 187.0);
+        assert_eq!(plan.weight_unit, "kg");
+        assert_eq!(plan.block_fuel, 6850.0);
+        assert_eq!(plan.trip_fuel, 2480.0);
+        assert_eq!(plan.zero_fuel_weight, 58210.0);
+        assert_eq!(plan.est_out, "2026-03-01T10:00:00+00:00");
+        assert_eq!(plan.est_in, "2026-03-01T11:00:00+00:00");
+        assert_eq!(plan.est_block_hours, 1.0);
+        assert_eq!(plan.est_air_hours, 0.75);
+    }
+
+    #[test]
+    fn test_parse_ofp_without_atc_callsign_in_pounds() {
+        let ofp = OFP
+            .replace("<atc><callsign>ALB101</callsign></atc>", "")
+            .replace("<units>kgs</units>", "<units>lbs</units>");
+        let plan = parse_ofp(ofp.as_bytes()).unwrap();
+        assert_eq!(plan.callsign, "ALB101");
+        assert_eq!(plan.weight_unit, "lb");
+    }
+
+    #[test]
+    fn test_parse_invalid_ofp() {
+        assert!(parse_ofp(b"<gpx><trk/></gpx>").is_err());
+        assert!(parse_ofp(b"not xml <<").is_err());
+        assert_eq!(
+            parse_ofp(OFP.replace("ENGM", "").as_bytes()),
+            Err(invalid("Origin and destination are required"))
+        );
+        assert!(parse_ofp(OFP.replace("6850", "lots").as_bytes()).is_err());
+    }
+}
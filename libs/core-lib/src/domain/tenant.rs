@@ -1,8 +1,18 @@
+use crate::domain::aircraft_type::{validate_aircraft_type, AircraftCatalog, AircraftType};
+use crate::domain::maintenance::{normalize_program, validate_program, MaintenanceProgram};
+use crate::domain::pirep_rules::{validate_rules, PirepRules};
+use crate::framework::{EventCodec, TenantScoped};
+use crate::services::DomainServices;
 use crate::{Command, CoreError, DomainEvent, Event};
 use cqrs_es::Aggregate;
+use prost::Message;
 pub use proto::tenant::tenant_command::TenantCommand;
-use proto::tenant::{CreateTenant, TenantCreated};
-use std::time::{SystemTime, UNIX_EPOCH};
+use proto::tenant::{
+    AircraftTypeDefined, AircraftTypeRemoved, ConfigureMaintenanceProgram, ConfigurePirepRules,
+    CreateTenant, DefineAircraftType, MaintenanceProgramConfigured, PirepRulesConfigured,
+    RemoveAircraftType, TenantCreated,
+};
+use std::collections::BTreeMap;
 
 // --- Tenant Aggregate ---
 
@@ -11,12 +21,20 @@ pub struct Tenant {
     id: String,
     version: usize,
     name: String,
+    pirep_rules: Option<PirepRules>,
+    // The tenant's own aircraft types, by designator
+    aircraft_types: BTreeMap<String, AircraftType>,
+    maintenance_program: Option<MaintenanceProgram>,
     // Add other tenant state fields here
 }
 
 // --- Commands ---
 
 impl Command for CreateTenant {}
+impl Command for ConfigurePirepRules {}
+impl Command for DefineAircraftType {}
+impl Command for RemoveAircraftType {}
+impl Command for ConfigureMaintenanceProgram {}
 
 // --- Events ---
 
@@ -24,12 +42,22 @@ impl Command for CreateTenant {}
 #[derive(Debug, Clone, PartialEq)]
 pub enum TenantEvent {
     Created(TenantCreated),
+    PirepRulesConfigured(PirepRulesConfigured),
+    AircraftTypeDefined(AircraftTypeDefined),
+    AircraftTypeRemoved(AircraftTypeRemoved),
+    MaintenanceProgramConfigured(MaintenanceProgramConfigured),
 }
 
 impl DomainEvent for TenantEvent {
     fn event_type(&self) -> String {
         match self {
             TenantEvent::Created(_) => "TenantCreated".to_string(),
+            TenantEvent::PirepRulesConfigured(_) => "PirepRulesConfigured".to_string(),
+            TenantEvent::AircraftTypeDefined(_) => "AircraftTypeDefined".to_string(),
+            TenantEvent::AircraftTypeRemoved(_) => "AircraftTypeRemoved".to_string(),
+            TenantEvent::MaintenanceProgramConfigured(_) => {
+                "MaintenanceProgramConfigured".to_string()
+            }
         }
     }
 
@@ -38,7 +66,49 @@ impl DomainEvent for TenantEvent {
     }
 }
 
+impl EventCodec for TenantEvent {
+    fn encode_payload(&self) -> Vec<u8> {
+        match self {
+            TenantEvent::Created(e) => e.encode_to_vec(),
+            TenantEvent::PirepRulesConfigured(e) => e.encode_to_vec(),
+            TenantEvent::AircraftTypeDefined(e) => e.encode_to_vec(),
+            TenantEvent::AircraftTypeRemoved(e) => e.encode_to_vec(),
+            TenantEvent::MaintenanceProgramConfigured(e) => e.encode_to_vec(),
+        }
+    }
+
+    fn decode_payload(event_type: &str, payload: &[u8]) -> Result<Self, CoreError> {
+        let decoded = match event_type {
+            "TenantCreated" => TenantCreated::decode(payload).map(TenantEvent::Created),
+            "PirepRulesConfigured" => {
+                PirepRulesConfigured::decode(payload).map(TenantEvent::PirepRulesConfigured)
+            }
+            "AircraftTypeDefined" => {
+                AircraftTypeDefined::decode(payload).map(TenantEvent::AircraftTypeDefined)
+            }
+            "AircraftTypeRemoved" => {
+                AircraftTypeRemoved::decode(payload).map(TenantEvent::AircraftTypeRemoved)
+            }
+            "MaintenanceProgramConfigured" => MaintenanceProgramConfigured::decode(payload)
+                .map(TenantEvent::MaintenanceProgramConfigured),
+            other => {
+                return Err(CoreError::Deserialization(format!(
+                    "Unknown tenant event type: {}",
+                    other
+                )))
+            }
+        };
+        decoded.map_err(|e| {
+            CoreError::Deserialization(format!("Failed to decode {}: {}", event_type, e))
+        })
+    }
+}
+
 impl Event for TenantCreated {}
+impl Event for PirepRulesConfigured {}
+impl Event for AircraftTypeDefined {}
+impl Event for AircraftTypeRemoved {}
+impl Event for MaintenanceProgramConfigured {}
 
 // --- Errors ---
 
@@ -50,6 +120,10 @@ pub enum TenantError {
     AlreadyExists(String),
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+    #[error("Tenant not found (ID: {0})")]
+    NotFound(String),
+    #[error("Aircraft type not defined by the tenant: {0}")]
+    AircraftTypeNotFound(String),
 }
 
 // --- Aggregate Implementation ---
@@ -59,7 +133,7 @@ impl Aggregate for Tenant {
     type Command = TenantCommand;
     type Event = TenantEvent;
     type Error = TenantError;
-    type Services = ();
+    type Services = DomainServices;
 
     fn aggregate_id(&self) -> &str {
         &self.id
@@ -71,7 +145,6 @@ impl Aggregate for Tenant {
 
     /// Apply state changes based on events.
     fn apply(&mut self, event: Self::Event) {
-        #[allow(clippy::match_single_binding)]
         match event {
             TenantEvent::Created(TenantCreated {
                 tenant_id, name, ..
@@ -80,6 +153,26 @@ impl Aggregate for Tenant {
                 self.name = name;
                 // Initialize other fields if necessary
             }
+            TenantEvent::PirepRulesConfigured(PirepRulesConfigured { rules, .. }) => {
+                self.pirep_rules = rules;
+            }
+            TenantEvent::AircraftTypeDefined(AircraftTypeDefined {
+                aircraft_type: Some(aircraft_type),
+                ..
+            }) => {
+                self.aircraft_types
+                    .insert(aircraft_type.icao_type.clone(), aircraft_type);
+            }
+            TenantEvent::AircraftTypeDefined(_) => {}
+            TenantEvent::AircraftTypeRemoved(AircraftTypeRemoved { icao_type, .. }) => {
+                self.aircraft_types.remove(&icao_type);
+            }
+            TenantEvent::MaintenanceProgramConfigured(MaintenanceProgramConfigured {
+                program,
+                ..
+            }) => {
+                self.maintenance_program = program;
+            }
         }
         self.version += 1; // Increment version after applying any event
     }
@@ -88,16 +181,59 @@ impl Aggregate for Tenant {
     async fn handle(
         &self,
         command: Self::Command,
-        _service: &Self::Services,
+        services: &Self::Services,
     ) -> Result<Vec<Self::Event>, Self::Error> {
         match command {
-            TenantCommand::Create(cmd) => self.handle_create(cmd).await,
+            TenantCommand::Create(cmd) => self.handle_create(cmd, services).await,
+            TenantCommand::ConfigurePirepRules(cmd) => {
+                self.handle_configure_pirep_rules(cmd, services)
+            }
+            TenantCommand::DefineAircraftType(cmd) => {
+                self.handle_define_aircraft_type(cmd, services)
+            }
+            TenantCommand::RemoveAircraftType(cmd) => {
+                self.handle_remove_aircraft_type(cmd, services)
+            }
+            TenantCommand::ConfigureMaintenanceProgram(cmd) => {
+                self.handle_configure_maintenance_program(cmd, services)
+            }
         }
     }
 }
 
+impl TenantScoped for Tenant {
+    // A tenant owns itself
+    fn owning_tenant(&self) -> Option<&str> {
+        (!self.id.is_empty()).then_some(self.id.as_str())
+    }
+}
+
 impl Tenant {
-    async fn handle_create(&self, command: CreateTenant) -> Result<Vec<TenantEvent>, TenantError> {
+    /// Rules PIREP submissions are checked against; `None` until configured.
+    pub fn pirep_rules(&self) -> Option<&PirepRules> {
+        self.pirep_rules.as_ref()
+    }
+
+    /// Aircraft types the tenant defined, ordered by designator.
+    pub fn aircraft_types(&self) -> impl Iterator<Item = &AircraftType> {
+        self.aircraft_types.values()
+    }
+
+    /// The embedded catalog with the tenant's own types added.
+    pub fn aircraft_catalog(&self) -> AircraftCatalog {
+        AircraftCatalog::embedded().with_tenant_types(self.aircraft_types.values())
+    }
+
+    /// Inspections the tenant's aircraft need; `None` until configured.
+    pub fn maintenance_program(&self) -> Option<&MaintenanceProgram> {
+        self.maintenance_program.as_ref()
+    }
+
+    async fn handle_create(
+        &self,
+        command: CreateTenant,
+        services: &DomainServices,
+    ) -> Result<Vec<TenantEvent>, TenantError> {
         // Validate command input
         if command.tenant_id.is_empty() {
             return Err(TenantError::InvalidInput(
@@ -117,10 +253,7 @@ impl Tenant {
         }
 
         // Create the event
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs().to_string()) // Simple timestamp, consider ISO 8601 later
-            .unwrap_or_default();
+        let timestamp = services.clock.timestamp();
 
         let event = TenantCreated {
             tenant_id: command.tenant_id,
@@ -130,6 +263,99 @@ impl Tenant {
 
         Ok(vec![TenantEvent::Created(event)])
     }
+
+    fn handle_configure_pirep_rules(
+        &self,
+        command: ConfigurePirepRules,
+        services: &DomainServices,
+    ) -> Result<Vec<TenantEvent>, TenantError> {
+        if self.version == 0 {
+            return Err(TenantError::NotFound(command.tenant_id));
+        }
+        let rules = command
+            .rules
+            .ok_or_else(|| TenantError::InvalidInput("Rules are required".into()))?;
+        validate_rules(&rules).map_err(TenantError::InvalidInput)?;
+
+        Ok(vec![TenantEvent::PirepRulesConfigured(
+            PirepRulesConfigured {
+                tenant_id: self.id.clone(),
+                rules: Some(rules),
+                timestamp: services.clock.timestamp(),
+            },
+        )])
+    }
+
+    fn handle_define_aircraft_type(
+        &self,
+        command: DefineAircraftType,
+        services: &DomainServices,
+    ) -> Result<Vec<TenantEvent>, TenantError> {
+        if self.version == 0 {
+            return Err(TenantError::NotFound(command.tenant_id));
+        }
+        let mut aircraft_type = command
+            .aircraft_type
+            .ok_or_else(|| TenantError::InvalidInput("An aircraft type is required".into()))?;
+        aircraft_type.icao_type = aircraft_type.icao_type.trim().to_uppercase();
+        aircraft_type.category = aircraft_type.category.trim().to_lowercase();
+        validate_aircraft_type(&aircraft_type).map_err(TenantError::InvalidInput)?;
+
+        Ok(vec![TenantEvent::AircraftTypeDefined(
+            AircraftTypeDefined {
+                tenant_id: self.id.clone(),
+                aircraft_type: Some(aircraft_type),
+                timestamp: services.clock.timestamp(),
+            },
+        )])
+    }
+
+    // Only the tenant's own types can be removed; embedded ones are always available
+    fn handle_remove_aircraft_type(
+        &self,
+        command: RemoveAircraftType,
+        services: &DomainServices,
+    ) -> Result<Vec<TenantEvent>, TenantError> {
+        if self.version == 0 {
+            return Err(TenantError::NotFound(command.tenant_id));
+        }
+        let icao_type = command.icao_type.trim().to_uppercase();
+        if !self.aircraft_types.contains_key(&icao_type) {
+            return Err(TenantError::AircraftTypeNotFound(icao_type));
+        }
+
+        Ok(vec![TenantEvent::AircraftTypeRemoved(
+            AircraftTypeRemoved {
+                tenant_id: self.id.clone(),
+                icao_type,
+                timestamp: services.clock.timestamp(),
+            },
+        )])
+    }
+
+    // Aircraft are checked against the new program the next time they fly
+    fn handle_configure_maintenance_program(
+        &self,
+        command: ConfigureMaintenanceProgram,
+        services: &DomainServices,
+    ) -> Result<Vec<TenantEvent>, TenantError> {
+        if self.version == 0 {
+            return Err(TenantError::NotFound(command.tenant_id));
+        }
+        let program = command
+            .program
+            .map(normalize_program)
+            .ok_or_else(|| TenantError::InvalidInput("A maintenance program is required".into()))?;
+        validate_program(&program).map_err(TenantError::InvalidInput)?;
+
+        Ok(vec![TenantEvent::MaintenanceProgramConfigured(
+            MaintenanceProgramConfigured {
+                tenant_id: self.id.clone(),
+                program: Some(program),
+                timestamp: services.clock.timestamp(),
+            },
+        )])
+    }
 }
 
 #[cfg(test)]
@@ -145,21 +371,23 @@ mod tests {
             name: "Test VA".to_string(),
         });
 
-        let result = aggregate.handle(command.clone(), &()).await;
+        let result = aggregate.handle(command.clone(), &DomainServices::default()).await;
         assert!(result.is_ok());
 
         let events = result.unwrap();
         assert_eq!(events.len(), 1);
 
-        #[allow(clippy::match_single_binding)]
-        match &events[0] {
-            TenantEvent::Created(TenantCreated {
-                tenant_id, name, ..
-            }) => {
-                let TenantCommand::Create(command) = command;
+        match (&events[0], command) {
+            (
+                TenantEvent::Created(TenantCreated {
+                    tenant_id, name, ..
+                }),
+                TenantCommand::Create(command),
+            ) => {
                 assert_eq!(tenant_id, &command.tenant_id);
                 assert_eq!(name, &command.name);
             }
+            other => panic!("Expected TenantCreated, got {:?}", other),
         }
     }
 
@@ -178,7 +406,7 @@ mod tests {
             name: "New VA".to_string(),
         });
 
-        let result = aggregate.handle(command, &()).await;
+        let result = aggregate.handle(command, &DomainServices::default()).await;
         assert!(result.is_err());
         match result.err().unwrap() {
             TenantError::AlreadyExists(id) => assert_eq!(id, "tenant-123"),
@@ -195,7 +423,7 @@ mod tests {
             tenant_id: "".to_string(),
             name: "Test VA".to_string(),
         });
-        let result_no_id = aggregate.handle(command_no_id, &()).await;
+        let result_no_id = aggregate.handle(command_no_id, &DomainServices::default()).await;
         assert!(result_no_id.is_err());
         match result_no_id.err().unwrap() {
             TenantError::InvalidInput(msg) => assert!(msg.contains("ID cannot be empty")),
@@ -207,7 +435,7 @@ mod tests {
             tenant_id: "tenant-123".to_string(),
             name: "".to_string(),
         });
-        let result_no_name = aggregate.handle(command_no_name, &()).await;
+        let result_no_name = aggregate.handle(command_no_name, &DomainServices::default()).await;
         assert!(result_no_name.is_err());
         match result_no_name.err().unwrap() {
             TenantError::InvalidInput(msg) => assert!(msg.contains("name cannot be empty")),
@@ -231,4 +459,207 @@ mod tests {
         assert_eq!(aggregate.id, event.tenant_id);
         assert_eq!(aggregate.name, event.name);
     }
+
+    fn configure(rules: Option<PirepRules>) -> TenantCommand {
+        TenantCommand::ConfigurePirepRules(ConfigurePirepRules {
+            tenant_id: "tenant-123".to_string(),
+            rules,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_configure_pirep_rules() {
+        let mut aggregate = Tenant::default();
+        let rules = PirepRules {
+            auto_accept: true,
+            max_landing_rate_fpm: Some(600.0),
+            ..Default::default()
+        };
+
+        // The tenant has to exist first
+        match aggregate
+            .handle(configure(Some(rules.clone())), &DomainServices::default())
+            .await
+        {
+            Err(TenantError::NotFound(id)) => assert_eq!(id, "tenant-123"),
+            other => panic!("Expected NotFound, got {:?}", other),
+        }
+
+        aggregate.apply(TenantEvent::Created(TenantCreated {
+            tenant_id: "tenant-123".to_string(),
+            name: "Test VA".to_string(),
+            timestamp: "0".to_string(),
+        }));
+        assert!(aggregate.pirep_rules().is_none());
+
+        let events = aggregate
+            .handle(configure(Some(rules.clone())), &DomainServices::default())
+            .await
+            .unwrap();
+        for event in events {
+            let decoded =
+                TenantEvent::decode_payload(&event.event_type(), &event.encode_payload()).unwrap();
+            assert_eq!(decoded, event);
+            aggregate.apply(event);
+        }
+        assert_eq!(aggregate.pirep_rules(), Some(&rules));
+    }
+
+    #[tokio::test]
+    async fn test_configure_pirep_rules_invalid() {
+        let mut aggregate = Tenant::default();
+        aggregate.apply(TenantEvent::Created(TenantCreated {
+            tenant_id: "tenant-123".to_string(),
+            name: "Test VA".to_string(),
+            timestamp: "0".to_string(),
+        }));
+
+        let inverted = PirepRules {
+            min_block_speed_kts: Some(500.0),
+            max_block_speed_kts: Some(100.0),
+            ..Default::default()
+        };
+        for command in [configure(None), configure(Some(inverted))] {
+            assert!(matches!(
+                aggregate.handle(command, &DomainServices::default()).await,
+                Err(TenantError::InvalidInput(_))
+            ));
+        }
+    }
+
+    fn define(icao_type: &str, name: &str) -> TenantCommand {
+        TenantCommand::DefineAircraftType(DefineAircraftType {
+            tenant_id: "tenant-123".to_string(),
+            aircraft_type: Some(AircraftType {
+                icao_type: icao_type.to_string(),
+                name: name.to_string(),
+                manufacturer: "Cessna".to_string(),
+                category: "Turboprop".to_string(),
+                cruise_speed_kts: 195.0,
+                range_nm: 900.0,
+                seats: 0,
+                mtow_kg: 3995.0,
+            }),
+        })
+    }
+
+    fn remove(icao_type: &str) -> TenantCommand {
+        TenantCommand::RemoveAircraftType(RemoveAircraftType {
+            tenant_id: "tenant-123".to_string(),
+            icao_type: icao_type.to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_define_and_remove_aircraft_types() {
+        let mut aggregate = Tenant::default();
+        assert!(matches!(
+            aggregate
+                .handle(define("C208", "Cargomaster"), &DomainServices::default())
+                .await,
+            Err(TenantError::NotFound(_))
+        ));
+        aggregate.apply(TenantEvent::Created(TenantCreated {
+            tenant_id: "tenant-123".to_string(),
+            name: "Test VA".to_string(),
+            timestamp: "0".to_string(),
+        }));
+
+        // Overrides the embedded Caravan for this tenant only
+        for command in [define("c208", "Cargomaster"), define("ZZ01", "Homebuilt")] {
+            for event in aggregate
+                .handle(command, &DomainServices::default())
+                .await
+                .unwrap()
+            {
+                let decoded =
+                    TenantEvent::decode_payload(&event.event_type(), &event.encode_payload())
+                        .unwrap();
+                assert_eq!(decoded, event);
+                aggregate.apply(event);
+            }
+        }
+        assert_eq!(aggregate.aircraft_types().count(), 2);
+        let catalog = aggregate.aircraft_catalog();
+        assert_eq!(catalog.get("C208").unwrap().name, "Cargomaster");
+        assert_eq!(catalog.get("C208").unwrap().category, "turboprop");
+        assert_eq!(catalog.len(), AircraftCatalog::embedded().len() + 1);
+
+        let events = aggregate
+            .handle(remove("c208"), &DomainServices::default())
+            .await
+            .unwrap();
+        for event in events {
+            aggregate.apply(event);
+        }
+        assert_eq!(aggregate.aircraft_catalog().get("C208").unwrap().seats, 12);
+
+        // Embedded types cannot be removed
+        match aggregate
+            .handle(remove("B738"), &DomainServices::default())
+            .await
+        {
+            Err(TenantError::AircraftTypeNotFound(icao_type)) => assert_eq!(icao_type, "B738"),
+            other => panic!("Expected AircraftTypeNotFound, got {:?}", other),
+        }
+        assert!(matches!(
+            aggregate
+                .handle(define("C-208", "Caravan"), &DomainServices::default())
+                .await,
+            Err(TenantError::InvalidInput(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_configure_maintenance_program() {
+        use crate::domain::maintenance::InspectionInterval;
+
+        let mut aggregate = Tenant::default();
+        aggregate.apply(TenantEvent::Created(TenantCreated {
+            tenant_id: "tenant-123".to_string(),
+            name: "Test VA".to_string(),
+            timestamp: "0".to_string(),
+        }));
+        let configure = |program: MaintenanceProgram| {
+            TenantCommand::ConfigureMaintenanceProgram(ConfigureMaintenanceProgram {
+                tenant_id: "tenant-123".to_string(),
+                program: Some(program),
+            })
+        };
+        let a_check = InspectionInterval {
+            name: " A-check ".to_string(),
+            every_hours: Some(500.0),
+            every_cycles: None,
+            icao_types: vec!["b738".to_string()],
+        };
+
+        let events = aggregate
+            .handle(
+                configure(MaintenanceProgram {
+                    inspections: vec![a_check.clone()],
+                }),
+                &DomainServices::default(),
+            )
+            .await
+            .unwrap();
+        for event in events {
+            let decoded =
+                TenantEvent::decode_payload(&event.event_type(), &event.encode_payload()).unwrap();
+            assert_eq!(decoded, event);
+            aggregate.apply(event);
+        }
+        let program = aggregate.maintenance_program().unwrap();
+        assert_eq!(program.inspections[0].name, "A-check");
+        assert_eq!(program.inspections[0].icao_types, vec!["B738"]);
+
+        let twice = MaintenanceProgram {
+            inspections: vec![a_check.clone(), a_check],
+        };
+        assert!(matches!(
+            aggregate
+                .handle(configure(twice), &DomainServices::default())
+                .await,
+            Err(TenantError::InvalidInput(_))
+        ));
+    }
 }
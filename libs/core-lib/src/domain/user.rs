@@ -1,12 +1,14 @@
+use crate::framework::{EventCodec, TenantScoped};
+use crate::services::DomainServices;
 use crate::{Command, CoreError, DomainEvent, Event};
 use cqrs_es::Aggregate;
+use prost::Message;
 pub use proto::user::user_command::UserCommand;
 use proto::user::{
     ApiKeyGenerated, ApiKeyRevoked, ChangePassword, GenerateApiKey, LoginUser, PasswordChanged,
     RegisterUser, RevokeApiKey, Role, UserLoggedIn, UserRegistered,
 };
 use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 // --- User Aggregate ---
 
@@ -59,6 +61,37 @@ impl DomainEvent for UserEvent {
     }
 }
 
+impl EventCodec for UserEvent {
+    fn encode_payload(&self) -> Vec<u8> {
+        match self {
+            UserEvent::Registered(e) => e.encode_to_vec(),
+            UserEvent::PasswordChanged(e) => e.encode_to_vec(),
+            UserEvent::ApiKeyGenerated(e) => e.encode_to_vec(),
+            UserEvent::ApiKeyRevoked(e) => e.encode_to_vec(),
+            UserEvent::LoggedIn(e) => e.encode_to_vec(),
+        }
+    }
+
+    fn decode_payload(event_type: &str, payload: &[u8]) -> Result<Self, CoreError> {
+        let decoded = match event_type {
+            "UserRegistered" => UserRegistered::decode(payload).map(UserEvent::Registered),
+            "PasswordChanged" => PasswordChanged::decode(payload).map(UserEvent::PasswordChanged),
+            "ApiKeyGenerated" => ApiKeyGenerated::decode(payload).map(UserEvent::ApiKeyGenerated),
+            "ApiKeyRevoked" => ApiKeyRevoked::decode(payload).map(UserEvent::ApiKeyRevoked),
+            "UserLoggedIn" => UserLoggedIn::decode(payload).map(UserEvent::LoggedIn),
+            other => {
+                return Err(CoreError::Deserialization(format!(
+                    "Unknown user event type: {}",
+                    other
+                )))
+            }
+        };
+        decoded.map_err(|e| {
+            CoreError::Deserialization(format!("Failed to decode {}: {}", event_type, e))
+        })
+    }
+}
+
 impl Event for UserRegistered {}
 impl Event for PasswordChanged {}
 impl Event for ApiKeyGenerated {}
@@ -97,7 +130,7 @@ impl Aggregate for User {
     type Command = UserCommand;
     type Event = UserEvent;
     type Error = UserError;
-    type Services = ();
+    type Services = DomainServices;
 
     fn aggregate_id(&self) -> &str {
         &self.id
@@ -151,7 +184,7 @@ impl Aggregate for User {
     async fn handle(
         &self,
         command: Self::Command,
-        _service: &Self::Services,
+        services: &Self::Services,
     ) -> Result<Vec<Self::Event>, Self::Error> {
         // Basic validation: Ensure aggregate exists for commands other than Register
         if self.version == 0 && !matches!(command, UserCommand::Register(_)) {
@@ -168,15 +201,21 @@ impl Aggregate for User {
         }
 
         match command {
-            UserCommand::Register(cmd) => self.handle_register(cmd).await,
-            UserCommand::ChangePassword(cmd) => self.handle_change_password(cmd).await,
-            UserCommand::GenerateApiKey(cmd) => self.handle_generate_api_key(cmd).await,
-            UserCommand::RevokeApiKey(cmd) => self.handle_revoke_api_key(cmd).await,
-            UserCommand::Login(cmd) => self.handle_login(cmd).await,
+            UserCommand::Register(cmd) => self.handle_register(cmd, services).await,
+            UserCommand::ChangePassword(cmd) => self.handle_change_password(cmd, services).await,
+            UserCommand::GenerateApiKey(cmd) => self.handle_generate_api_key(cmd, services).await,
+            UserCommand::RevokeApiKey(cmd) => self.handle_revoke_api_key(cmd, services).await,
+            UserCommand::Login(cmd) => self.handle_login(cmd, services).await,
         }
     }
 }
 
+impl TenantScoped for User {
+    fn owning_tenant(&self) -> Option<&str> {
+        self.tenant_id.as_deref()
+    }
+}
+
 impl User {
     // --- Public Getters ---
     pub fn tenant_id(&self) -> Option<&String> {
@@ -194,7 +233,11 @@ impl User {
 
     // --- Command Handlers ---
 
-    async fn handle_register(&self, command: RegisterUser) -> Result<Vec<UserEvent>, UserError> {
+    async fn handle_register(
+        &self,
+        command: RegisterUser,
+        services: &DomainServices,
+    ) -> Result<Vec<UserEvent>, UserError> {
         if self.version > 0 {
             return Err(UserError::AlreadyExists(self.id.clone()));
         }
@@ -218,7 +261,7 @@ impl User {
             ));
         }
 
-        let timestamp = generate_timestamp();
+        let timestamp = services.clock.timestamp();
         let event = UserRegistered {
             user_id: command.user_id,
             username: command.username,
@@ -240,6 +283,7 @@ impl User {
     async fn handle_change_password(
         &self,
         command: ChangePassword,
+        services: &DomainServices,
     ) -> Result<Vec<UserEvent>, UserError> {
         if command.user_id != self.id {
             return Err(UserError::NotFound(command.user_id)); // Or Unauthorized
@@ -250,7 +294,7 @@ impl User {
             ));
         }
 
-        let timestamp = generate_timestamp();
+        let timestamp = services.clock.timestamp();
         let event = PasswordChanged {
             user_id: command.user_id,
             timestamp,
@@ -264,6 +308,7 @@ impl User {
     async fn handle_generate_api_key(
         &self,
         command: GenerateApiKey,
+        services: &DomainServices,
     ) -> Result<Vec<UserEvent>, UserError> {
         if command.user_id != self.id {
             return Err(UserError::NotFound(command.user_id)); // Or Unauthorized
@@ -276,7 +321,7 @@ impl User {
             ));
         }
 
-        let timestamp = generate_timestamp();
+        let timestamp = services.clock.timestamp();
         let event = ApiKeyGenerated {
             user_id: command.user_id,
             key_id: command.key_id, // Use key_id from command
@@ -291,6 +336,7 @@ impl User {
     async fn handle_login(
         &self,
         _command: LoginUser, // Prefixed with underscore as it's currently unused in this placeholder
+        services: &DomainServices,
     ) -> Result<Vec<UserEvent>, UserError> {
         // !!! SECURITY WARNING !!!
         // Aggregates should typically NOT handle plain text passwords.
@@ -306,7 +352,7 @@ impl User {
         //     return Err(UserError::InvalidPassword);
         // }
 
-        let timestamp = generate_timestamp();
+        let timestamp = services.clock.timestamp();
         let event = UserLoggedIn {
             user_id: self.id.clone(), // Need the user ID
             timestamp,
@@ -318,6 +364,7 @@ impl User {
     async fn handle_revoke_api_key(
         &self,
         command: RevokeApiKey,
+        services: &DomainServices,
     ) -> Result<Vec<UserEvent>, UserError> {
         if command.user_id != self.id {
             return Err(UserError::NotFound(command.user_id)); // Or Unauthorized
@@ -326,7 +373,7 @@ impl User {
             return Err(UserError::ApiKeyNotFound(command.key_id));
         }
 
-        let timestamp = generate_timestamp();
+        let timestamp = services.clock.timestamp();
         let event = ApiKeyRevoked {
             user_id: command.user_id,
             key_id: command.key_id,
@@ -337,18 +384,11 @@ impl User {
     }
 }
 
-// Helper function for timestamps
-fn generate_timestamp() -> String {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_secs().to_string())
-        .unwrap_or_default()
-}
-
 // --- Tests ---
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::{TimeZone, Utc};
     use cqrs_es::Aggregate;
     use proto::user::Role;
 
@@ -364,7 +404,8 @@ mod tests {
             tenant_id: Some("tenant-1".to_string()),
         });
 
-        let result = aggregate.handle(command, &()).await;
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let result = aggregate.handle(command, &DomainServices::fixed(now)).await;
         assert!(result.is_ok());
         let events = result.unwrap();
         assert_eq!(events.len(), 1);
@@ -376,8 +417,10 @@ mod tests {
                 email,
                 role,
                 tenant_id,
+                timestamp,
                 ..
             }) => {
+                assert_eq!(timestamp, "2024-05-01T12:00:00.000Z");
                 assert_eq!(user_id, "user-1");
                 assert_eq!(username, "testuser");
                 assert_eq!(email, "test@example.com");
@@ -412,7 +455,7 @@ mod tests {
             tenant_id: Some("tenant-1".to_string()),
         });
 
-        let result = aggregate.handle(command, &()).await;
+        let result = aggregate.handle(command, &DomainServices::default()).await;
         assert!(result.is_err());
         match result.err().unwrap() {
             UserError::AlreadyExists(id) => assert_eq!(id, "user-1"),
@@ -432,7 +475,7 @@ mod tests {
             tenant_id: None,
         });
 
-        let result = aggregate.handle(command, &()).await;
+        let result = aggregate.handle(command, &DomainServices::default()).await;
         assert!(result.is_err());
         assert!(matches!(result.err().unwrap(), UserError::TenantIdRequired));
     }
@@ -449,7 +492,7 @@ mod tests {
             tenant_id: Some("tenant-1".to_string()), // PlatformAdmin should NOT have tenant_id
         });
 
-        let result = aggregate.handle(command, &()).await;
+        let result = aggregate.handle(command, &DomainServices::default()).await;
         assert!(result.is_err());
         match result.err().unwrap() {
             UserError::InvalidRole(msg) => assert!(msg.contains("PlatformAdmin cannot belong")),
@@ -476,7 +519,7 @@ mod tests {
             new_password_hash: "new_hashed_password".to_string(),
         });
 
-        let result = aggregate.handle(command, &()).await;
+        let result = aggregate.handle(command, &DomainServices::default()).await;
         assert!(result.is_ok());
         let events = result.unwrap();
         assert_eq!(events.len(), 1);
@@ -512,7 +555,7 @@ mod tests {
             api_key_hash: "test-hash".to_string(), // Added placeholder
         });
 
-        let result = aggregate.handle(command, &()).await;
+        let result = aggregate.handle(command, &DomainServices::default()).await;
         assert!(result.is_ok());
         let events = result.unwrap();
         assert_eq!(events.len(), 1);
@@ -571,7 +614,7 @@ mod tests {
             key_id: "key-to-revoke".to_string(),
         });
 
-        let result = aggregate.handle(command, &()).await;
+        let result = aggregate.handle(command, &DomainServices::default()).await;
         assert!(result.is_ok());
         let events = result.unwrap();
         assert_eq!(events.len(), 1);
@@ -613,7 +656,7 @@ mod tests {
             key_id: "non-existent-key".to_string(),
         });
 
-        let result = aggregate.handle(command, &()).await;
+        let result = aggregate.handle(command, &DomainServices::default()).await;
         assert!(result.is_err());
         match result.err().unwrap() {
             UserError::ApiKeyNotFound(key_id) => assert_eq!(key_id, "non-existent-key"),
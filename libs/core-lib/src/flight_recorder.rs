@@ -0,0 +1,415 @@
+use crate::domain::aircraft::{Aircraft, AircraftCommand, AircraftStatus};
+use crate::domain::pirep::{Pirep, PirepStatus};
+use crate::domain::tenant::Tenant;
+use crate::framework::{aggregate_cqrs, tenant_metadata, AggregateCqrs, AggregateRepository};
+use crate::services::DomainServices;
+use crate::{CoreError, EventPublisher, HandlerOutcome, MessageHandler, ReceivedEvent, Repository};
+use async_trait::async_trait;
+use prost::Message;
+use proto::aircraft::RecordAircraftFlight;
+use proto::pirep::{PirepApproved, PirepValidated};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::{error, warn};
+
+/// Attempts at recording a PIREP's flight before its event is rejected (dead-lettered
+/// where the bus has a dead-letter exchange). RabbitMQ redelivers requeued messages
+/// without limit, so a failure that does not pass would otherwise loop forever.
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// Adds approved PIREPs to the airframe hours and cycles of the fleet aircraft they
+/// were flown with, which may ground the aircraft for maintenance.
+///
+/// Subscribe it to the PIREP events. A PIREP counts once it is approved by a reviewer
+/// (`PirepApproved`) or automatically (`PirepValidated` with `auto_accepted`). Failures
+/// that may pass, such as a concurrent change to the aircraft, are requeued up to
+/// [`MAX_ATTEMPTS`] times; recording a PIREP again changes nothing, so redeliveries are
+/// harmless.
+pub struct FlightRecorder {
+    pireps: AggregateRepository<Pirep>,
+    tenants: AggregateRepository<Tenant>,
+    aircraft: AggregateRepository<Aircraft>,
+    fleet: AggregateCqrs<Aircraft>,
+    // Failed attempts of the PIREPs being retried
+    attempts: Mutex<HashMap<String, u32>>,
+}
+
+impl FlightRecorder {
+    pub fn new(
+        pirep_repository: Arc<dyn Repository>,
+        tenant_repository: Arc<dyn Repository>,
+        aircraft_repository: Arc<dyn Repository>,
+        event_publisher: Arc<dyn EventPublisher>,
+        services: DomainServices,
+    ) -> Self {
+        Self {
+            pireps: AggregateRepository::new(pirep_repository, services.clock.clone()),
+            tenants: AggregateRepository::new(tenant_repository, services.clock.clone()),
+            aircraft: AggregateRepository::new(aircraft_repository.clone(), services.clock.clone()),
+            fleet: aggregate_cqrs(aircraft_repository, event_publisher, services),
+            attempts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records the flight of an approved PIREP. PIREPs that are not approved, or that
+    /// name an aircraft outside the tenant's active fleet, are left alone.
+    pub async fn record(&self, pirep_id: &str) -> Result<(), CoreError> {
+        let pirep = self.pireps.load_existing(pirep_id).await?.aggregate;
+        if pirep.status() != PirepStatus::Approved {
+            return Ok(());
+        }
+        let aircraft = self.aircraft.load(pirep.aircraft_id()).await?.aggregate;
+        if aircraft.tenant_id() != pirep.tenant_id() || aircraft.status() == AircraftStatus::Retired
+        {
+            return Ok(());
+        }
+        let tenant = self.tenants.load(pirep.tenant_id()).await?.aggregate;
+
+        let command = RecordAircraftFlight {
+            aircraft_id: aircraft.id().to_string(),
+            tenant_id: aircraft.tenant_id().to_string(),
+            pirep_id: pirep_id.to_string(),
+            flight_time_hours: pirep.flight_time_hours(),
+            program: tenant.maintenance_program().cloned(),
+        };
+        self.fleet
+            .execute_with_metadata(
+                aircraft.id(),
+                AircraftCommand::RecordFlight(command),
+                tenant_metadata(Some(aircraft.tenant_id())),
+            )
+            .await?;
+        Ok(())
+    }
+
+    // Counts a failed attempt; true once the PIREP has used up its attempts
+    fn exhausted(&self, pirep_id: &str) -> bool {
+        let mut attempts = self.attempts.lock().expect("attempts mutex poisoned");
+        let failed = attempts.entry(pirep_id.to_string()).or_default();
+        *failed += 1;
+        if *failed < MAX_ATTEMPTS {
+            return false;
+        }
+        attempts.remove(pirep_id);
+        true
+    }
+
+    fn settled(&self, pirep_id: &str) {
+        self.attempts
+            .lock()
+            .expect("attempts mutex poisoned")
+            .remove(pirep_id);
+    }
+}
+
+// The PIREP an event approves, if any
+fn approved_pirep(event: &ReceivedEvent) -> Result<Option<String>, prost::DecodeError> {
+    match event.event_type.as_str() {
+        "PirepApproved" => Ok(Some(
+            PirepApproved::decode(event.payload.as_slice())?.pirep_id,
+        )),
+        "PirepValidated" => {
+            let validated = PirepValidated::decode(event.payload.as_slice())?;
+            Ok(validated.auto_accepted.then_some(validated.pirep_id))
+        }
+        _ => Ok(None),
+    }
+}
+
+#[async_trait]
+impl MessageHandler for FlightRecorder {
+    async fn handle(&self, event: ReceivedEvent) -> HandlerOutcome {
+        let pirep_id = match approved_pirep(&event) {
+            Ok(Some(pirep_id)) => pirep_id,
+            Ok(None) => return HandlerOutcome::Ack,
+            Err(e) => {
+                error!(
+                    "Failed to decode {} on {}: {}",
+                    event.event_type, event.topic, e
+                );
+                return HandlerOutcome::Nack;
+            }
+        };
+        match self.record(&pirep_id).await {
+            Ok(()) => {
+                self.settled(&pirep_id);
+                HandlerOutcome::Ack
+            }
+            Err(e @ (CoreError::Validation(_) | CoreError::NotFound(_))) => {
+                self.settled(&pirep_id);
+                error!("Cannot record the flight of PIREP {}: {}", pirep_id, e);
+                HandlerOutcome::Nack
+            }
+            Err(e) if self.exhausted(&pirep_id) => {
+                error!(
+                    "Failed to record the flight of PIREP {} {} times, giving up: {}",
+                    pirep_id, MAX_ATTEMPTS, e
+                );
+                HandlerOutcome::Nack
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to record the flight of PIREP {}, retrying: {}",
+                    pirep_id, e
+                );
+                HandlerOutcome::Requeue
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::in_memory_event_bus::InMemoryEventBus;
+    use crate::adapters::in_memory_repository::InMemoryEventRepository;
+    use crate::domain::pirep::PirepCommand;
+    use crate::domain::tenant::TenantCommand;
+    use crate::{StoredEvent, TenantEventQuery};
+    use proto::aircraft::{AddAircraft, InspectionInterval, MaintenanceProgram};
+    use proto::pirep::{ApprovePirep, SubmitPirep};
+    use proto::tenant::{ConfigureMaintenanceProgram, CreateTenant};
+
+    struct Fixture {
+        recorder: FlightRecorder,
+        pireps: AggregateCqrs<Pirep>,
+        aircraft: AggregateRepository<Aircraft>,
+        pirep_repo: Arc<dyn Repository>,
+        aircraft_repo: Arc<dyn Repository>,
+        bus: Arc<dyn EventPublisher>,
+    }
+
+    // Tenant store that is down
+    struct UnavailableRepository;
+
+    #[async_trait]
+    impl Repository for UnavailableRepository {
+        async fn load(&self, _aggregate_id: &str) -> Result<Vec<StoredEvent>, CoreError> {
+            Err(CoreError::Internal("store unavailable".to_string()))
+        }
+
+        async fn save(
+            &self,
+            _aggregate_id: &str,
+            _expected_version: usize,
+            _events: &[StoredEvent],
+        ) -> Result<(), CoreError> {
+            Err(CoreError::Internal("store unavailable".to_string()))
+        }
+
+        async fn load_by_tenant(
+            &self,
+            _tenant_id: &str,
+            _query: &TenantEventQuery,
+        ) -> Result<Vec<StoredEvent>, CoreError> {
+            Err(CoreError::Internal("store unavailable".to_string()))
+        }
+    }
+
+    // Tenant-1 with an A-check every 5 hours and aircraft ac-1 in its fleet
+    async fn fixture() -> Fixture {
+        let pirep_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+        let tenant_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+        let aircraft_repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+        let bus: Arc<dyn EventPublisher> = Arc::new(InMemoryEventBus::default());
+        let services = DomainServices::default();
+
+        let tenants = aggregate_cqrs::<Tenant>(tenant_repo.clone(), bus.clone(), services.clone());
+        for command in [
+            TenantCommand::Create(CreateTenant {
+                tenant_id: "tenant-1".to_string(),
+                name: "Tenant 1".to_string(),
+            }),
+            TenantCommand::ConfigureMaintenanceProgram(ConfigureMaintenanceProgram {
+                tenant_id: "tenant-1".to_string(),
+                program: Some(MaintenanceProgram {
+                    inspections: vec![InspectionInterval {
+                        name: "A-check".to_string(),
+                        every_hours: Some(5.0),
+                        ..Default::default()
+                    }],
+                }),
+            }),
+        ] {
+            tenants
+                .execute_with_metadata("tenant-1", command, tenant_metadata(Some("tenant-1")))
+                .await
+                .unwrap();
+        }
+        aggregate_cqrs::<Aircraft>(aircraft_repo.clone(), bus.clone(), services.clone())
+            .execute_with_metadata(
+                "ac-1",
+                AircraftCommand::Add(AddAircraft {
+                    aircraft_id: "ac-1".to_string(),
+                    tenant_id: "tenant-1".to_string(),
+                    registration: "LN-ALB".to_string(),
+                    icao_type: "B738".to_string(),
+                    home_base_icao: "ENGM".to_string(),
+                    name: String::new(),
+                }),
+                tenant_metadata(Some("tenant-1")),
+            )
+            .await
+            .unwrap();
+
+        Fixture {
+            recorder: FlightRecorder::new(
+                pirep_repo.clone(),
+                tenant_repo,
+                aircraft_repo.clone(),
+                bus.clone(),
+                services.clone(),
+            ),
+            pireps: aggregate_cqrs(pirep_repo.clone(), bus.clone(), services.clone()),
+            aircraft: AggregateRepository::new(aircraft_repo.clone(), services.clock),
+            pirep_repo,
+            aircraft_repo,
+            bus,
+        }
+    }
+
+    impl Fixture {
+        async fn submit(&self, pirep_id: &str, aircraft_id: &str) {
+            let command = PirepCommand::Submit(SubmitPirep {
+                pirep_id: pirep_id.to_string(),
+                tenant_id: "tenant-1".to_string(),
+                user_id: "user-1".to_string(),
+                aircraft_id: aircraft_id.to_string(),
+                departure_icao: "ENGM".to_string(),
+                arrival_icao: "ENBR".to_string(),
+                flight_time_hours: 3.0,
+                ..Default::default()
+            });
+            self.pireps
+                .execute_with_metadata(pirep_id, command, tenant_metadata(Some("tenant-1")))
+                .await
+                .unwrap();
+        }
+
+        async fn approve(&self, pirep_id: &str) -> ReceivedEvent {
+            let command = PirepCommand::Approve(ApprovePirep {
+                pirep_id: pirep_id.to_string(),
+                reviewer_id: "user-2".to_string(),
+                comment: String::new(),
+            });
+            self.pireps
+                .execute_with_metadata(pirep_id, command, tenant_metadata(Some("tenant-1")))
+                .await
+                .unwrap();
+            approved(pirep_id)
+        }
+
+        async fn aircraft(&self) -> Aircraft {
+            self.aircraft.load("ac-1").await.unwrap().aggregate
+        }
+    }
+
+    fn approved(pirep_id: &str) -> ReceivedEvent {
+        ReceivedEvent {
+            topic: format!("pirep.{}", pirep_id),
+            event_type: "PirepApproved".to_string(),
+            payload: PirepApproved {
+                pirep_id: pirep_id.to_string(),
+                ..Default::default()
+            }
+            .encode_to_vec(),
+        }
+    }
+
+    fn validated(pirep_id: &str, auto_accepted: bool) -> ReceivedEvent {
+        ReceivedEvent {
+            topic: format!("pirep.{}", pirep_id),
+            event_type: "PirepValidated".to_string(),
+            payload: PirepValidated {
+                pirep_id: pirep_id.to_string(),
+                auto_accepted,
+                ..Default::default()
+            }
+            .encode_to_vec(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reviewed_approvals_are_recorded_once() {
+        let fixture = fixture().await;
+        fixture.submit("pirep-1", "ac-1").await;
+
+        // Still pending: nothing to record yet
+        let outcome = fixture.recorder.handle(validated("pirep-1", false)).await;
+        assert_eq!(outcome, HandlerOutcome::Ack);
+        let outcome = fixture.recorder.handle(approved("pirep-1")).await;
+        assert_eq!(outcome, HandlerOutcome::Ack);
+        assert_eq!(fixture.aircraft().await.cycles(), 0);
+
+        let event = fixture.approve("pirep-1").await;
+        assert_eq!(
+            fixture.recorder.handle(event.clone()).await,
+            HandlerOutcome::Ack
+        );
+        // Redelivered
+        assert_eq!(fixture.recorder.handle(event).await, HandlerOutcome::Ack);
+        let aircraft = fixture.aircraft().await;
+        assert_eq!(aircraft.cycles(), 1);
+        assert_eq!(aircraft.airframe_hours(), 3.0);
+        assert_eq!(aircraft.status(), AircraftStatus::Active);
+
+        // The second flight takes the airframe past the A-check interval
+        fixture.submit("pirep-2", "ac-1").await;
+        let event = fixture.approve("pirep-2").await;
+        assert_eq!(fixture.recorder.handle(event).await, HandlerOutcome::Ack);
+        let aircraft = fixture.aircraft().await;
+        assert_eq!(aircraft.cycles(), 2);
+        assert_eq!(aircraft.status(), AircraftStatus::Grounded);
+    }
+
+    #[tokio::test]
+    async fn test_flights_outside_the_fleet_are_skipped() {
+        let fixture = fixture().await;
+        fixture.submit("pirep-1", "ac-9").await;
+        let event = fixture.approve("pirep-1").await;
+        assert_eq!(fixture.recorder.handle(event).await, HandlerOutcome::Ack);
+        assert_eq!(fixture.aircraft().await.cycles(), 0);
+
+        // Unknown PIREPs and undecodable events are not retried
+        assert_eq!(
+            fixture.recorder.handle(approved("pirep-x")).await,
+            HandlerOutcome::Nack
+        );
+        let mut garbled = approved("pirep-1");
+        garbled.payload = vec![0xff, 0xff];
+        assert_eq!(fixture.recorder.handle(garbled).await, HandlerOutcome::Nack);
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_retried_a_limited_number_of_times() {
+        let fixture = fixture().await;
+        fixture.submit("pirep-1", "ac-1").await;
+        let event = fixture.approve("pirep-1").await;
+        let unavailable = FlightRecorder::new(
+            fixture.pirep_repo.clone(),
+            Arc::new(UnavailableRepository),
+            fixture.aircraft_repo.clone(),
+            fixture.bus.clone(),
+            DomainServices::default(),
+        );
+
+        for _ in 1..MAX_ATTEMPTS {
+            assert_eq!(
+                unavailable.handle(event.clone()).await,
+                HandlerOutcome::Requeue
+            );
+        }
+        // Rejected rather than requeued forever
+        assert_eq!(
+            unavailable.handle(event.clone()).await,
+            HandlerOutcome::Nack
+        );
+        // Retried afresh if the event comes back, e.g. moved back from the dead letters
+        assert_eq!(
+            unavailable.handle(event.clone()).await,
+            HandlerOutcome::Requeue
+        );
+        assert_eq!(fixture.recorder.handle(event).await, HandlerOutcome::Ack);
+        assert_eq!(fixture.aircraft().await.cycles(), 1);
+    }
+}
@@ -0,0 +1,450 @@
+use crate::services::{Clock, DomainServices};
+use crate::{CoreError, EventPublisher, OutgoingEvent, PersistedEventRepo, Repository};
+use async_trait::async_trait;
+use cqrs_es::persist::{EventStoreAggregateContext, PersistedEventStore, PersistenceError};
+use cqrs_es::{
+    Aggregate, AggregateError, Binarize, CqrsFramework, DomainEvent, EventEnvelope, EventStore,
+    Query,
+};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Metadata key under which the owning tenant of an event is recorded.
+pub const TENANT_ID_METADATA_KEY: &str = "tenant_id";
+
+// --- Event Encoding ---
+
+/// Protobuf encoding of an aggregate's event enum. The event type string
+/// (`DomainEvent::event_type`) selects the message to decode.
+pub trait EventCodec: DomainEvent + Sized {
+    fn encode_payload(&self) -> Vec<u8>;
+
+    /// Decodes a stored payload. Unknown event types and malformed payloads are
+    /// both reported as `CoreError::Deserialization`; events are never skipped.
+    fn decode_payload(event_type: &str, payload: &[u8]) -> Result<Self, CoreError>;
+}
+
+/// `Binarize` implementation for any event enum implementing [`EventCodec`].
+pub struct ProtoBinarizer<E> {
+    _event: PhantomData<fn() -> E>,
+}
+
+impl<E> Default for ProtoBinarizer<E> {
+    fn default() -> Self {
+        Self {
+            _event: PhantomData,
+        }
+    }
+}
+
+impl<E: EventCodec + 'static> Binarize<E> for ProtoBinarizer<E> {
+    fn event_to_bytes(&self, event: &E) -> Result<Vec<u8>, PersistenceError> {
+        Ok(event.encode_payload())
+    }
+
+    fn event_from_bytes(&self, event_type: &str, bytes: &[u8]) -> Result<E, PersistenceError> {
+        E::decode_payload(event_type, bytes)
+            .map_err(|e| PersistenceError::DeserializationError(Box::new(e)))
+    }
+}
+
+// --- Tenant Scoping ---
+
+/// Aggregates that belong to a tenant. Used to stamp committed events with
+/// their tenant so the event store can be queried per tenant.
+pub trait TenantScoped {
+    /// Tenant owning this aggregate, or `None` for platform-level aggregates
+    /// and for aggregates that have not been created yet.
+    fn owning_tenant(&self) -> Option<&str>;
+}
+
+/// Builds command metadata carrying the owning tenant. Needed for creation
+/// commands, where the aggregate does not know its tenant yet.
+pub fn tenant_metadata(tenant_id: Option<&str>) -> HashMap<String, String> {
+    tenant_id
+        .map(|tenant_id| {
+            HashMap::from([(TENANT_ID_METADATA_KEY.to_string(), tenant_id.to_string())])
+        })
+        .unwrap_or_default()
+}
+
+/// Event store decorator that records the aggregate's tenant in the metadata
+/// of every committed event, unless the caller already supplied one.
+pub struct TenantScopedEventStore<A, ES> {
+    inner: ES,
+    _aggregate: PhantomData<fn() -> A>,
+}
+
+impl<A, ES> TenantScopedEventStore<A, ES> {
+    pub fn new(inner: ES) -> Self {
+        Self {
+            inner,
+            _aggregate: PhantomData,
+        }
+    }
+}
+
+impl<A, ES> EventStore<A> for TenantScopedEventStore<A, ES>
+where
+    A: Aggregate + TenantScoped,
+    ES: EventStore<A>,
+    ES::AC: Send,
+{
+    type AC = ES::AC;
+
+    async fn load_events(
+        &self,
+        aggregate_id: &str,
+    ) -> Result<Vec<EventEnvelope<A>>, AggregateError<A::Error>> {
+        self.inner.load_events(aggregate_id).await
+    }
+
+    async fn load_aggregate(
+        &self,
+        aggregate_id: &str,
+    ) -> Result<Self::AC, AggregateError<A::Error>> {
+        self.inner.load_aggregate(aggregate_id).await
+    }
+
+    async fn commit(
+        &self,
+        events: Vec<A::Event>,
+        context: Self::AC,
+        mut metadata: HashMap<String, String>,
+    ) -> Result<Vec<EventEnvelope<A>>, AggregateError<A::Error>> {
+        if let Some(tenant_id) = cqrs_es::AggregateContext::aggregate(&context).owning_tenant() {
+            metadata
+                .entry(TENANT_ID_METADATA_KEY.to_string())
+                .or_insert_with(|| tenant_id.to_string());
+        }
+        self.inner.commit(events, context, metadata).await
+    }
+}
+
+// --- Publishing ---
+
+/// Query that forwards committed events to the message bus on topic
+/// `{aggregate type}.{aggregate id}` (e.g. `user.42`). Publishing happens after
+/// the events are stored, so failures are logged rather than returned.
+pub struct PublishingQuery<A> {
+    publisher: Arc<dyn EventPublisher>,
+    _aggregate: PhantomData<fn() -> A>,
+}
+
+impl<A> PublishingQuery<A> {
+    pub fn new(publisher: Arc<dyn EventPublisher>) -> Self {
+        Self {
+            publisher,
+            _aggregate: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<A> Query<A> for PublishingQuery<A>
+where
+    A: Aggregate,
+    A::Event: EventCodec,
+{
+    async fn dispatch(&self, aggregate_id: &str, events: &[EventEnvelope<A>]) {
+        let topic = format!("{}.{}", A::TYPE, aggregate_id);
+        let batch: Vec<OutgoingEvent> = events
+            .iter()
+            .map(|envelope| {
+                OutgoingEvent::new(
+                    topic.clone(),
+                    envelope.payload.event_type(),
+                    envelope.payload.encode_payload(),
+                )
+            })
+            .collect();
+        if let Err(e) = self.publisher.publish_batch(&batch).await {
+            tracing::error!(
+                "Failed to publish {} event(s) (sequences {}..={}) to {}: {}",
+                events.len(),
+                events.first().map_or(0, |envelope| envelope.sequence),
+                events.last().map_or(0, |envelope| envelope.sequence),
+                topic,
+                e
+            );
+        }
+    }
+}
+
+// --- Framework Wiring ---
+
+/// Event store used for every aggregate: protobuf payloads on top of the
+/// `Repository` port, with tenant stamping.
+pub type AggregateStore<A> = TenantScopedEventStore<
+    A,
+    PersistedEventStore<
+        PersistedEventRepo<Arc<dyn Repository>>,
+        A,
+        ProtoBinarizer<<A as Aggregate>::Event>,
+    >,
+>;
+
+/// The command pipeline for an aggregate: load, handle, save, publish.
+pub type AggregateCqrs<A> = CqrsFramework<A, AggregateStore<A>>;
+
+pub fn aggregate_store<A>(
+    repository: Arc<dyn Repository>,
+    clock: Arc<dyn Clock>,
+) -> AggregateStore<A>
+where
+    A: Aggregate + TenantScoped + Send + Sync,
+    A::Event: EventCodec + 'static,
+{
+    TenantScopedEventStore::new(PersistedEventStore::new_event_store(
+        PersistedEventRepo::new_event_repo(repository, clock),
+        ProtoBinarizer::default(),
+    ))
+}
+
+pub fn aggregate_cqrs<A>(
+    repository: Arc<dyn Repository>,
+    publisher: Arc<dyn EventPublisher>,
+    services: DomainServices,
+) -> AggregateCqrs<A>
+where
+    A: Aggregate<Services = DomainServices> + TenantScoped + Send + Sync + 'static,
+    A::Event: EventCodec + 'static,
+{
+    let store = aggregate_store::<A>(repository, services.clock.clone());
+    let queries: Vec<Box<dyn Query<A>>> = vec![Box::new(PublishingQuery::<A>::new(publisher))];
+    CqrsFramework::new(store, queries, services)
+}
+
+// --- Aggregate Repository ---
+
+/// An aggregate hydrated from its events, together with the sequence of its
+/// last event (`current_sequence`, 0 for an aggregate without events).
+pub type LoadedAggregate<A> = EventStoreAggregateContext<A>;
+
+/// Typed access to a single aggregate type for code that needs aggregate state
+/// outside of a command (authorization checks, cache enrichment, ...).
+///
+/// Uses the same store as [`aggregate_cqrs`], so events are decoded with the
+/// aggregate's [`EventCodec`] (undecodable events are errors, never skipped)
+/// and new events are saved after the loaded sequence, stamped with the tenant.
+pub struct AggregateRepository<A>
+where
+    A: Aggregate + TenantScoped + Send + Sync,
+    A::Event: EventCodec + 'static,
+{
+    store: AggregateStore<A>,
+}
+
+impl<A> AggregateRepository<A>
+where
+    A: Aggregate + TenantScoped + Send + Sync,
+    A::Event: EventCodec + 'static,
+    A::Error: Into<CoreError>,
+{
+    pub fn new(repository: Arc<dyn Repository>, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            store: aggregate_store(repository, clock),
+        }
+    }
+
+    /// Loads the aggregate. An unknown ID yields the default aggregate at sequence 0.
+    pub async fn load(&self, aggregate_id: &str) -> Result<LoadedAggregate<A>, CoreError> {
+        Ok(self.store.load_aggregate(aggregate_id).await?)
+    }
+
+    /// Loads the aggregate, failing with `CoreError::NotFound` if it has no events.
+    pub async fn load_existing(&self, aggregate_id: &str) -> Result<LoadedAggregate<A>, CoreError> {
+        let loaded = self.load(aggregate_id).await?;
+        if loaded.current_sequence == 0 {
+            return Err(CoreError::NotFound(format!(
+                "{} not found: {}",
+                A::TYPE,
+                aggregate_id
+            )));
+        }
+        Ok(loaded)
+    }
+
+    /// Saves `events` produced against `loaded`, expecting no other writer to
+    /// have appended since it was loaded (`CoreError::Concurrency` otherwise).
+    pub async fn save(
+        &self,
+        loaded: LoadedAggregate<A>,
+        events: Vec<A::Event>,
+        metadata: HashMap<String, String>,
+    ) -> Result<Vec<EventEnvelope<A>>, CoreError> {
+        Ok(self.store.commit(events, loaded, metadata).await?)
+    }
+}
+
+// --- Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::in_memory_event_bus::InMemoryEventBus;
+    use crate::adapters::in_memory_repository::InMemoryEventRepository;
+    use crate::domain::user::{User, UserCommand, UserEvent};
+    use crate::{EventSubscriber, StoredEvent};
+    use chrono::{TimeZone, Utc};
+    use proto::user::{ChangePassword, RegisterUser, Role};
+    use tokio::time::{timeout, Duration};
+
+    fn register(tenant_id: Option<&str>) -> UserCommand {
+        UserCommand::Register(RegisterUser {
+            user_id: "user-1".to_string(),
+            username: "testuser".to_string(),
+            email: "test@example.com".to_string(),
+            password_hash: "hashed_password".to_string(),
+            initial_role: Role::Pilot as i32,
+            tenant_id: tenant_id.map(str::to_string),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_execute_saves_stamps_tenant_and_publishes() {
+        let repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+        let bus = InMemoryEventBus::default();
+        let (tx, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let forward = move |event: crate::ReceivedEvent| {
+            let tx = tx.clone();
+            async move {
+                let _ = tx.send(event);
+                crate::HandlerOutcome::Ack
+            }
+        };
+        let _subscription = bus
+            .subscribe(
+                crate::Subscription::new("test", "user.user-1"),
+                Arc::new(forward),
+            )
+            .await
+            .unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let cqrs = aggregate_cqrs::<User>(repo.clone(), Arc::new(bus), DomainServices::fixed(now));
+
+        cqrs.execute_with_metadata(
+            "user-1",
+            register(Some("tenant-1")),
+            tenant_metadata(Some("tenant-1")),
+        )
+        .await
+        .unwrap();
+        // No explicit metadata: the tenant comes from the loaded aggregate.
+        cqrs.execute(
+            "user-1",
+            UserCommand::ChangePassword(ChangePassword {
+                user_id: "user-1".to_string(),
+                new_password_hash: "new_hash".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let stored = repo.load("user-1").await.unwrap();
+        assert_eq!(stored.len(), 2);
+        assert!(stored
+            .iter()
+            .all(|e| e.tenant_id.as_deref() == Some("tenant-1")));
+        assert!(stored.iter().all(|e| e.timestamp == now));
+        assert_eq!(stored[1].event.sequence, 2);
+
+        let message = timeout(Duration::from_millis(100), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(message.event_type, "UserRegistered");
+        assert!(matches!(
+            UserEvent::decode_payload(&message.event_type, &message.payload),
+            Ok(UserEvent::Registered(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_execute_maps_aggregate_errors_to_core_errors() {
+        let repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+        let cqrs = aggregate_cqrs::<User>(
+            repo,
+            Arc::new(InMemoryEventBus::default()),
+            DomainServices::default(),
+        );
+
+        cqrs.execute("user-1", register(Some("tenant-1")))
+            .await
+            .unwrap();
+        let err: CoreError = cqrs
+            .execute("user-1", register(Some("tenant-1")))
+            .await
+            .unwrap_err()
+            .into();
+        assert!(matches!(err, CoreError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_repository_hydrates_and_saves_after_loaded_sequence() {
+        let repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+        let services = DomainServices::default();
+        let users = AggregateRepository::<User>::new(repo.clone(), services.clock.clone());
+
+        let loaded = users.load("user-1").await.unwrap();
+        assert_eq!(loaded.current_sequence, 0);
+        let events = loaded
+            .aggregate
+            .handle(register(Some("tenant-1")), &services)
+            .await
+            .unwrap();
+        users
+            .save(loaded, events, tenant_metadata(Some("tenant-1")))
+            .await
+            .unwrap();
+
+        let loaded = users.load_existing("user-1").await.unwrap();
+        assert_eq!(loaded.current_sequence, 1);
+        assert_eq!(
+            loaded.aggregate.tenant_id().map(String::as_str),
+            Some("tenant-1")
+        );
+
+        // A second writer appending against the same loaded state conflicts.
+        let stale = users.load("user-1").await.unwrap();
+        let change = UserCommand::ChangePassword(ChangePassword {
+            user_id: "user-1".to_string(),
+            new_password_hash: "new_hash".to_string(),
+        });
+        let events = loaded
+            .aggregate
+            .handle(change.clone(), &services)
+            .await
+            .unwrap();
+        users.save(loaded, events, HashMap::new()).await.unwrap();
+        let events = stale.aggregate.handle(change, &services).await.unwrap();
+        let err = users.save(stale, events, HashMap::new()).await.unwrap_err();
+        assert!(matches!(err, CoreError::Concurrency { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_repository_rejects_missing_and_undecodable_streams() {
+        let repo: Arc<dyn Repository> = Arc::new(InMemoryEventRepository::default());
+        let users = AggregateRepository::<User>::new(repo.clone(), DomainServices::default().clock);
+
+        let result = users.load_existing("missing").await;
+        assert!(matches!(result, Err(CoreError::NotFound(_))));
+
+        let garbage = StoredEvent::new(
+            cqrs_es::persist::SerializedEvent::new(
+                "user-2".to_string(),
+                1,
+                User::TYPE.to_string(),
+                "UserRegistered".to_string(),
+                "1.0".to_string(),
+                vec![0xff, 0xff, 0xff],
+                vec![],
+            ),
+            None,
+            Utc::now(),
+        );
+        repo.save("user-2", 0, &[garbage]).await.unwrap();
+        let result = users.load("user-2").await;
+        assert!(matches!(result, Err(CoreError::Deserialization(_))));
+    }
+}
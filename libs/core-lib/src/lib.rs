@@ -1,11 +1,19 @@
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use cqrs_es::persist::{PersistedEventRepository, PersistenceError, SerializedEvent};
-use cqrs_es::{Aggregate, DomainEvent};
-use std::{error::Error as StdError, future::Future};
+use cqrs_es::{Aggregate, AggregateError, DomainEvent};
+use services::Clock;
+use std::{error::Error as StdError, future::Future, sync::Arc};
 
 // Declare modules
 pub mod adapters;
+#[cfg(any(test, feature = "conformance"))]
+pub mod conformance;
 pub mod domain;
+pub mod flight_recorder;
+pub mod framework;
+pub mod lock;
+pub mod services;
 
 // Define a common error type for the core library
 #[derive(thiserror::Error, Debug)]
@@ -66,6 +74,10 @@ impl From<domain::tenant::TenantError> for CoreError {
                 CoreError::Validation(format!("Tenant already exists: {}", id))
             }
             domain::tenant::TenantError::InvalidInput(msg) => CoreError::Validation(msg),
+            domain::tenant::TenantError::NotFound(id) => CoreError::NotFound(id),
+            domain::tenant::TenantError::AircraftTypeNotFound(icao_type) => {
+                CoreError::NotFound(icao_type)
+            }
         }
     }
 }
@@ -78,7 +90,69 @@ impl From<domain::pirep::PirepError> for CoreError {
             domain::pirep::PirepError::AlreadyExists(id) => {
                 CoreError::Validation(format!("PIREP already submitted: {}", id))
             }
+            domain::pirep::PirepError::NotFound(id) => CoreError::NotFound(id),
             domain::pirep::PirepError::InvalidInput(msg) => CoreError::Validation(msg),
+            err @ domain::pirep::PirepError::InvalidTransition { .. } => {
+                CoreError::Validation(err.to_string())
+            }
+            domain::pirep::PirepError::Forbidden(msg) => CoreError::Unauthorized(msg),
+        }
+    }
+}
+
+// Implement From<AircraftError> for CoreError
+impl From<domain::aircraft::AircraftError> for CoreError {
+    fn from(err: domain::aircraft::AircraftError) -> Self {
+        match err {
+            domain::aircraft::AircraftError::Core(ce) => ce,
+            domain::aircraft::AircraftError::AlreadyExists(id) => {
+                CoreError::Validation(format!("Aircraft already added: {}", id))
+            }
+            domain::aircraft::AircraftError::NotFound(id) => CoreError::NotFound(id),
+            domain::aircraft::AircraftError::InvalidInput(msg) => CoreError::Validation(msg),
+            err @ domain::aircraft::AircraftError::Retired(_) => {
+                CoreError::Validation(err.to_string())
+            }
+        }
+    }
+}
+
+impl From<domain::flight_log::FlightLogError> for CoreError {
+    fn from(err: domain::flight_log::FlightLogError) -> Self {
+        CoreError::Validation(err.to_string())
+    }
+}
+
+impl From<domain::simbrief::SimbriefError> for CoreError {
+    fn from(err: domain::simbrief::SimbriefError) -> Self {
+        CoreError::Validation(err.to_string())
+    }
+}
+
+// Convert errors coming out of CqrsFramework. Errors raised by our own ports travel
+// through cqrs_es boxed, so they are unwrapped back into the original CoreError.
+impl<E> From<AggregateError<E>> for CoreError
+where
+    E: StdError + Into<CoreError>,
+{
+    fn from(err: AggregateError<E>) -> Self {
+        let unbox = |boxed: Box<dyn StdError + Send + Sync>| match boxed.downcast::<CoreError>() {
+            Ok(core) => *core,
+            Err(other) => CoreError::Infrastructure(other),
+        };
+        match err {
+            AggregateError::UserError(e) => e.into(),
+            AggregateError::AggregateConflict => {
+                // Repositories report conflicts as CoreError::Concurrency, so this is unexpected
+                CoreError::Internal("Aggregate conflict".into())
+            }
+            AggregateError::DatabaseConnectionError(e) | AggregateError::UnexpectedError(e) => {
+                unbox(e)
+            }
+            AggregateError::DeserializationError(e) => match e.downcast::<CoreError>() {
+                Ok(core) => *core,
+                Err(other) => CoreError::Deserialization(other.to_string()),
+            },
         }
     }
 }
@@ -104,14 +178,15 @@ where
     R: Repository,
 {
     repo: R,
+    clock: Arc<dyn Clock>,
 }
 
 impl<R> PersistedEventRepo<R>
 where
     R: Repository,
 {
-    pub fn new_event_repo(repo: R) -> Self {
-        Self { repo }
+    pub fn new_event_repo(repo: R, clock: Arc<dyn Clock>) -> Self {
+        Self { repo, clock }
     }
 }
 
@@ -126,6 +201,7 @@ where
         self.repo
             .load(aggregate_id)
             .await
+            .map(|events| events.into_iter().map(|stored| stored.event).collect())
             .map_err(|e| PersistenceError::UnknownError(Box::new(e)))
     }
 
@@ -137,33 +213,160 @@ where
             return Ok(());
         }
         let aggregate_id = events[0].aggregate_id.clone();
-        let mut evts = vec![];
-        for e in events {
-            evts.push((e.event_type.clone(), e.payload.clone()));
-        }
+        // The framework numbers new events after the last loaded one
+        let expected_version = events[0].sequence.saturating_sub(1);
+        let timestamp = self.clock.now();
+        let evts: Vec<StoredEvent> = events
+            .iter()
+            .map(|e| StoredEvent::new(e.clone(), tenant_from_metadata(&e.metadata), timestamp))
+            .collect();
+        // CoreError is boxed as-is so callers can recover it (see From<AggregateError<E>>)
         self.repo
-            .save(&aggregate_id, 0, &evts)
+            .save(&aggregate_id, expected_version, &evts)
             .await
             .map_err(|e| PersistenceError::UnknownError(Box::new(e)))
     }
 }
+
+// Reads the tenant recorded by `framework::TenantScopedEventStore` from serialized metadata
+fn tenant_from_metadata(metadata: &[u8]) -> Option<String> {
+    if metadata.is_empty() {
+        return None;
+    }
+    serde_json::from_slice::<std::collections::HashMap<String, String>>(metadata)
+        .ok()?
+        .remove(framework::TENANT_ID_METADATA_KEY)
+}
 // Extra closing brace removed.
 
+/// An event as kept by the event store: the serialized event plus the
+/// store-level context that is not part of the payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredEvent {
+    pub event: SerializedEvent,
+    /// Tenant the event belongs to; `None` for platform-level aggregates.
+    pub tenant_id: Option<String>,
+    /// Time the event was recorded.
+    pub timestamp: DateTime<Utc>,
+}
+
+impl StoredEvent {
+    pub fn new(event: SerializedEvent, tenant_id: Option<String>, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            event,
+            tenant_id,
+            timestamp,
+        }
+    }
+}
+
+/// Filter for reading events across all aggregates of one tenant.
+#[derive(Debug, Clone, Default)]
+pub struct TenantEventQuery {
+    /// Only return events of this aggregate type (e.g. "pirep").
+    pub aggregate_type: Option<String>,
+    /// Only return events recorded at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Maximum number of events to return.
+    pub limit: Option<usize>,
+}
+
 // Port for interacting with the event store
 #[async_trait]
 pub trait Repository: Send + Sync {
-    /// Load events for a specific aggregate instance.
-    /// Returns raw event data (payload + metadata).
-    async fn load(&self, aggregate_id: &str) -> Result<Vec<SerializedEvent>, CoreError>;
+    /// Load events for a specific aggregate instance, ordered by sequence.
+    async fn load(&self, aggregate_id: &str) -> Result<Vec<StoredEvent>, CoreError>;
 
     /// Save new events for an aggregate instance, handling concurrency.
-    /// Takes raw event data (type string + payload bytes).
+    /// Events must belong to `aggregate_id` and carry consecutive sequence
+    /// numbers starting at `expected_version + 1`.
     async fn save(
         &self,
         aggregate_id: &str,
         expected_version: usize,
-        events: &[(String, Vec<u8>)], // Tuple of (event_type, payload)
+        events: &[StoredEvent],
     ) -> Result<(), CoreError>;
+
+    /// Load events of all aggregates belonging to a tenant, ordered by
+    /// timestamp (ties keep insertion order).
+    async fn load_by_tenant(
+        &self,
+        tenant_id: &str,
+        query: &TenantEventQuery,
+    ) -> Result<Vec<StoredEvent>, CoreError>;
+}
+
+#[async_trait]
+impl<T> Repository for Arc<T>
+where
+    T: Repository + ?Sized,
+{
+    async fn load(&self, aggregate_id: &str) -> Result<Vec<StoredEvent>, CoreError> {
+        (**self).load(aggregate_id).await
+    }
+
+    async fn save(
+        &self,
+        aggregate_id: &str,
+        expected_version: usize,
+        events: &[StoredEvent],
+    ) -> Result<(), CoreError> {
+        (**self).save(aggregate_id, expected_version, events).await
+    }
+
+    async fn load_by_tenant(
+        &self,
+        tenant_id: &str,
+        query: &TenantEventQuery,
+    ) -> Result<Vec<StoredEvent>, CoreError> {
+        (**self).load_by_tenant(tenant_id, query).await
+    }
+}
+
+/// Checks that `events` can be appended to `aggregate_id` at `expected_version`.
+/// Shared by the repository adapters so they agree on what a valid batch is.
+pub(crate) fn validate_event_batch(
+    aggregate_id: &str,
+    expected_version: usize,
+    events: &[StoredEvent],
+) -> Result<(), CoreError> {
+    for (expected_sequence, stored) in (expected_version + 1..).zip(events) {
+        if stored.event.aggregate_id != aggregate_id {
+            return Err(CoreError::Validation(format!(
+                "Event for aggregate {} cannot be saved to aggregate {}",
+                stored.event.aggregate_id, aggregate_id
+            )));
+        }
+        if stored.event.sequence != expected_sequence {
+            return Err(CoreError::Validation(format!(
+                "Event sequence {} does not follow expected version {} (expected {})",
+                stored.event.sequence, expected_version, expected_sequence
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// A message to publish with [`EventPublisher::publish_batch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutgoingEvent {
+    pub topic: String,
+    pub event_type: String,
+    pub payload: Vec<u8>,
+}
+
+impl OutgoingEvent {
+    pub fn new(
+        topic: impl Into<String>,
+        event_type: impl Into<String>,
+        payload: impl Into<Vec<u8>>,
+    ) -> Self {
+        Self {
+            topic: topic.into(),
+            event_type: event_type.into(),
+            payload: payload.into(),
+        }
+    }
 }
 
 // Port for publishing events to a message bus
@@ -175,23 +378,369 @@ pub trait EventPublisher: Send + Sync {
         event_type: &str,
         event_payload: &[u8],
     ) -> Result<(), CoreError>;
-    // Consider a batch publish method later
+
+    /// Publishes several messages in order, e.g. all events of one commit, and
+    /// returns once the transport has accepted all of them. The default
+    /// publishes them one at a time; adapters override it to pipeline.
+    async fn publish_batch(&self, events: &[OutgoingEvent]) -> Result<(), CoreError> {
+        for event in events {
+            self.publish(&event.topic, &event.event_type, &event.payload)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+// --- Subscriptions ---
+
+/// A message delivered to a subscriber.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedEvent {
+    pub topic: String,
+    /// Event type as given to `EventPublisher::publish`. Empty when the
+    /// transport does not carry it (Redis Pub/Sub only carries the payload).
+    pub event_type: String,
+    pub payload: Vec<u8>,
+}
+
+/// What the bus does with a delivered message once its handler returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandlerOutcome {
+    /// Processed; the message is removed.
+    Ack,
+    /// Failed permanently; the message is removed (dead-lettered where the
+    /// transport supports it) and never redelivered.
+    Nack,
+    /// Failed transiently; the message is delivered again.
+    Requeue,
+}
+
+// Handler invoked for every message matching a subscription
+#[async_trait]
+pub trait MessageHandler: Send + Sync {
+    async fn handle(&self, event: ReceivedEvent) -> HandlerOutcome;
+}
+
+// Closures can be used as handlers: `Arc::new(|event| async move { HandlerOutcome::Ack })`
+#[async_trait]
+impl<F, Fut> MessageHandler for F
+where
+    F: Fn(ReceivedEvent) -> Fut + Send + Sync,
+    Fut: Future<Output = HandlerOutcome> + Send,
+{
+    async fn handle(&self, event: ReceivedEvent) -> HandlerOutcome {
+        self(event).await
+    }
+}
+
+/// Which messages a subscriber receives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Subscription {
+    /// Durable name of the subscription. On transports with queues (RabbitMQ)
+    /// it names the queue, so subscribers sharing a name share the messages;
+    /// elsewhere it is only used for logging.
+    pub name: String,
+    /// Topic pattern in AMQP topic syntax: words are separated by `.`, `*`
+    /// matches exactly one word and `#` matches zero or more words.
+    pub pattern: String,
+}
+
+impl Subscription {
+    pub fn new(name: impl Into<String>, pattern: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            pattern: pattern.into(),
+        }
+    }
+
+    pub fn matches(&self, topic: &str) -> bool {
+        topic_matches(&self.pattern, topic)
+    }
+}
+
+/// Matches a topic against an AMQP-style pattern (see [`Subscription::pattern`]).
+pub fn topic_matches(pattern: &str, topic: &str) -> bool {
+    fn matches(pattern: &[&str], topic: &[&str]) -> bool {
+        match pattern.split_first() {
+            None => topic.is_empty(),
+            Some((&"#", rest)) => (0..=topic.len()).any(|skip| matches(rest, &topic[skip..])),
+            Some((&word, rest)) => match topic.split_first() {
+                Some((&first, topic_rest)) => {
+                    (word == "*" || word == first) && matches(rest, topic_rest)
+                }
+                None => false,
+            },
+        }
+    }
+    let pattern: Vec<&str> = pattern.split('.').collect();
+    let topic: Vec<&str> = topic.split('.').collect();
+    matches(&pattern, &topic)
+}
+
+/// A running subscription. Dropping the handle leaves the subscription
+/// running; use [`SubscriptionHandle::cancel`] to stop it.
+#[derive(Debug)]
+pub struct SubscriptionHandle {
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl SubscriptionHandle {
+    pub fn new(task: tokio::task::JoinHandle<()>) -> Self {
+        Self { task }
+    }
+
+    pub fn cancel(&self) {
+        self.task.abort();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Waits until the subscription stops, either because it was cancelled or
+    /// because the transport closed the delivery stream.
+    pub async fn closed(self) {
+        let _ = self.task.await;
+    }
 }
 
 // Port for subscribing to events from a message bus
-// Actual subscription mechanism is adapter-specific. This might evolve.
 #[async_trait]
 pub trait EventSubscriber: Send + Sync {
-    // Placeholder: The adapter implementation will handle the subscription loop.
-    // Maybe add methods to register handlers?
-    // async fn subscribe<E: Event, H: EventHandler<E>>(&self, topic: &str, handler: H) -> Result<(), CoreError>;
+    /// Starts delivering messages matching `subscription` to `handler`.
+    /// Messages are handled one at a time, in delivery order.
+    async fn subscribe(
+        &self,
+        subscription: Subscription,
+        handler: Arc<dyn MessageHandler>,
+    ) -> Result<SubscriptionHandle, CoreError>;
 }
 
 // Port for caching data
 #[async_trait]
 pub trait Cache: Send + Sync {
     async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, CoreError>;
+    /// Stores `value` under `key`. `ttl_seconds` overrides the adapter's default expiry for this entry.
     async fn set(&self, key: &str, value: &[u8], ttl_seconds: Option<u64>)
         -> Result<(), CoreError>;
     async fn delete(&self, key: &str) -> Result<(), CoreError>;
+
+    /// Fetches several keys at once; the result has one slot per requested key, in order.
+    async fn get_many(&self, keys: &[&str]) -> Result<Vec<Option<Vec<u8>>>, CoreError> {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            values.push(self.get(key).await?);
+        }
+        Ok(values)
+    }
+
+    /// Stores several entries sharing the same expiry.
+    async fn set_many(
+        &self,
+        entries: &[(&str, &[u8])],
+        ttl_seconds: Option<u64>,
+    ) -> Result<(), CoreError> {
+        for (key, value) in entries {
+            self.set(key, value, ttl_seconds).await?;
+        }
+        Ok(())
+    }
+
+    /// Stores `value` like `set` and records `key` under each tag so the group can be
+    /// dropped with `invalidate_tag`.
+    async fn set_tagged(
+        &self,
+        key: &str,
+        value: &[u8],
+        ttl_seconds: Option<u64>,
+        tags: &[&str],
+    ) -> Result<(), CoreError>;
+
+    /// Removes every entry stored with `tag`.
+    async fn invalidate_tag(&self, tag: &str) -> Result<(), CoreError>;
+
+    /// Removes every entry whose key starts with `prefix`.
+    async fn invalidate_prefix(&self, prefix: &str) -> Result<(), CoreError>;
+}
+
+// --- Locks ---
+
+/// A lock held by one holder until its TTL runs out or it is released.
+///
+/// `token` is a fencing token: every acquisition of the same lock name gets a larger
+/// token than the previous one, so storage written under the lock can reject writes
+/// from a holder whose lease has silently expired.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockLease {
+    pub name: String,
+    /// Unique per acquisition; identifies this lease to the lock service.
+    pub holder: String,
+    pub token: u64,
+    pub ttl: std::time::Duration,
+}
+
+// Port for distributed locks and leader election (see `lock` for renewal and leader helpers)
+#[async_trait]
+pub trait LockService: Send + Sync {
+    /// Acquires `name` for `ttl`, or returns `None` if another holder has it.
+    async fn try_acquire(
+        &self,
+        name: &str,
+        ttl: std::time::Duration,
+    ) -> Result<Option<LockLease>, CoreError>;
+
+    /// Extends the lease by its TTL. Returns `false` if the lease was lost,
+    /// i.e. it expired or the lock now belongs to someone else.
+    async fn renew(&self, lease: &LockLease) -> Result<bool, CoreError>;
+
+    /// Releases the lease; releasing a lost lease is a no-op.
+    async fn release(&self, lease: &LockLease) -> Result<(), CoreError>;
+}
+
+// --- Blob Storage ---
+
+/// Blobs larger than this are rejected unless a store is configured otherwise.
+pub const DEFAULT_MAX_BLOB_SIZE: u64 = 10 * 1024 * 1024;
+
+// Longest key accepted by S3-compatible stores
+const MAX_BLOB_KEY_LENGTH: usize = 1024;
+
+/// Metadata of a stored blob. Events reference blobs through `proto::blob::BlobRef`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobMetadata {
+    pub key: String,
+    pub content_type: String,
+    pub size: u64,
+    /// Hex-encoded SHA-256 of the content.
+    pub sha256: String,
+}
+
+impl From<BlobMetadata> for proto::blob::BlobRef {
+    fn from(metadata: BlobMetadata) -> Self {
+        Self {
+            key: metadata.key,
+            content_type: metadata.content_type,
+            size: metadata.size,
+            sha256: metadata.sha256,
+        }
+    }
+}
+
+impl From<proto::blob::BlobRef> for BlobMetadata {
+    fn from(blob: proto::blob::BlobRef) -> Self {
+        Self {
+            key: blob.key,
+            content_type: blob.content_type,
+            size: blob.size,
+            sha256: blob.sha256,
+        }
+    }
+}
+
+/// A blob's content together with its metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blob {
+    pub metadata: BlobMetadata,
+    pub data: Vec<u8>,
+}
+
+/// Operation allowed by a presigned URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobAccess {
+    Read,
+    Write,
+}
+
+/// Limits enforced by a blob store on `put`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlobLimits {
+    pub max_size: u64,
+    /// Accepted content types; empty accepts any.
+    pub content_types: Vec<String>,
+}
+
+impl Default for BlobLimits {
+    fn default() -> Self {
+        Self {
+            max_size: DEFAULT_MAX_BLOB_SIZE,
+            content_types: Vec::new(),
+        }
+    }
+}
+
+impl BlobLimits {
+    /// Validates a blob about to be stored.
+    pub fn check(&self, key: &str, content_type: &str, size: u64) -> Result<(), CoreError> {
+        validate_blob_key(key)?;
+        let valid_type = content_type
+            .split_once('/')
+            .is_some_and(|(kind, subtype)| !kind.is_empty() && !subtype.is_empty())
+            && content_type.is_ascii()
+            && !content_type.chars().any(|c| c.is_ascii_control());
+        if !valid_type {
+            return Err(CoreError::Validation(format!(
+                "Invalid content type '{}'",
+                content_type
+            )));
+        }
+        if !self.content_types.is_empty() && !self.content_types.iter().any(|t| t == content_type) {
+            return Err(CoreError::Validation(format!(
+                "Content type '{}' is not allowed",
+                content_type
+            )));
+        }
+        if size > self.max_size {
+            return Err(CoreError::Validation(format!(
+                "Blob of {} bytes exceeds the limit of {} bytes",
+                size, self.max_size
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Keys are `/`-separated paths of letters, digits, `-`, `_` and `.`, without empty,
+/// `.` or `..` segments, so every store can map them to files or object names.
+pub fn validate_blob_key(key: &str) -> Result<(), CoreError> {
+    let valid = !key.is_empty()
+        && key.len() <= MAX_BLOB_KEY_LENGTH
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(CoreError::Validation(format!("Invalid blob key '{}'", key)))
+    }
+}
+
+// Port for storing binary assets (logos, avatars, screenshots, flight tracks)
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    /// Stores `data` under `key`, replacing any existing blob.
+    /// Fails with `Validation` if the key, content type or size is not accepted.
+    async fn put(&self, key: &str, content_type: &str, data: &[u8])
+        -> Result<BlobMetadata, CoreError>;
+
+    /// Fails with `NotFound` if there is no blob under `key`.
+    async fn get(&self, key: &str) -> Result<Blob, CoreError>;
+
+    /// Metadata without the content; `None` if there is no blob under `key`.
+    async fn head(&self, key: &str) -> Result<Option<BlobMetadata>, CoreError>;
+
+    /// Deletes the blob; deleting a missing blob is a no-op.
+    async fn delete(&self, key: &str) -> Result<(), CoreError>;
+
+    /// URL that lets a client read or upload the blob directly until `expires_in` has passed.
+    async fn presign(
+        &self,
+        key: &str,
+        access: BlobAccess,
+        expires_in: std::time::Duration,
+    ) -> Result<String, CoreError>;
 }
@@ -0,0 +1,309 @@
+use crate::{CoreError, LockLease, LockService};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, Instant};
+use tracing::{info, warn};
+
+/// A lease that is renewed in the background every third of its TTL.
+///
+/// The lease counts as lost once a renewal is refused, or once renewals have been
+/// failing for so long that the lease may have expired. Work done under the lock
+/// should stop when `lost` resolves and pass `token` to anything that supports fencing.
+/// Dropping the guard stops renewing and releases the lock in the background; call
+/// `release` to wait for it and see errors.
+pub struct HeldLock {
+    lease: LockLease,
+    service: Arc<dyn LockService>,
+    lost: watch::Receiver<bool>,
+    renewer: JoinHandle<()>,
+    released: bool,
+}
+
+impl HeldLock {
+    /// Starts renewing an acquired lease.
+    pub fn new(service: Arc<dyn LockService>, lease: LockLease) -> Self {
+        let (lost_tx, lost) = watch::channel(false);
+        let renewer = tokio::spawn(renew_until_lost(service.clone(), lease.clone(), lost_tx));
+        Self {
+            lease,
+            service,
+            lost,
+            renewer,
+            released: false,
+        }
+    }
+
+    pub fn lease(&self) -> &LockLease {
+        &self.lease
+    }
+
+    pub fn token(&self) -> u64 {
+        self.lease.token
+    }
+
+    pub fn is_lost(&self) -> bool {
+        *self.lost.borrow()
+    }
+
+    /// Resolves once the lease has been lost.
+    pub async fn lost(&self) {
+        let mut lost = self.lost.clone();
+        let _ = lost.wait_for(|lost| *lost).await;
+    }
+
+    /// Stops renewing and releases the lock.
+    pub async fn release(mut self) -> Result<(), CoreError> {
+        self.renewer.abort();
+        self.released = true;
+        self.service.release(&self.lease).await
+    }
+}
+
+impl Drop for HeldLock {
+    fn drop(&mut self) {
+        self.renewer.abort();
+        if self.released {
+            return;
+        }
+        // Without a runtime (at shutdown) there is nothing to release with; the process
+        // exit ends the lease or its session.
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let service = self.service.clone();
+        let lease = self.lease.clone();
+        runtime.spawn(async move {
+            if let Err(e) = service.release(&lease).await {
+                warn!("Failed to release dropped lock '{}': {}", lease.name, e);
+            }
+        });
+    }
+}
+
+async fn renew_until_lost(
+    service: Arc<dyn LockService>,
+    lease: LockLease,
+    lost: watch::Sender<bool>,
+) {
+    let interval = lease.ttl / 3;
+    // Until a renewal succeeds, the lease is only known to be valid until this instant.
+    let mut valid_until = Instant::now() + lease.ttl;
+    loop {
+        sleep(interval).await;
+        let attempted_at = Instant::now();
+        match service.renew(&lease).await {
+            Ok(true) => valid_until = attempted_at + lease.ttl,
+            Ok(false) => {
+                warn!("Lock '{}' (token {}) was lost", lease.name, lease.token);
+                break;
+            }
+            Err(e) => {
+                warn!("Failed to renew lock '{}': {}", lease.name, e);
+                if Instant::now() + interval >= valid_until {
+                    warn!(
+                        "Lock '{}' (token {}) may have expired; treating it as lost",
+                        lease.name, lease.token
+                    );
+                    break;
+                }
+            }
+        }
+    }
+    let _ = lost.send(true);
+}
+
+/// Waits until `name` is acquired, retrying every `retry`, and keeps the lease renewed.
+pub async fn acquire(
+    service: Arc<dyn LockService>,
+    name: &str,
+    ttl: Duration,
+    retry: Duration,
+) -> Result<HeldLock, CoreError> {
+    loop {
+        if let Some(lease) = service.try_acquire(name, ttl).await? {
+            return Ok(HeldLock::new(service, lease));
+        }
+        sleep(retry).await;
+    }
+}
+
+/// Leader election: runs `work` only while this process holds `name`.
+///
+/// If the lease is lost, `work` is cancelled and the process competes for leadership
+/// again. Returns once `work` completes, releasing the lock. `work` receives the lease
+/// of the current term so it can fence its writes with the token.
+pub async fn run_as_leader<F, Fut>(
+    service: Arc<dyn LockService>,
+    name: &str,
+    ttl: Duration,
+    mut work: F,
+) -> Result<(), CoreError>
+where
+    F: FnMut(LockLease) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    loop {
+        let held = acquire(service.clone(), name, ttl, ttl / 3).await?;
+        info!("Became leader for '{}' (token {})", name, held.token());
+        tokio::select! {
+            _ = work(held.lease().clone()) => return held.release().await,
+            _ = held.lost() => warn!("Lost leadership for '{}'; competing again", name),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::in_memory_lock::InMemoryLockService;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    // Lock service whose renewals start failing on demand.
+    struct FlakyLocks {
+        inner: InMemoryLockService,
+        refuse_renewals: AtomicBool,
+    }
+
+    #[async_trait]
+    impl LockService for FlakyLocks {
+        async fn try_acquire(
+            &self,
+            name: &str,
+            ttl: Duration,
+        ) -> Result<Option<LockLease>, CoreError> {
+            self.inner.try_acquire(name, ttl).await
+        }
+
+        async fn renew(&self, lease: &LockLease) -> Result<bool, CoreError> {
+            if self.refuse_renewals.load(Ordering::SeqCst) {
+                return Ok(false);
+            }
+            self.inner.renew(lease).await
+        }
+
+        async fn release(&self, lease: &LockLease) -> Result<(), CoreError> {
+            self.inner.release(lease).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_held_lock_outlives_ttl_while_renewed() {
+        let service: Arc<dyn LockService> = Arc::new(InMemoryLockService::default());
+        let held = acquire(
+            service.clone(),
+            "rebuild",
+            Duration::from_millis(150),
+            Duration::from_millis(10),
+        )
+        .await
+        .unwrap();
+
+        sleep(Duration::from_millis(400)).await;
+        assert!(!held.is_lost());
+        assert!(service
+            .try_acquire("rebuild", Duration::from_secs(1))
+            .await
+            .unwrap()
+            .is_none());
+
+        held.release().await.unwrap();
+        assert!(service
+            .try_acquire("rebuild", Duration::from_secs(1))
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_lock_is_released() {
+        let service: Arc<dyn LockService> = Arc::new(InMemoryLockService::default());
+        let held = acquire(
+            service.clone(),
+            "rebuild",
+            Duration::from_secs(60),
+            Duration::from_millis(10),
+        )
+        .await
+        .unwrap();
+
+        drop(held);
+        let next = acquire(
+            service.clone(),
+            "rebuild",
+            Duration::from_secs(60),
+            Duration::from_millis(10),
+        );
+        tokio::time::timeout(Duration::from_millis(500), next)
+            .await
+            .expect("Dropped lock was not released")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_refused_renewal_marks_lock_lost() {
+        let service = Arc::new(FlakyLocks {
+            inner: InMemoryLockService::default(),
+            refuse_renewals: AtomicBool::new(false),
+        });
+        let held = acquire(
+            service.clone(),
+            "scheduler",
+            Duration::from_millis(150),
+            Duration::from_millis(10),
+        )
+        .await
+        .unwrap();
+
+        service.refuse_renewals.store(true, Ordering::SeqCst);
+        tokio::time::timeout(Duration::from_millis(500), held.lost())
+            .await
+            .expect("Loss was not detected");
+        assert!(held.is_lost());
+    }
+
+    #[tokio::test]
+    async fn test_only_one_leader_at_a_time() {
+        let service: Arc<dyn LockService> = Arc::new(InMemoryLockService::default());
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let ttl = Duration::from_millis(150);
+
+        let mut leaders = Vec::new();
+        for id in 0..2 {
+            let service = service.clone();
+            let tx = tx.clone();
+            leaders.push(tokio::spawn(async move {
+                run_as_leader(service, "leader", ttl, |lease| {
+                    let tx = tx.clone();
+                    async move {
+                        let _ = tx.send((id, lease.token, "start"));
+                        sleep(Duration::from_millis(100)).await;
+                        let _ = tx.send((id, lease.token, "end"));
+                    }
+                })
+                .await
+                .unwrap();
+            }));
+        }
+        for leader in leaders {
+            leader.await.unwrap();
+        }
+        drop(tx);
+
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        // Terms never overlap and the second leader has a larger fencing token
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].2, "start");
+        assert_eq!(events[1].2, "end");
+        assert_eq!(events[0].0, events[1].0);
+        assert_eq!(events[2].2, "start");
+        assert_eq!(events[3].2, "end");
+        assert!(events[2].1 > events[0].1);
+    }
+}
@@ -0,0 +1,192 @@
+use crate::domain::airport::AirportDatabase;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+// --- Clock ---
+
+/// Source of "now" for aggregates and command handlers.
+///
+/// Aggregates must never read the system time directly; they receive a clock
+/// through `Aggregate::Services` so tests can pin time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Current time formatted as an ISO-8601 timestamp (see [`format_timestamp`]).
+    fn timestamp(&self) -> String {
+        format_timestamp(self.now())
+    }
+}
+
+/// Formats a timestamp as ISO-8601 / RFC 3339 in UTC with millisecond precision,
+/// e.g. `2024-05-01T12:30:00.000Z`. All event timestamps use this format.
+pub fn format_timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Clock backed by the system time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that always returns the same instant until it is moved explicitly.
+/// Clones share the same underlying time.
+#[derive(Debug, Clone)]
+pub struct FixedClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl FixedClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().expect("FixedClock mutex poisoned") = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().expect("FixedClock mutex poisoned");
+        *now += by;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().expect("FixedClock mutex poisoned")
+    }
+}
+
+// --- ID Generation ---
+
+/// Generator for new identifiers (aggregate IDs, API key IDs, ...).
+pub trait IdGenerator: Send + Sync {
+    fn next_id(&self) -> String;
+}
+
+/// Generates random (v4) UUIDs.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UuidIdGenerator;
+
+impl IdGenerator for UuidIdGenerator {
+    fn next_id(&self) -> String {
+        Uuid::new_v4().to_string()
+    }
+}
+
+/// Generates predictable IDs of the form `{prefix}{n}`, starting at 1.
+/// Clones share the same counter.
+#[derive(Debug, Clone)]
+pub struct SequentialIdGenerator {
+    prefix: String,
+    counter: Arc<AtomicU64>,
+}
+
+impl SequentialIdGenerator {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            counter: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn next_id(&self) -> String {
+        let n = self.counter.fetch_add(1, Ordering::SeqCst) + 1;
+        format!("{}{}", self.prefix, n)
+    }
+}
+
+// --- Aggregate Services ---
+
+/// Services handed to every aggregate through `Aggregate::Services`.
+#[derive(Clone)]
+pub struct DomainServices {
+    pub clock: Arc<dyn Clock>,
+    pub ids: Arc<dyn IdGenerator>,
+    /// Airports that flights and aircraft locations are checked against.
+    pub airports: &'static AirportDatabase,
+}
+
+impl DomainServices {
+    /// Services with the airport database installed at startup (see
+    /// [`AirportDatabase::global`]), so create them after installing it.
+    pub fn new(clock: Arc<dyn Clock>, ids: Arc<dyn IdGenerator>) -> Self {
+        Self {
+            clock,
+            ids,
+            airports: AirportDatabase::global(),
+        }
+    }
+
+    /// The same services, checking airports against `airports` instead.
+    pub fn with_airports(self, airports: &'static AirportDatabase) -> Self {
+        Self { airports, ..self }
+    }
+
+    /// Deterministic services for tests: a [`FixedClock`] at `now`, a
+    /// [`SequentialIdGenerator`] producing `id-1`, `id-2`, ... and the embedded airports.
+    pub fn fixed(now: DateTime<Utc>) -> Self {
+        Self::new(
+            Arc::new(FixedClock::new(now)),
+            Arc::new(SequentialIdGenerator::new("id-")),
+        )
+        .with_airports(AirportDatabase::embedded())
+    }
+}
+
+impl Default for DomainServices {
+    fn default() -> Self {
+        Self::new(Arc::new(SystemClock), Arc::new(UuidIdGenerator))
+    }
+}
+
+impl fmt::Debug for DomainServices {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DomainServices")
+            .field("now", &self.clock.now())
+            .field("airports", &self.airports.len())
+            .finish_non_exhaustive()
+    }
+}
+
+// --- Tests ---
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_fixed_clock_formats_iso8601() {
+        let clock = FixedClock::new(Utc.with_ymd_and_hms(2024, 5, 1, 12, 30, 0).unwrap());
+        assert_eq!(clock.timestamp(), "2024-05-01T12:30:00.000Z");
+
+        clock.advance(Duration::milliseconds(1500));
+        assert_eq!(clock.timestamp(), "2024-05-01T12:30:01.500Z");
+    }
+
+    #[test]
+    fn test_sequential_id_generator_shares_counter_between_clones() {
+        let ids = SequentialIdGenerator::new("key-");
+        let clone = ids.clone();
+        assert_eq!(ids.next_id(), "key-1");
+        assert_eq!(clone.next_id(), "key-2");
+        assert_eq!(ids.next_id(), "key-3");
+    }
+
+    #[test]
+    fn test_uuid_id_generator_produces_valid_uuids() {
+        let id = UuidIdGenerator.next_id();
+        assert!(Uuid::parse_str(&id).is_ok());
+    }
+}
@@ -1,6 +1,9 @@
 use crate::persist::PersistenceError;
 
+/// Converts events to and from their stored binary form.
 pub trait Binarize<E>: Send + Sync + 'static {
     fn event_to_bytes(&self, event: &E) -> Result<Vec<u8>, PersistenceError>;
-    fn event_from_bytes(&self, bytes: &[u8]) -> Result<E, PersistenceError>;
+    /// Decodes a stored payload. `event_type` is the `DomainEvent::event_type` recorded
+    /// alongside the payload, needed by formats that do not tag the payload themselves.
+    fn event_from_bytes(&self, event_type: &str, bytes: &[u8]) -> Result<E, PersistenceError>;
 }
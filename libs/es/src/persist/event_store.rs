@@ -41,7 +41,7 @@ where
             let event_type = event.payload.event_type();
             let event_version = event.payload.event_version();
             let payload = self.binarizer.event_to_bytes(&event.payload)?;
-            let metadata = vec![];
+            let metadata = serialize_metadata(&event.metadata)?;
             results.push(SerializedEvent {
                 aggregate_id: event.aggregate_id.clone(),
                 sequence: event.sequence,
@@ -61,8 +61,10 @@ where
     ) -> Result<Vec<EventEnvelope<A>>, PersistenceError> {
         let mut results = Vec::default();
         for event in events {
-            let payload = self.binarizer.event_from_bytes(&event.payload)?;
-            let metadata = HashMap::new();
+            let payload = self
+                .binarizer
+                .event_from_bytes(&event.event_type, &event.payload)?;
+            let metadata = deserialize_metadata(&event.metadata)?;
             results.push(EventEnvelope {
                 aggregate_id: event.aggregate_id,
                 sequence: event.sequence,
@@ -92,6 +94,21 @@ where
     }
 }
 
+/// Metadata is stored as a JSON object; an empty map is stored as no bytes at all.
+fn serialize_metadata(metadata: &HashMap<String, String>) -> Result<Vec<u8>, PersistenceError> {
+    if metadata.is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::to_vec(metadata).map_err(|e| PersistenceError::UnknownError(Box::new(e)))
+}
+
+fn deserialize_metadata(bytes: &[u8]) -> Result<HashMap<String, String>, PersistenceError> {
+    if bytes.is_empty() {
+        return Ok(HashMap::new());
+    }
+    serde_json::from_slice(bytes).map_err(|e| PersistenceError::DeserializationError(Box::new(e)))
+}
+
 impl<R, A, B> EventStore<A> for PersistedEventStore<R, A, B>
 where
     R: PersistedEventRepository,
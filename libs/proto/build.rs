@@ -37,6 +37,18 @@ fn main() -> Result<()> {
     config.type_attribute(".", "#[derive(serde::Serialize)]");
     // Optionally, add Deserialize if needed later:
     // config.type_attribute(".", "#[derive(serde::Deserialize)]");
+    // PIREP rules, tenants' aircraft types and maintenance programs are configured as
+    // JSON through the API; missing fields take their defaults
+    for json_type in [
+        ".pirep.PirepRules",
+        ".pirep.RouteAircraft",
+        ".aircraft.AircraftType",
+        ".aircraft.MaintenanceProgram",
+        ".aircraft.InspectionInterval",
+    ] {
+        config.type_attribute(json_type, "#[derive(serde::Deserialize)]");
+        config.type_attribute(json_type, "#[serde(default)]");
+    }
 
     // Compile the protos using the configured builder
     config.compile_protos(&proto_files, &[proto_dir])?;
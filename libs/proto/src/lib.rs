@@ -8,11 +8,23 @@ pub mod user {
     include!(concat!(env!("OUT_DIR"), "/user.rs"));
 }
 
-// Include the generated code for the pirep package
+// Include the generated code for the pirep package.
+// The submit command carries the tenant's rules, making it much larger than the other commands.
+#[allow(clippy::large_enum_variant)]
 pub mod pirep {
     include!(concat!(env!("OUT_DIR"), "/pirep.rs"));
 }
 
+// Include the generated code for the aircraft package
+pub mod aircraft {
+    include!(concat!(env!("OUT_DIR"), "/aircraft.rs"));
+}
+
+// Include the generated code for the blob package
+pub mod blob {
+    include!(concat!(env!("OUT_DIR"), "/blob.rs"));
+}
+
 // Optional: Re-export common types if desired
 // pub use pirep::*;
 // pub use tenant::*;